@@ -0,0 +1,524 @@
+// Writes the KQvK, KRvK and KPvK Syzygy tables used by tests/syzygy.rs
+//
+//     cargo run --release --example syzygy_fixtures -- tests/fixtures/syzygy
+//
+// The endings are solved here by retrograde analysis with their own move generator, so the tables
+// don't depend on the engine's.  Positions are indexed by the reader's own encoding, and every
+// value is read back through it before the files are written.  Each value is stored as one
+// fixed-length symbol or a pair symbol for a run of 2^k equal values.
+
+use chessica::syzygy::table;
+use chessica::syzygy::table::Table;
+use std::fs;
+use std::path::Path;
+
+const WIN: i8 = 2;
+const DRAW: i8 = 0;
+const LOSS: i8 = -2;
+
+const WHITE_KING: u8 = 6;
+const BLACK_KING: u8 = 14;
+
+// DTZ table flags: the side to move stored, and wins and losses counted in plies
+const FLAG_WIN_PLIES: u8 = 4;
+const FLAG_LOSS_PLIES: u8 = 8;
+const FLAG_SINGLE_VALUE: u8 = 128;
+
+const BLOCK_BITS: u8 = 6;
+const SPAN_BITS: u8 = 10;
+// Symbols expand to at most 256 values, as their lengths are kept in bytes
+const LONGEST_RUN_BITS: usize = 8;
+
+#[derive(PartialEq,Eq,Copy,Clone,Debug)]
+enum Piece {
+    Queen,
+    Rook,
+    Pawn,
+}
+
+impl Piece {
+    fn code(&self) -> u8 {
+        match self {
+            Piece::Queen => 5,
+            Piece::Rook => 4,
+            Piece::Pawn => 1,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Piece::Queen => "KQvK",
+            Piece::Rook => "KRvK",
+            Piece::Pawn => "KPvK",
+        }
+    }
+}
+
+/// A position with the white king, the white piece and the black king on squares a1 = 0 .. h8 = 63
+#[derive(Copy,Clone)]
+struct Position {
+    stm: usize,
+    white_king: usize,
+    piece: usize,
+    black_king: usize,
+}
+
+fn position_index(p: &Position) -> usize {
+    ((p.stm * 64 + p.white_king) * 64 + p.piece) * 64 + p.black_king
+}
+
+fn all_positions() -> impl Iterator<Item = Position> {
+    (0..2 * 64 * 64 * 64).map(|i| Position { stm: i >> 18, white_king: (i >> 12) & 63, piece: (i >> 6) & 63, black_king: i & 63 })
+}
+
+fn file_of(square: usize) -> i32 {
+    (square & 7) as i32
+}
+
+fn rank_of(square: usize) -> i32 {
+    (square >> 3) as i32
+}
+
+fn next_to(a: usize, b: usize) -> bool {
+    a != b && (file_of(a) - file_of(b)).abs() <= 1 && (rank_of(a) - rank_of(b)).abs() <= 1
+}
+
+fn king_steps(from: usize) -> Vec<usize> {
+    (0..64).filter(|to| next_to(from, *to)).collect()
+}
+
+/// Does `piece` standing on `from` attack `to`, with `blocker` in the way?
+fn attacks(piece: Piece, from: usize, to: usize, blocker: usize) -> bool {
+    let (df, dr) = (file_of(to) - file_of(from), rank_of(to) - rank_of(from));
+    if piece == Piece::Pawn {
+        return dr == 1 && df.abs() == 1;
+    }
+    let straight = df == 0 || dr == 0;
+    let diagonal = df.abs() == dr.abs();
+    if from == to || !(straight || (piece == Piece::Queen && diagonal)) {
+        return false;
+    }
+    let step = df.signum() + 8 * dr.signum();
+    let mut square = from as i32 + step;
+    while square != to as i32 {
+        if square == blocker as i32 {
+            return false;
+        }
+        square += step;
+    }
+    true
+}
+
+/// Where a move leads: a position of the same ending, or a finished result for the side to move
+enum Child {
+    Same(Position, bool),
+    Done(i8),
+}
+
+struct Ending {
+    piece: Piece,
+    wdl: Vec<i8>,
+    dtz: Vec<i32>,
+}
+
+impl Ending {
+
+    fn legal(&self, p: &Position) -> bool {
+        let squares_differ = p.white_king != p.piece && p.white_king != p.black_king && p.piece != p.black_king;
+        let pawn_on_board = self.piece != Piece::Pawn || (1..7).contains(&rank_of(p.piece));
+        squares_differ && pawn_on_board && !next_to(p.white_king, p.black_king)
+            && !(p.stm == 0 && self.in_check(p))
+    }
+
+    /// Is the black king attacked?  (A lone black king never attacks the white king.)
+    fn in_check(&self, p: &Position) -> bool {
+        attacks(self.piece, p.piece, p.black_king, p.white_king)
+    }
+
+    fn children(&self, p: &Position, promotions: &[&Ending]) -> Vec<Child> {
+        let mut children = Vec::new();
+        if p.stm == 0 {
+            for to in king_steps(p.white_king) {
+                if to != p.piece && !next_to(to, p.black_king) && to != p.black_king {
+                    children.push(Child::Same(Position { stm: 1, white_king: to, ..*p }, false));
+                }
+            }
+            if self.piece == Piece::Pawn {
+                let push = p.piece + 8;
+                if push != p.white_king && push != p.black_king {
+                    if rank_of(push) == 7 {
+                        for ending in promotions.iter() {
+                            let promoted = Position { stm: 1, piece: push, ..*p };
+                            children.push(Child::Done(ending.wdl[position_index(&promoted)]));
+                        }
+                        // Bishops and knights can't win
+                        children.push(Child::Done(DRAW));
+                        children.push(Child::Done(DRAW));
+                    } else {
+                        children.push(Child::Same(Position { stm: 1, piece: push, ..*p }, true));
+                        let double = push + 8;
+                        if rank_of(p.piece) == 1 && double != p.white_king && double != p.black_king {
+                            children.push(Child::Same(Position { stm: 1, piece: double, ..*p }, true));
+                        }
+                    }
+                }
+            } else {
+                for to in 0..64 {
+                    if to != p.white_king && to != p.black_king && attacks(self.piece, p.piece, to, p.white_king)
+                        && attacks(self.piece, p.piece, to, p.black_king) {
+                        children.push(Child::Same(Position { stm: 1, piece: to, ..*p }, false));
+                    }
+                }
+            }
+        } else {
+            for to in king_steps(p.black_king) {
+                if to == p.white_king || next_to(to, p.white_king) || attacks(self.piece, p.piece, to, p.white_king) {
+                    continue;
+                }
+                if to == p.piece {
+                    // Only the kings are left
+                    children.push(Child::Done(DRAW));
+                } else {
+                    children.push(Child::Same(Position { stm: 0, black_king: to, ..*p }, false));
+                }
+            }
+        }
+        children
+    }
+
+    /// Value of a move's result for the side making the move, if it is known yet
+    fn result_for_mover(&self, child: &Child) -> Option<i8> {
+        match child {
+            Child::Done(value) => Some(-*value),
+            Child::Same(position, _) => {
+                let value = self.wdl[position_index(position)];
+                if value == i8::MIN { None } else { Some(-value) }
+            },
+        }
+    }
+
+    fn solve_wdl(&mut self, promotions: &[&Ending]) {
+        self.wdl = vec![i8::MIN; 2 * 64 * 64 * 64];
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for p in all_positions() {
+                let i = position_index(&p);
+                if self.wdl[i] != i8::MIN || !self.legal(&p) {
+                    continue;
+                }
+                let children = self.children(&p, promotions);
+                let results: Vec<Option<i8>> = children.iter().map(|child| self.result_for_mover(child)).collect();
+                let value = if children.is_empty() {
+                    Some(if p.stm == 1 && self.in_check(&p) { LOSS } else { DRAW })
+                } else if results.contains(&Some(WIN)) {
+                    Some(WIN)
+                } else if results.iter().all(|result| result.is_some()) {
+                    results.iter().copied().max().unwrap()
+                } else {
+                    None
+                };
+                if let Some(value) = value {
+                    self.wdl[i] = value;
+                    changed = true;
+                }
+            }
+        }
+        // Whatever is still open can be held forever
+        for p in all_positions() {
+            let i = position_index(&p);
+            if self.wdl[i] == i8::MIN && self.legal(&p) {
+                self.wdl[i] = DRAW;
+            }
+        }
+    }
+
+    fn is_checkmate(&self, p: &Position, promotions: &[&Ending]) -> bool {
+        p.stm == 1 && self.in_check(p) && self.children(p, promotions).is_empty()
+    }
+
+    /// Plies to the next capture, pawn move or checkmate, as the probing code counts them: a win
+    /// whose best move zeroes the counter or mates is 1, and a checkmated side has -1
+    fn solve_dtz(&mut self, promotions: &[&Ending]) {
+        self.dtz = vec![0; 2 * 64 * 64 * 64];
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for p in all_positions() {
+                let i = position_index(&p);
+                let wdl = self.wdl[i];
+                if !self.legal(&p) || wdl == DRAW {
+                    continue;
+                }
+                let children = self.children(&p, promotions);
+                let mut distances = Vec::new();
+                let mut complete = true;
+                for child in children.iter() {
+                    if self.result_for_mover(child) != Some(wdl) {
+                        continue;
+                    }
+                    match child {
+                        Child::Same(position, false) if !self.is_checkmate(position, promotions) => {
+                            let dtz = self.dtz[position_index(position)];
+                            if dtz == 0 {
+                                complete = false;
+                            } else {
+                                distances.push(dtz.abs() + 1);
+                            }
+                        },
+                        _ => distances.push(1),
+                    }
+                }
+                let dtz = if wdl == WIN {
+                    distances.iter().copied().min().unwrap_or(0)
+                } else if children.is_empty() {
+                    -1
+                } else if complete {
+                    -distances.iter().copied().max().unwrap()
+                } else {
+                    0
+                };
+                if dtz != 0 && dtz != self.dtz[i] {
+                    self.dtz[i] = dtz;
+                    changed = true;
+                }
+            }
+        }
+    }
+
+}
+
+fn solve(piece: Piece, promotions: &[&Ending]) -> Ending {
+    let mut ending = Ending { piece, wdl: vec![], dtz: vec![] };
+    ending.solve_wdl(promotions);
+    ending.solve_dtz(promotions);
+    let longest = |stm: usize| all_positions().filter(|p| p.stm == stm).map(|p| ending.dtz[position_index(&p)].abs()).max().unwrap();
+    println!("{}: longest DTZ {} plies with white to move, {} with black to move",piece.name(),longest(0),longest(1));
+    ending
+}
+
+/// Files with a leading pawn are split by its file (a-d), the others are not
+fn file_count(piece: Piece) -> usize {
+    if piece == Piece::Pawn { 4 } else { 1 }
+}
+
+/// Magic number, flags, and the piece order of each file
+fn header(piece: Piece, dtz: bool) -> Vec<u8> {
+    let mut bytes = if dtz { table::DTZ_MAGIC.to_vec() } else { table::WDL_MAGIC.to_vec() };
+    bytes.push(if piece == Piece::Pawn { 3 } else { 1 });
+    let pieces = if piece == Piece::Pawn {
+        [piece.code(), WHITE_KING, BLACK_KING]
+    } else {
+        [WHITE_KING, piece.code(), BLACK_KING]
+    };
+    // Each file's groups are indexed in the order the pieces are listed, for both sides to move
+    let entry: Vec<u8> = std::iter::once(0).chain(pieces.iter().map(|code| code | (code << 4))).collect();
+    for _ in 0..file_count(piece) {
+        bytes.extend_from_slice(&entry);
+    }
+    if bytes.len() & 1 == 1 {
+        bytes.push(0);
+    }
+    bytes
+}
+
+/// A table with nothing but its header, to index positions with
+fn index_table(piece: Piece) -> Table {
+    let mut bytes = header(piece, false);
+    for _ in 0..2 * file_count(piece) {
+        bytes.extend_from_slice(&[FLAG_SINGLE_VALUE, 0]);
+    }
+    bytes.resize((bytes.len() + 0x7F) & !0x3F, 0);
+    table::from_bytes(piece.name(), bytes, false).unwrap()
+}
+
+fn table_size(index: &Table, file: usize) -> usize {
+    let d = index.get(0, file);
+    let groups = d.group_len.iter().position(|len| *len == 0).unwrap();
+    d.group_idx[groups] as usize
+}
+
+/// The values to store for each (side to move, file), in index order, `None` where any will do
+fn fill(ending: &Ending, index: &Table, stored: &[usize], value: impl Fn(&Position) -> Option<u16>) -> Vec<Vec<Option<u16>>> {
+    let files = file_count(ending.piece);
+    let mut values: Vec<Vec<Option<u16>>> = (0..2 * files).map(|i| vec![None; table_size(index, i % files)]).collect();
+    for p in all_positions() {
+        if !stored.contains(&p.stm) || !ending.legal(&p) {
+            continue;
+        }
+        let value = match value(&p) {
+            Some(value) => value,
+            None => continue,
+        };
+        let pieces = [(p.white_king, WHITE_KING), (p.piece, ending.piece.code()), (p.black_king, BLACK_KING)];
+        let (file, idx) = index.encode(&pieces, p.stm).unwrap().unwrap();
+        let slot = &mut values[p.stm * files + file][idx as usize];
+        // Mirror images share an index, so they had better agree
+        assert!(slot.is_none() || *slot == Some(value),"{} has two values at index {}",ending.piece.name(),idx);
+        *slot = Some(value);
+    }
+    values
+}
+
+/// The bytes of one compressed (side to move, file): its sizes entry, sparse index, block
+/// lengths and blocks
+struct Section {
+    sizes: Vec<u8>,
+    sparse_index: Vec<u8>,
+    block_lengths: Vec<u8>,
+    data: Vec<u8>,
+}
+
+fn compress(values: &[Option<u16>], flags: u8) -> Section {
+    // Anything may be stored where nothing is needed, so runs go on through the gaps
+    let mut last = values.iter().flatten().next().copied().unwrap_or(0);
+    let values: Vec<u16> = values.iter().map(|value| {
+        last = value.unwrap_or(last);
+        last
+    }).collect();
+    let mut distinct = values.clone();
+    distinct.sort_unstable();
+    distinct.dedup();
+    if distinct.len() == 1 {
+        return Section { sizes: vec![flags | FLAG_SINGLE_VALUE, distinct[0] as u8], sparse_index: vec![], block_lengths: vec![], data: vec![] };
+    }
+
+    // Symbol `v * runs + k` is 2^k copies of the v-th distinct value
+    let runs = LONGEST_RUN_BITS + 1;
+    let symbols = distinct.len() * runs;
+    let symbol_bits = (usize::BITS - (symbols - 1).leading_zeros()) as usize;
+    let mut btree = Vec::new();
+    for value in distinct.iter() {
+        btree.extend_from_slice(&[*value as u8, 0xF0 | (*value >> 8) as u8, 0xFF]);
+        for _ in 1..runs {
+            let half = btree.len() / 3 - 1;
+            btree.extend_from_slice(&[half as u8, ((half >> 8) as u8) | ((half as u8 & 0xF) << 4), (half >> 4) as u8]);
+        }
+    }
+    let block_bits = 8 << BLOCK_BITS;
+    let span = 1 << SPAN_BITS;
+    // Offsets into a block are 16 bits, including those of the last sparse index entry
+    let block_capacity = 65536 - span;
+
+    let mut blocks: Vec<Vec<u8>> = Vec::new();
+    let mut block_starts = Vec::new();
+    let mut idx = 0;
+    while idx < values.len() {
+        block_starts.push(idx);
+        let mut block = vec![0u8; block_bits / 8];
+        let (mut bits, mut count) = (0, 0);
+        while bits + symbol_bits <= block_bits && idx < values.len() && count < block_capacity {
+            let run = values[idx..].iter().take_while(|value| **value == values[idx]).count().min(block_capacity - count);
+            let k = (usize::BITS - 1 - run.leading_zeros()) as usize;
+            let k = k.min(LONGEST_RUN_BITS);
+            let symbol = distinct.binary_search(&values[idx]).unwrap() * runs + k;
+            for bit in 0..symbol_bits {
+                if symbol & (1 << (symbol_bits - 1 - bit)) != 0 {
+                    block[(bits + bit) / 8] |= 0x80 >> ((bits + bit) % 8);
+                }
+            }
+            bits += symbol_bits;
+            count += 1 << k;
+            idx += 1 << k;
+        }
+        blocks.push(block);
+    }
+
+    let mut sizes = vec![flags, BLOCK_BITS, SPAN_BITS, 0];
+    sizes.extend_from_slice(&(blocks.len() as u32).to_le_bytes());
+    sizes.extend_from_slice(&[symbol_bits as u8, symbol_bits as u8, 0, 0]);
+    sizes.extend_from_slice(&(symbols as u16).to_le_bytes());
+    sizes.extend_from_slice(&btree);
+    if symbols & 1 == 1 {
+        sizes.push(0);
+    }
+
+    let mut sparse_index = Vec::new();
+    for k in 0..(values.len() + span - 1) / span {
+        let middle = k * span + span / 2;
+        let block = block_starts.iter().rposition(|start| *start <= middle).unwrap();
+        sparse_index.extend_from_slice(&(block as u32).to_le_bytes());
+        sparse_index.extend_from_slice(&((middle - block_starts[block]) as u16).to_le_bytes());
+    }
+    let mut block_lengths = Vec::new();
+    for (i, start) in block_starts.iter().enumerate() {
+        let end = block_starts.get(i + 1).copied().unwrap_or(values.len());
+        block_lengths.extend_from_slice(&((end - start - 1) as u16).to_le_bytes());
+    }
+    Section { sizes, sparse_index, block_lengths, data: blocks.concat() }
+}
+
+/// Lay the sections out the way the reader expects: every sizes entry, then every sparse index,
+/// every block length list and every block list, each of those aligned to 64 bytes
+fn assemble(mut bytes: Vec<u8>, sections: &[Section], dtz: bool) -> Vec<u8> {
+    for section in sections.iter() {
+        bytes.extend_from_slice(&section.sizes);
+    }
+    if dtz && bytes.len() & 1 == 1 {
+        bytes.push(0);
+    }
+    for section in sections.iter() {
+        bytes.extend_from_slice(&section.sparse_index);
+    }
+    for section in sections.iter() {
+        bytes.extend_from_slice(&section.block_lengths);
+    }
+    for section in sections.iter() {
+        bytes.resize((bytes.len() + 0x3F) & !0x3F, 0);
+        bytes.extend_from_slice(&section.data);
+    }
+    bytes
+}
+
+/// Compress and write one table, then read every needed value back through the reader
+fn write(directory: &Path, ending: &Ending, dtz: bool, stored: &[usize], flags: &[u8], values: Vec<Vec<Option<u16>>>) {
+    let files = file_count(ending.piece);
+    let mut sections = Vec::new();
+    // The file holds the sections file by file, each with its sides to move in turn
+    for file in 0..files {
+        for (side, stm) in stored.iter().enumerate() {
+            sections.push(compress(&values[stm * files + file], flags[side]));
+        }
+    }
+    let bytes = assemble(header(ending.piece, dtz), &sections, dtz);
+    let table = table::from_bytes(ending.piece.name(), bytes.clone(), dtz).unwrap();
+    for stm in stored.iter() {
+        for file in 0..files {
+            for (idx, value) in values[stm * files + file].iter().enumerate() {
+                if let Some(value) = value {
+                    assert_eq!(table.decompress_pairs(table.get(*stm, file), idx as u64).unwrap(),*value,"stm {} file {} index {}",stm,file,idx);
+                }
+            }
+        }
+    }
+    let path = directory.join(format!("{}.{}",ending.piece.name(),if dtz { "rtbz" } else { "rtbw" }));
+    println!("{}: {} bytes",path.display(),bytes.len());
+    fs::write(path, bytes).unwrap();
+}
+
+/// Write the WDL table, and the DTZ table for one side to move
+fn write_tables(directory: &Path, ending: &Ending, dtz_side: usize) {
+    let index = index_table(ending.piece);
+    let wdl = fill(ending, &index, &[0, 1], |p| Some((ending.wdl[position_index(p)] + 2) as u16));
+    write(directory, ending, false, &[0, 1], &[0, 0], wdl);
+
+    let dtz = fill(ending, &index, &[dtz_side], |p| {
+        let dtz = ending.dtz[position_index(p)];
+        if dtz == 0 { None } else { Some(dtz.unsigned_abs() as u16 - 1) }
+    });
+    write(directory, ending, true, &[dtz_side], &[dtz_side as u8 | FLAG_WIN_PLIES | FLAG_LOSS_PLIES], dtz);
+}
+
+fn main() {
+    let directory = std::env::args().nth(1).unwrap_or_else(|| String::from("tests/fixtures/syzygy"));
+    let directory = Path::new(&directory);
+    fs::create_dir_all(directory).unwrap();
+
+    let queen = solve(Piece::Queen, &[]);
+    let rook = solve(Piece::Rook, &[]);
+    let pawn = solve(Piece::Pawn, &[&queen, &rook]);
+    // The DTZ tables hold white to move, except KRvK's, so both ways of probing them get used
+    write_tables(directory, &queen, 0);
+    write_tables(directory, &rook, 1);
+    write_tables(directory, &pawn, 0);
+}
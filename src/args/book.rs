@@ -0,0 +1,179 @@
+use crate::args::ArgStruct;
+use crate::book::options::BookSelection;
+use crate::book::builder::BookFormat;
+
+pub fn args_book(arg: &str, option: &str, response: &mut ArgStruct) {
+    match option {
+        "--book" => {
+            response.book_options.path = Some(arg.to_string());
+        },
+        "--book-depth" => args_book_depth(arg, response),
+        "--book-selection" => args_book_selection(arg, response),
+        "--build-book" => {
+            response.book_build_options.output = Some(arg.to_string());
+        },
+        "--pgn" => {
+            response.book_build_options.pgn_files.push(arg.to_string());
+        },
+        "--book-min-games" => args_book_min_games(arg, response),
+        "--book-min-score" => args_book_min_score(arg, response),
+        "--book-format" => args_book_format(arg, response),
+        _ => {
+            // Will not get here
+        },
+    }
+}
+
+pub fn args_book_depth(arg: &str, response: &mut ArgStruct) {
+    match arg.parse::<u16>() {
+        Ok(n) => {
+            response.book_options.max_depth = n;
+            response.book_build_options.max_ply = n;
+        },
+        _ => {
+            response.error = true;
+            println!("Unexpected book-depth: \"{}\"!",arg);
+        },
+    }
+}
+
+pub fn args_book_selection(arg: &str, response: &mut ArgStruct) {
+    match arg {
+        "weighted" => {
+            response.book_options.selection = BookSelection::Weighted;
+        },
+        "best" => {
+            response.book_options.selection = BookSelection::Best;
+        },
+        _ => {
+            response.error = true;
+            println!("The \"--book-selection\" option must be specified with one of the following: [weighted,best]");
+        },
+    }
+}
+
+pub fn args_book_min_games(arg: &str, response: &mut ArgStruct) {
+    match arg.parse::<u32>() {
+        Ok(n) => {
+            response.book_build_options.min_occurrences = n;
+        },
+        _ => {
+            response.error = true;
+            println!("Unexpected book-min-games: \"{}\"!",arg);
+        },
+    }
+}
+
+pub fn args_book_min_score(arg: &str, response: &mut ArgStruct) {
+    match arg.parse::<u8>() {
+        Ok(n) if n <= 100 => {
+            response.book_build_options.min_score = n;
+        },
+        _ => {
+            response.error = true;
+            println!("Unexpected book-min-score (expected a percentage): \"{}\"!",arg);
+        },
+    }
+}
+
+pub fn args_book_format(arg: &str, response: &mut ArgStruct) {
+    match arg {
+        "polyglot" => {
+            response.book_build_options.format = BookFormat::Polyglot;
+        },
+        "native" => {
+            response.book_build_options.format = BookFormat::Native;
+        },
+        _ => {
+            response.error = true;
+            println!("The \"--book-format\" option must be specified with one of the following: [polyglot,native]");
+        },
+    }
+}
+
+impl ArgStruct {
+    pub fn book_build(&self) -> bool {
+        self.book_build_options.output.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::args::process_args;
+    use crate::book::options::BookSelection;
+    use crate::book::builder::BookFormat;
+
+    fn convert_to_strings(arr: &[&str]) -> Vec<String> {
+        let mut vec = Vec::new();
+        for item in arr.iter().enumerate() {
+            let (_i, x): (usize, &&str) = item;
+            vec.push(x.to_string());
+        }
+        vec
+    }
+
+    #[test]
+    fn args_book_valid() {
+        let arr = ["chessica","--book","openings.bin","--book-depth","16","--book-selection","best"];
+        let vec = convert_to_strings(&arr);
+        let a = process_args(vec);
+        assert!(!a.error);
+        assert!(!a.profile);
+        assert_eq!(a.book_options.path,Some("openings.bin".to_string()));
+        assert_eq!(a.book_options.max_depth,16);
+        assert_eq!(a.book_options.selection,BookSelection::Best);
+    }
+
+    #[test]
+    fn args_build_book_valid() {
+        let arr = ["chessica","--build-book","mine.txt","--pgn","a.pgn","--pgn","b.pgn","--book-depth","20",
+                   "--book-min-games","3","--book-min-score","55","--book-format","native"];
+        let vec = convert_to_strings(&arr);
+        let a = process_args(vec);
+        assert!(!a.error);
+        assert!(a.book_build());
+        assert_eq!(a.book_build_options.output,Some("mine.txt".to_string()));
+        assert_eq!(a.book_build_options.pgn_files,vec!["a.pgn".to_string(),"b.pgn".to_string()]);
+        assert_eq!(a.book_build_options.max_ply,20);
+        assert_eq!(a.book_build_options.min_occurrences,3);
+        assert_eq!(a.book_build_options.min_score,55);
+        assert_eq!(a.book_build_options.format,BookFormat::Native);
+    }
+
+    #[test]
+    fn args_build_book_invalid() {
+        let arr = ["chessica","--build-book","mine.bin"];
+        let vec = convert_to_strings(&arr);
+        let a = process_args(vec);
+        assert!(a.error);
+
+        let arr = ["chessica","--book-min-score","101"];
+        let vec = convert_to_strings(&arr);
+        let a = process_args(vec);
+        assert!(a.error);
+
+        let arr = ["chessica","--book-format","ctg"];
+        let vec = convert_to_strings(&arr);
+        let a = process_args(vec);
+        assert!(a.error);
+    }
+
+    #[test]
+    fn args_book_invalid() {
+        let arr = ["chessica","--book-selection","random"];
+        let vec = convert_to_strings(&arr);
+        let a = process_args(vec);
+        assert!(a.error);
+
+        let arr = ["chessica","--book-depth","deep"];
+        let vec = convert_to_strings(&arr);
+        let a = process_args(vec);
+        assert!(a.error);
+
+        let arr = ["chessica","--book"];
+        let vec = convert_to_strings(&arr);
+        let a = process_args(vec);
+        assert!(a.error);
+    }
+
+}
@@ -0,0 +1,69 @@
+use crate::args::ArgStruct;
+
+pub fn args_deterministic(arg: &str, option: &str, response: &mut ArgStruct) {
+    match option {
+        "--seed" => args_seed(arg, response),
+        _ => {
+            // Will not get here
+        },
+    }
+}
+
+pub fn args_seed(arg: &str, response: &mut ArgStruct) {
+    match arg.parse::<u64>() {
+        Ok(n) => {
+            response.seed = Some(n);
+            response.mcts_options.seed = n;
+        },
+        _ => {
+            response.error = true;
+            println!("Unexpected seed: \"{}\"!",arg);
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::args::process_args;
+
+    fn convert_to_strings(arr: &[&str]) -> Vec<String> {
+        let mut vec = Vec::new();
+        for item in arr.iter().enumerate() {
+            let (_i, x): (usize, &&str) = item;
+            vec.push(x.to_string());
+        }
+        vec
+    }
+
+    #[test]
+    fn args_deterministic_valid() {
+        let arr = ["chessica","--deterministic","--seed","1234"];
+        let vec = convert_to_strings(&arr);
+        let a = process_args(vec);
+        assert!(!a.error);
+        assert!(!a.profile);
+        assert!(a.deterministic);
+        assert_eq!(a.seed,Some(1234));
+        assert_eq!(a.mcts_options.seed,1234);
+
+        let arr = ["chessica","--profile","mcts","--mcts-iterations","500"];
+        let vec = convert_to_strings(&arr);
+        let a = process_args(vec);
+        assert!(!a.deterministic);
+        assert_eq!(a.seed,None);
+    }
+
+    #[test]
+    fn args_deterministic_invalid() {
+        let arr = ["chessica","--seed","-1"];
+        let vec = convert_to_strings(&arr);
+        let a = process_args(vec);
+        assert!(a.error);
+
+        let arr = ["chessica","--seed"];
+        let vec = convert_to_strings(&arr);
+        let a = process_args(vec);
+        assert!(a.error);
+    }
+
+}
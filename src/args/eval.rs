@@ -0,0 +1,100 @@
+use crate::args::ArgStruct;
+
+pub fn args_eval(arg: &str, option: &str, response: &mut ArgStruct) {
+    match option {
+        "--eval-params" => {
+            response.eval_params_path = Some(arg.to_string());
+        },
+        "--tune" => {
+            response.tune_positions = Some(arg.to_string());
+        },
+        "--tune-passes" => args_tune_passes(arg, response),
+        _ => {
+            // Will not get here
+        },
+    }
+}
+
+pub fn args_tune_passes(arg: &str, response: &mut ArgStruct) {
+    match arg.parse::<u32>() {
+        Ok(n) if n > 0 => {
+            response.tune_passes = n;
+        },
+        _ => {
+            response.error = true;
+            println!("Unexpected tune-passes: \"{}\"!",arg);
+        },
+    }
+}
+
+impl ArgStruct {
+    pub fn tune(&self) -> bool {
+        self.tune_positions.is_some()
+    }
+
+    pub fn eval_trace(&self) -> bool {
+        self.eval_trace
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::args::process_args;
+
+    fn convert_to_strings(arr: &[&str]) -> Vec<String> {
+        let mut vec = Vec::new();
+        for item in arr.iter().enumerate() {
+            let (_i, x): (usize, &&str) = item;
+            vec.push(x.to_string());
+        }
+        vec
+    }
+
+    #[test]
+    fn args_eval_valid() {
+        let arr = ["chessica","--eval-params","eval.params"];
+        let vec = convert_to_strings(&arr);
+        let a = process_args(vec);
+        assert!(!a.error);
+        assert!(!a.tune());
+        assert_eq!(a.eval_params_path,Some("eval.params".to_string()));
+
+        let arr = ["chessica","--tune","quiet.epd","--eval-params","eval.params","--tune-passes","50"];
+        let vec = convert_to_strings(&arr);
+        let a = process_args(vec);
+        assert!(!a.error);
+        assert!(!a.profile);
+        assert!(a.tune());
+        assert_eq!(a.tune_positions,Some("quiet.epd".to_string()));
+        assert_eq!(a.tune_passes,50);
+    }
+
+    #[test]
+    fn args_eval_trace() {
+        let arr = ["chessica","--eval","--fen","8/8/8/4k3/8/8/8/KQ6 w - - 0 1"];
+        let vec = convert_to_strings(&arr);
+        let a = process_args(vec);
+        assert!(!a.error);
+        assert!(a.eval_trace());
+        assert!(!a.tune());
+    }
+
+    #[test]
+    fn args_eval_invalid() {
+        let arr = ["chessica","--tune","quiet.epd"];
+        let vec = convert_to_strings(&arr);
+        let a = process_args(vec);
+        assert!(a.error);
+
+        let arr = ["chessica","--tune","quiet.epd","--eval-params","eval.params","--tune-passes","0"];
+        let vec = convert_to_strings(&arr);
+        let a = process_args(vec);
+        assert!(a.error);
+
+        let arr = ["chessica","--eval-params"];
+        let vec = convert_to_strings(&arr);
+        let a = process_args(vec);
+        assert!(a.error);
+    }
+
+}
@@ -0,0 +1,92 @@
+use crate::args::ArgStruct;
+
+pub fn args_fuzz(arg: &str, option: &str, response: &mut ArgStruct) {
+    match option {
+        "--fuzz" => args_fuzz_games(arg, response),
+        "--fuzz-plies" => args_fuzz_plies(arg, response),
+        _ => {
+            // Will not get here
+        },
+    }
+}
+
+pub fn args_fuzz_games(arg: &str, response: &mut ArgStruct) {
+    match arg.parse::<u32>() {
+        Ok(n) if n > 0 => {
+            response.fuzz_games = Some(n);
+        },
+        _ => {
+            response.error = true;
+            println!("Unexpected number of games to fuzz: \"{}\"!",arg);
+        },
+    }
+}
+
+pub fn args_fuzz_plies(arg: &str, response: &mut ArgStruct) {
+    match arg.parse::<u32>() {
+        Ok(n) if n > 0 => {
+            response.fuzz_plies = n;
+        },
+        _ => {
+            response.error = true;
+            println!("Unexpected number of plies per fuzzed game: \"{}\"!",arg);
+        },
+    }
+}
+
+impl ArgStruct {
+    pub fn fuzz(&self) -> bool {
+        self.fuzz_games.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::args::process_args;
+
+    fn convert_to_strings(arr: &[&str]) -> Vec<String> {
+        let mut vec = Vec::new();
+        for item in arr.iter().enumerate() {
+            let (_i, x): (usize, &&str) = item;
+            vec.push(x.to_string());
+        }
+        vec
+    }
+
+    #[test]
+    fn args_fuzz_valid() {
+        let arr = ["chessica","--fuzz","500","--fuzz-plies","80","--seed","9"];
+        let vec = convert_to_strings(&arr);
+        let a = process_args(vec);
+        assert!(!a.error);
+        assert!(a.fuzz());
+        assert_eq!(a.fuzz_games,Some(500));
+        assert_eq!(a.fuzz_plies,80);
+        assert_eq!(a.seed,Some(9));
+
+        let arr = ["chessica"];
+        let vec = convert_to_strings(&arr);
+        let a = process_args(vec);
+        assert!(!a.fuzz());
+        assert_eq!(a.fuzz_plies,200);
+    }
+
+    #[test]
+    fn args_fuzz_invalid() {
+        let arr = ["chessica","--fuzz","0"];
+        let vec = convert_to_strings(&arr);
+        let a = process_args(vec);
+        assert!(a.error);
+
+        let arr = ["chessica","--fuzz","10","--fuzz-plies","many"];
+        let vec = convert_to_strings(&arr);
+        let a = process_args(vec);
+        assert!(a.error);
+
+        let arr = ["chessica","--fuzz"];
+        let vec = convert_to_strings(&arr);
+        let a = process_args(vec);
+        assert!(a.error);
+    }
+
+}
@@ -0,0 +1,83 @@
+use crate::args::ArgStruct;
+
+pub fn args_mate(arg: &str, option: &str, response: &mut ArgStruct) {
+    match option {
+        "--mate" => args_mate_moves(arg, response),
+        "--fen" => args_fen(arg, response),
+        _ => {
+            // Will not get here
+        },
+    }
+}
+
+pub fn args_mate_moves(arg: &str, response: &mut ArgStruct) {
+    match arg.parse::<u8>() {
+        Ok(n) if n > 0 => {
+            response.mate_moves = Some(n);
+        },
+        _ => {
+            response.error = true;
+            println!("Unexpected number of moves to mate: \"{}\"!",arg);
+        },
+    }
+}
+
+pub fn args_fen(arg: &str, response: &mut ArgStruct) {
+    if arg.split_whitespace().count() < 4 {
+        response.error = true;
+        println!("Unexpected FEN: \"{}\"!",arg);
+    } else {
+        response.fen = Some(arg.to_string());
+    }
+}
+
+impl ArgStruct {
+    pub fn mate_search(&self) -> bool {
+        self.mate_moves.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::args::process_args;
+
+    fn convert_to_strings(arr: &[&str]) -> Vec<String> {
+        let mut vec = Vec::new();
+        for item in arr.iter().enumerate() {
+            let (_i, x): (usize, &&str) = item;
+            vec.push(x.to_string());
+        }
+        vec
+    }
+
+    #[test]
+    fn args_mate_valid() {
+        let arr = ["chessica","--mate","3","--fen","k7/8/2K5/8/8/8/8/7R w - - 0 1","--checks-only"];
+        let vec = convert_to_strings(&arr);
+        let a = process_args(vec);
+        assert!(!a.error);
+        assert!(a.mate_search());
+        assert_eq!(a.mate_moves,Some(3));
+        assert_eq!(a.fen,Some("k7/8/2K5/8/8/8/8/7R w - - 0 1".to_string()));
+        assert!(a.checks_only);
+    }
+
+    #[test]
+    fn args_mate_invalid() {
+        let arr = ["chessica","--mate","0"];
+        let vec = convert_to_strings(&arr);
+        let a = process_args(vec);
+        assert!(a.error);
+
+        let arr = ["chessica","--fen","k7/8/2K5"];
+        let vec = convert_to_strings(&arr);
+        let a = process_args(vec);
+        assert!(a.error);
+
+        let arr = ["chessica","--mate"];
+        let vec = convert_to_strings(&arr);
+        let a = process_args(vec);
+        assert!(a.error);
+    }
+
+}
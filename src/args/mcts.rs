@@ -0,0 +1,79 @@
+use crate::args::ArgStruct;
+use crate::tree::mcts_options::PlayoutType;
+
+pub fn args_mcts(arg: &str, option: &str, response: &mut ArgStruct) {
+    match option {
+        "--mcts-iterations" => args_mcts_iterations(arg, response),
+        "--mcts-playout" => args_mcts_playout(arg, response),
+        _ => {
+            // Will not get here
+        },
+    }
+}
+
+pub fn args_mcts_iterations(arg: &str, response: &mut ArgStruct) {
+    match arg.parse::<u32>() {
+        Ok(n) if n > 0 => {
+            response.mcts_options.iterations = n;
+        },
+        _ => {
+            response.error = true;
+            println!("Unexpected mcts-iterations: \"{}\"!",arg);
+        },
+    }
+}
+
+pub fn args_mcts_playout(arg: &str, response: &mut ArgStruct) {
+    match arg {
+        "random" => {
+            response.mcts_options.playout = PlayoutType::Random;
+        },
+        "eval" => {
+            response.mcts_options.playout = PlayoutType::Evaluation;
+        },
+        _ => {
+            response.error = true;
+            println!("The \"--mcts-playout\" option must be specified with one of the following: [random,eval]");
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::args::process_args;
+    use crate::tree::mcts_options::PlayoutType;
+
+    fn convert_to_strings(arr: &[&str]) -> Vec<String> {
+        let mut vec = Vec::new();
+        for item in arr.iter().enumerate() {
+            let (_i, x): (usize, &&str) = item;
+            vec.push(x.to_string());
+        }
+        vec
+    }
+
+    #[test]
+    fn args_mcts_valid() {
+        let arr = ["chessica","--profile","mcts","--mcts-iterations","500","--mcts-playout","random"];
+        let vec = convert_to_strings(&arr);
+        let a = process_args(vec);
+        assert!(!a.error);
+        assert!(a.profile_mcts());
+        assert_eq!(a.mcts_options.iterations,500);
+        assert_eq!(a.mcts_options.playout,PlayoutType::Random);
+    }
+
+    #[test]
+    fn args_mcts_invalid() {
+        let arr = ["chessica","--mcts-playout","guess"];
+        let vec = convert_to_strings(&arr);
+        let a = process_args(vec);
+        assert!(a.error);
+
+        let arr = ["chessica","--mcts-iterations","0"];
+        let vec = convert_to_strings(&arr);
+        let a = process_args(vec);
+        assert!(a.error);
+    }
+
+}
@@ -1,12 +1,54 @@
 pub mod profile;
 pub mod ab;
+pub mod selectivity;
+pub mod mate;
+pub mod proof;
+pub mod mcts;
+pub mod book;
+pub mod syzygy;
+pub mod nnue;
+pub mod eval;
+pub mod deterministic;
+pub mod fuzz;
+pub mod random;
 use crate::args::profile::ProfileType;
+use crate::args::proof::ProofSearchType;
+use crate::tree::search_options::SearchOptions;
+use crate::tree::mcts_options::MctsOptions;
+use crate::book::options::BookOptions;
+use crate::book::builder::BookBuildOptions;
+use crate::reset::random::PositionFormat;
 
 pub struct ArgStruct {
     pub profile: bool,
     pub profile_type: ProfileType,
     pub ab_search_depth: u8,
     pub ab_keep_depth: u8,
+    pub search_options: SearchOptions,
+    pub mate_moves: Option<u8>,
+    pub checks_only: bool,
+    pub fen: Option<String>,
+    pub proof_search_type: ProofSearchType,
+    pub proof_nodes: u64,
+    pub mcts_options: MctsOptions,
+    pub book_options: BookOptions,
+    pub book_build_options: BookBuildOptions,
+    pub syzygy_path: Option<String>,
+    pub syzygy_probe: bool,
+    pub nnue_path: Option<String>,
+    pub eval_params_path: Option<String>,
+    pub tune_positions: Option<String>,
+    pub tune_passes: u32,
+    pub eval_trace: bool,
+    pub deterministic: bool,
+    pub seed: Option<u64>,
+    pub fuzz_games: Option<u32>,
+    pub fuzz_plies: u32,
+    pub random_positions: Option<u32>,
+    pub random_min_plies: u16,
+    pub random_max_plies: u16,
+    pub random_material: Option<String>,
+    pub random_format: PositionFormat,
     pub error: bool,
 }
 
@@ -17,6 +59,17 @@ pub fn usage() {
 pub fn process_args(args: Vec<String>) -> ArgStruct {
     use crate::args::profile::args_profile;
     use crate::args::ab::args_ab;
+    use crate::args::selectivity::args_selectivity;
+    use crate::args::mate::args_mate;
+    use crate::args::proof::args_proof;
+    use crate::args::mcts::args_mcts;
+    use crate::args::book::args_book;
+    use crate::args::syzygy::args_syzygy;
+    use crate::args::nnue::args_nnue;
+    use crate::args::eval::args_eval;
+    use crate::args::deterministic::args_deterministic;
+    use crate::args::fuzz::args_fuzz;
+    use crate::args::random::args_random;
 
     let mut arg_index = 1;
     let mut response = ArgStruct {
@@ -24,6 +77,31 @@ pub fn process_args(args: Vec<String>) -> ArgStruct {
         profile_type: ProfileType::None,
         ab_search_depth: 8, // <= This should be a constant somewhere
         ab_keep_depth: 4, // <= This should be a constant somewhere
+        search_options: crate::tree::search_options::new(),
+        mate_moves: None,
+        checks_only: false,
+        fen: None,
+        proof_search_type: ProofSearchType::None,
+        proof_nodes: 1000000, // <= This should be a constant somewhere
+        mcts_options: crate::tree::mcts_options::new(),
+        book_options: crate::book::options::new(),
+        book_build_options: crate::book::builder::new_options(),
+        syzygy_path: None,
+        syzygy_probe: false,
+        nnue_path: None,
+        eval_params_path: None,
+        tune_positions: None,
+        tune_passes: 1000, // <= This should be a constant somewhere
+        eval_trace: false,
+        deterministic: false,
+        seed: None,
+        fuzz_games: None,
+        fuzz_plies: 200, // <= This should be a constant somewhere
+        random_positions: None,
+        random_min_plies: 10, // <= This should be a constant somewhere
+        random_max_plies: 60, // <= This should be a constant somewhere
+        random_material: None,
+        random_format: PositionFormat::Fen,
         error: false,
     };
 
@@ -54,6 +132,145 @@ pub fn process_args(args: Vec<String>) -> ArgStruct {
                     arg_index += 1;
                 }
             },
+            "--null-move" | "--null-move-reduction" | "--lmr" | "--lmr-full-depth-moves" | "--lmr-min-depth" | "--make-unmake" => {
+                if arg_index + 1 < args.len() {
+                    let parameter: &str = &((&args[arg_index+1])[..]);
+                    args_selectivity(parameter,option,&mut response);
+                    arg_index += 2;
+                } else {
+                    println!("No argument specifed for \"{}\" option",option);
+                    response.error = true;
+                    arg_index += 1;
+                }
+            },
+            "--mate" | "--fen" => {
+                if arg_index + 1 < args.len() {
+                    let parameter: &str = &((&args[arg_index+1])[..]);
+                    args_mate(parameter,option,&mut response);
+                    arg_index += 2;
+                } else {
+                    println!("No argument specifed for \"{}\" option",option);
+                    response.error = true;
+                    arg_index += 1;
+                }
+            },
+            "--proof-search" | "--proof-nodes" => {
+                if arg_index + 1 < args.len() {
+                    let parameter: &str = &((&args[arg_index+1])[..]);
+                    args_proof(parameter,option,&mut response);
+                    arg_index += 2;
+                } else {
+                    println!("No argument specifed for \"{}\" option",option);
+                    response.error = true;
+                    arg_index += 1;
+                }
+            },
+            "--mcts-iterations" | "--mcts-playout" => {
+                response.profile = true;
+                if arg_index + 1 < args.len() {
+                    let parameter: &str = &((&args[arg_index+1])[..]);
+                    args_mcts(parameter,option,&mut response);
+                    arg_index += 2;
+                } else {
+                    println!("No argument specifed for \"{}\" option",option);
+                    response.error = true;
+                    arg_index += 1;
+                }
+            },
+            "--book" | "--book-depth" | "--book-selection" | "--build-book" | "--pgn" |
+            "--book-min-games" | "--book-min-score" | "--book-format" => {
+                if arg_index + 1 < args.len() {
+                    let parameter: &str = &((&args[arg_index+1])[..]);
+                    args_book(parameter,option,&mut response);
+                    arg_index += 2;
+                } else {
+                    println!("No argument specifed for \"{}\" option",option);
+                    response.error = true;
+                    arg_index += 1;
+                }
+            },
+            "--syzygy-path" => {
+                if arg_index + 1 < args.len() {
+                    let parameter: &str = &((&args[arg_index+1])[..]);
+                    args_syzygy(parameter,option,&mut response);
+                    arg_index += 2;
+                } else {
+                    println!("No argument specifed for \"{}\" option",option);
+                    response.error = true;
+                    arg_index += 1;
+                }
+            },
+            "--nnue" => {
+                if arg_index + 1 < args.len() {
+                    let parameter: &str = &((&args[arg_index+1])[..]);
+                    args_nnue(parameter,option,&mut response);
+                    arg_index += 2;
+                } else {
+                    println!("No argument specifed for \"{}\" option",option);
+                    response.error = true;
+                    arg_index += 1;
+                }
+            },
+            "--eval-params" | "--tune" | "--tune-passes" => {
+                if arg_index + 1 < args.len() {
+                    let parameter: &str = &((&args[arg_index+1])[..]);
+                    args_eval(parameter,option,&mut response);
+                    arg_index += 2;
+                } else {
+                    println!("No argument specifed for \"{}\" option",option);
+                    response.error = true;
+                    arg_index += 1;
+                }
+            },
+            "--seed" => {
+                if arg_index + 1 < args.len() {
+                    let parameter: &str = &((&args[arg_index+1])[..]);
+                    args_deterministic(parameter,option,&mut response);
+                    arg_index += 2;
+                } else {
+                    println!("No argument specifed for \"{}\" option",option);
+                    response.error = true;
+                    arg_index += 1;
+                }
+            },
+            "--fuzz" | "--fuzz-plies" => {
+                if arg_index + 1 < args.len() {
+                    let parameter: &str = &((&args[arg_index+1])[..]);
+                    args_fuzz(parameter,option,&mut response);
+                    arg_index += 2;
+                } else {
+                    println!("No argument specifed for \"{}\" option",option);
+                    response.error = true;
+                    arg_index += 1;
+                }
+            },
+            "--random-positions" | "--random-plies" | "--random-material" | "--random-format" => {
+                if arg_index + 1 < args.len() {
+                    let parameter: &str = &((&args[arg_index+1])[..]);
+                    args_random(parameter,option,&mut response);
+                    arg_index += 2;
+                } else {
+                    println!("No argument specifed for \"{}\" option",option);
+                    response.error = true;
+                    arg_index += 1;
+                }
+            },
+            "--deterministic" => {
+                response.deterministic = true;
+                arg_index += 1;
+            },
+            "--eval" => {
+                response.eval_trace = true;
+                arg_index += 1;
+            },
+            "--syzygy-probe" => {
+                response.syzygy_probe = true;
+                arg_index += 1;
+            },
+            "--checks-only" => {
+                response.checks_only = true;
+                arg_index += 1;
+            },
             _ => {
                 println!("I don't know what to do with argument \"{}\"",option);
                 response.error = true;
@@ -61,6 +278,18 @@ pub fn process_args(args: Vec<String>) -> ArgStruct {
             },
         }
     }
+    if response.book_build_options.output.is_some() && response.book_build_options.pgn_files.is_empty() {
+        println!("The \"--build-book\" option needs at least one \"--pgn\" file");
+        response.error = true;
+    }
+    if response.syzygy_probe && response.syzygy_path.is_none() {
+        println!("The \"--syzygy-probe\" option needs a \"--syzygy-path\" directory");
+        response.error = true;
+    }
+    if response.tune_positions.is_some() && response.eval_params_path.is_none() {
+        println!("The \"--tune\" option needs an \"--eval-params\" file to write");
+        response.error = true;
+    }
     response
 }
 
@@ -0,0 +1,45 @@
+use crate::args::ArgStruct;
+
+pub fn args_nnue(arg: &str, option: &str, response: &mut ArgStruct) {
+    match option {
+        "--nnue" => {
+            response.nnue_path = Some(arg.to_string());
+        },
+        _ => {
+            // Will not get here
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::args::process_args;
+
+    fn convert_to_strings(arr: &[&str]) -> Vec<String> {
+        let mut vec = Vec::new();
+        for item in arr.iter().enumerate() {
+            let (_i, x): (usize, &&str) = item;
+            vec.push(x.to_string());
+        }
+        vec
+    }
+
+    #[test]
+    fn args_nnue_valid() {
+        let arr = ["chessica","--nnue","nets/halfkp.nnue"];
+        let vec = convert_to_strings(&arr);
+        let a = process_args(vec);
+        assert!(!a.error);
+        assert!(!a.profile);
+        assert_eq!(a.nnue_path,Some("nets/halfkp.nnue".to_string()));
+    }
+
+    #[test]
+    fn args_nnue_invalid() {
+        let arr = ["chessica","--nnue"];
+        let vec = convert_to_strings(&arr);
+        let a = process_args(vec);
+        assert!(a.error);
+    }
+
+}
@@ -4,10 +4,12 @@ use crate::args::ArgStruct;
 pub enum ProfileType {
     None,
     Reset,
+    ResetMakeUnmake,
     Tree,
     InPlaceAB,
     PromotePruneAB,
     IterativePromotePruneAB,
+    Mcts,
 }
 
 pub fn args_profile(arg: &str, response: &mut ArgStruct) {
@@ -15,6 +17,9 @@ pub fn args_profile(arg: &str, response: &mut ArgStruct) {
         "reset" => {
             response.profile_type = ProfileType::Reset;
         },
+        "reset-make-unmake" => {
+            response.profile_type = ProfileType::ResetMakeUnmake;
+        },
         "tree" => {
             response.profile_type = ProfileType::Tree;
         },
@@ -27,9 +32,12 @@ pub fn args_profile(arg: &str, response: &mut ArgStruct) {
         "iterative-promote-prune-ab" => {
             response.profile_type = ProfileType::IterativePromotePruneAB;
         },
+        "mcts" => {
+            response.profile_type = ProfileType::Mcts;
+        },
         "" => {
             response.error = true;
-            println!("The \"--profile\" option must be specified with one of the following: [reset,tree]");
+            println!("The \"--profile\" option must be specified with one of the following: [reset,reset-make-unmake,tree]");
         },
         _ => {
             response.error = true;
@@ -43,6 +51,10 @@ impl ArgStruct {
         self.profile & (self.profile_type == ProfileType::Reset)
     }
 
+    pub fn profile_reset_make_unmake(&self) -> bool {
+        self.profile & (self.profile_type == ProfileType::ResetMakeUnmake)
+    }
+
     pub fn profile_tree(&self) -> bool {
         self.profile & (self.profile_type == ProfileType::Tree)
     }
@@ -59,6 +71,10 @@ impl ArgStruct {
         self.profile & (self.profile_type == ProfileType::IterativePromotePruneAB)
     }
 
+    pub fn profile_mcts(&self) -> bool {
+        self.profile & (self.profile_type == ProfileType::Mcts)
+    }
+
 }
 
 #[cfg(test)]
@@ -88,6 +104,17 @@ mod tests {
         assert!(!a.profile_in_place_ab());
         assert!(!a.profile_promote_prune_ab());
         assert!(!a.profile_iterative_promote_prune_ab());
+        assert!(!a.profile_reset_make_unmake());
+
+        let arr = ["chessica","--profile","reset-make-unmake"];
+        let vec = convert_to_strings(&arr);
+        let a = process_args(vec);
+        assert!(a.profile);
+        assert_eq!(a.profile_type,ProfileType::ResetMakeUnmake);
+        assert!(!a.error);
+        assert!(a.profile_reset_make_unmake());
+        assert!(!a.profile_reset());
+        assert!(!a.profile_tree());
 
         let arr = ["chessica","--profile","tree"];
         let vec = convert_to_strings(&arr);
@@ -137,6 +164,16 @@ mod tests {
         assert!(!a.profile_in_place_ab());
         assert!(!a.profile_promote_prune_ab());
         assert!(a.profile_iterative_promote_prune_ab());
+        assert!(!a.profile_mcts());
+
+        let arr = ["chessica","--profile","mcts"];
+        let vec = convert_to_strings(&arr);
+        let a = process_args(vec);
+        assert!(a.profile);
+        assert_eq!(a.profile_type,ProfileType::Mcts);
+        assert!(!a.error);
+        assert!(!a.profile_iterative_promote_prune_ab());
+        assert!(a.profile_mcts());
 
         let arr = ["chessica","--profile","noclue"];
         let vec = convert_to_strings(&arr);
@@ -0,0 +1,91 @@
+use crate::args::ArgStruct;
+
+#[derive(PartialEq,Eq,Debug)]
+pub enum ProofSearchType {
+    None,
+    PN,
+    PN2,
+}
+
+pub fn args_proof(arg: &str, option: &str, response: &mut ArgStruct) {
+    match option {
+        "--proof-search" => args_proof_search(arg, response),
+        "--proof-nodes" => args_proof_nodes(arg, response),
+        _ => {
+            // Will not get here
+        },
+    }
+}
+
+pub fn args_proof_search(arg: &str, response: &mut ArgStruct) {
+    match arg {
+        "pn" => {
+            response.proof_search_type = ProofSearchType::PN;
+        },
+        "pn2" => {
+            response.proof_search_type = ProofSearchType::PN2;
+        },
+        _ => {
+            response.error = true;
+            println!("The \"--proof-search\" option must be specified with one of the following: [pn,pn2]");
+        },
+    }
+}
+
+pub fn args_proof_nodes(arg: &str, response: &mut ArgStruct) {
+    match arg.parse::<u64>() {
+        Ok(n) if n > 0 => {
+            response.proof_nodes = n;
+        },
+        _ => {
+            response.error = true;
+            println!("Unexpected proof-nodes: \"{}\"!",arg);
+        },
+    }
+}
+
+impl ArgStruct {
+    pub fn proof_search(&self) -> bool {
+        self.proof_search_type != ProofSearchType::None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::args::process_args;
+    use crate::args::proof::ProofSearchType;
+
+    fn convert_to_strings(arr: &[&str]) -> Vec<String> {
+        let mut vec = Vec::new();
+        for item in arr.iter().enumerate() {
+            let (_i, x): (usize, &&str) = item;
+            vec.push(x.to_string());
+        }
+        vec
+    }
+
+    #[test]
+    fn args_proof_valid() {
+        let arr = ["chessica","--proof-search","pn2","--proof-nodes","5000"];
+        let vec = convert_to_strings(&arr);
+        let a = process_args(vec);
+        assert!(!a.error);
+        assert!(a.proof_search());
+        assert_eq!(a.proof_search_type,ProofSearchType::PN2);
+        assert_eq!(a.proof_nodes,5000);
+    }
+
+    #[test]
+    fn args_proof_invalid() {
+        let arr = ["chessica","--proof-search","alpha-beta"];
+        let vec = convert_to_strings(&arr);
+        let a = process_args(vec);
+        assert!(a.error);
+
+        let arr = ["chessica","--proof-nodes","lots"];
+        let vec = convert_to_strings(&arr);
+        let a = process_args(vec);
+        assert!(a.error);
+    }
+
+}
@@ -0,0 +1,129 @@
+use crate::args::ArgStruct;
+use crate::reset::random::PositionFormat;
+
+pub fn args_random(arg: &str, option: &str, response: &mut ArgStruct) {
+    match option {
+        "--random-positions" => args_random_positions(arg, response),
+        "--random-plies" => args_random_plies(arg, response),
+        "--random-material" => args_random_material(arg, response),
+        "--random-format" => args_random_format(arg, response),
+        _ => {
+            // Will not get here
+        },
+    }
+}
+
+pub fn args_random_positions(arg: &str, response: &mut ArgStruct) {
+    match arg.parse::<u32>() {
+        Ok(n) if n > 0 => {
+            response.random_positions = Some(n);
+        },
+        _ => {
+            response.error = true;
+            println!("Unexpected number of random positions: \"{}\"!",arg);
+        },
+    }
+}
+
+pub fn args_random_plies(arg: &str, response: &mut ArgStruct) {
+    let range = match arg.split_once('-') {
+        Some((min, max)) => (min.parse::<u16>(), max.parse::<u16>()),
+        None => (arg.parse::<u16>(), arg.parse::<u16>()),
+    };
+    match range {
+        (Ok(min), Ok(max)) if min <= max => {
+            response.random_min_plies = min;
+            response.random_max_plies = max;
+        },
+        _ => {
+            response.error = true;
+            println!("Unexpected ply range: \"{}\"!",arg);
+        },
+    }
+}
+
+pub fn args_random_material(arg: &str, response: &mut ArgStruct) {
+    response.random_material = Some(arg.to_string());
+}
+
+pub fn args_random_format(arg: &str, response: &mut ArgStruct) {
+    match arg {
+        "fen" => response.random_format = PositionFormat::Fen,
+        "epd" => response.random_format = PositionFormat::Epd,
+        _ => {
+            response.error = true;
+            println!("The \"--random-format\" option must be one of the following: [fen,epd]");
+        },
+    }
+}
+
+impl ArgStruct {
+    pub fn random_positions(&self) -> bool {
+        self.random_positions.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::args::process_args;
+    use crate::reset::random::PositionFormat;
+
+    fn convert_to_strings(arr: &[&str]) -> Vec<String> {
+        let mut vec = Vec::new();
+        for item in arr.iter().enumerate() {
+            let (_i, x): (usize, &&str) = item;
+            vec.push(x.to_string());
+        }
+        vec
+    }
+
+    #[test]
+    fn args_random_valid() {
+        let arr = ["chessica","--random-positions","1000","--random-plies","8-40","--random-format","epd","--seed","3"];
+        let vec = convert_to_strings(&arr);
+        let a = process_args(vec);
+        assert!(!a.error);
+        assert!(a.random_positions());
+        assert_eq!(a.random_positions,Some(1000));
+        assert_eq!((a.random_min_plies,a.random_max_plies),(8,40));
+        assert_eq!(a.random_format,PositionFormat::Epd);
+        assert_eq!(a.random_material,None);
+
+        let arr = ["chessica","--random-positions","10","--random-material","KRPvKR","--random-plies","12"];
+        let vec = convert_to_strings(&arr);
+        let a = process_args(vec);
+        assert!(!a.error);
+        assert_eq!(a.random_material,Some("KRPvKR".to_string()));
+        assert_eq!((a.random_min_plies,a.random_max_plies),(12,12));
+        assert_eq!(a.random_format,PositionFormat::Fen);
+
+        let arr = ["chessica"];
+        let vec = convert_to_strings(&arr);
+        let a = process_args(vec);
+        assert!(!a.random_positions());
+    }
+
+    #[test]
+    fn args_random_invalid() {
+        let arr = ["chessica","--random-positions","0"];
+        let vec = convert_to_strings(&arr);
+        let a = process_args(vec);
+        assert!(a.error);
+
+        let arr = ["chessica","--random-positions","10","--random-plies","40-8"];
+        let vec = convert_to_strings(&arr);
+        let a = process_args(vec);
+        assert!(a.error);
+
+        let arr = ["chessica","--random-positions","10","--random-format","pgn"];
+        let vec = convert_to_strings(&arr);
+        let a = process_args(vec);
+        assert!(a.error);
+
+        let arr = ["chessica","--random-material"];
+        let vec = convert_to_strings(&arr);
+        let a = process_args(vec);
+        assert!(a.error);
+    }
+
+}
@@ -0,0 +1,145 @@
+use crate::args::ArgStruct;
+
+pub fn args_selectivity(arg: &str, option: &str, response: &mut ArgStruct) {
+    match option {
+        "--null-move" => args_null_move(arg, response),
+        "--null-move-reduction" => args_null_move_reduction(arg, response),
+        "--lmr" => args_lmr(arg, response),
+        "--lmr-full-depth-moves" => args_lmr_full_depth_moves(arg, response),
+        "--lmr-min-depth" => args_lmr_min_depth(arg, response),
+        "--make-unmake" => args_make_unmake(arg, response),
+        _ => {
+            // Will not get here
+        },
+    }
+}
+
+fn args_on_off(arg: &str, option: &str, response: &mut ArgStruct) -> bool {
+    match arg {
+        "on" => true,
+        "off" => false,
+        _ => {
+            response.error = true;
+            println!("The \"{}\" option must be specified with one of the following: [on,off]",option);
+            false
+        },
+    }
+}
+
+pub fn args_null_move(arg: &str, response: &mut ArgStruct) {
+    response.search_options.null_move = args_on_off(arg, "--null-move", response);
+}
+
+pub fn args_null_move_reduction(arg: &str, response: &mut ArgStruct) {
+    match arg.parse::<u8>() {
+        Ok(n) => {
+            response.search_options.null_move_reduction = n;
+        },
+        Err(_e) => {
+            response.error = true;
+            println!("Unexpected null-move-reduction: \"{}\"!",arg);
+        },
+    }
+}
+
+pub fn args_lmr(arg: &str, response: &mut ArgStruct) {
+    response.search_options.late_move_reductions = args_on_off(arg, "--lmr", response);
+}
+
+pub fn args_lmr_full_depth_moves(arg: &str, response: &mut ArgStruct) {
+    match arg.parse::<u8>() {
+        Ok(n) => {
+            response.search_options.lmr_full_depth_moves = n;
+        },
+        Err(_e) => {
+            response.error = true;
+            println!("Unexpected lmr-full-depth-moves: \"{}\"!",arg);
+        },
+    }
+}
+
+pub fn args_lmr_min_depth(arg: &str, response: &mut ArgStruct) {
+    match arg.parse::<u8>() {
+        Ok(n) => {
+            response.search_options.lmr_min_depth = n;
+        },
+        Err(_e) => {
+            response.error = true;
+            println!("Unexpected lmr-min-depth: \"{}\"!",arg);
+        },
+    }
+}
+
+pub fn args_make_unmake(arg: &str, response: &mut ArgStruct) {
+    response.search_options.make_unmake = args_on_off(arg, "--make-unmake", response);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::args::process_args;
+
+    fn convert_to_strings(arr: &[&str]) -> Vec<String> {
+        let mut vec = Vec::new();
+        for item in arr.iter().enumerate() {
+            let (_i, x): (usize, &&str) = item;
+            vec.push(x.to_string());
+        }
+        vec
+    }
+
+    #[test]
+    fn args_selectivity_defaults() {
+        let arr = ["chessica","--profile","in-place-ab"];
+        let vec = convert_to_strings(&arr);
+        let a = process_args(vec);
+        assert!(!a.error);
+        assert!(!a.search_options.null_move);
+        assert!(!a.search_options.late_move_reductions);
+        assert!(!a.search_options.make_unmake);
+    }
+
+    #[test]
+    fn args_selectivity_valid() {
+        let arr = ["chessica","--null-move","on","--null-move-reduction","3","--lmr","on","--lmr-full-depth-moves","6","--lmr-min-depth","5"];
+        let vec = convert_to_strings(&arr);
+        let a = process_args(vec);
+        assert!(!a.error);
+        assert!(a.search_options.null_move);
+        assert_eq!(a.search_options.null_move_reduction,3);
+        assert!(a.search_options.late_move_reductions);
+        assert_eq!(a.search_options.lmr_full_depth_moves,6);
+        assert_eq!(a.search_options.lmr_min_depth,5);
+        // Selectivity tunes whatever search runs; it doesn't start a profile of its own
+        assert!(!a.profile);
+
+        let arr = ["chessica","--make-unmake","on"];
+        let vec = convert_to_strings(&arr);
+        let a = process_args(vec);
+        assert!(!a.error);
+        assert!(a.search_options.make_unmake);
+    }
+
+    #[test]
+    fn args_selectivity_invalid() {
+        let arr = ["chessica","--null-move","maybe"];
+        let vec = convert_to_strings(&arr);
+        let a = process_args(vec);
+        assert!(a.error);
+
+        let arr = ["chessica","--lmr-full-depth-moves","-1"];
+        let vec = convert_to_strings(&arr);
+        let a = process_args(vec);
+        assert!(a.error);
+
+        let arr = ["chessica","--lmr-min-depth","three"];
+        let vec = convert_to_strings(&arr);
+        let a = process_args(vec);
+        assert!(a.error);
+
+        let arr = ["chessica","--lmr"];
+        let vec = convert_to_strings(&arr);
+        let a = process_args(vec);
+        assert!(a.error);
+    }
+
+}
@@ -0,0 +1,57 @@
+use crate::args::ArgStruct;
+
+pub fn args_syzygy(arg: &str, option: &str, response: &mut ArgStruct) {
+    match option {
+        "--syzygy-path" => {
+            response.syzygy_path = Some(arg.to_string());
+        },
+        _ => {
+            // Will not get here
+        },
+    }
+}
+
+impl ArgStruct {
+    pub fn syzygy_probe(&self) -> bool {
+        self.syzygy_probe
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::args::process_args;
+
+    fn convert_to_strings(arr: &[&str]) -> Vec<String> {
+        let mut vec = Vec::new();
+        for item in arr.iter().enumerate() {
+            let (_i, x): (usize, &&str) = item;
+            vec.push(x.to_string());
+        }
+        vec
+    }
+
+    #[test]
+    fn args_syzygy_valid() {
+        let arr = ["chessica","--syzygy-path","/tb/syzygy","--syzygy-probe","--fen","8/8/8/4k3/8/8/8/KQ6 w - - 0 1"];
+        let vec = convert_to_strings(&arr);
+        let a = process_args(vec);
+        assert!(!a.error);
+        assert!(!a.profile);
+        assert!(a.syzygy_probe());
+        assert_eq!(a.syzygy_path,Some("/tb/syzygy".to_string()));
+    }
+
+    #[test]
+    fn args_syzygy_invalid() {
+        let arr = ["chessica","--syzygy-path"];
+        let vec = convert_to_strings(&arr);
+        let a = process_args(vec);
+        assert!(a.error);
+
+        let arr = ["chessica","--syzygy-probe","--fen","8/8/8/4k3/8/8/8/KQ6 w - - 0 1"];
+        let vec = convert_to_strings(&arr);
+        let a = process_args(vec);
+        assert!(a.error);
+    }
+
+}
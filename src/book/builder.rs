@@ -0,0 +1,305 @@
+use crate::reset;
+use crate::book::BookEntry;
+use crate::book::pgn::GameResult;
+use crate::book::pgn::PgnGame;
+use std::collections::HashMap;
+
+/// File format written by the book builder
+#[derive(PartialEq,Eq,Copy,Clone,Hash,Debug)]
+pub enum BookFormat {
+    /// Polyglot `.bin`, readable by `book::from_file` and most other engines
+    Polyglot,
+    /// Chessica's text format: one line per move with its key, UCI move and game statistics
+    Native,
+}
+
+/// Settings for building a book from PGN games
+///
+/// | field           | description |
+/// | --------------- | ----------- |
+/// | output          | File to write, or `None` when not building a book |
+/// | pgn_files       | PGN files to read games from |
+/// | max_ply         | Only record moves played before this ply |
+/// | min_occurrences | Drop moves played in fewer games than this |
+/// | min_score       | Drop moves scoring below this percentage for the side that played them |
+/// | format          | Polyglot or native output |
+#[derive(PartialEq,Eq,Clone,Hash,Debug)]
+pub struct BookBuildOptions {
+    pub output: Option<String>,
+    pub pgn_files: Vec<String>,
+    pub max_ply: u16,
+    pub min_occurrences: u32,
+    pub min_score: u8,
+    pub format: BookFormat,
+}
+
+/// Constructs a new set of BookBuildOptions
+///
+/// # Examples
+///
+/// ```
+/// use chessica::book::builder::BookFormat;
+/// let options = chessica::book::builder::new_options();
+/// assert_eq!(options.format,BookFormat::Polyglot);
+/// assert_eq!(options.min_occurrences,1);
+/// ```
+pub fn new_options() -> BookBuildOptions {
+    BookBuildOptions {
+        output: None,
+        pgn_files: Vec::new(),
+        max_ply: 40,
+        min_occurrences: 1,
+        min_score: 0,
+        format: BookFormat::Polyglot,
+    }
+}
+
+/// How often a move was played from a position, and how those games ended
+#[derive(PartialEq,Eq,Clone,Hash,Debug)]
+pub struct MoveStats {
+    pub uci_move: String,
+    pub white_to_move: bool,
+    pub games: u32,
+    pub white_wins: u32,
+    pub draws: u32,
+    pub black_wins: u32,
+}
+
+impl MoveStats {
+
+    /// Wins for the side that played the move
+    pub fn wins(&self) -> u32 {
+        if self.white_to_move { self.white_wins } else { self.black_wins }
+    }
+
+    /// Losses for the side that played the move
+    pub fn losses(&self) -> u32 {
+        if self.white_to_move { self.black_wins } else { self.white_wins }
+    }
+
+    /// Percentage score (0-100) for the side that played the move, counting draws as half a win.
+    /// Games with an unknown result don't count; with no decided games the score is 50.
+    pub fn score(&self) -> u8 {
+        let decided = self.wins() + self.draws + self.losses();
+        if decided == 0 {
+            return 50;
+        }
+        ((u64::from(self.wins()) * 200 + u64::from(self.draws) * 100) / (u64::from(decided) * 2)) as u8
+    }
+
+    /// Polyglot weight: two points per win and one per draw, at least 1 so the move stays playable
+    pub fn weight(&self) -> u16 {
+        (u64::from(self.wins()) * 2 + u64::from(self.draws)).clamp(1, u64::from(u16::MAX)) as u16
+    }
+
+}
+
+/// Move statistics gathered from games, keyed by Polyglot position key and move
+pub struct BookBuilder {
+    pub positions: HashMap<u64, HashMap<u16, MoveStats>>,
+    pub games: u32,
+    pub rejected_games: u32,
+}
+
+/// Constructs a new, empty BookBuilder
+pub fn new() -> BookBuilder {
+    BookBuilder {
+        positions: HashMap::new(),
+        games: 0,
+        rejected_games: 0,
+    }
+}
+
+impl BookBuilder {
+
+    /// Replay a game, recording each move played before `max_ply`
+    ///
+    /// Returns false (keeping the moves recorded before the problem) if the starting FEN or a
+    /// move can't be understood.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let games = chessica::book::pgn::parse_pgn("1. e4 e5 2. Nf3 1-0");
+    /// let mut builder = chessica::book::builder::new();
+    /// assert!(builder.add_game(&games[0], 2));
+    /// assert_eq!(builder.positions.len(),2);
+    /// ```
+    pub fn add_game(&mut self, game: &PgnGame, max_ply: u16) -> bool {
+        let fen = game.fen.clone().unwrap_or(String::from("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"));
+        let mut position = match reset::fen::from_fen(&fen) {
+            Ok(position) => position,
+            Err(_) => {
+                self.rejected_games += 1;
+                return false;
+            },
+        };
+        self.games += 1;
+        for (ply, san) in game.moves.iter().enumerate() {
+            if ply >= usize::from(max_ply) {
+                break;
+            }
+            let mut child = match position.child_from_san(san) {
+                Some(child) => child,
+                None => {
+                    self.rejected_games += 1;
+                    return false;
+                },
+            };
+            let key = position.polyglot_key();
+            let raw_move = position.polyglot_move(&child);
+            let white_to_move = position.white_to_move();
+            let uci_move = child.uci_move_text();
+            let stats = self.positions.entry(key).or_default().entry(raw_move).or_insert(MoveStats {
+                uci_move,
+                white_to_move,
+                games: 0,
+                white_wins: 0,
+                draws: 0,
+                black_wins: 0,
+            });
+            stats.games += 1;
+            match game.result {
+                GameResult::WhiteWins => stats.white_wins += 1,
+                GameResult::BlackWins => stats.black_wins += 1,
+                GameResult::Draw => stats.draws += 1,
+                GameResult::Unknown => {},
+            }
+            position = child;
+        }
+        true
+    }
+
+    /// Moves passing the occurrence and score filters, sorted by key and then by weight
+    pub fn filtered(&self, min_occurrences: u32, min_score: u8) -> Vec<(u64, u16, &MoveStats)> {
+        let mut moves = Vec::new();
+        for (key, position) in self.positions.iter() {
+            for (raw_move, stats) in position.iter() {
+                if stats.games >= min_occurrences && stats.score() >= min_score {
+                    moves.push((*key, *raw_move, stats));
+                }
+            }
+        }
+        moves.sort_by(|a, b| a.0.cmp(&b.0).then(b.2.weight().cmp(&a.2.weight())).then(a.1.cmp(&b.1)));
+        moves
+    }
+
+    /// Polyglot `.bin` contents for the moves passing the filters
+    pub fn to_polyglot(&self, min_occurrences: u32, min_score: u8) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        for (key, raw_move, stats) in self.filtered(min_occurrences, min_score) {
+            let entry = BookEntry { key, raw_move, weight: stats.weight(), learn: 0 };
+            bytes.extend_from_slice(&entry.to_bytes());
+        }
+        bytes
+    }
+
+    /// Native text contents for the moves passing the filters
+    ///
+    /// Each line holds the Polyglot key in hex, the move in UCI notation, then the number of
+    /// games, white wins, draws and black wins.
+    pub fn to_native(&self, min_occurrences: u32, min_score: u8) -> String {
+        let mut text = String::from("# chessica book: key move games white_wins draws black_wins\n");
+        for (key, _, stats) in self.filtered(min_occurrences, min_score) {
+            text.push_str(&format!("{:016x} {} {} {} {} {}\n",key,stats.uci_move,stats.games,stats.white_wins,stats.draws,stats.black_wins));
+        }
+        text
+    }
+
+}
+
+/// Read the PGN files, build the book and write it, returning the number of entries written
+pub fn build_book(options: &BookBuildOptions) -> std::io::Result<usize> {
+    use std::fs;
+    let mut builder = new();
+    for path in options.pgn_files.iter() {
+        let text = String::from_utf8_lossy(&fs::read(path)?).to_string();
+        for game in crate::book::pgn::parse_pgn(&text).iter() {
+            builder.add_game(game, options.max_ply);
+        }
+    }
+    println!("Games read == {}  Rejected == {}  Positions == {}",builder.games,builder.rejected_games,builder.positions.len());
+    let entries = builder.filtered(options.min_occurrences, options.min_score).len();
+    if let Some(output) = &options.output {
+        match options.format {
+            BookFormat::Polyglot => fs::write(output, builder.to_polyglot(options.min_occurrences, options.min_score))?,
+            BookFormat::Native => fs::write(output, builder.to_native(options.min_occurrences, options.min_score))?,
+        }
+    }
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::book;
+    use crate::book::builder;
+    use crate::book::pgn::parse_pgn;
+    use crate::reset;
+
+    const GAMES: &str = "[Result \"1-0\"]\n1. e4 e5 2. Nf3 1-0\n\
+                         [Result \"1-0\"]\n1. e4 c5 1-0\n\
+                         [Result \"1/2-1/2\"]\n1. e4 e5 1/2-1/2\n\
+                         [Result \"0-1\"]\n1. d4 d5 0-1\n\
+                         [Result \"1-0\"]\n1. d4 Nf6 1-0\n";
+
+    fn built() -> builder::BookBuilder {
+        let mut b = builder::new();
+        for game in parse_pgn(GAMES).iter() {
+            assert!(b.add_game(game, 2));
+        }
+        b
+    }
+
+    #[test]
+    fn builder_statistics() {
+        let b = built();
+        assert_eq!(b.games,5);
+        assert_eq!(b.rejected_games,0);
+        let mut r = reset::new();
+        r.init_from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1".to_string());
+        let start = &b.positions[&r.polyglot_key()];
+        assert_eq!(start.len(),2);
+        let e4 = &start[&((12 << 6) | 28)];
+        assert_eq!(e4.games,3);
+        assert_eq!(e4.white_wins,2);
+        assert_eq!(e4.draws,1);
+        assert_eq!(e4.score(),83);
+        assert_eq!(e4.weight(),5);
+        let d4 = &start[&((11 << 6) | 27)];
+        assert_eq!(d4.score(),50);
+        // Nf3 is past the ply limit
+        assert_eq!(b.positions.len(),3);
+    }
+
+    #[test]
+    fn builder_rejects_bad_games() {
+        let mut b = builder::new();
+        assert!(!b.add_game(&parse_pgn("1. e4 e4 1-0")[0], 10));
+        assert_eq!(b.rejected_games,1);
+        assert_eq!(b.positions.len(),1);
+    }
+
+    #[test]
+    fn builder_filters_and_formats() {
+        let b = built();
+        assert_eq!(b.filtered(1, 0).len(),6);
+        // e4 (3 games) and d4 (2 games) from the start, e5 (2 games) after e4
+        assert_eq!(b.filtered(2, 0).len(),3);
+        // Black's e5 scored 1/4, d4 scored 1/2 for white
+        assert_eq!(b.filtered(2, 50).len(),2);
+
+        let polyglot = book::from_bytes(&b.to_polyglot(2, 50)).unwrap();
+        assert_eq!(polyglot.entries.len(),2);
+        let mut r = reset::new();
+        r.init_from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1".to_string());
+        let mut moves = polyglot.book_moves(&mut r);
+        assert_eq!(moves.len(),2);
+        let best = moves.iter_mut().max_by_key(|(_, weight)| *weight).unwrap();
+        assert_eq!(best.0.uci_move_text(),"e2e4");
+
+        let native = b.to_native(3, 0);
+        let lines: Vec<&str> = native.lines().collect();
+        assert_eq!(lines.len(),2);
+        assert_eq!(lines[1],"463b96181691fc9c e2e4 3 2 1 0");
+    }
+}
@@ -0,0 +1,257 @@
+pub mod options;
+pub mod pgn;
+pub mod builder;
+
+use crate::reset;
+use crate::reset::Reset;
+use crate::utils::rng::Rng;
+use crate::book::options::BookSelection;
+use std::fs;
+use std::io;
+
+/// Size in bytes of one entry in a Polyglot `.bin` file
+pub const BOOK_ENTRY_SIZE: usize = 16;
+
+/// One Polyglot book entry
+///
+/// On disk each entry is 16 big-endian bytes: an 8 byte key, a 2 byte move, a 2 byte weight and
+/// a 4 byte learn value.  Entries are sorted by key.  See `Reset::polyglot_key` and
+/// `Reset::polyglot_move` for the key and move encodings.
+#[derive(PartialEq,Eq,Copy,Clone,Hash,Debug)]
+pub struct BookEntry {
+    pub key: u64,
+    pub raw_move: u16,
+    pub weight: u16,
+    pub learn: u32,
+}
+
+impl BookEntry {
+
+    /// Serialize the entry in Polyglot's big-endian layout
+    pub fn to_bytes(&self) -> [u8; BOOK_ENTRY_SIZE] {
+        let mut bytes = [0; BOOK_ENTRY_SIZE];
+        bytes[0..8].copy_from_slice(&self.key.to_be_bytes());
+        bytes[8..10].copy_from_slice(&self.raw_move.to_be_bytes());
+        bytes[10..12].copy_from_slice(&self.weight.to_be_bytes());
+        bytes[12..16].copy_from_slice(&self.learn.to_be_bytes());
+        bytes
+    }
+
+    /// Deserialize an entry from Polyglot's big-endian layout
+    pub fn from_bytes(bytes: &[u8; BOOK_ENTRY_SIZE]) -> BookEntry {
+        let mut key = [0; 8];
+        key.copy_from_slice(&bytes[0..8]);
+        let mut learn = [0; 4];
+        learn.copy_from_slice(&bytes[12..16]);
+        BookEntry {
+            key: u64::from_be_bytes(key),
+            raw_move: u16::from_be_bytes([bytes[8], bytes[9]]),
+            weight: u16::from_be_bytes([bytes[10], bytes[11]]),
+            learn: u32::from_be_bytes(learn),
+        }
+    }
+
+}
+
+/// An opening book held in memory, entries sorted by key
+pub struct Book {
+    pub entries: Vec<BookEntry>,
+}
+
+/// Constructs a new, empty Book
+///
+/// # Examples
+///
+/// ```
+/// let book = chessica::book::new();
+/// assert!(book.entries.is_empty());
+/// ```
+pub fn new() -> Book {
+    Book {
+        entries: Vec::new(),
+    }
+}
+
+/// Reads a Polyglot `.bin` book from disk
+pub fn from_file(path: &str) -> io::Result<Book> {
+    from_bytes(&fs::read(path)?)
+}
+
+/// Builds a Book from the contents of a Polyglot `.bin` file
+///
+/// Fails if the data is not a whole number of 16 byte entries.
+pub fn from_bytes(bytes: &[u8]) -> io::Result<Book> {
+    if bytes.len() % BOOK_ENTRY_SIZE != 0 {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "book size is not a multiple of 16 bytes"));
+    }
+    let mut book = new();
+    for chunk in bytes.chunks(BOOK_ENTRY_SIZE) {
+        let mut entry = [0; BOOK_ENTRY_SIZE];
+        entry.copy_from_slice(chunk);
+        book.entries.push(BookEntry::from_bytes(&entry));
+    }
+    // Polyglot books are already sorted, but don't rely on it for the binary search
+    book.entries.sort_by_key(|entry| entry.key);
+    Ok(book)
+}
+
+impl Book {
+
+    /// All entries for the given key
+    pub fn entries_for_key(&self, key: u64) -> &[BookEntry] {
+        let start = self.entries.partition_point(|entry| entry.key < key);
+        let end = self.entries.partition_point(|entry| entry.key <= key);
+        &self.entries[start..end]
+    }
+
+    /// Legal moves the book offers for this position, as child Resets paired with their weights
+    ///
+    /// Entries whose move doesn't match any legal move (a corrupt book, or a key collision) are
+    /// ignored.
+    pub fn book_moves(&self, reset: &mut Reset) -> Vec<(Reset, u16)> {
+        let entries = self.entries_for_key(reset.polyglot_key());
+        let mut moves = Vec::new();
+        if entries.is_empty() {
+            return moves;
+        }
+        reset.restart_move_generation();
+        let mut child = reset::new();
+        while reset.generate_next_move(&mut child) {
+            let raw_move = reset.polyglot_move(&child);
+            if let Some(entry) = entries.iter().find(|entry| entry.raw_move == raw_move) {
+                moves.push((reset::clone::clone_from(&mut child), entry.weight));
+            }
+        }
+        moves
+    }
+
+    /// Pick a book move for this position, if the book has one
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chessica::book::BookEntry;
+    /// use chessica::book::options::BookSelection;
+    /// let mut r = chessica::reset::new();
+    /// r.init_from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1".to_string());
+    /// let mut book = chessica::book::new();
+    /// // e2e4: from e2 (12) to e4 (28)
+    /// book.entries.push(BookEntry { key: r.polyglot_key(), raw_move: (12 << 6) | 28, weight: 1, learn: 0 });
+    /// let mut rng = chessica::utils::rng::new(1);
+    /// let mut child = book.choose_move(&mut r, BookSelection::Best, &mut rng).unwrap();
+    /// assert_eq!(child.uci_move_text(),"e2e4");
+    /// ```
+    pub fn choose_move(&self, reset: &mut Reset, selection: BookSelection, rng: &mut Rng) -> Option<Reset> {
+        let mut moves = self.book_moves(reset);
+        if moves.is_empty() {
+            return None;
+        }
+        let index = match selection {
+            BookSelection::Best => {
+                let mut best = 0;
+                for (i, (_, weight)) in moves.iter().enumerate() {
+                    if *weight > moves[best].1 {
+                        best = i;
+                    }
+                }
+                best
+            },
+            BookSelection::Weighted => {
+                let total: usize = moves.iter().map(|(_, weight)| usize::from(*weight)).sum();
+                if total == 0 {
+                    rng.below(moves.len())
+                } else {
+                    let mut pick = rng.below(total);
+                    let mut chosen = 0;
+                    for (i, (_, weight)) in moves.iter().enumerate() {
+                        if pick < usize::from(*weight) {
+                            chosen = i;
+                            break;
+                        }
+                        pick -= usize::from(*weight);
+                    }
+                    chosen
+                }
+            },
+        };
+        Some(moves.swap_remove(index).0)
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::book;
+    use crate::book::BookEntry;
+    use crate::book::options::BookSelection;
+    use crate::reset;
+    use crate::utils::rng;
+
+    const START: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+    fn start_book() -> Vec<u8> {
+        let mut r = reset::new();
+        r.init_from_fen(START.to_string());
+        let key = r.polyglot_key();
+        let mut bytes = Vec::new();
+        // d2d4 (11 -> 27), e2e4 (12 -> 28), and a move that isn't legal (e1e8)
+        for (raw_move, weight) in [((11 << 6) | 27, 10), ((12 << 6) | 28, 30), ((4 << 6) | 60, 100)].iter() {
+            let entry = BookEntry { key, raw_move: *raw_move, weight: *weight, learn: 0 };
+            bytes.extend_from_slice(&entry.to_bytes());
+        }
+        // An entry for some other position
+        bytes.extend_from_slice(&BookEntry { key: 1, raw_move: 0, weight: 5, learn: 0 }.to_bytes());
+        bytes
+    }
+
+    #[test]
+    fn book_entry_round_trip() {
+        let entry = BookEntry { key: 0x463b96181691fc9c, raw_move: 0x031c, weight: 0x1234, learn: 0xdeadbeef };
+        let bytes = entry.to_bytes();
+        assert_eq!(bytes[0],0x46);
+        assert_eq!(bytes[8],0x03);
+        assert_eq!(bytes[9],0x1c);
+        assert_eq!(BookEntry::from_bytes(&bytes),entry);
+    }
+
+    #[test]
+    fn book_read_and_match() {
+        assert!(book::from_bytes(&[0; 15]).is_err());
+        let b = book::from_bytes(&start_book()).unwrap();
+        assert_eq!(b.entries.len(),4);
+        assert_eq!(b.entries[0].key,1);
+
+        let mut r = reset::new();
+        r.init_from_fen(START.to_string());
+        let mut moves = b.book_moves(&mut r);
+        let texts: Vec<String> = moves.iter_mut().map(|(child, _)| child.uci_move_text()).collect();
+        assert_eq!(texts.len(),2);
+        assert!(texts.contains(&"d2d4".to_string()));
+        assert!(texts.contains(&"e2e4".to_string()));
+
+        let mut r = reset::new();
+        r.init_from_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1".to_string());
+        assert!(b.book_moves(&mut r).is_empty());
+    }
+
+    #[test]
+    fn book_selection() {
+        let b = book::from_bytes(&start_book()).unwrap();
+        let mut r = reset::new();
+        r.init_from_fen(START.to_string());
+        let mut random = rng::new(7);
+        for _ in 0..10 {
+            let mut child = b.choose_move(&mut r, BookSelection::Best, &mut random).unwrap();
+            assert_eq!(child.uci_move_text(),"e2e4");
+        }
+        let mut e4 = 0;
+        for _ in 0..400 {
+            let mut child = b.choose_move(&mut r, BookSelection::Weighted, &mut random).unwrap();
+            if child.uci_move_text() == "e2e4" {
+                e4 += 1;
+            }
+        }
+        // e2e4 carries three quarters of the weight
+        assert!(e4 > 240 && e4 < 360,"{}",e4);
+    }
+}
@@ -0,0 +1,40 @@
+/// How a move is picked when the book offers several for the same position
+#[derive(PartialEq,Eq,Copy,Clone,Hash,Debug)]
+pub enum BookSelection {
+    /// Choose at random, in proportion to each entry's weight
+    Weighted,
+    /// Always play the entry with the highest weight
+    Best,
+}
+
+/// Settings for the opening book
+///
+/// | field     | description |
+/// | --------- | ----------- |
+/// | path      | Polyglot `.bin` file to read, or `None` to play without a book |
+/// | max_depth | Only consult the book while fewer than this many plies have been played |
+/// | selection | Weighted random or best-only move selection |
+#[derive(PartialEq,Eq,Clone,Hash,Debug)]
+pub struct BookOptions {
+    pub path: Option<String>,
+    pub max_depth: u16,
+    pub selection: BookSelection,
+}
+
+/// Constructs a new set of BookOptions
+///
+/// # Examples
+///
+/// ```
+/// use chessica::book::options::BookSelection;
+/// let options = chessica::book::options::new();
+/// assert_eq!(options.path,None);
+/// assert_eq!(options.selection,BookSelection::Weighted);
+/// ```
+pub fn new() -> BookOptions {
+    BookOptions {
+        path: None,
+        max_depth: 40,
+        selection: BookSelection::Weighted,
+    }
+}
@@ -0,0 +1,187 @@
+/// Outcome of a game, as recorded in its PGN
+#[derive(PartialEq,Eq,Copy,Clone,Hash,Debug)]
+pub enum GameResult {
+    WhiteWins,
+    BlackWins,
+    Draw,
+    Unknown,
+}
+
+/// One game read from a PGN file: its result, starting position and moves in SAN
+#[derive(PartialEq,Eq,Clone,Hash,Debug)]
+pub struct PgnGame {
+    pub result: GameResult,
+    pub fen: Option<String>,
+    pub moves: Vec<String>,
+}
+
+fn result_from_text(text: &str) -> Option<GameResult> {
+    match text {
+        "1-0" => Some(GameResult::WhiteWins),
+        "0-1" => Some(GameResult::BlackWins),
+        "1/2-1/2" => Some(GameResult::Draw),
+        "*" => Some(GameResult::Unknown),
+        _ => None,
+    }
+}
+
+fn new_game() -> PgnGame {
+    PgnGame {
+        result: GameResult::Unknown,
+        fen: None,
+        moves: Vec::new(),
+    }
+}
+
+/// Splits the text of a PGN file into games
+///
+/// Tag pairs other than `Result` and `FEN` are ignored, as are comments, variations, NAGs and
+/// move numbers.  A game ends at its termination marker (`1-0`, `0-1`, `1/2-1/2` or `*`), or at
+/// the tag section of the next game.
+///
+/// # Examples
+///
+/// ```
+/// use chessica::book::pgn::GameResult;
+/// let games = chessica::book::pgn::parse_pgn("[Result \"1-0\"]\n\n1. e4 {best by test} e5 (1... c5) 2. Nf3 $1 1-0");
+/// assert_eq!(games.len(),1);
+/// assert_eq!(games[0].result,GameResult::WhiteWins);
+/// assert_eq!(games[0].moves,vec!["e4","e5","Nf3"]);
+/// ```
+pub fn parse_pgn(text: &str) -> Vec<PgnGame> {
+    let mut games = Vec::new();
+    let mut game = new_game();
+    let mut in_movetext = false;
+    let mut chars = text.chars().peekable();
+    let mut token = String::new();
+
+    // Pushes the pending movetext token, returning true if it ended the game
+    fn finish_token(token: &mut String, game: &mut PgnGame) -> bool {
+        let word = std::mem::take(token);
+        if word.is_empty() {
+            return false;
+        }
+        if let Some(result) = result_from_text(&word) {
+            game.result = result;
+            return true;
+        }
+        // Move numbers ("12." / "12...") may be glued to the move ("12.e4")
+        let word = word.trim_start_matches(|c: char| c.is_ascii_digit() || c == '.');
+        if !word.is_empty() && !word.starts_with('$') {
+            game.moves.push(word.to_string());
+        }
+        false
+    }
+
+    while let Some(c) = chars.next() {
+        match c {
+            '[' if !in_movetext || token.is_empty() => {
+                if in_movetext {
+                    // A new game started without a termination marker
+                    games.push(std::mem::replace(&mut game, new_game()));
+                    in_movetext = false;
+                }
+                let mut tag = String::new();
+                for t in chars.by_ref() {
+                    if t == ']' {
+                        break;
+                    }
+                    tag.push(t);
+                }
+                let mut parts = tag.splitn(2, ' ');
+                let name = parts.next().unwrap_or("");
+                let value = parts.next().unwrap_or("").trim().trim_matches('"').to_string();
+                match name {
+                    "Result" => {
+                        if let Some(result) = result_from_text(&value) {
+                            game.result = result;
+                        }
+                    },
+                    "FEN" => game.fen = Some(value),
+                    _ => {},
+                }
+            },
+            '{' => {
+                for t in chars.by_ref() {
+                    if t == '}' {
+                        break;
+                    }
+                }
+            },
+            ';' => {
+                for t in chars.by_ref() {
+                    if t == '\n' {
+                        break;
+                    }
+                }
+            },
+            '(' => {
+                let mut depth = 1;
+                while depth > 0 {
+                    match chars.next() {
+                        Some('(') => depth += 1,
+                        Some(')') => depth -= 1,
+                        Some('{') => {
+                            for t in chars.by_ref() {
+                                if t == '}' {
+                                    break;
+                                }
+                            }
+                        },
+                        Some(_) => {},
+                        None => break,
+                    }
+                }
+            },
+            _ if c.is_whitespace() => {
+                if finish_token(&mut token, &mut game) {
+                    games.push(std::mem::replace(&mut game, new_game()));
+                    in_movetext = false;
+                }
+            },
+            _ => {
+                in_movetext = true;
+                token.push(c);
+            },
+        }
+    }
+    let ended = finish_token(&mut token, &mut game);
+    if ended || !game.moves.is_empty() {
+        games.push(game);
+    }
+    games
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::book::pgn::parse_pgn;
+    use crate::book::pgn::GameResult;
+
+    #[test]
+    fn pgn_several_games() {
+        let text = "[Event \"Test\"]\n[Result \"0-1\"]\n\n1.f3 e5 2.g4?? Qh4# 0-1\n\n\
+                    [Event \"Test\"]\n[FEN \"4k3/8/8/8/8/8/8/4K2R w K - 0 1\"]\n[Result \"1/2-1/2\"]\n\n\
+                    1. O-O Kd7 ; resigns?\n2. Rd1+ (2. Kh1) 1/2-1/2\n\n\
+                    1. d4 d5 *\n";
+        let games = parse_pgn(text);
+        assert_eq!(games.len(),3);
+        assert_eq!(games[0].result,GameResult::BlackWins);
+        assert_eq!(games[0].moves,vec!["f3","e5","g4??","Qh4#"]);
+        assert_eq!(games[0].fen,None);
+        assert_eq!(games[1].result,GameResult::Draw);
+        assert_eq!(games[1].fen,Some("4k3/8/8/8/8/8/8/4K2R w K - 0 1".to_string()));
+        assert_eq!(games[1].moves,vec!["O-O","Kd7","Rd1+"]);
+        assert_eq!(games[2].result,GameResult::Unknown);
+        assert_eq!(games[2].moves,vec!["d4","d5"]);
+    }
+
+    #[test]
+    fn pgn_missing_termination() {
+        let games = parse_pgn("[Result \"1-0\"]\n1. e4 e5\n[Result \"0-1\"]\n1. d4 {x} 1... Nf6 2. c4");
+        assert_eq!(games.len(),2);
+        assert_eq!(games[0].moves,vec!["e4","e5"]);
+        assert_eq!(games[0].result,GameResult::WhiteWins);
+        assert_eq!(games[1].moves,vec!["d4","Nf6","c4"]);
+        assert_eq!(games[1].result,GameResult::BlackWins);
+    }
+}
@@ -2,8 +2,12 @@ use std::sync::{Arc, Barrier, Mutex};
 use std::sync::atomic::{AtomicI32,AtomicBool,Ordering};
 use crate::tree;
 use tree::Tree;
+use tree::search_options::SearchOptions;
+use tree::ordering::MoveOrdering;
 use crate::reset::r#const::SCORE_MIN;
 use crate::reset::r#const::SCORE_MAX;
+use crate::operator::CommunicationProtocol;
+use crate::operator::score::search_report;
 
 
 /// Data necessary for the Cogitator functionality to run successfully
@@ -19,8 +23,14 @@ pub struct Cogitator {
     pub global_max: Arc<AtomicI32>,
     pub white_move: bool,
     pub children: Vec<Arc<Mutex<Tree>>>,
+    pub search_options: SearchOptions,
+    pub ordering: MoveOrdering,
     pub red_light: Arc<AtomicBool>,
     pub exit_signal: Arc<AtomicBool>,
+    /// Nodes searched by this Cogitator so far
+    pub node_count: u64,
+    /// How the best move of each finished iteration is reported
+    pub communication_protocol: CommunicationProtocol,
 }
 
 
@@ -30,7 +40,7 @@ pub struct Cogitator {
 ///
 /// ```
 /// use chessica::cogitator::Cogitator;
-
+///
 /// //let mut my_cogitator = chessica::cogitator::new();
 /// ```
 pub fn new(
@@ -49,8 +59,12 @@ pub fn new(
         global_max,
         white_move,
         children: vec![],
+        search_options: tree::search_options::new(),
+        ordering: tree::ordering::new(),
         red_light,
         exit_signal,
+        node_count: 0,
+        communication_protocol: CommunicationProtocol::ChessEngineCommunicationProtocol,
     }
 }
 
@@ -62,31 +76,53 @@ impl Cogitator {
         self.children = child_list;
     }
 
+    /// Set the selectivity options used by this Cogitator's searches
+    pub fn set_search_options(&mut self, search_options: SearchOptions) {
+        self.search_options = search_options;
+    }
+
+    /// Set the protocol the Cogitator reports its search results in
+    pub fn set_communication_protocol(&mut self, communication_protocol: CommunicationProtocol) {
+        self.communication_protocol = communication_protocol;
+    }
+
     /// Run Chessica's Cogitator
     pub fn run(&mut self) {
         for i in 3..9 {
             self.search(i);
+            self.ordering.age();
             self.barrier.wait();
             if self.id == 0 && self.search_got_far_enough() {
                 self.sort_children();
                 self.children[0].lock().unwrap().print();
+                println!("{}",self.iteration_report(i));
                 self.prep_for_next_iteration();
             }
             self.barrier.wait();
         }
     }
 
+    /// Report the best move found by the iteration searched to `depth`
+    pub fn iteration_report(&mut self, depth: u8) -> String {
+        let mut best = self.children[0].lock().unwrap();
+        let score = best.score.unwrap_or(0);
+        search_report(self.communication_protocol, depth, score, self.white_move, self.node_count, &best.reset.uci_move_text())
+    }
+
     pub fn search(&mut self, depth: u8) {
 
         let mut locked_trees = Vec::new();
         for tree in &mut self.children {
             if let Ok(mut tree) = tree.try_lock() {
                 let mut move_count: u64 = 0;
+                // The trees handed to a Cogitator are the root's children, one ply below the root
                 let (success, score) = tree.alpha_beta_promote_prune_parallel(
-                    0,
-                    depth,
+                    1,
+                    depth + 1,
                     &(self.global_min),
                     &(self.global_max),
+                    &(self.search_options),
+                    &mut self.ordering,
                     &(self.red_light),
                     &mut move_count
                 );
@@ -104,6 +140,7 @@ impl Cogitator {
                         }
                     }
                 }
+                self.node_count += move_count;
                 println!("# Move = {}, Depth = {}, Thread = {}, Score == {} [{}] {}",tree.reset.move_text(),depth,self.id,score,move_count,success);
                 locked_trees.push(tree);
             }
@@ -326,4 +363,29 @@ mod tests {
         assert_eq!(c.children[3].lock().unwrap().score,None);
     }
 
+    #[test]
+    fn cogitator_deterministic_search() {
+        // A single Cogitator over the same children finds the same scores with the same nodes
+        let run = || {
+            let mut root = tree::from_fen("r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3".to_string());
+            let mut children = Vec::new();
+            let mut child = tree::new();
+            while root.get_next_child(&mut child) {
+                children.push(Arc::new(Mutex::new(child)));
+                child = tree::new();
+            }
+            let mut c = prep_cogitator();
+            c.global_min.store(crate::reset::r#const::SCORE_MAX, std::sync::atomic::Ordering::SeqCst);
+            c.global_max.store(crate::reset::r#const::SCORE_MIN, std::sync::atomic::Ordering::SeqCst);
+            c.set_child_list(children);
+            c.search(2);
+            let scores: Vec<Option<i32>> = c.children.iter().map(|child| child.lock().unwrap().score).collect();
+            (scores, c.node_count)
+        };
+        let (scores, nodes) = run();
+        assert!(nodes > 0);
+        assert!(scores.iter().any(|score| score.is_some()));
+        assert_eq!(run(),(scores,nodes));
+    }
+
 }
@@ -0,0 +1,253 @@
+use crate::reset;
+use crate::reset::Reset;
+
+/// Pawn squares a2-d7; pawns on the e-h files are mirrored onto a-d
+const PAWN_SQUARES: usize = 24;
+const POSITIONS: usize = PAWN_SQUARES * 64 * 64 * 2;
+
+#[derive(PartialEq,Eq,Copy,Clone,Debug)]
+enum Outcome {
+    Unknown,
+    Draw,
+    Win,
+}
+
+lazy_static! {
+    /// One bit per position: set when the side with the pawn wins
+    static ref KPK: Vec<u64> = generate();
+}
+
+/// Build the bitbase now rather than on the first probe
+pub fn init() {
+    lazy_static::initialize(&KPK);
+}
+
+/// Squares count a1 = 0, b1 = 1 ... h8 = 63
+fn distance(s1: usize, s2: usize) -> usize {
+    let files = ((s1 & 7) as i32 - (s2 & 7) as i32).unsigned_abs();
+    let ranks = ((s1 >> 3) as i32 - (s2 >> 3) as i32).unsigned_abs();
+    files.max(ranks) as usize
+}
+
+/// Position index, or `None` for a pawn on the first or last rank
+fn index(white_king: usize, pawn: usize, black_king: usize, white_to_move: bool) -> Option<usize> {
+    let (white_king, pawn, black_king) = if pawn & 7 > 3 {
+        (white_king ^ 7, pawn ^ 7, black_king ^ 7)
+    } else {
+        (white_king, pawn, black_king)
+    };
+    let rank = pawn >> 3;
+    if !(1..=6).contains(&rank) {
+        return None;
+    }
+    let pawn_index = (rank - 1) * 4 + (pawn & 7);
+    Some(((pawn_index * 64 + white_king) * 64 + black_king) * 2 + white_to_move as usize)
+}
+
+/// Can the position be reached with this side to move?
+fn is_legal(white_king: usize, pawn: usize, black_king: usize, white_to_move: bool) -> bool {
+    if white_king == pawn || black_king == pawn || distance(white_king, black_king) <= 1 {
+        return false;
+    }
+    // Black can't be in check with white to move
+    let pawn_attacks_black_king = (black_king == pawn + 9 && pawn & 7 != 7) || (black_king == pawn + 7 && pawn & 7 != 0);
+    !(white_to_move && pawn_attacks_black_king)
+}
+
+fn fen(white_king: usize, pawn: usize, black_king: usize, white_to_move: bool) -> String {
+    let mut placement = String::new();
+    for rank in (0..8).rev() {
+        let mut empty = 0;
+        for file in 0..8 {
+            let square = rank * 8 + file;
+            let piece = if square == white_king {
+                'K'
+            } else if square == pawn {
+                'P'
+            } else if square == black_king {
+                'k'
+            } else {
+                empty += 1;
+                continue;
+            };
+            if empty > 0 {
+                placement.push_str(&empty.to_string());
+                empty = 0;
+            }
+            placement.push(piece);
+        }
+        if empty > 0 {
+            placement.push_str(&empty.to_string());
+        }
+        if rank > 0 {
+            placement.push('/');
+        }
+    }
+    format!("{} {} - - 0 1",placement,if white_to_move { "w" } else { "b" })
+}
+
+/// Outcome of a promotion, with black to move: a queen or rook wins unless black can take it or
+/// is stalemated
+fn promotion_outcome(child: &mut Reset) -> Outcome {
+    let promoted = child.tablebase_pieces().iter().any(|(_, code)| *code == 4 || *code == 5);
+    if !promoted {
+        return Outcome::Draw;
+    }
+    let mut reply = reset::new();
+    child.restart_move_generation();
+    let mut moves = 0;
+    while child.generate_next_move(&mut reply) {
+        if reply.is_capture() {
+            return Outcome::Draw;
+        }
+        moves += 1;
+    }
+    if moves == 0 && !child.in_check() {
+        Outcome::Draw
+    } else {
+        Outcome::Win
+    }
+}
+
+/// Retrograde analysis over every position, starting from the ones decided in a single move:
+/// promotions, captures of the pawn, checkmates and stalemates
+fn generate() -> Vec<u64> {
+    let mut outcomes = vec![Outcome::Unknown; POSITIONS];
+    let mut successors: Vec<Vec<u32>> = vec![Vec::new(); POSITIONS];
+    let mut child = reset::new();
+
+    for pawn_index in 0..PAWN_SQUARES {
+        let pawn = (pawn_index / 4 + 1) * 8 + pawn_index % 4;
+        for white_king in 0..64 {
+            for black_king in 0..64 {
+                for white_to_move in [false, true] {
+                    let position = index(white_king, pawn, black_king, white_to_move).unwrap();
+                    if !is_legal(white_king, pawn, black_king, white_to_move) {
+                        outcomes[position] = Outcome::Draw;
+                        continue;
+                    }
+                    let mut r = reset::new();
+                    r.init_from_fen(fen(white_king, pawn, black_king, white_to_move));
+                    r.restart_move_generation();
+                    let mut moves = 0;
+                    let mut decided = None;
+                    while r.generate_next_move(&mut child) {
+                        moves += 1;
+                        let pieces = child.tablebase_pieces();
+                        let find = |code: u8| pieces.iter().find(|(_, c)| *c == code).map(|(square, _)| *square);
+                        let outcome = match (find(6), find(1), find(14)) {
+                            (Some(wk), Some(p), Some(bk)) => {
+                                successors[position].push(index(wk, p, bk, !white_to_move).unwrap() as u32);
+                                continue;
+                            },
+                            (_, None, _) if pieces.len() == 2 => Outcome::Draw,
+                            _ => promotion_outcome(&mut child),
+                        };
+                        // White needs one winning move; black needs one drawing move
+                        if (outcome == Outcome::Win) == white_to_move {
+                            decided = Some(outcome);
+                            break;
+                        }
+                    }
+                    outcomes[position] = match decided {
+                        Some(outcome) => outcome,
+                        None if moves == 0 => if r.in_check() { Outcome::Win } else { Outcome::Draw },
+                        None if successors[position].is_empty() => if white_to_move { Outcome::Draw } else { Outcome::Win },
+                        None => Outcome::Unknown,
+                    };
+                }
+            }
+        }
+    }
+
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for position in 0..POSITIONS {
+            if outcomes[position] != Outcome::Unknown {
+                continue;
+            }
+            let white_to_move = position & 1 == 1;
+            let (good, bad) = if white_to_move { (Outcome::Win, Outcome::Draw) } else { (Outcome::Draw, Outcome::Win) };
+            let next = &successors[position];
+            if next.iter().any(|s| outcomes[*s as usize] == good) {
+                outcomes[position] = good;
+                changed = true;
+            } else if next.iter().all(|s| outcomes[*s as usize] == bad) {
+                outcomes[position] = bad;
+                changed = true;
+            }
+        }
+    }
+
+    // Anything still undecided can't be forced, so it's a draw
+    let mut bits = vec![0u64; (POSITIONS + 63) / 64];
+    for (position, outcome) in outcomes.iter().enumerate() {
+        if *outcome == Outcome::Win {
+            bits[position / 64] |= 1 << (position % 64);
+        }
+    }
+    bits
+}
+
+/// Does the side with the pawn win?  Squares count a1 = 0 ... h8 = 63, with the pawn moving up
+/// the board.
+///
+/// # Examples
+///
+/// ```
+/// use chessica::endgame::kpk;
+/// // White: Ke5, Pe4.  Black: Ke7.  Whoever has the move loses the opposition.
+/// assert!(!kpk::probe(36, 28, 52, true));
+/// assert!(kpk::probe(36, 28, 52, false));
+/// ```
+pub fn probe(white_king: usize, pawn: usize, black_king: usize, white_to_move: bool) -> bool {
+    match index(white_king, pawn, black_king, white_to_move) {
+        Some(position) => KPK[position / 64] & (1 << (position % 64)) != 0,
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::endgame::kpk;
+
+    fn square(name: &str) -> usize {
+        let bytes = name.as_bytes();
+        (bytes[1] - b'1') as usize * 8 + (bytes[0] - b'a') as usize
+    }
+
+    fn probe(white_king: &str, pawn: &str, black_king: &str, white_to_move: bool) -> bool {
+        kpk::probe(square(white_king), square(pawn), square(black_king), white_to_move)
+    }
+
+    #[test]
+    fn kpk_known_positions() {
+        // King on the sixth rank in front of its pawn wins whoever is to move
+        assert!(probe("e6","e5","e8",true));
+        assert!(probe("e6","e5","e8",false));
+        // The defender holds the opposition
+        assert!(!probe("e5","e4","e7",true));
+        assert!(probe("e5","e4","e7",false));
+        // Mirrored onto the king side
+        assert!(!probe("d5","d4","d7",true));
+        assert!(probe("d5","d4","d7",false));
+        // Rook pawn with the defender in the corner
+        assert!(!probe("a1","a2","a8",true));
+        assert!(!probe("b6","a6","a8",true));
+        // Pawn on the seventh runs in, unless it can be taken
+        assert!(probe("e1","e7","a2",true));
+        assert!(probe("e1","e7","a2",false));
+        assert!(!probe("e1","e7","d7",false));
+        // Outside the square of the pawn
+        assert!(probe("h1","b4","h5",false));
+        assert!(!probe("h1","b4","e5",false));
+    }
+
+    #[test]
+    fn kpk_illegal_positions() {
+        assert!(!probe("e1","e1","e8",true));
+        assert!(!probe("e4","e2","e5",true));
+        assert!(!kpk::probe(4, 60, 0, true));
+    }
+}
@@ -0,0 +1,200 @@
+pub mod kpk;
+
+use crate::eval;
+use crate::eval::EvalParams;
+use crate::reset::Reset;
+use crate::reset::r#const::SCORE_KNOWN_WIN;
+use crate::reset::r#const::SCORE_PAWN;
+
+/// Hundredth of a pawn, for the small bonuses that guide a won ending
+const CENTIPAWN: i32 = SCORE_PAWN / 100;
+
+/// Material that can't be won against any defence: (stronger side, weaker side)
+const DRAWN: [(&str, &str); 8] = [
+    ("K", "K"),
+    ("KN", "K"),
+    ("KB", "K"),
+    ("KNN", "K"),
+    ("KN", "KN"),
+    ("KB", "KB"),
+    ("KB", "KN"),
+    ("KN", "KB"),
+];
+
+/// Material that is usually drawn even though one side is ahead
+const DRAWISH: [(&str, &str); 4] = [
+    ("KR", "KB"),
+    ("KR", "KN"),
+    ("KRB", "KR"),
+    ("KRN", "KR"),
+];
+
+/// Value in pawns of a side's pieces, written as in a material signature ("KRP")
+fn side_material(side: &str) -> i32 {
+    side.chars().map(|letter| match letter {
+        'Q' => 9,
+        'R' => 5,
+        'B' | 'N' => 3,
+        'P' => 1,
+        _ => 0,
+    }).sum()
+}
+
+/// Squares count a1 = 0, b1 = 1 ... h8 = 63
+fn file_of(square: usize) -> i32 {
+    (square & 7) as i32
+}
+
+fn rank_of(square: usize) -> i32 {
+    (square >> 3) as i32
+}
+
+fn distance(s1: usize, s2: usize) -> i32 {
+    (file_of(s1) - file_of(s2)).abs().max((rank_of(s1) - rank_of(s2)).abs())
+}
+
+/// Bonus for the losing king being near the edge of the board
+fn push_to_edge(square: usize) -> i32 {
+    let file_distance = file_of(square).min(7 - file_of(square));
+    let rank_distance = rank_of(square).min(7 - rank_of(square));
+    90 - (7 * file_distance * file_distance / 2 + 7 * rank_distance * rank_distance / 2)
+}
+
+/// Bonus for the kings being close together
+fn push_close(s1: usize, s2: usize) -> i32 {
+    140 - 20 * distance(s1, s2)
+}
+
+/// Bonus for a king being near the a1 or h8 corner
+fn push_to_corner(square: usize) -> i32 {
+    (7 - rank_of(square) - file_of(square)).abs()
+}
+
+/// Score for a board with specialized endgame knowledge, from white's point of view
+///
+/// The material signature picks the evaluation: the KPK bitbase, driving the lone king to the
+/// edge in KQK and KRK (and to the bishop's corner in KBNK), and drawn or drawish material.
+/// Returns `None` for anything else, which is left to the general evaluation.
+///
+/// # Examples
+///
+/// ```
+/// let mut r = chessica::reset::new();
+/// r.init_from_fen("8/8/8/4k3/8/8/8/KN6 w - - 0 1".to_string());
+/// assert_eq!(chessica::endgame::evaluate(&r),Some(0));
+/// let mut r = chessica::reset::new();
+/// r.init_from_fen("8/8/8/4k3/8/8/8/KQ6 w - - 0 1".to_string());
+/// assert!(chessica::endgame::evaluate(&r).unwrap() > 9 * chessica::reset::r#const::SCORE_PAWN);
+/// let mut r = chessica::reset::new();
+/// r.init_from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1".to_string());
+/// assert_eq!(chessica::endgame::evaluate(&r),None);
+/// ```
+pub fn evaluate(reset: &Reset) -> Option<i32> {
+    evaluate_with(reset, &eval::params())
+}
+
+/// `evaluate` with the bonuses taken from `params` rather than the shared parameters
+pub fn evaluate_with(reset: &Reset, params: &EvalParams) -> Option<i32> {
+    if reset.piece_count() > 5 {
+        return None;
+    }
+    let signature = reset.material_signature();
+    let (white, black) = signature.split_once('v')?;
+    let white_stronger = side_material(white) >= side_material(black);
+    let (strong, weak) = if white_stronger { (white, black) } else { (black, white) };
+
+    // Look at the board from the stronger side, as white
+    let mut pieces = reset.tablebase_pieces();
+    if !white_stronger {
+        for (square, code) in pieces.iter_mut() {
+            *square ^= 56;
+            *code ^= 8;
+        }
+    }
+    let strong_to_move = reset.white_to_move() == white_stronger;
+    let find = |code: u8| pieces.iter().find(|(_, c)| *c == code).map(|(square, _)| *square);
+    let strong_king = find(6)?;
+    let weak_king = find(14)?;
+
+    let score = match (strong, weak) {
+        ("KP", "K") => {
+            let pawn = find(1)?;
+            if kpk::probe(strong_king, pawn, weak_king, strong_to_move) {
+                SCORE_KNOWN_WIN + SCORE_PAWN + rank_of(pawn) * params.kpk_rank * CENTIPAWN
+            } else {
+                0
+            }
+        },
+        ("KQ", "K") | ("KR", "K") => {
+            SCORE_KNOWN_WIN + side_material(strong) * SCORE_PAWN
+                + (push_to_edge(weak_king) + push_close(strong_king, weak_king)) * CENTIPAWN
+        },
+        ("KBN", "K") => {
+            let bishop = find(3)?;
+            // a1 is a dark square, so a light-squared bishop mates in the a8 or h1 corner
+            let corner_square = if (file_of(bishop) + rank_of(bishop)) % 2 == 0 { weak_king } else { weak_king ^ 7 };
+            SCORE_KNOWN_WIN + side_material(strong) * SCORE_PAWN
+                + (push_close(strong_king, weak_king) + params.kbnk_corner * push_to_corner(corner_square)) * CENTIPAWN
+        },
+        _ if DRAWN.contains(&(strong, weak)) => 0,
+        _ if DRAWISH.contains(&(strong, weak)) => {
+            (side_material(strong) - side_material(weak)) * SCORE_PAWN / 8
+        },
+        _ => return None,
+    };
+    Some(if white_stronger { score } else { -score })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::endgame;
+    use crate::reset;
+    use crate::reset::r#const::SCORE_KNOWN_WIN;
+    use crate::reset::r#const::SCORE_PAWN;
+
+    fn evaluate(fen: &str) -> Option<i32> {
+        let mut r = reset::new();
+        r.init_from_fen(fen.to_string());
+        endgame::evaluate(&r)
+    }
+
+    #[test]
+    fn endgame_kpk() {
+        assert!(evaluate("8/4k3/8/4K3/4P3/8/8/8 b - - 0 1").unwrap() > SCORE_KNOWN_WIN);
+        assert_eq!(evaluate("8/4k3/8/4K3/4P3/8/8/8 w - - 0 1"),Some(0));
+        // Colours reversed
+        assert!(evaluate("8/8/8/4p3/4k3/8/4K3/8 w - - 0 1").unwrap() < -SCORE_KNOWN_WIN);
+        assert_eq!(evaluate("8/8/8/4p3/4k3/8/4K3/8 b - - 0 1"),Some(0));
+    }
+
+    #[test]
+    fn endgame_mating_material() {
+        // The lone king is worse off on the edge, and with the other king close by
+        let centre = evaluate("8/8/8/4k3/8/8/8/KQ6 w - - 0 1").unwrap();
+        let edge = evaluate("8/8/8/k7/8/8/8/KQ6 w - - 0 1").unwrap();
+        let edge_close = evaluate("8/8/8/k1K5/8/8/8/1Q6 w - - 0 1").unwrap();
+        assert!(centre > SCORE_KNOWN_WIN + 9 * SCORE_PAWN);
+        assert!(edge > centre);
+        assert!(edge_close > edge);
+        assert!(evaluate("8/8/8/4K3/8/8/8/kr6 w - - 0 1").unwrap() < -SCORE_KNOWN_WIN);
+
+        // Dark-squared bishop: a1 and h8 are the mating corners
+        let right_corner = evaluate("7k/8/5K2/8/8/8/8/2B1N3 w - - 0 1").unwrap();
+        let wrong_corner = evaluate("k7/8/2K5/8/8/8/8/2B1N3 w - - 0 1").unwrap();
+        assert!(right_corner > wrong_corner);
+        let right_corner = evaluate("k7/8/2K5/8/8/8/8/3BN3 w - - 0 1").unwrap();
+        let wrong_corner = evaluate("7k/8/5K2/8/8/8/8/3BN3 w - - 0 1").unwrap();
+        assert!(right_corner > wrong_corner);
+    }
+
+    #[test]
+    fn endgame_drawn_material() {
+        assert_eq!(evaluate("8/8/8/4k3/8/8/8/K7 w - - 0 1"),Some(0));
+        assert_eq!(evaluate("8/8/8/4k3/8/8/8/KNN5 w - - 0 1"),Some(0));
+        assert_eq!(evaluate("8/8/8/4kb2/8/8/8/KN6 w - - 0 1"),Some(0));
+        assert_eq!(evaluate("8/8/8/4kn2/8/8/8/KR6 b - - 0 1"),Some(2 * SCORE_PAWN / 8));
+        assert_eq!(evaluate("8/8/8/4kr2/8/8/8/KB6 b - - 0 1"),Some(-2 * SCORE_PAWN / 8));
+        assert_eq!(evaluate("8/8/8/4kr2/8/8/8/KR6 b - - 0 1"),None);
+        assert_eq!(evaluate("8/8/8/4k3/8/8/P7/KQ6 b - - 0 1"),None);
+    }
+}
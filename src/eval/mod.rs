@@ -0,0 +1,242 @@
+pub mod tune;
+pub mod trace;
+
+use std::fs;
+use std::io;
+use std::sync::RwLock;
+use std::cell::Cell;
+
+/// Weights of the evaluation terms, in centipawns
+///
+/// | field         | description |
+/// | ------------- | ----------- |
+/// | pawn          | Value of a pawn |
+/// | knight        | Value of a knight |
+/// | bishop        | Value of a bishop |
+/// | rook          | Value of a rook |
+/// | queen         | Value of a queen |
+/// | kpk_rank      | Bonus per rank of advance for a pawn winning KPK |
+/// | kbnk_corner   | Bonus per step the lone king is pushed toward the bishop's corner in KBNK |
+#[derive(PartialEq,Eq,Copy,Clone,Hash,Debug)]
+pub struct EvalParams {
+    pub pawn: i32,
+    pub knight: i32,
+    pub bishop: i32,
+    pub rook: i32,
+    pub queen: i32,
+    pub kpk_rank: i32,
+    pub kbnk_corner: i32,
+}
+
+/// Names of the parameters, as written in parameter files
+pub const NAMES: [&str; 7] = ["pawn", "knight", "bishop", "rook", "queen", "kpk_rank", "kbnk_corner"];
+
+/// Constructs the built-in EvalParams
+///
+/// # Examples
+///
+/// ```
+/// let params = chessica::eval::new();
+/// assert_eq!(params.pawn,100);
+/// assert_eq!(params.get(4),900);
+/// ```
+pub fn new() -> EvalParams {
+    EvalParams {
+        pawn: 100,
+        knight: 300,
+        bishop: 300,
+        rook: 500,
+        queen: 900,
+        kpk_rank: 10,
+        kbnk_corner: 42,
+    }
+}
+
+fn invalid(line_number: usize, message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("line {}: {}",line_number,message))
+}
+
+/// Read parameters from text: one `name value` pair per line, with `#` starting a comment
+///
+/// Parameters left out keep their built-in values.
+///
+/// # Examples
+///
+/// ```
+/// let params = chessica::eval::from_text("# lighter knights\nknight 290\n").unwrap();
+/// assert_eq!(params.knight,290);
+/// assert_eq!(params.bishop,300);
+/// assert!(chessica::eval::from_text("king 10000").is_err());
+/// ```
+pub fn from_text(text: &str) -> io::Result<EvalParams> {
+    let mut params = new();
+    for (line_index, line) in text.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or("").trim();
+        if line.is_empty() {
+            continue;
+        }
+        let mut fields = line.split_whitespace();
+        let name = fields.next().unwrap_or("");
+        let index = NAMES.iter().position(|n| *n == name)
+            .ok_or_else(|| invalid(line_index + 1, &format!("unknown parameter \"{}\"",name)))?;
+        let value = match (fields.next().map(|v| v.parse::<i32>()), fields.next()) {
+            (Some(Ok(value)), None) => value,
+            _ => return Err(invalid(line_index + 1, &format!("expected \"{} <integer>\"",name))),
+        };
+        params.set(index, value);
+    }
+    Ok(params)
+}
+
+/// Read parameters from a file written by `EvalParams::to_text` (or by hand)
+pub fn from_file(path: &str) -> io::Result<EvalParams> {
+    from_text(&String::from_utf8_lossy(&fs::read(path)?))
+}
+
+impl EvalParams {
+
+    /// Parameter by its position in `NAMES`
+    pub fn get(&self, index: usize) -> i32 {
+        match index {
+            0 => self.pawn,
+            1 => self.knight,
+            2 => self.bishop,
+            3 => self.rook,
+            4 => self.queen,
+            5 => self.kpk_rank,
+            _ => self.kbnk_corner,
+        }
+    }
+
+    /// Change a parameter by its position in `NAMES`
+    pub fn set(&mut self, index: usize, value: i32) {
+        match index {
+            0 => self.pawn = value,
+            1 => self.knight = value,
+            2 => self.bishop = value,
+            3 => self.rook = value,
+            4 => self.queen = value,
+            5 => self.kpk_rank = value,
+            _ => self.kbnk_corner = value,
+        }
+    }
+
+    /// Every parameter as a `name value` line, readable by `from_text`
+    pub fn to_text(&self) -> String {
+        let mut text = String::from("# chessica evaluation parameters, in centipawns\n");
+        for (index, name) in NAMES.iter().enumerate() {
+            text.push_str(&format!("{} {}\n",name,self.get(index)));
+        }
+        text
+    }
+
+    pub fn write_file(&self, path: &str) -> io::Result<()> {
+        fs::write(path, self.to_text())
+    }
+
+}
+
+lazy_static! {
+    /// The parameters used by `Reset::score`
+    static ref PARAMS: RwLock<EvalParams> = RwLock::new(new());
+}
+
+/// Replace the shared parameters with the ones in a file
+pub fn init(path: &str) -> io::Result<()> {
+    let params = from_file(path)?;
+    set_params(params);
+    Ok(())
+}
+
+pub fn set_params(params: EvalParams) {
+    if let Ok(mut shared) = PARAMS.write() {
+        *shared = params;
+    }
+}
+
+/// A copy of the shared parameters
+pub fn params() -> EvalParams {
+    match PARAMS.read() {
+        Ok(shared) => *shared,
+        Err(_) => new(),
+    }
+}
+
+thread_local! {
+    /// Whether material scores get a little position-dependent noise to break ties between moves
+    ///
+    /// Each thread has its own switch, so a test that turns the noise off can't change the
+    /// scores seen by tests running beside it.  The Orchestrator hands its setting on to the
+    /// Cogitators it starts.
+    static NOISE: Cell<bool> = const { Cell::new(true) };
+}
+
+/// Switch the tie-breaking noise on or off for this thread; deterministic mode switches it off
+pub fn set_noise(enabled: bool) {
+    NOISE.with(|noise| noise.set(enabled));
+}
+
+/// Whether this thread's scores get the tie-breaking noise
+pub fn noise_enabled() -> bool {
+    NOISE.with(|noise| noise.get())
+}
+
+/// Tie-breaking noise for a position, less than a tenth of a centipawn, given its occupancy
+pub fn noise(b_all: u64) -> i32 {
+    if noise_enabled() {
+        (b_all % 1997) as i32 - 998
+    } else {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::eval;
+    use crate::reset;
+
+    #[test]
+    fn eval_params_round_trip() {
+        let mut params = eval::new();
+        for index in 0..eval::NAMES.len() {
+            params.set(index, 1000 + index as i32);
+        }
+        assert_eq!(eval::from_text(&params.to_text()).unwrap(),params);
+
+        let path = std::env::temp_dir().join(format!("chessica_params_{}.txt",std::process::id()));
+        let path = path.to_str().unwrap();
+        params.write_file(path).unwrap();
+        assert_eq!(eval::from_file(path).unwrap(),params);
+        std::fs::remove_file(path).unwrap();
+        assert!(eval::from_file(path).is_err());
+    }
+
+    #[test]
+    fn eval_params_bad_text() {
+        assert!(eval::from_text("pawn").is_err());
+        assert!(eval::from_text("pawn one").is_err());
+        assert!(eval::from_text("pawn 100 200").is_err());
+        let error = eval::from_text("pawn 100\n\nbishop 3.5").unwrap_err();
+        assert!(error.to_string().starts_with("line 3"));
+        assert_eq!(eval::from_text("  # nothing but comments\n\n").unwrap(),eval::new());
+    }
+
+    #[test]
+    fn eval_params_change_the_score() {
+        let mut r = reset::new();
+        r.init_from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKB1R w KQkq - 0 1".to_string());
+        assert_eq!(r.evaluate_with(&eval::new()),-3 * 1000000);
+        let mut params = eval::new();
+        params.knight = 325;
+        assert_eq!(r.evaluate_with(&params),-325 * 10000);
+    }
+
+    #[test]
+    fn eval_noise_per_thread() {
+        eval::set_noise(false);
+        assert_eq!(eval::noise(0xFFFF),0);
+        assert!(std::thread::spawn(eval::noise_enabled).join().unwrap());
+        eval::set_noise(true);
+        assert_eq!(eval::noise(0xFFFF),(0xFFFF % 1997) - 998);
+    }
+}
@@ -0,0 +1,285 @@
+use crate::eval;
+use crate::eval::EvalParams;
+use crate::nnue;
+use crate::reset;
+use crate::reset::Reset;
+use crate::reset::PieceType;
+use crate::reset::r#const::SCORE_PAWN;
+use crate::reset::variant::Variant;
+
+/// One line of the breakdown: what a term is worth to each side, in centipawns
+#[derive(PartialEq,Eq,Clone,Hash,Debug)]
+pub struct EvalTerm {
+    pub name: &'static str,
+    pub white: i32,
+    pub black: i32,
+}
+
+/// A term added to the score of the deciding evaluator, from white's point of view
+#[derive(PartialEq,Eq,Clone,Hash,Debug)]
+pub struct EvalAdjustment {
+    pub name: &'static str,
+    pub score: i32,
+}
+
+/// Where the final score came from
+#[derive(PartialEq,Eq,Clone,Hash,Debug)]
+pub enum EvalSource {
+    Checkmate,
+    Stalemate,
+    /// Endgame knowledge for this material signature
+    Endgame(String),
+    Nnue,
+    Material,
+}
+
+/// Every term of the evaluation of a position, and the score `Reset::score` settles on
+///
+/// Only the evaluator that decides the score is listed beyond material: the endgame knowledge or
+/// the NNUE network, when they apply.  The variant's adjustments (Antichess turning material
+/// around, the progress toward a variant's goal) and the tie-breaking noise follow, so that the
+/// rows add up to the score.
+#[derive(PartialEq,Eq,Clone,Hash,Debug)]
+pub struct EvalTrace {
+    pub terms: Vec<EvalTerm>,
+    pub adjustments: Vec<EvalAdjustment>,
+    /// Endgame knowledge score, from white's point of view
+    pub endgame: Option<i32>,
+    /// NNUE network score, from white's point of view
+    pub nnue: Option<i32>,
+    pub source: EvalSource,
+    /// The final score, from white's point of view
+    pub score: i32,
+}
+
+/// Break down the evaluation of a position with the shared parameters
+///
+/// # Examples
+///
+/// ```
+/// use chessica::eval::trace::EvalSource;
+/// let mut r = chessica::reset::new();
+/// r.init_from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 0 1".to_string());
+/// let trace = chessica::eval::trace::trace(&mut r);
+/// assert_eq!(trace.source,EvalSource::Endgame("KRvK".to_string()));
+/// assert_eq!(trace.terms[3].white,500);
+/// ```
+pub fn trace(reset: &mut Reset) -> EvalTrace {
+    trace_with(reset, &eval::params())
+}
+
+/// `trace` with the given parameters in place of the shared ones
+///
+/// Follows the same steps as `Reset::score`, so a trace made with the shared parameters adds up
+/// to the score.
+pub fn trace_with(reset: &mut Reset, params: &EvalParams) -> EvalTrace {
+    let pieces = [
+        ("Pawns", PieceType::Pawn, params.pawn),
+        ("Knights", PieceType::Knight, params.knight),
+        ("Bishops", PieceType::Bishop, params.bishop),
+        ("Rooks", PieceType::Rook, params.rook),
+        ("Queens", PieceType::Queen, params.queen),
+    ];
+    let mut terms: Vec<EvalTerm> = pieces.iter().map(|(name, piece_type, value)| EvalTerm {
+        name,
+        white: reset.count_of(*piece_type, true) as i32 * value,
+        black: reset.count_of(*piece_type, false) as i32 * value,
+    }).collect();
+    let variant = reset.variant();
+    if variant == Variant::Crazyhouse {
+        let pocket = |white: bool| -> i32 {
+            pieces.iter().map(|(_, piece_type, value)| reset.pocket_count(white, *piece_type) as i32 * value).sum()
+        };
+        terms.push(EvalTerm { name: "Pockets", white: pocket(true), black: pocket(false) });
+    }
+
+    // Endgame knowledge and the network only know standard chess rules
+    let standard_rules = !variant.has_capture_rules() && !variant.has_win_conditions();
+    let endgame = if standard_rules { crate::endgame::evaluate_with(reset, params) } else { None };
+    let nnue = if standard_rules {
+        nnue::network().map(|network| network.evaluate(reset, &nnue::accumulator::refresh(&network, reset)))
+    } else {
+        None
+    };
+    let mut clone = reset::clone::clone_from(reset);
+    clone.restart_move_generation();
+    let has_move = clone.generate_next_move(&mut reset::new());
+    let source = if !has_move && reset.in_check() {
+        EvalSource::Checkmate
+    } else if !has_move {
+        EvalSource::Stalemate
+    } else if endgame.is_some() {
+        EvalSource::Endgame(reset.material_signature())
+    } else if nnue.is_some() {
+        EvalSource::Nnue
+    } else {
+        EvalSource::Material
+    };
+
+    let mut adjustments = Vec::new();
+    if source == EvalSource::Material {
+        if variant == Variant::Antichess {
+            adjustments.push(EvalAdjustment { name: "Antichess", score: -2 * reset.material_with(params) });
+        }
+        if variant.has_win_conditions() {
+            adjustments.push(EvalAdjustment { name: "Goal", score: reset.goal_progress() });
+        }
+        if reset.eval_noise() != 0 {
+            adjustments.push(EvalAdjustment { name: "Noise", score: reset.eval_noise() });
+        }
+    }
+    EvalTrace {
+        terms,
+        adjustments,
+        endgame,
+        nnue,
+        source,
+        score: reset.score(),
+    }
+}
+
+fn pawns(centipawns: i32) -> String {
+    format!("{:7.2}",centipawns as f64 / 100.0)
+}
+
+fn score_pawns(score: i32) -> String {
+    format!("{:7.2}",score as f64 / SCORE_PAWN as f64)
+}
+
+impl EvalTrace {
+
+    /// What the rows add up to: the deciding evaluator's score plus the adjustments
+    ///
+    /// Checkmates and stalemates have no rows to add, so they give the score itself.
+    pub fn total(&self) -> i32 {
+        let base = match self.source {
+            EvalSource::Checkmate | EvalSource::Stalemate => return self.score,
+            EvalSource::Endgame(_) => self.endgame.unwrap_or(0),
+            EvalSource::Nnue => self.nnue.unwrap_or(0),
+            EvalSource::Material => self.terms.iter().map(|term| term.white - term.black).sum::<i32>() * (SCORE_PAWN / 100),
+        };
+        base + self.adjustments.iter().map(|adjustment| adjustment.score).sum::<i32>()
+    }
+
+    /// The breakdown as a table, one string per line, with all values in pawns
+    pub fn lines(&self) -> Vec<String> {
+        let rule = String::from("-----------+---------+---------+---------");
+        let mut lines = vec![
+            String::from("Term       |   White |   Black |   Total"),
+            rule.clone(),
+        ];
+        let (mut white, mut black) = (0, 0);
+        for term in self.terms.iter() {
+            lines.push(format!("{:<10} | {} | {} | {}",term.name,pawns(term.white),pawns(term.black),pawns(term.white - term.black)));
+            white += term.white;
+            black += term.black;
+        }
+        lines.push(format!("{:<10} | {} | {} | {}","Material",pawns(white),pawns(black),pawns(white - black)));
+        if let Some(endgame) = self.endgame {
+            lines.push(format!("{:<10} |         |         | {}","Endgame",score_pawns(endgame)));
+        }
+        if let Some(nnue) = self.nnue {
+            lines.push(format!("{:<10} |         |         | {}","NNUE",score_pawns(nnue)));
+        }
+        for adjustment in self.adjustments.iter() {
+            lines.push(format!("{:<10} |         |         | {}",adjustment.name,score_pawns(adjustment.score)));
+        }
+        lines.push(rule);
+        let source = match &self.source {
+            EvalSource::Checkmate => String::from("checkmate"),
+            EvalSource::Stalemate => String::from("stalemate"),
+            EvalSource::Endgame(signature) => format!("endgame {}",signature),
+            EvalSource::Nnue => String::from("NNUE"),
+            EvalSource::Material => String::from("material"),
+        };
+        lines.push(format!("{:<10} |         |         | {}  ({})","Score",score_pawns(self.score),source));
+        lines.push(String::from("(white's point of view)"));
+        lines
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::eval;
+    use crate::eval::trace;
+    use crate::eval::trace::EvalSource;
+    use crate::reset;
+    use crate::reset::r#const::SCORE_BLACK_CHECKMATE;
+
+    fn trace(fen: &str) -> trace::EvalTrace {
+        let mut r = reset::new();
+        r.init_from_fen(fen.to_string());
+        trace::trace_with(&mut r, &eval::new())
+    }
+
+    #[test]
+    fn trace_material() {
+        let t = trace("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKB1R b KQkq - 0 1");
+        assert_eq!(t.terms.len(),5);
+        assert_eq!((t.terms[0].white,t.terms[0].black),(800,800));
+        assert_eq!((t.terms[1].white,t.terms[1].black),(300,600));
+        assert_eq!(t.terms[4].name,"Queens");
+        assert_eq!(t.endgame,None);
+        let lines = t.lines();
+        assert!(lines.contains(&"Knights    |    3.00 |    6.00 |   -3.00".to_string()));
+        assert!(lines.contains(&"Material   |   36.00 |   39.00 |   -3.00".to_string()));
+        assert!(lines.iter().any(|line| line.starts_with("Score ") && line.contains("-3.00")));
+    }
+
+    #[test]
+    fn trace_sources() {
+        let t = trace("8/8/8/4k3/8/8/8/KN6 w - - 0 1");
+        assert_eq!(t.source,EvalSource::Endgame("KNvK".to_string()));
+        assert_eq!(t.endgame,Some(0));
+        assert!(t.lines().iter().any(|line| line.contains("(endgame KNvK)")));
+
+        let t = trace("rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3");
+        assert_eq!(t.source,EvalSource::Checkmate);
+        assert_eq!(t.score,SCORE_BLACK_CHECKMATE);
+        assert_eq!(trace("7K/5k2/p4n2/Pp2b3/1P6/8/8/8 w - - 0 1").source,EvalSource::Stalemate);
+    }
+
+    #[test]
+    fn trace_adds_up() {
+        use crate::reset::variant::Variant;
+        eval::set_noise(true);
+        let positions = [
+            ("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKB1R b KQkq - 0 1", Variant::Standard),
+            ("8/8/8/4k3/8/8/8/KR6 w - - 0 1", Variant::Standard),
+            ("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKB1R b - - 0 1", Variant::Antichess),
+            ("8/8/8/8/3K4/8/krbnNBR1/qrbnNBRQ w - - 0 1", Variant::RacingKings),
+            ("rnbqkbnr/pppp1ppp/8/8/8/8/PPPPPPPP/RNBQKBNR[Pp] w KQkq - 0 1", Variant::Crazyhouse),
+        ];
+        for (fen, variant) in positions.iter() {
+            let mut r = reset::new();
+            r.init_from_fen(fen.to_string());
+            r.set_variant(*variant);
+            let t = trace::trace(&mut r);
+            assert_eq!(t.total(),r.score(),"{}",fen);
+        }
+
+        let mut r = reset::new();
+        r.init_from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKB1R b - - 0 1".to_string());
+        r.set_variant(Variant::Antichess);
+        let t = trace::trace(&mut r);
+        assert_eq!(t.adjustments[0],trace::EvalAdjustment { name: "Antichess", score: 6 * 1000000 });
+        assert_eq!(t.adjustments[1].name,"Noise");
+        assert!(t.lines().iter().any(|line| line.starts_with("Antichess  |")));
+
+        let mut r = reset::new();
+        r.init_from_fen("8/8/8/8/3K4/8/krbnNBR1/qrbnNBRQ w - - 0 1".to_string());
+        r.set_variant(Variant::RacingKings);
+        let t = trace::trace(&mut r);
+        assert_eq!(t.adjustments[0],trace::EvalAdjustment { name: "Goal", score: 2 * 500000 });
+        assert_eq!(t.source,EvalSource::Material);
+        assert_eq!(t.endgame,None);
+
+        // Without noise, and in the endgame tables' territory, there's nothing to adjust
+        eval::set_noise(false);
+        let t = trace("8/8/8/4k3/8/8/8/KR6 w - - 0 1");
+        assert!(t.adjustments.is_empty());
+        assert_eq!(t.total(),t.score);
+        eval::set_noise(true);
+    }
+}
@@ -0,0 +1,205 @@
+use crate::eval;
+use crate::eval::EvalParams;
+use crate::reset;
+use crate::reset::Reset;
+use crate::reset::r#const::SCORE_PAWN;
+use std::fs;
+use std::io;
+
+/// A quiet position and the result of the game it was taken from (1 white won, 0.5 draw, 0 black won)
+pub struct LabelledPosition {
+    pub reset: Reset,
+    pub result: f64,
+}
+
+fn result_from_text(text: &str) -> Option<f64> {
+    match text.trim_matches(|c| c == '"' || c == ';' || c == '[' || c == ']') {
+        "1-0" | "1.0" | "1" => Some(1.0),
+        "1/2-1/2" | "0.5" => Some(0.5),
+        "0-1" | "0.0" | "0" => Some(0.0),
+        _ => None,
+    }
+}
+
+/// Split a line of a training set into a FEN and a result
+///
+/// Takes EPD (`... c9 "1-0";`) or a FEN followed by the result, written as `1-0`, `0-1` or
+/// `1/2-1/2` (quoted or in brackets), or as 1.0, 0.5 or 0.0.  Returns `None` for lines without
+/// both.
+///
+/// # Examples
+///
+/// ```
+/// use chessica::eval::tune::parse_line;
+/// let (fen, result) = parse_line("8/8/8/4k3/8/8/8/KQ6 w - - c9 \"1-0\";").unwrap();
+/// assert_eq!(fen,"8/8/8/4k3/8/8/8/KQ6 w - - 0 1");
+/// assert_eq!(result,1.0);
+/// assert_eq!(parse_line("8/8/8/4k3/8/8/8/KQ6 b - - 12 40 [0.5]").unwrap().1,0.5);
+/// assert!(parse_line("8/8/8/4k3/8/8/8/KQ6 w - -").is_none());
+/// ```
+pub fn parse_line(line: &str) -> Option<(String, f64)> {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    if fields.len() < 5 || fields[0].matches('/').count() != 7 {
+        return None;
+    }
+    let counters = match (fields.get(4).and_then(|f| f.parse::<u16>().ok()), fields.get(5).and_then(|f| f.parse::<u16>().ok())) {
+        (Some(halfmove), Some(fullmove)) => Some(format!("{} {}",halfmove,fullmove)),
+        _ => None,
+    };
+    let rest = if counters.is_some() { &fields[6..] } else { &fields[4..] };
+    let result = rest.iter().rev().find_map(|field| result_from_text(field))?;
+    let counters = counters.unwrap_or_else(|| String::from("0 1"));
+    Some((format!("{} {}",fields[..4].join(" "),counters), result))
+}
+
+/// Read every labelled position in a file, skipping lines that aren't one (or whose FEN isn't
+/// a legal position)
+pub fn read_positions(path: &str) -> io::Result<Vec<LabelledPosition>> {
+    let text = String::from_utf8_lossy(&fs::read(path)?).to_string();
+    Ok(text.lines().filter_map(parse_line).filter_map(|(fen, result)| {
+        let r = reset::fen::from_fen(&fen).ok()?;
+        Some(LabelledPosition { reset: r, result })
+    }).collect())
+}
+
+/// Expected result for white of a score in centipawns, with scaling constant `k`
+pub fn sigmoid(centipawns: f64, k: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf(-k * centipawns / 400.0))
+}
+
+/// Mean squared difference between the game results and the static evaluation's expected results
+pub fn error(positions: &[LabelledPosition], params: &EvalParams, k: f64) -> f64 {
+    if positions.is_empty() {
+        return 0.0;
+    }
+    let centipawn = (SCORE_PAWN / 100) as f64;
+    let total: f64 = positions.iter().map(|position| {
+        let expected = sigmoid(position.reset.evaluate_with(params) as f64 / centipawn, k);
+        (position.result - expected) * (position.result - expected)
+    }).sum();
+    total / positions.len() as f64
+}
+
+/// The scaling constant that best fits the results to the evaluation before any tuning
+pub fn fit_k(positions: &[LabelledPosition], params: &EvalParams) -> f64 {
+    let (mut low, mut high) = (0.01, 5.0);
+    for _ in 0..60 {
+        let third = (high - low) / 3.0;
+        if error(positions, params, low + third) < error(positions, params, high - third) {
+            high -= third;
+        } else {
+            low += third;
+        }
+    }
+    (low + high) / 2.0
+}
+
+/// Texel local search: nudge each parameter up or down, keeping any change that lowers the
+/// error, and halve the step once a whole pass finds nothing
+///
+/// Stops when a pass at a step of one centipawn finds nothing, or after `max_passes` passes.
+/// Returns the tuned parameters and their error.
+pub fn tune(positions: &[LabelledPosition], start: &EvalParams, k: f64, max_passes: u32) -> (EvalParams, f64) {
+    let mut params = *start;
+    let mut best = error(positions, &params, k);
+    let mut step = 16;
+    let mut passes = 0;
+    while step >= 1 && passes < max_passes {
+        passes += 1;
+        let mut improved = false;
+        for index in 0..eval::NAMES.len() {
+            for delta in [step, -step] {
+                let mut candidate = params;
+                candidate.set(index, params.get(index) + delta);
+                let candidate_error = error(positions, &candidate, k);
+                if candidate_error < best {
+                    best = candidate_error;
+                    params = candidate;
+                    improved = true;
+                    break;
+                }
+            }
+        }
+        println!("# pass {}  step {}  error {:.6}",passes,step,best);
+        if !improved {
+            step /= 2;
+        }
+    }
+    (params, best)
+}
+
+/// Tune the parameters in `params_path` (the built-in ones if it doesn't exist yet) against the
+/// positions in `positions_path`, and write the result back to `params_path`
+pub fn tune_file(positions_path: &str, params_path: &str, max_passes: u32) -> io::Result<EvalParams> {
+    let positions = read_positions(positions_path)?;
+    println!("Positions read == {}",positions.len());
+    let start = if fs::metadata(params_path).is_ok() { eval::from_file(params_path)? } else { eval::new() };
+    let k = fit_k(&positions, &start);
+    println!("K == {:.4}  Error == {:.6}",k,error(&positions, &start, k));
+    let (params, tuned_error) = tune(&positions, &start, k, max_passes);
+    println!("Tuned error == {:.6}",tuned_error);
+    params.write_file(params_path)?;
+    Ok(params)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::eval;
+    use crate::eval::tune;
+
+    fn positions(lines: &[&str]) -> Vec<tune::LabelledPosition> {
+        lines.iter().filter_map(|line| tune::parse_line(line)).map(|(fen, result)| {
+            let mut r = crate::reset::new();
+            r.init_from_fen(fen);
+            tune::LabelledPosition { reset: r, result }
+        }).collect()
+    }
+
+    #[test]
+    fn tune_parse_lines() {
+        assert_eq!(tune::parse_line("4k3/8/8/8/8/8/8/4K3 w - - 0 1 \"1/2-1/2\"").unwrap(),("4k3/8/8/8/8/8/8/4K3 w - - 0 1".to_string(),0.5));
+        assert_eq!(tune::parse_line("4k3/8/8/8/8/8/8/4K3 b - - 0-1").unwrap(),("4k3/8/8/8/8/8/8/4K3 b - - 0 1".to_string(),0.0));
+        assert_eq!(tune::parse_line("4k3/8/8/8/8/8/8/4K3 b - - id \"x\"; c9 \"1-0\";").unwrap().1,1.0);
+        assert!(tune::parse_line("4k3/8/8/8/8/8/8/4K3 w - - 0 1").is_none());
+        assert!(tune::parse_line("").is_none());
+        assert!(tune::parse_line("# comment 1-0").is_none());
+    }
+
+    #[test]
+    fn tune_lowers_the_error() {
+        // A knight up wins every game, but an extra pawn only ever draws
+        let set = positions(&[
+            "rnbqkb1r/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1 1-0",
+            "r1bqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR b KQkq - 0 1 1-0",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/R1BQKBNR w KQkq - 0 1 0-1",
+            "rnbqkbnr/ppppppp1/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1 1/2-1/2",
+            "rnbqkbnr/pppppppp/8/8/8/8/1PPPPPPP/RNBQKBNR b KQkq - 0 1 1/2-1/2",
+        ]);
+        assert_eq!(set.len(),5);
+        let start = eval::new();
+        let k = tune::fit_k(&set, &start);
+        assert!(k > 0.01 && k < 5.0);
+        let before = tune::error(&set, &start, k);
+        let (tuned, after) = tune::tune(&set, &start, k, 200);
+        assert!(after < before);
+        assert!(tuned.knight > start.knight);
+        assert!(tuned.pawn < start.pawn);
+        // Nothing in the set has a rook imbalance, so the rook is left alone
+        assert_eq!(tuned.rook,start.rook);
+        assert_eq!(tune::error(&[], &start, k),0.0);
+    }
+
+    #[test]
+    fn tune_writes_back() {
+        let directory = std::env::temp_dir().join(format!("chessica_tune_{}",std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let positions_path = directory.join("quiet.epd");
+        let params_path = directory.join("eval.params");
+        std::fs::write(&positions_path, "rnbqkb1r/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - c9 \"1-0\";\nnot a position\n").unwrap();
+        std::fs::write(&params_path, "knight 250\n").unwrap();
+        let tuned = tune::tune_file(positions_path.to_str().unwrap(), params_path.to_str().unwrap(), 3).unwrap();
+        assert_eq!(eval::from_file(params_path.to_str().unwrap()).unwrap(),tuned);
+        assert!(tuned.knight > 250);
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
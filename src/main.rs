@@ -12,6 +12,12 @@ pub mod args; //include "args/mod.rs";
 pub mod operator; //include "operator/mod.rs"
 pub mod orchestrator; //include "operator/mod.rs"
 pub mod cogitator; //include "operator/mod.rs"
+pub mod book; //include "book/mod.rs"
+pub mod syzygy; //include "syzygy/mod.rs"
+pub mod endgame; //include "endgame/mod.rs"
+pub mod nnue; //include "nnue/mod.rs"
+pub mod eval; //include "eval/mod.rs"
+pub mod reference; //include "reference/mod.rs"
 
 #[allow(dead_code)]
 fn main() {
@@ -33,10 +39,147 @@ fn main() {
         return;
     }
 
-    if argdata.profile {
+    if let Some(fen) = &argdata.fen {
+        if let Err(e) = crate::reset::fen::from_fen(fen) {
+            println!("Invalid FEN \"{}\": {}",fen,e);
+            return;
+        }
+    }
+
+    if let Some(path) = &argdata.syzygy_path {
+        match crate::syzygy::init(path) {
+            Ok(found) => println!("# found {} Syzygy tables in {}, up to {} pieces",found,path,crate::syzygy::max_pieces()),
+            Err(e) => println!("# could not read Syzygy tables from {}: {}",path,e),
+        }
+    }
+
+    if let Some(path) = &argdata.nnue_path {
+        match crate::nnue::init(path) {
+            Ok(()) => println!("# NNUE evaluation using {}",path),
+            Err(e) => println!("# could not read network {}: {}",path,e),
+        }
+    }
+
+    if argdata.deterministic {
+        crate::eval::set_noise(false);
+    }
+
+    if argdata.fuzz() {
+        let games = argdata.fuzz_games.unwrap();
+        let seed = argdata.seed.unwrap_or(1);
+        println!("Fuzzing the move generator against the reference over {} games of up to {} plies (seed {})...",games,argdata.fuzz_plies,seed);
+        match crate::reference::fuzz::fuzz(seed, games, argdata.fuzz_plies) {
+            Ok(positions) => println!("All {} positions agree",positions.to_formatted_string(&Locale::en)),
+            Err(failure) => println!("Mismatch {}",failure),
+        }
+        return;
+    }
+
+    if argdata.random_positions() {
+        use crate::reset::random::PositionSource;
+        let source = match &argdata.random_material {
+            Some(signature) => PositionSource::Placement { signature: signature.clone() },
+            None => PositionSource::Playout {
+                fen: argdata.fen.clone().unwrap_or(String::from("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1")),
+                min_plies: argdata.random_min_plies,
+                max_plies: argdata.random_max_plies,
+            },
+        };
+        let wanted = argdata.random_positions.unwrap();
+        match crate::reset::random::new(source, argdata.seed.unwrap_or(1)) {
+            Ok(generator) => {
+                let mut count = 0;
+                for mut r in generator.take(wanted as usize) {
+                    println!("{}",argdata.random_format.write(&mut r));
+                    count += 1;
+                }
+                if count < wanted {
+                    println!("# gave up after {} positions",count);
+                }
+            },
+            Err(e) => println!("Can't generate positions: {}",e),
+        }
+        return;
+    }
+
+    if argdata.tune() {
+        let positions = argdata.tune_positions.as_ref().unwrap();
+        let params_path = argdata.eval_params_path.as_ref().unwrap();
+        println!("Tuning {} against {}, up to {} passes...",params_path,positions,argdata.tune_passes);
+        match crate::eval::tune::tune_file(positions, params_path, argdata.tune_passes) {
+            Ok(params) => println!("Wrote tuned parameters to {}:\n{}",params_path,params.to_text()),
+            Err(e) => println!("Could not tune: {}",e),
+        }
+        return;
+    }
+
+    if let Some(path) = &argdata.eval_params_path {
+        match crate::eval::init(path) {
+            Ok(()) => println!("# evaluation parameters from {}",path),
+            Err(e) => println!("# could not read evaluation parameters from {}: {}",path,e),
+        }
+    }
+
+    if argdata.book_build() {
+        let options = &argdata.book_build_options;
+        println!("Building {:?} book from {} PGN file(s), up to ply {}...",options.format,options.pgn_files.len(),options.max_ply);
+        match crate::book::builder::build_book(options) {
+            Ok(entries) => println!("Wrote {} book entries to {}",entries,options.output.as_ref().unwrap()),
+            Err(e) => println!("Could not build book: {}",e),
+        }
+
+    } else if argdata.eval_trace() {
+        let starting_fen = argdata.fen.clone().unwrap_or(String::from("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"));
+        let mut r = crate::reset::new();
+        r.init_from_fen(starting_fen);
+        r.print_eval();
+
+    } else if argdata.syzygy_probe() {
+        let starting_fen = argdata.fen.clone().unwrap_or(String::from("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"));
+        let mut r = crate::reset::new();
+        r.init_from_fen(starting_fen);
+        match crate::syzygy::probe::wdl(&r) {
+            Some(wdl) => println!("WDL == {:?}",wdl),
+            None => println!("WDL == (not in the tables)"),
+        }
+        if let Some(dtz) = crate::syzygy::probe::dtz(&r) {
+            println!("DTZ == {}",dtz);
+        }
+        if let Some((mut child, dtz)) = crate::syzygy::probe::root_move(&r) {
+            println!("Best move == {}  DTZ == {}",child.uci_move_text(),dtz);
+        }
+
+    } else if argdata.mate_search() {
+        let mate_moves = argdata.mate_moves.unwrap();
+        let starting_fen = argdata.fen.clone().unwrap_or(String::from("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"));
+        let mut t: Tree = tree::from_fen(starting_fen);
+        let mut move_count: u64 = 0;
+        println!("Searching for mate in {}{}...",mate_moves,if argdata.checks_only { " (checks only)" } else { "" });
+        match t.mate_search(mate_moves, argdata.checks_only, &mut move_count) {
+            Some(moves) => println!("Mate in {}: {}",moves,t.mating_line()),
+            None => println!("No forced mate in {} moves",mate_moves),
+        }
+        println!("Move count == {}",move_count.to_formatted_string(&Locale::en));
+
+    } else if argdata.proof_search() {
+        use crate::args::proof::ProofSearchType;
+        let starting_fen = argdata.fen.clone().unwrap_or(String::from("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"));
+        let mut t: Tree = tree::from_fen(starting_fen);
+        let mut node_count: u64 = 0;
+        println!("Running {:?} search, up to {} nodes...",argdata.proof_search_type,argdata.proof_nodes);
+        let result = if argdata.proof_search_type == ProofSearchType::PN2 {
+            t.pn2_search(argdata.proof_nodes, &mut node_count)
+        } else {
+            t.pn_search(argdata.proof_nodes, &mut node_count)
+        };
+        println!("Result == {:?}",result);
+        println!("Proof == {}",t.proof_line());
+        println!("Node count == {}",node_count.to_formatted_string(&Locale::en));
+
+    } else if argdata.profile {
         use crate::reset::r#const::SCORE_MIN;
         use crate::reset::r#const::SCORE_MAX;
-        let starting_fen = String::from("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+        let starting_fen = argdata.fen.clone().unwrap_or(String::from("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"));
         let mut t: Tree = tree::from_fen(starting_fen);
         let mut move_count: u64 = 0;
 
@@ -44,6 +187,10 @@ fn main() {
             println!("Running profile script for resets...");
             crate::reset::profiling::burn();
 
+        } else if argdata.profile_reset_make_unmake() {
+            println!("Running profile script for make/unmake resets...");
+            crate::reset::profiling::burn_make_unmake();
+
         } else if argdata.profile_tree() {
             println!("Running profile script for trees...");
             t.simple_move_tree(4, &mut move_count);
@@ -52,10 +199,14 @@ fn main() {
         } else if argdata.profile_in_place_ab() {
             println!("Running profile script for In Place Alpha-Beta...");
             println!("Search Depth == {}",argdata.ab_search_depth);
+            println!("Search Options == {:?}",argdata.search_options);
             let score = t.alpha_beta_in_place(
                 argdata.ab_search_depth,
+                0,
                 SCORE_MAX,
                 SCORE_MIN,
+                &argdata.search_options,
+                &mut tree::ordering::new(),
                 &mut move_count
             );
             println!("Score == {}  Move count == {}",score,move_count.to_formatted_string(&Locale::en));
@@ -63,12 +214,15 @@ fn main() {
         } else if argdata.profile_promote_prune_ab() {
             println!("Running profile script for Promote Prune Alpha-Beta...");
             println!("Search Depth == {}",argdata.ab_search_depth);
+            println!("Search Options == {:?}",argdata.search_options);
             let red_light = Arc::new(AtomicBool::new(false));
             let score = t.alpha_beta_promote_prune(
                 0,
                 argdata.ab_search_depth,
                 SCORE_MAX,
                 SCORE_MIN,
+                &argdata.search_options,
+                &mut tree::ordering::new(),
                 &red_light,
                 &mut move_count
             );
@@ -77,15 +231,31 @@ fn main() {
         } else if argdata.profile_iterative_promote_prune_ab() {
             println!("Running profile script for Iterative Promote Prune Alpha-Beta...");
             println!("Search Depth == {}",argdata.ab_search_depth);
+            println!("Search Options == {:?}",argdata.search_options);
             let score = t.iterative_alpha_beta_promote_prune(
                 argdata.ab_search_depth,
                 SCORE_MAX,
-                SCORE_MIN
+                SCORE_MIN,
+                &argdata.search_options
             );
             println!("Score == {}",score);
+
+        } else if argdata.profile_mcts() {
+            println!("Running profile script for Monte Carlo Tree Search...");
+            println!("MCTS Options == {:?}",argdata.mcts_options);
+            if let Some(best) = t.mcts_search(&argdata.mcts_options, &mut move_count) {
+                let child = &mut t.children[best];
+                println!("Best move == {}  Visits == {}  Value == {:.3}",child.reset.move_text(),child.visits,child.mcts_value());
+            }
+            println!("Line == {}",t.mcts_principal_variation());
+            println!("Move count == {}",move_count.to_formatted_string(&Locale::en));
         }
     } else {
+        crate::endgame::kpk::init();
         let mut operator = operator::new();
+        operator.book_options = argdata.book_options.clone();
+        operator.deterministic = argdata.deterministic;
+        operator.seed = argdata.seed;
         operator.run();
         // Game Time!
         // Build game object
@@ -0,0 +1,134 @@
+use crate::nnue::HALF_DIMENSIONS;
+use crate::nnue::Network;
+use crate::nnue::simd;
+use crate::reset::Reset;
+use std::sync::Arc;
+
+/// The feature transformer's output for one position: a half for each side's king
+///
+/// Children are worked out from their parent's accumulator by taking away the features of the
+/// pieces that moved and adding them back on their new squares.  Only a king move forces that
+/// king's half to be rebuilt from scratch.
+#[derive(Clone)]
+pub struct Accumulator {
+    pub network: Arc<Network>,
+    /// Indexed by perspective: 0 for white, 1 for black
+    pub values: [[i16; HALF_DIMENSIONS]; 2],
+    /// King squares (a1 = 0) the halves were built for
+    pub kings: [usize; 2],
+}
+
+/// Builds an Accumulator for a position from scratch
+pub fn refresh(network: &Arc<Network>, reset: &Reset) -> Accumulator {
+    let mut accumulator = Accumulator {
+        network: Arc::clone(network),
+        values: [[0; HALF_DIMENSIONS]; 2],
+        kings: [0; 2],
+    };
+    let pieces = reset.tablebase_pieces();
+    for perspective in 0..2 {
+        accumulator.refresh_perspective(&pieces, perspective);
+    }
+    accumulator
+}
+
+impl Accumulator {
+
+    fn refresh_perspective(&mut self, pieces: &[(usize, u8)], perspective: usize) {
+        let king_code = if perspective == 0 { 6 } else { 14 };
+        let king = pieces.iter().find(|(_, code)| *code == king_code).map(|(square, _)| *square).unwrap_or(0);
+        self.kings[perspective] = king;
+        let network = Arc::clone(&self.network);
+        let values = &mut self.values[perspective];
+        values.copy_from_slice(network.biases());
+        for (square, code) in pieces {
+            if let Some(index) = network.feature_set.index(perspective, king, *square, *code) {
+                simd::add_assign(values, network.feature_row(index));
+            }
+        }
+    }
+
+    /// Make this the accumulator of `child`, reached by one move from `parent`, whose
+    /// accumulator is `from`
+    pub fn update(&mut self, from: &Accumulator, parent: &Reset, child: &Reset) {
+        if !Arc::ptr_eq(&self.network, &from.network) {
+            self.network = Arc::clone(&from.network);
+        }
+        let (removed, added) = parent.piece_changes(child);
+        let mut child_pieces = None;
+        for perspective in 0..2 {
+            let king_code = if perspective == 0 { 6 } else { 14 };
+            if removed.iter().any(|(_, code)| *code == king_code) {
+                let pieces = child_pieces.get_or_insert_with(|| child.tablebase_pieces());
+                self.refresh_perspective(pieces, perspective);
+                continue;
+            }
+            let king = from.kings[perspective];
+            self.kings[perspective] = king;
+            self.values[perspective] = from.values[perspective];
+            let feature_set = self.network.feature_set;
+            for (square, code) in removed.iter() {
+                if let Some(index) = feature_set.index(perspective, king, *square, *code) {
+                    simd::sub_assign(&mut self.values[perspective], self.network.feature_row(index));
+                }
+            }
+            for (square, code) in added.iter() {
+                if let Some(index) = feature_set.index(perspective, king, *square, *code) {
+                    simd::add_assign(&mut self.values[perspective], self.network.feature_row(index));
+                }
+            }
+        }
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::nnue;
+    use crate::nnue::FeatureSet;
+    use crate::nnue::accumulator;
+    use crate::nnue::tests::random_network_bytes;
+    use crate::reset;
+    use crate::utils::rng;
+    use std::sync::Arc;
+
+    #[test]
+    fn accumulator_incremental_matches_refresh() {
+        // Castling both ways, en passant and promotions with and without captures all come up
+        let fens = [
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+        ];
+        for feature_set in [FeatureSet::HalfKP, FeatureSet::HalfKA] {
+            let network = Arc::new(nnue::from_bytes(&random_network_bytes(feature_set, 4)).unwrap());
+            let mut rng = rng::new(36);
+            for fen in fens.iter() {
+                let mut parent = reset::new();
+                parent.init_from_fen(fen.to_string());
+                let mut parent_accumulator = accumulator::refresh(&network, &parent);
+                for _ in 0..40 {
+                    let mut children = Vec::new();
+                    let mut child = reset::new();
+                    parent.restart_move_generation();
+                    while parent.generate_next_move(&mut child) {
+                        let mut incremental = parent_accumulator.clone();
+                        incremental.update(&parent_accumulator, &parent, &child);
+                        let refreshed = accumulator::refresh(&network, &child);
+                        assert_eq!(incremental.values,refreshed.values,"{} {}",parent.to_fen(),child.uci_move_text());
+                        assert_eq!(network.evaluate(&child, &incremental),network.evaluate(&child, &refreshed));
+                        children.push((child.to_fen(), incremental));
+                        child = reset::new();
+                    }
+                    if children.is_empty() {
+                        break;
+                    }
+                    let (fen, next_accumulator) = children.swap_remove(rng.below(children.len()));
+                    parent = reset::new();
+                    parent.init_from_fen(fen);
+                    parent_accumulator = next_accumulator;
+                }
+            }
+        }
+    }
+}
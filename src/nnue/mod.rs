@@ -0,0 +1,337 @@
+pub mod accumulator;
+pub mod simd;
+
+use crate::nnue::accumulator::Accumulator;
+use crate::reset::Reset;
+use crate::reset::r#const::SCORE_KNOWN_WIN;
+use crate::reset::r#const::SCORE_PAWN;
+use std::fs;
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+
+/// Version word at the start of every Stockfish-format network file
+pub const VERSION: u32 = 0x7AF32F16;
+/// Width of each half of the accumulator (one half per side)
+pub const HALF_DIMENSIONS: usize = 256;
+const L1_SIZE: usize = 32;
+const L2_SIZE: usize = 32;
+/// Right shift that takes a hidden layer's sums back to the 0-127 range
+const WEIGHT_SCALE_BITS: u32 = 6;
+/// The output layer's units per internal pawn ...
+const OUTPUT_SCALE: i32 = 16;
+/// ... and the internal units per pawn of the networks this format was trained for
+const NETWORK_PAWN: i32 = 208;
+
+/// Inputs of the feature transformer, always relative to one side's king
+#[derive(PartialEq,Eq,Copy,Clone,Debug)]
+pub enum FeatureSet {
+    /// Every piece but the kings, by square, type and colour (41,024 inputs)
+    HalfKP,
+    /// The same with both kings included (49,152 inputs)
+    HalfKA,
+}
+
+impl FeatureSet {
+
+    pub fn inputs(&self) -> usize {
+        match self {
+            FeatureSet::HalfKP => 64 * 641,
+            FeatureSet::HalfKA => 64 * 768,
+        }
+    }
+
+    /// Input for a piece seen from `perspective` (0 for white, 1 for black), with squares
+    /// counting a1 = 0 and pieces coded as in `Reset::tablebase_pieces`
+    ///
+    /// Black sees the board rotated, so both sides look up the same weights for the same
+    /// relation between their king and a piece.  HalfKP has no inputs for the kings.
+    pub fn index(&self, perspective: usize, king: usize, square: usize, code: u8) -> Option<usize> {
+        let orient = |square: usize| if perspective == 1 { square ^ 63 } else { square };
+        let piece_type = (code & 7) as usize - 1;
+        let theirs = ((code & 8 != 0) as usize != perspective) as usize;
+        match self {
+            FeatureSet::HalfKP if piece_type == 5 => None,
+            FeatureSet::HalfKP => Some(orient(square) + 1 + (piece_type * 2 + theirs) * 64 + 641 * orient(king)),
+            FeatureSet::HalfKA => Some(orient(square) + (piece_type * 2 + theirs) * 64 + 768 * orient(king)),
+        }
+    }
+
+}
+
+/// A halfkp_256x2-32-32 (or halfka_256x2-32-32) network, as written by the Stockfish trainers
+pub struct Network {
+    pub feature_set: FeatureSet,
+    pub description: String,
+    feature_biases: Vec<i16>,
+    feature_weights: Vec<i16>,
+    l1_biases: Vec<i32>,
+    l1_weights: Vec<i8>,
+    l2_biases: Vec<i32>,
+    l2_weights: Vec<i8>,
+    output_bias: i32,
+    output_weights: Vec<i8>,
+}
+
+/// Little-endian reader over the bytes of a network file
+struct Reader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+
+    fn take(&mut self, length: usize) -> &'a [u8] {
+        let slice = &self.bytes[self.offset..self.offset + length];
+        self.offset += length;
+        slice
+    }
+
+    fn u32(&mut self) -> u32 {
+        let bytes = self.take(4);
+        u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+    }
+
+    fn i16s(&mut self, count: usize) -> Vec<i16> {
+        self.take(count * 2).chunks_exact(2).map(|pair| i16::from_le_bytes([pair[0], pair[1]])).collect()
+    }
+
+    fn i32s(&mut self, count: usize) -> Vec<i32> {
+        self.take(count * 4).chunks_exact(4).map(|word| i32::from_le_bytes([word[0], word[1], word[2], word[3]])).collect()
+    }
+
+    fn i8s(&mut self, count: usize) -> Vec<i8> {
+        self.take(count).iter().map(|byte| *byte as i8).collect()
+    }
+
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+/// Bytes after the feature transformer: a hash, then the three affine layers
+const NETWORK_BYTES: usize = 4 + (L1_SIZE * 4 + L1_SIZE * 2 * HALF_DIMENSIONS) + (L2_SIZE * 4 + L2_SIZE * L1_SIZE) + (4 + L2_SIZE);
+
+/// Read a network from the bytes of a `.nnue` file
+///
+/// The feature set is worked out from the size of the feature transformer.  The architecture
+/// hashes are skipped rather than checked, so any file with the right layer sizes loads.
+pub fn from_bytes(bytes: &[u8]) -> io::Result<Network> {
+    if bytes.len() < 12 {
+        return Err(invalid("network file too short"));
+    }
+    let mut reader = Reader { bytes, offset: 0 };
+    if reader.u32() != VERSION {
+        return Err(invalid("not a network file (bad version)"));
+    }
+    reader.u32();
+    let description_length = reader.u32() as usize;
+    let header = 12 + description_length;
+    let transformer_bytes = bytes.len().checked_sub(header + NETWORK_BYTES + 4 + HALF_DIMENSIONS * 2)
+        .ok_or_else(|| invalid("network file too short"))?;
+    let feature_set = [FeatureSet::HalfKP, FeatureSet::HalfKA].iter()
+        .find(|set| set.inputs() * HALF_DIMENSIONS * 2 == transformer_bytes)
+        .copied()
+        .ok_or_else(|| invalid("network file has unsupported layer sizes"))?;
+    let description = String::from_utf8_lossy(reader.take(description_length)).to_string();
+
+    reader.u32();
+    let feature_biases = reader.i16s(HALF_DIMENSIONS);
+    let feature_weights = reader.i16s(feature_set.inputs() * HALF_DIMENSIONS);
+    reader.u32();
+    let l1_biases = reader.i32s(L1_SIZE);
+    let l1_weights = reader.i8s(L1_SIZE * 2 * HALF_DIMENSIONS);
+    let l2_biases = reader.i32s(L2_SIZE);
+    let l2_weights = reader.i8s(L2_SIZE * L1_SIZE);
+    let output_bias = reader.i32s(1)[0];
+    let output_weights = reader.i8s(L2_SIZE);
+    Ok(Network {
+        feature_set,
+        description,
+        feature_biases,
+        feature_weights,
+        l1_biases,
+        l1_weights,
+        l2_biases,
+        l2_weights,
+        output_bias,
+        output_weights,
+    })
+}
+
+/// Read a network from a `.nnue` file
+pub fn from_file(path: &str) -> io::Result<Network> {
+    from_bytes(&fs::read(path)?)
+}
+
+/// One hidden layer: affine transform, then scale back and clip to 0-127
+fn clipped_layer(input: &[u8], biases: &[i32], weights: &[i8], output: &mut [u8]) {
+    let width = input.len();
+    for (o, out) in output.iter_mut().enumerate() {
+        let sum = biases[o] + simd::dot(input, &weights[o * width..(o + 1) * width]);
+        *out = (sum >> WEIGHT_SCALE_BITS).clamp(0, 127) as u8;
+    }
+}
+
+impl Network {
+
+    /// Starting point of every accumulator half
+    pub fn biases(&self) -> &[i16] {
+        &self.feature_biases
+    }
+
+    /// Weights added to an accumulator half when a feature is present
+    pub fn feature_row(&self, index: usize) -> &[i16] {
+        &self.feature_weights[index * HALF_DIMENSIONS..(index + 1) * HALF_DIMENSIONS]
+    }
+
+    /// Score of a position, from white's point of view, given its accumulator
+    pub fn evaluate(&self, reset: &Reset, accumulator: &Accumulator) -> i32 {
+        let us = if reset.white_to_move() { 0 } else { 1 };
+        let mut transformed = [0u8; 2 * HALF_DIMENSIONS];
+        for (half, perspective) in [us, 1 - us].iter().enumerate() {
+            for (out, value) in transformed[half * HALF_DIMENSIONS..].iter_mut().zip(accumulator.values[*perspective].iter()) {
+                *out = (*value).clamp(0, 127) as u8;
+            }
+        }
+        let mut l1 = [0u8; L1_SIZE];
+        clipped_layer(&transformed, &self.l1_biases, &self.l1_weights, &mut l1);
+        let mut l2 = [0u8; L2_SIZE];
+        clipped_layer(&l1, &self.l2_biases, &self.l2_weights, &mut l2);
+        let output = self.output_bias + simd::dot(&l2, &self.output_weights);
+
+        let score = (output / OUTPUT_SCALE) as i64 * SCORE_PAWN as i64 / NETWORK_PAWN as i64;
+        let score = score.clamp(-(SCORE_KNOWN_WIN as i64 - 1), SCORE_KNOWN_WIN as i64 - 1) as i32;
+        if us == 0 { score } else { -score }
+    }
+
+}
+
+lazy_static! {
+    /// The network used in place of the material evaluation, once one is loaded
+    static ref NETWORK: RwLock<Option<Arc<Network>>> = RwLock::new(None);
+}
+
+/// UCI "UseNNUE": lets a loaded network be switched off without forgetting it
+static USE_NNUE: AtomicBool = AtomicBool::new(true);
+
+/// Load the network used by `Reset::score`, or unload it with an empty path
+pub fn init(path: &str) -> io::Result<()> {
+    let network = if path.is_empty() { None } else { Some(Arc::new(from_file(path)?)) };
+    match NETWORK.write() {
+        Ok(mut shared) => *shared = network,
+        Err(_) => return Err(io::Error::new(io::ErrorKind::Other, "network lock poisoned")),
+    }
+    Ok(())
+}
+
+pub fn set_enabled(enabled: bool) {
+    USE_NNUE.store(enabled, Ordering::Relaxed);
+}
+
+/// The network to evaluate with, if one is loaded and switched on
+pub fn network() -> Option<Arc<Network>> {
+    if !USE_NNUE.load(Ordering::Relaxed) {
+        return None;
+    }
+    NETWORK.read().ok()?.clone()
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::nnue;
+    use crate::nnue::FeatureSet;
+    use crate::nnue::accumulator;
+    use crate::reset;
+    use crate::utils::rng;
+
+    /// A network file with small random weights, so every layer stays in range
+    pub fn random_network_bytes(feature_set: FeatureSet, seed: u64) -> Vec<u8> {
+        let mut rng = rng::new(seed);
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&nnue::VERSION.to_le_bytes());
+        bytes.extend_from_slice(&0u32.to_le_bytes());
+        let description = b"random test network";
+        bytes.extend_from_slice(&(description.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(description);
+        bytes.extend_from_slice(&0u32.to_le_bytes());
+        for _ in 0..nnue::HALF_DIMENSIONS {
+            bytes.extend_from_slice(&(rng.below(64) as i16).to_le_bytes());
+        }
+        for _ in 0..feature_set.inputs() * nnue::HALF_DIMENSIONS {
+            bytes.extend_from_slice(&(rng.below(41) as i16 - 20).to_le_bytes());
+        }
+        bytes.extend_from_slice(&0u32.to_le_bytes());
+        for (outputs, inputs) in [(32, 512), (32, 32), (1, 32)] {
+            for _ in 0..outputs {
+                bytes.extend_from_slice(&(rng.below(2000) as i32).to_le_bytes());
+            }
+            for _ in 0..outputs * inputs {
+                bytes.push((rng.below(21) as i8 - 10) as u8);
+            }
+        }
+        bytes
+    }
+
+    #[test]
+    fn nnue_loads_both_feature_sets() {
+        for feature_set in [FeatureSet::HalfKP, FeatureSet::HalfKA] {
+            let network = nnue::from_bytes(&random_network_bytes(feature_set, 1)).unwrap();
+            assert_eq!(network.feature_set,feature_set);
+            assert_eq!(network.description,"random test network");
+        }
+    }
+
+    #[test]
+    fn nnue_rejects_bad_files() {
+        let bytes = random_network_bytes(FeatureSet::HalfKP, 2);
+        assert!(nnue::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(nnue::from_bytes(&bytes[..8]).is_err());
+        let mut wrong_version = bytes.clone();
+        wrong_version[0] ^= 1;
+        assert!(nnue::from_bytes(&wrong_version).is_err());
+        let mut extra = bytes;
+        extra.push(0);
+        assert!(nnue::from_bytes(&extra).is_err());
+        assert!(nnue::from_file("/nonexistent/network.nnue").is_err());
+    }
+
+    #[test]
+    fn nnue_feature_indexes() {
+        // The same relation between king and piece gives the same input for either side
+        let white = FeatureSet::HalfKP.index(0, 4, 12, 1);
+        let black = FeatureSet::HalfKP.index(1, 59, 51, 9);
+        assert_eq!(white,black);
+        assert_eq!(FeatureSet::HalfKP.index(0, 4, 60, 14),None);
+        assert_eq!(FeatureSet::HalfKA.index(1, 63, 63, 14),Some(5 * 128));
+        for perspective in 0..2 {
+            for set in [FeatureSet::HalfKP, FeatureSet::HalfKA] {
+                assert!(set.index(perspective, 63, 63, 13).unwrap() < set.inputs());
+            }
+        }
+    }
+
+    /// The board turned half a turn with the colours swapped, so black stands where white stood
+    fn rotated_fen(fen: &str) -> String {
+        let mut fields = fen.split(' ');
+        let placement: String = fields.next().unwrap().chars().rev().map(|c| {
+            if c.is_ascii_uppercase() { c.to_ascii_lowercase() } else { c.to_ascii_uppercase() }
+        }).collect();
+        let side = if fields.next() == Some("w") { "b" } else { "w" };
+        format!("{} {} - - 0 1",placement,side)
+    }
+
+    #[test]
+    fn nnue_colour_symmetry() {
+        let network = std::sync::Arc::new(nnue::from_bytes(&random_network_bytes(FeatureSet::HalfKP, 3)).unwrap());
+        let fen = "r1bqkb1r/pppp1ppp/2n2n2/4p3/2B1P3/5N2/PPPP1PPP/RNBQK2R w - - 4 4";
+        let mut r = reset::new();
+        r.init_from_fen(fen.to_string());
+        let mut rotated = reset::new();
+        rotated.init_from_fen(rotated_fen(fen));
+        let score = network.evaluate(&r, &accumulator::refresh(&network, &r));
+        assert_ne!(score,0);
+        assert_eq!(network.evaluate(&rotated, &accumulator::refresh(&network, &rotated)),-score);
+    }
+}
@@ -0,0 +1,128 @@
+//! The inner loops of the network, with AVX2 versions used when the CPU has them
+
+/// Add a feature's weights to one half of an accumulator
+pub fn add_assign(values: &mut [i16], row: &[i16]) {
+    #[cfg(target_arch = "x86_64")]
+    {
+        if is_x86_feature_detected!("avx2") {
+            unsafe { avx2::add_assign(values, row) };
+            return;
+        }
+    }
+    add_assign_scalar(values, row);
+}
+
+/// Take a feature's weights away from one half of an accumulator
+pub fn sub_assign(values: &mut [i16], row: &[i16]) {
+    #[cfg(target_arch = "x86_64")]
+    {
+        if is_x86_feature_detected!("avx2") {
+            unsafe { avx2::sub_assign(values, row) };
+            return;
+        }
+    }
+    sub_assign_scalar(values, row);
+}
+
+/// Dot product of a layer's clipped inputs with one row of its weights
+///
+/// Inputs are at most 127, so no pair of products can saturate the 16 bit sums AVX2 uses.
+pub fn dot(input: &[u8], weights: &[i8]) -> i32 {
+    #[cfg(target_arch = "x86_64")]
+    {
+        if is_x86_feature_detected!("avx2") {
+            return unsafe { avx2::dot(input, weights) };
+        }
+    }
+    dot_scalar(input, weights)
+}
+
+fn add_assign_scalar(values: &mut [i16], row: &[i16]) {
+    for (value, weight) in values.iter_mut().zip(row) {
+        *value = value.wrapping_add(*weight);
+    }
+}
+
+fn sub_assign_scalar(values: &mut [i16], row: &[i16]) {
+    for (value, weight) in values.iter_mut().zip(row) {
+        *value = value.wrapping_sub(*weight);
+    }
+}
+
+fn dot_scalar(input: &[u8], weights: &[i8]) -> i32 {
+    input.iter().zip(weights).map(|(x, w)| *x as i32 * *w as i32).sum()
+}
+
+/// Slices are a multiple of 32 bytes long; the layer sizes guarantee it
+#[cfg(target_arch = "x86_64")]
+mod avx2 {
+    use std::arch::x86_64::*;
+
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn add_assign(values: &mut [i16], row: &[i16]) {
+        debug_assert!(values.len() % 16 == 0 && row.len() == values.len());
+        for i in (0..values.len()).step_by(16) {
+            let value = _mm256_loadu_si256(values.as_ptr().add(i) as *const __m256i);
+            let weight = _mm256_loadu_si256(row.as_ptr().add(i) as *const __m256i);
+            _mm256_storeu_si256(values.as_mut_ptr().add(i) as *mut __m256i, _mm256_add_epi16(value, weight));
+        }
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn sub_assign(values: &mut [i16], row: &[i16]) {
+        debug_assert!(values.len() % 16 == 0 && row.len() == values.len());
+        for i in (0..values.len()).step_by(16) {
+            let value = _mm256_loadu_si256(values.as_ptr().add(i) as *const __m256i);
+            let weight = _mm256_loadu_si256(row.as_ptr().add(i) as *const __m256i);
+            _mm256_storeu_si256(values.as_mut_ptr().add(i) as *mut __m256i, _mm256_sub_epi16(value, weight));
+        }
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn dot(input: &[u8], weights: &[i8]) -> i32 {
+        debug_assert!(input.len() % 32 == 0 && weights.len() == input.len());
+        let ones = _mm256_set1_epi16(1);
+        let mut sum = _mm256_setzero_si256();
+        for i in (0..input.len()).step_by(32) {
+            let x = _mm256_loadu_si256(input.as_ptr().add(i) as *const __m256i);
+            let w = _mm256_loadu_si256(weights.as_ptr().add(i) as *const __m256i);
+            let pairs = _mm256_maddubs_epi16(x, w);
+            sum = _mm256_add_epi32(sum, _mm256_madd_epi16(pairs, ones));
+        }
+        let mut lanes = [0i32; 8];
+        _mm256_storeu_si256(lanes.as_mut_ptr() as *mut __m256i, sum);
+        lanes.iter().sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::nnue::simd;
+    use crate::utils::rng;
+
+    #[test]
+    fn simd_matches_scalar() {
+        let mut rng = rng::new(36);
+        let row: Vec<i16> = (0..256).map(|_| rng.below(4000) as i16 - 2000).collect();
+        let start: Vec<i16> = (0..256).map(|_| rng.below(65536) as u16 as i16).collect();
+
+        let mut fast = start.clone();
+        let mut slow = start.clone();
+        simd::add_assign(&mut fast, &row);
+        simd::add_assign_scalar(&mut slow, &row);
+        assert_eq!(fast,slow);
+        simd::sub_assign(&mut fast, &row);
+        simd::sub_assign_scalar(&mut slow, &row);
+        assert_eq!(fast,slow);
+        assert_eq!(fast,start);
+
+        for length in [32, 512] {
+            let input: Vec<u8> = (0..length).map(|_| rng.below(128) as u8).collect();
+            let weights: Vec<i8> = (0..length).map(|_| rng.below(256) as u8 as i8).collect();
+            assert_eq!(simd::dot(&input, &weights),simd::dot_scalar(&input, &weights));
+        }
+        let input = vec![127u8; 64];
+        let weights = vec![-128i8; 64];
+        assert_eq!(simd::dot(&input, &weights),-127 * 128 * 64);
+    }
+}
@@ -1,18 +1,69 @@
 use crate::operator::Operator;
 use crate::operator::OperatorMessage;
 use crate::operator::message;
+use crate::operator::CommunicationProtocol::UCI;
+use crate::operator::CommunicationProtocol::ChessEngineCommunicationProtocol;
+use crate::orchestrator::Engine;
+use crate::book::options::BookSelection;
+use crate::reset::variant::Variant;
+use crate::reset::variant::VARIANTS;
 
 impl Operator {
 
     // Process an instruction, communicating with the Orchestrator as appropriate.
-    pub fn process_instruction(&mut self, instruction: &String) {
-        println!("Board Input == \"{}\"",instruction);
+    pub fn process_instruction(&mut self, instruction: &str) {
         let mut message = message::new();
-        match instruction.as_str() {
+        match instruction {
             "e2e4" => {
                 message.move_taken();
                 self.send(&message);
             }
+            "uci" => {
+                self.communication_protocol = UCI;
+                message.protocol_change(UCI);
+                self.send(&message);
+                println!("id name Chessica");
+                println!("option name MateChecksOnly type check default false");
+                println!("option name Engine type combo default AlphaBeta var AlphaBeta var MCTS");
+                println!("option name BookFile type string default <empty>");
+                println!("option name BookDepth type spin default {} min 0 max 1000",self.book_options.max_depth);
+                println!("option name BookSelection type combo default Weighted var Weighted var Best");
+                println!("option name SyzygyPath type string default <empty>");
+                println!("option name EvalFile type string default <empty>");
+                println!("option name UseNNUE type check default true");
+                println!("option name EvalParams type string default <empty>");
+                println!("option name Deterministic type check default false");
+                println!("option name Seed type string default <random>");
+                println!("option name UCI_Chess960 type check default false");
+                let variant_names: Vec<String> = VARIANTS.iter().map(|variant| format!("var {}",variant.name())).collect();
+                println!("option name UCI_Variant type combo default {} {}",Variant::Standard.name(),variant_names.join(" "));
+                println!("uciok");
+            },
+            "xboard" => {
+                self.communication_protocol = ChessEngineCommunicationProtocol;
+                message.protocol_change(ChessEngineCommunicationProtocol);
+                self.send(&message);
+            },
+            "protover 2" => {
+                let variant_names: Vec<&str> = VARIANTS.iter().map(|variant| variant.cecp_name()).collect();
+                println!("feature variants=\"{}\" done=1",variant_names.join(","));
+            },
+            "go" => {
+                message.move_taken();
+                self.send(&message);
+            },
+            "setoption name Engine value MCTS" | "option Engine=MCTS" => {
+                message.engine_change(Engine::Mcts);
+                self.send(&message);
+            },
+            "setoption name Engine value AlphaBeta" | "option Engine=AlphaBeta" => {
+                message.engine_change(Engine::AlphaBeta);
+                self.send(&message);
+            },
+            "eval" => {
+                message.eval_trace();
+                self.send(&message);
+            },
             "quit" => {
                 message.exit_program();
                 self.send(&message);
@@ -28,7 +79,125 @@ impl Operator {
                 self.send(&message);
             },
             _ => {
-
+                let words: Vec<&str> = instruction.split_whitespace().collect();
+                match words.as_slice() {
+                    ["position", setup @ ..] => {
+                        let (setup, moves) = match setup.iter().position(|word| *word == "moves") {
+                            Some(index) => (&setup[..index], &setup[index+1..]),
+                            None => (setup, &[][..]),
+                        };
+                        let fen = match setup {
+                            ["startpos"] => "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1".to_string(),
+                            ["fen", fen @ ..] if !fen.is_empty() => fen.join(" "),
+                            _ => {
+                                println!("info string Unexpected position: \"{}\"",setup.join(" "));
+                                return;
+                            },
+                        };
+                        message.new_position(fen, moves.iter().map(|word| word.to_string()).collect());
+                        self.send(&message);
+                    },
+                    ["go", "mate", moves] => {
+                        match moves.parse::<u8>() {
+                            Ok(moves) if moves > 0 => {
+                                message.mate_search(moves, self.mate_checks_only);
+                                self.send(&message);
+                            },
+                            _ => println!("info string Unexpected number of moves to mate: \"{}\"",moves),
+                        }
+                    },
+                    ["setoption", "name", "MateChecksOnly", "value", value] => {
+                        self.mate_checks_only = *value == "true";
+                    },
+                    ["setoption", "name", "BookFile", "value", path @ ..] => {
+                        let path = path.join(" ");
+                        self.book_options.path = if path.is_empty() || path == "<empty>" { None } else { Some(path) };
+                        message.book_change(self.book_options.clone());
+                        self.send(&message);
+                    },
+                    ["setoption", "name", "BookDepth", "value", depth] => {
+                        match depth.parse::<u16>() {
+                            Ok(depth) => {
+                                self.book_options.max_depth = depth;
+                                message.book_change(self.book_options.clone());
+                                self.send(&message);
+                            },
+                            _ => println!("info string Unexpected book depth: \"{}\"",depth),
+                        }
+                    },
+                    ["setoption", "name", "BookSelection", "value", selection] => {
+                        match *selection {
+                            "Weighted" => self.book_options.selection = BookSelection::Weighted,
+                            "Best" => self.book_options.selection = BookSelection::Best,
+                            _ => {
+                                println!("info string Unexpected book selection: \"{}\"",selection);
+                                return;
+                            },
+                        }
+                        message.book_change(self.book_options.clone());
+                        self.send(&message);
+                    },
+                    ["setoption", "name", "SyzygyPath", "value", path @ ..] => {
+                        let path = path.join(" ");
+                        let path = if path == "<empty>" { String::new() } else { path };
+                        match crate::syzygy::init(&path) {
+                            Ok(found) => println!("info string found {} Syzygy tables, up to {} pieces",found,crate::syzygy::max_pieces()),
+                            Err(e) => println!("info string could not read Syzygy tables from \"{}\": {}",path,e),
+                        }
+                    },
+                    ["setoption", "name", "EvalFile", "value", path @ ..] => {
+                        let path = path.join(" ");
+                        let path = if path == "<empty>" { String::new() } else { path };
+                        match crate::nnue::init(&path) {
+                            Ok(()) => println!("info string NNUE evaluation {}",if path.is_empty() { "off".to_string() } else { format!("using {}",path) }),
+                            Err(e) => println!("info string could not read network \"{}\": {}",path,e),
+                        }
+                    },
+                    ["setoption", "name", "UseNNUE", "value", value] => {
+                        crate::nnue::set_enabled(*value == "true");
+                    },
+                    ["setoption", "name", "UCI_Chess960", "value", value] => {
+                        crate::reset::chess960::set_enabled(*value == "true");
+                    },
+                    ["setoption", "name", "UCI_Variant", "value", name] | ["variant", name] => {
+                        match Variant::from_name(name) {
+                            Some(variant) => {
+                                message.variant_change(variant);
+                                self.send(&message);
+                            },
+                            None => println!("info string Unexpected variant: \"{}\"",name),
+                        }
+                    },
+                    ["setoption", "name", "Deterministic", "value", value] => {
+                        self.deterministic = *value == "true";
+                        message.determinism_change(self.deterministic, self.seed);
+                        self.send(&message);
+                    },
+                    ["setoption", "name", "Seed", "value", value] => {
+                        let seed = match *value {
+                            "<random>" => None,
+                            _ => match value.parse::<u64>() {
+                                Ok(seed) => Some(seed),
+                                Err(_) => {
+                                    println!("info string Unexpected seed: \"{}\"",value);
+                                    return;
+                                },
+                            },
+                        };
+                        self.seed = seed;
+                        message.determinism_change(self.deterministic, self.seed);
+                        self.send(&message);
+                    },
+                    ["setoption", "name", "EvalParams", "value", path @ ..] => {
+                        let path = path.join(" ");
+                        if path.is_empty() || path == "<empty>" {
+                            crate::eval::set_params(crate::eval::new());
+                        } else if let Err(e) = crate::eval::init(&path) {
+                            println!("info string could not read evaluation parameters \"{}\": {}",path,e);
+                        }
+                    },
+                    _ => {},
+                }
             },
         }
      }
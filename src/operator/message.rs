@@ -5,6 +5,13 @@ pub enum OperatorInstruction {
     MoveTaken,
     PlayerStatusChange,
     ExitProgram,
+    MateSearch,
+    EngineChange,
+    ProtocolChange,
+    BookChange,
+    EvalTrace,
+    DeterminismChange,
+    VariantChange,
 }
 
 use crate::operator::message::OperatorInstruction::PlaceHolder;
@@ -12,21 +19,50 @@ use crate::operator::message::OperatorInstruction::NewBoard;
 use crate::operator::message::OperatorInstruction::MoveTaken;
 use crate::operator::message::OperatorInstruction::PlayerStatusChange;
 use crate::operator::message::OperatorInstruction::ExitProgram;
+use crate::operator::message::OperatorInstruction::MateSearch;
+use crate::operator::message::OperatorInstruction::EngineChange;
+use crate::operator::message::OperatorInstruction::ProtocolChange;
+use crate::operator::message::OperatorInstruction::BookChange;
+use crate::operator::message::OperatorInstruction::EvalTrace;
+use crate::operator::message::OperatorInstruction::DeterminismChange;
+use crate::operator::message::OperatorInstruction::VariantChange;
+use crate::operator::CommunicationProtocol;
+use crate::orchestrator::Engine;
+use crate::book::options::BookOptions;
+use crate::reset::variant::Variant;
 
 #[derive(PartialEq,Eq,Clone,Hash,Debug)]
 pub struct OperatorMessage {
     pub instruction: OperatorInstruction,
     pub data_string: String,
+    pub moves: Vec<String>,
     pub computer_white: bool,
     pub computer_black: bool,
+    pub mate_moves: u8,
+    pub checks_only: bool,
+    pub engine: Engine,
+    pub communication_protocol: CommunicationProtocol,
+    pub book_options: BookOptions,
+    pub deterministic: bool,
+    pub seed: Option<u64>,
+    pub variant: Variant,
 }
 
 pub fn new() -> OperatorMessage {
     OperatorMessage {
         instruction: PlaceHolder,
         data_string: "".to_string(),
+        moves: Vec::new(),
         computer_white: false,
         computer_black: false,
+        mate_moves: 0,
+        checks_only: false,
+        engine: Engine::AlphaBeta,
+        communication_protocol: CommunicationProtocol::ChessEngineCommunicationProtocol,
+        book_options: crate::book::options::new(),
+        deterministic: false,
+        seed: None,
+        variant: Variant::Standard,
     }
 }
 
@@ -36,6 +72,13 @@ impl OperatorMessage {
         self.data_string = fen;
     }
 
+    /// A new board reached by playing `moves` (in UCI notation) from `fen`
+    pub fn new_position(&mut self, fen: String, moves: Vec<String>) {
+        self.instruction = NewBoard;
+        self.data_string = fen;
+        self.moves = moves;
+    }
+
     pub fn move_taken(&mut self) {
         self.instruction = MoveTaken;
     }
@@ -50,5 +93,41 @@ impl OperatorMessage {
         self.instruction = ExitProgram;
     }
 
+    pub fn engine_change(&mut self, engine: Engine) {
+        self.instruction = EngineChange;
+        self.engine = engine;
+    }
+
+    pub fn protocol_change(&mut self, communication_protocol: CommunicationProtocol) {
+        self.instruction = ProtocolChange;
+        self.communication_protocol = communication_protocol;
+    }
+
+    pub fn book_change(&mut self, book_options: BookOptions) {
+        self.instruction = BookChange;
+        self.book_options = book_options;
+    }
+
+    pub fn determinism_change(&mut self, deterministic: bool, seed: Option<u64>) {
+        self.instruction = DeterminismChange;
+        self.deterministic = deterministic;
+        self.seed = seed;
+    }
+
+    pub fn variant_change(&mut self, variant: Variant) {
+        self.instruction = VariantChange;
+        self.variant = variant;
+    }
+
+    pub fn eval_trace(&mut self) {
+        self.instruction = EvalTrace;
+    }
+
+    pub fn mate_search(&mut self, mate_moves: u8, checks_only: bool) {
+        self.instruction = MateSearch;
+        self.mate_moves = mate_moves;
+        self.checks_only = checks_only;
+    }
+
 }
 
@@ -1,10 +1,12 @@
 pub mod message;
 pub mod instructions;
+pub mod score;
 
 use std::thread;
 use std::sync::mpsc;
 use std::sync::mpsc::Sender;
 use crate::operator::message::OperatorMessage;
+use crate::book::options::BookOptions;
 
 #[derive(PartialEq,Eq,Copy,Clone,Hash,Debug)]
 pub enum CommunicationProtocol {
@@ -24,8 +26,11 @@ pub struct Operator {
     black_is_engine: bool,
     #[allow(dead_code)] //todo: Get rid of this
     game_fen: String,
-    #[allow(dead_code)] //todo: Get rid of this
     communication_protocol: CommunicationProtocol,
+    mate_checks_only: bool,
+    pub book_options: BookOptions,
+    pub deterministic: bool,
+    pub seed: Option<u64>,
     orchestrator_transmit_channel: Option<Sender<OperatorMessage>>,
 }
 
@@ -44,6 +49,10 @@ pub fn new() -> Operator {
         black_is_engine: false,
         game_fen: "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1".to_string(),
         communication_protocol: ChessEngineCommunicationProtocol,
+        mate_checks_only: false,
+        book_options: crate::book::options::new(),
+        deterministic: false,
+        seed: None,
         orchestrator_transmit_channel: None
     }
 }
@@ -72,6 +81,18 @@ impl Operator {
 
 
         println!("Spawned!");
+        // Hand over any opening book chosen on the command line
+        if self.book_options.path.is_some() {
+            let mut message = crate::operator::message::new();
+            message.book_change(self.book_options.clone());
+            self.send(&message);
+        }
+        // ... and deterministic mode or a fixed seed
+        if self.deterministic || self.seed.is_some() {
+            let mut message = crate::operator::message::new();
+            message.determinism_change(self.deterministic, self.seed);
+            self.send(&message);
+        }
         // Now we need to do all the Operator things
 
         loop {
@@ -95,6 +116,7 @@ impl Operator {
 mod tests {
     use crate::operator;
     use crate::operator::CommunicationProtocol::ChessEngineCommunicationProtocol;
+    use crate::operator::CommunicationProtocol::UCI;
 
     #[test]
     fn new_operator() {
@@ -103,6 +125,169 @@ mod tests {
         assert!(!o.black_is_engine);
         assert_eq!(o.game_fen,"rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1".to_string());
         assert_eq!(o.communication_protocol,ChessEngineCommunicationProtocol);
+        assert!(!o.mate_checks_only);
+        assert_eq!(o.book_options.path,None);
+        assert!(!o.deterministic);
+        assert_eq!(o.seed,None);
+    }
+
+    #[test]
+    fn operator_uci_mate_search() {
+        use std::sync::mpsc;
+        use crate::operator::message::OperatorInstruction::NewBoard;
+        use crate::operator::message::OperatorInstruction::MateSearch;
+
+        let mut o = operator::new();
+        let (tx, rx) = mpsc::channel();
+        o.orchestrator_transmit_channel = Some(tx);
+        o.process_instruction("uci");
+        assert_eq!(rx.try_recv().unwrap().communication_protocol,UCI);
+        o.process_instruction("position fen k7/8/2K5/8/8/8/8/7R w - - 0 1");
+        o.process_instruction("setoption name MateChecksOnly value true");
+        o.process_instruction("go mate 2");
+        o.process_instruction("go mate soon");
+
+        let message = rx.try_recv().unwrap();
+        assert_eq!(message.instruction,NewBoard);
+        assert_eq!(message.data_string,"k7/8/2K5/8/8/8/8/7R w - - 0 1");
+        let message = rx.try_recv().unwrap();
+        assert_eq!(message.instruction,MateSearch);
+        assert_eq!(message.mate_moves,2);
+        assert!(message.checks_only);
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn operator_uci_position_moves() {
+        use std::sync::mpsc;
+        use crate::operator::message::OperatorInstruction::NewBoard;
+
+        let mut o = operator::new();
+        let (tx, rx) = mpsc::channel();
+        o.orchestrator_transmit_channel = Some(tx);
+        o.process_instruction("position startpos");
+        o.process_instruction("position startpos moves e2e4 e7e5");
+        o.process_instruction("position fen k7/8/2K5/8/8/8/8/7R w - - 0 1 moves c6b6 a8b8");
+        o.process_instruction("position fen");
+        o.process_instruction("position somewhere");
+
+        let message = rx.try_recv().unwrap();
+        assert_eq!(message.instruction,NewBoard);
+        assert_eq!(message.data_string,"rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+        assert!(message.moves.is_empty());
+        let message = rx.try_recv().unwrap();
+        assert_eq!(message.data_string,"rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+        assert_eq!(message.moves,vec!["e2e4","e7e5"]);
+        let message = rx.try_recv().unwrap();
+        assert_eq!(message.data_string,"k7/8/2K5/8/8/8/8/7R w - - 0 1");
+        assert_eq!(message.moves,vec!["c6b6","a8b8"]);
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn operator_select_engine() {
+        use std::sync::mpsc;
+        use crate::orchestrator::Engine;
+        use crate::operator::message::OperatorInstruction::EngineChange;
+        use crate::operator::message::OperatorInstruction::MoveTaken;
+
+        let mut o = operator::new();
+        let (tx, rx) = mpsc::channel();
+        o.orchestrator_transmit_channel = Some(tx);
+        o.process_instruction("option Engine=MCTS");
+        o.process_instruction("go");
+        o.process_instruction("setoption name Engine value AlphaBeta");
+
+        let message = rx.try_recv().unwrap();
+        assert_eq!(message.instruction,EngineChange);
+        assert_eq!(message.engine,Engine::Mcts);
+        assert_eq!(rx.try_recv().unwrap().instruction,MoveTaken);
+        assert_eq!(rx.try_recv().unwrap().engine,Engine::AlphaBeta);
+    }
+
+    #[test]
+    fn operator_book_options() {
+        use std::sync::mpsc;
+        use crate::book::options::BookSelection;
+        use crate::operator::message::OperatorInstruction::BookChange;
+
+        let mut o = operator::new();
+        let (tx, rx) = mpsc::channel();
+        o.orchestrator_transmit_channel = Some(tx);
+        o.process_instruction("setoption name BookFile value /tmp/my book.bin");
+        o.process_instruction("setoption name BookDepth value 12");
+        o.process_instruction("setoption name BookDepth value lots");
+        o.process_instruction("setoption name BookSelection value Best");
+
+        let message = rx.try_recv().unwrap();
+        assert_eq!(message.instruction,BookChange);
+        assert_eq!(message.book_options.path,Some("/tmp/my book.bin".to_string()));
+        assert_eq!(rx.try_recv().unwrap().book_options.max_depth,12);
+        let message = rx.try_recv().unwrap();
+        assert_eq!(message.book_options.max_depth,12);
+        assert_eq!(message.book_options.selection,BookSelection::Best);
+        assert!(rx.try_recv().is_err());
+        assert_eq!(o.book_options.selection,BookSelection::Best);
+    }
+
+    #[test]
+    fn operator_eval_trace() {
+        use std::sync::mpsc;
+        use crate::operator::message::OperatorInstruction::EvalTrace;
+
+        let mut o = operator::new();
+        let (tx, rx) = mpsc::channel();
+        o.orchestrator_transmit_channel = Some(tx);
+        o.process_instruction("eval");
+        assert_eq!(rx.try_recv().unwrap().instruction,EvalTrace);
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn operator_determinism_options() {
+        use std::sync::mpsc;
+        use crate::operator::message::OperatorInstruction::DeterminismChange;
+
+        let mut o = operator::new();
+        let (tx, rx) = mpsc::channel();
+        o.orchestrator_transmit_channel = Some(tx);
+        o.process_instruction("setoption name Seed value 42");
+        o.process_instruction("setoption name Deterministic value true");
+        o.process_instruction("setoption name Seed value soon");
+        o.process_instruction("setoption name Seed value <random>");
+
+        let message = rx.try_recv().unwrap();
+        assert_eq!(message.instruction,DeterminismChange);
+        assert!(!message.deterministic);
+        assert_eq!(message.seed,Some(42));
+        let message = rx.try_recv().unwrap();
+        assert!(message.deterministic);
+        assert_eq!(message.seed,Some(42));
+        let message = rx.try_recv().unwrap();
+        assert!(message.deterministic);
+        assert_eq!(message.seed,None);
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn operator_variant_selection() {
+        use std::sync::mpsc;
+        use crate::operator::message::OperatorInstruction::VariantChange;
+        use crate::reset::variant::Variant;
+
+        let mut o = operator::new();
+        let (tx, rx) = mpsc::channel();
+        o.orchestrator_transmit_channel = Some(tx);
+        o.process_instruction("setoption name UCI_Variant value crazyhouse");
+        o.process_instruction("variant bughouse");
+        o.process_instruction("variant normal");
+
+        let message = rx.try_recv().unwrap();
+        assert_eq!(message.instruction,VariantChange);
+        assert_eq!(message.variant,Variant::Crazyhouse);
+        let message = rx.try_recv().unwrap();
+        assert_eq!(message.variant,Variant::Standard);
+        assert!(rx.try_recv().is_err());
     }
 
 }
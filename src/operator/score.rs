@@ -0,0 +1,114 @@
+use crate::operator::Operator;
+use crate::operator::CommunicationProtocol;
+use crate::operator::CommunicationProtocol::UCI;
+use crate::operator::CommunicationProtocol::ChessEngineCommunicationProtocol;
+use crate::reset::mate;
+use crate::reset::r#const::SCORE_PAWN;
+
+/// CECP reports "mate in N" as 100000 + N and "mated in N" as -100000 - N
+pub const CECP_MATE_SCORE: i32 = 100000;
+
+impl Operator {
+
+    /// Format a search score the way the current protocol expects it
+    ///
+    /// See [`score_text`] for the formats.
+    pub fn score_text(&self, score: i32, white_to_move: bool) -> String {
+        score_text(self.communication_protocol, score, white_to_move)
+    }
+
+}
+
+/// Format a search score the way `protocol` expects it
+///
+/// Search scores are absolute (positive favours white) while both protocols want them from
+/// the point of view of the side to move, in centipawns.
+///
+/// | protocol | normal score | side to move mates in N | side to move is mated in N |
+/// | -------- | ------------ | ----------------------- | -------------------------- |
+/// | UCI      | `cp 35`      | `mate N`                | `mate -N`                  |
+/// | CECP     | `35`         | `100000+N`              | `-100000-N`                |
+///
+/// # Examples
+///
+/// ```
+/// use chessica::operator::CommunicationProtocol::UCI;
+/// use chessica::operator::score::score_text;
+/// use chessica::reset::mate::checkmate_score;
+/// assert_eq!(score_text(UCI, checkmate_score(false, 3), true),"mate 2");
+/// ```
+pub fn score_text(protocol: CommunicationProtocol, score: i32, white_to_move: bool) -> String {
+    match mate::mate_in_moves(score, white_to_move) {
+        Some(moves) => match protocol {
+            UCI => format!("mate {}",moves),
+            ChessEngineCommunicationProtocol => {
+                if moves > 0 {
+                    format!("{}",CECP_MATE_SCORE + moves)
+                } else {
+                    format!("{}",-CECP_MATE_SCORE + moves)
+                }
+            },
+        },
+        None => {
+            let centipawns = score / (SCORE_PAWN / 100);
+            let centipawns = if white_to_move { centipawns } else { -centipawns };
+            match protocol {
+                UCI => format!("cp {}",centipawns),
+                ChessEngineCommunicationProtocol => format!("{}",centipawns),
+            }
+        },
+    }
+}
+
+/// Format one search result: a UCI `info` line, or a CECP post line (`ply score time nodes pv`)
+///
+/// # Examples
+///
+/// ```
+/// use chessica::operator::CommunicationProtocol::ChessEngineCommunicationProtocol;
+/// use chessica::operator::score::search_report;
+/// assert_eq!(search_report(ChessEngineCommunicationProtocol, 4, 350_000, false, 1234, "e7e5"),"4 -35 0 1234 e7e5");
+/// ```
+pub fn search_report(protocol: CommunicationProtocol, depth: u8, score: i32, white_to_move: bool, nodes: u64, pv: &str) -> String {
+    let score = score_text(protocol, score, white_to_move);
+    match protocol {
+        UCI => format!("info depth {} score {} nodes {} pv {}",depth,score,nodes,pv),
+        ChessEngineCommunicationProtocol => format!("{} {} 0 {} {}",depth,score,nodes,pv),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::operator;
+    use crate::operator::CommunicationProtocol::UCI;
+    use crate::reset::mate::checkmate_score;
+
+    #[test]
+    fn score_text_cecp() {
+        let o = operator::new();
+        assert_eq!(o.score_text(1_500_000, true),"150");
+        assert_eq!(o.score_text(1_500_000, false),"-150");
+        assert_eq!(o.score_text(checkmate_score(false, 3), true),"100002");
+        assert_eq!(o.score_text(checkmate_score(false, 3), false),"-100002");
+    }
+
+    #[test]
+    fn score_text_uci() {
+        let mut o = operator::new();
+        o.communication_protocol = UCI;
+        assert_eq!(o.score_text(-2_000_000, true),"cp -200");
+        assert_eq!(o.score_text(checkmate_score(false, 1), true),"mate 1");
+        assert_eq!(o.score_text(checkmate_score(true, 4), true),"mate -2");
+        assert_eq!(o.score_text(checkmate_score(true, 4), false),"mate 2");
+    }
+
+    #[test]
+    fn search_report_lines() {
+        use crate::operator::CommunicationProtocol::ChessEngineCommunicationProtocol;
+        use crate::operator::score::search_report;
+        let mate = checkmate_score(true, 3);
+        assert_eq!(search_report(UCI, 3, mate, false, 500, "a1a8 b8a8"),"info depth 3 score mate 2 nodes 500 pv a1a8 b8a8");
+        assert_eq!(search_report(ChessEngineCommunicationProtocol, 3, mate, false, 500, "a1a8"),"3 100002 0 500 a1a8");
+        assert_eq!(search_report(UCI, 6, 250_000, true, 9, "e2e4"),"info depth 6 score cp 25 nodes 9 pv e2e4");
+    }
+}
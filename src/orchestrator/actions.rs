@@ -4,39 +4,78 @@ use crate::operator::message::OperatorInstruction::NewBoard;
 use crate::operator::message::OperatorInstruction::MoveTaken;
 use crate::operator::message::OperatorInstruction::PlayerStatusChange;
 use crate::operator::message::OperatorInstruction::ExitProgram;
+use crate::operator::message::OperatorInstruction::MateSearch;
+use crate::operator::message::OperatorInstruction::EngineChange;
+use crate::operator::message::OperatorInstruction::ProtocolChange;
+use crate::operator::message::OperatorInstruction::BookChange;
+use crate::operator::message::OperatorInstruction::EvalTrace;
+use crate::operator::message::OperatorInstruction::DeterminismChange;
+use crate::operator::message::OperatorInstruction::VariantChange;
+use crate::operator::CommunicationProtocol::UCI;
+use crate::operator::CommunicationProtocol::ChessEngineCommunicationProtocol;
+use crate::orchestrator::Engine;
 use crate::tree;
+use crate::reset::variant::Variant;
+use crate::reset::fen::FenError;
 use std::thread;
 use std::sync::{Arc, Barrier, Mutex};
 use crate::reset::r#const::SCORE_MIN;
 use crate::reset::r#const::SCORE_MAX;
 use std::sync::atomic::{AtomicI32,Ordering};
 use crate::cogitator;
+use crate::utils::rng;
+use crate::orchestrator::time_seed;
+use crate::orchestrator::r#const::SEARCH_THREADS_DEFAULT;
 
 impl Orchestrator {
 
     pub fn process_command(&mut self, received_message: OperatorMessage) -> bool {
             match received_message.instruction {
                 MoveTaken => {
-                    self.launch_cogitators();
+                    if self.book_move().is_some() || self.tablebase_move().is_some() {
+                        return false;
+                    }
+                    match self.engine {
+                        Engine::AlphaBeta => self.launch_cogitators(),
+                        Engine::Mcts => {
+                            self.mcts_move();
+                        },
+                    }
+                },
+                EngineChange => {
+                    self.engine = received_message.engine;
+                },
+                ProtocolChange => {
+                    self.communication_protocol = received_message.communication_protocol;
+                },
+                BookChange => {
+                    self.set_book_options(received_message.book_options);
                 },
                 NewBoard => {
-                    self.tree_root = tree::from_fen(received_message.data_string);
-                    //Do I need to initialize move generation??
-                    loop {
-                        let mut child = crate::tree::new();
-                        if self.tree_root.get_next_child(&mut child) {
-                            let serialized_child = Arc::new(Mutex::new(child));
-                            self.tree_children.push(serialized_child);
-                        } else {
-                            break;
-                        }
+                    if let Err(error) = self.new_position(received_message.data_string.clone(), &received_message.moves) {
+                        println!("{}",self.note_line(&error));
+                    }
+                },
+                VariantChange => {
+                    // CECP names the variant after "new", so the board already set up is replayed
+                    // under the new rules
+                    self.variant = received_message.variant;
+                    let fen = self.tree_root.to_fen();
+                    if let Err(error) = self.new_board(fen) {
+                        println!("{}",self.note_line(&format!("the board isn't legal in {}: {}",self.variant.name(),error)));
                     }
-                    let mut m: u64 = 0;
-
-                    self.tree_root.simple_move_tree(1,&mut m);
                 },
                 PlayerStatusChange => {
 
+                },
+                DeterminismChange => {
+                    self.set_determinism(received_message.deterministic, received_message.seed);
+                },
+                EvalTrace => {
+                    self.print_eval_trace();
+                },
+                MateSearch => {
+                    self.mate_search(received_message.mate_moves, received_message.checks_only);
                 },
                 ExitProgram => {
                     self.close_cogitators();
@@ -48,6 +87,215 @@ impl Orchestrator {
             false
     }
 
+    /// Tell the chess board which move we're playing
+    pub fn announce_move(&self, move_text: &str) {
+        println!("{}",self.move_announcement(move_text));
+    }
+
+    /// The line that tells the chess board which move we're playing
+    pub fn move_announcement(&self, move_text: &str) -> String {
+        match self.communication_protocol {
+            UCI => format!("bestmove {}",move_text),
+            ChessEngineCommunicationProtocol => format!("move {}",move_text),
+        }
+    }
+
+    /// A line telling the chess board something that isn't a command: "info string" for UCI, a
+    /// "#" debug line for CECP
+    pub fn note_line(&self, text: &str) -> String {
+        match self.communication_protocol {
+            UCI => format!("info string {}",text),
+            ChessEngineCommunicationProtocol => format!("# {}",text),
+        }
+    }
+
+    /// Show how the current position is evaluated: beside the board for UCI, and as "#" debug
+    /// lines for CECP, where anything else would be taken for a command
+    pub fn print_eval_trace(&mut self) {
+        match self.communication_protocol {
+            UCI => self.tree_root.reset.print_eval(),
+            ChessEngineCommunicationProtocol => {
+                for line in crate::eval::trace::trace(&mut self.tree_root.reset).lines() {
+                    println!("# {}",line);
+                }
+            },
+        }
+    }
+
+    /// Set up the board from a FEN, played by the current variant's rules
+    ///
+    /// A FEN that names its variant (a Crazyhouse pocket) keeps it.  One that can't be read, or
+    /// doesn't describe a legal position by those rules, leaves the board as it was.
+    pub fn new_board(&mut self, fen: String) -> Result<(), FenError> {
+        let reset = self.board_from_fen(&fen)?;
+        self.set_board(reset);
+        Ok(())
+    }
+
+    /// Set up the board from a FEN followed by moves in UCI notation, as UCI's `position` sends
+    /// them
+    ///
+    /// A FEN that `new_board` wouldn't take, or a move that isn't legal where it's played,
+    /// leaves the board as it was; the error says which.
+    pub fn new_position(&mut self, fen: String, moves: &[String]) -> Result<(), String> {
+        let mut reset = self.board_from_fen(&fen).map_err(|error| format!("invalid FEN \"{}\": {}",fen,error))?;
+        for text in moves {
+            reset = match reset.child_from_uci(text) {
+                Some(child) => child,
+                None => return Err(format!("illegal move \"{}\" in {}",text,reset.to_fen())),
+            };
+        }
+        reset.restart_move_generation();
+        self.set_board(reset);
+        Ok(())
+    }
+
+    /// The position a FEN describes, checked against the current variant's rules
+    fn board_from_fen(&self, fen: &str) -> Result<crate::reset::Reset, FenError> {
+        let mut reset = crate::reset::new();
+        reset.parse_fen(fen)?;
+        if self.variant != Variant::Standard {
+            reset.set_variant(self.variant);
+        }
+        reset.validate_position()?;
+        Ok(reset)
+    }
+
+    /// Make `reset` the board to play from
+    fn set_board(&mut self, reset: crate::reset::Reset) {
+        self.tree_root = tree::new();
+        self.tree_root.reset = reset;
+        self.tree_children.clear();
+        //Do I need to initialize move generation??
+        loop {
+            let mut child = crate::tree::new();
+            if self.tree_root.get_next_child(&mut child) {
+                let serialized_child = Arc::new(Mutex::new(child));
+                self.tree_children.push(serialized_child);
+            } else {
+                break;
+            }
+        }
+        let mut m: u64 = 0;
+
+        self.tree_root.simple_move_tree(1,&mut m);
+    }
+
+    /// Switch deterministic mode on or off, and seed the random choices we do want
+    ///
+    /// Deterministic mode searches with a single Cogitator, so no thread races another for a
+    /// child, and drops the evaluation's tie-breaking noise.  The seed drives the book's move
+    /// choice and the MCTS rollouts; without one, deterministic mode uses 0 and normal play a
+    /// seed taken from the clock.
+    pub fn set_determinism(&mut self, deterministic: bool, seed: Option<u64>) {
+        self.cogitator_thread_count = if deterministic { 1 } else { SEARCH_THREADS_DEFAULT };
+        crate::eval::set_noise(!deterministic);
+        let seed = seed.unwrap_or_else(|| if deterministic { 0 } else { time_seed() });
+        self.book_rng = rng::new(seed);
+        self.mcts_options.seed = seed;
+    }
+
+    /// Use new book settings, (re)loading the book file if it changed
+    pub fn set_book_options(&mut self, book_options: crate::book::options::BookOptions) {
+        if book_options.path != self.book_options.path || self.book.is_none() {
+            self.book = match &book_options.path {
+                Some(path) => match crate::book::from_file(path) {
+                    Ok(book) => {
+                        println!("# loaded {} book entries from {}",book.entries.len(),path);
+                        Some(book)
+                    },
+                    Err(e) => {
+                        println!("# could not read book {}: {}",path,e);
+                        None
+                    },
+                },
+                None => None,
+            };
+        }
+        self.book_options = book_options;
+    }
+
+    /// Play a move straight from the opening book, if it has one for the current board
+    ///
+    /// Books cover standard chess only.
+    pub fn book_move(&mut self) -> Option<String> {
+        let book = self.book.as_ref()?;
+        if self.tree_root.reset.variant() != Variant::Standard || self.tree_root.reset.game_ply() >= self.book_options.max_depth {
+            return None;
+        }
+        let mut child = book.choose_move(&mut self.tree_root.reset, self.book_options.selection, &mut self.book_rng)?;
+        let move_text = child.uci_move_text();
+        println!("# book move {}",move_text);
+        self.announce_move(&move_text);
+        Some(move_text)
+    }
+
+    /// Play the DTZ-optimal move if the board is covered by the Syzygy tables
+    ///
+    /// The tables cover standard chess only.
+    pub fn tablebase_move(&mut self) -> Option<String> {
+        if self.tree_root.reset.variant() != Variant::Standard || self.tree_root.reset.piece_count() > crate::syzygy::max_pieces() {
+            return None;
+        }
+        let (mut child, dtz) = crate::syzygy::probe::root_move(&self.tree_root.reset)?;
+        let move_text = child.uci_move_text();
+        println!("# tablebase move {} dtz == {}",move_text,dtz);
+        self.announce_move(&move_text);
+        Some(move_text)
+    }
+
+    /// Pick a move with Monte Carlo tree search instead of the Cogitators
+    pub fn mcts_move(&mut self) -> Option<String> {
+        let mut move_count: u64 = 0;
+        let best = self.tree_root.mcts_search(&self.mcts_options, &mut move_count)?;
+        let child = &mut self.tree_root.children[best];
+        let move_text = child.reset.uci_move_text();
+        println!("# MCTS: {} visits == {} value == {:.3} nodes == {}",move_text,child.visits,child.mcts_value(),move_count);
+        self.announce_move(&move_text);
+        Some(move_text)
+    }
+
+    /// Format a search result for the current protocol and board
+    pub fn search_report(&self, depth: u8, score: i32, nodes: u64, pv: &str) -> String {
+        crate::operator::score::search_report(self.communication_protocol, depth, score, self.tree_root.reset.white_to_move(), nodes, pv)
+    }
+
+    /// Prove or disprove a forced mate from the current board and report the result
+    pub fn mate_search(&mut self, mate_moves: u8, checks_only: bool) -> Option<u8> {
+        let (result, report) = self.mate_search_report(mate_moves, checks_only);
+        for line in report {
+            println!("{}",line);
+        }
+        result
+    }
+
+    /// Run a mate search, returning the lines that report its result
+    pub fn mate_search_report(&mut self, mate_moves: u8, checks_only: bool) -> (Option<u8>, Vec<String>) {
+        let mut move_count: u64 = 0;
+        let result = self.tree_root.mate_search(mate_moves, checks_only, &mut move_count);
+        let mut report = Vec::new();
+        match result {
+            Some(moves) => {
+                let line = self.tree_root.mating_line();
+                let plies = moves * 2 - 1;
+                let score = crate::reset::mate::checkmate_score(!self.tree_root.reset.white_to_move(), plies);
+                report.push(self.search_report(plies, score, move_count, &line));
+                report.push(self.move_announcement(line.split_whitespace().next().unwrap()));
+            },
+            None => match self.communication_protocol {
+                UCI => {
+                    report.push(format!("info string No forced mate in {} moves, nodes {}",mate_moves,move_count));
+                    report.push(self.move_announcement("0000"));
+                },
+                // CECP has no null move to play, so only say so in a comment
+                ChessEngineCommunicationProtocol => {
+                    report.push(format!("# No forced mate in {} moves, nodes {}",mate_moves,move_count));
+                },
+            },
+        }
+        (result, report)
+    }
+
     pub fn launch_cogitators(&mut self) {
         //Shared variables
         let barrier = Arc::new(Barrier::new(self.cogitator_thread_count.into()));
@@ -68,8 +316,12 @@ impl Orchestrator {
             );
 
             cogitator.set_child_list(self.tree_children.clone());
+            cogitator.set_search_options(self.search_options);
+            cogitator.set_communication_protocol(self.communication_protocol);
 
+            let noise = crate::eval::noise_enabled();
             let handle = thread::spawn(move || {
+                crate::eval::set_noise(noise);
                 cogitator.run();
             });
             self.cogitator_handles.push(handle);
@@ -90,8 +342,7 @@ impl Orchestrator {
     pub fn close_cogitators(&mut self) {
         self.pause_cogitation();
         self.exit_signal.store(true,Ordering::Relaxed);
-        while !self.cogitator_handles.is_empty() {
-            let handle = self.cogitator_handles.pop().unwrap();
+        while let Some(handle) = self.cogitator_handles.pop() {
             handle.join().unwrap();
         }
     }
@@ -119,6 +370,153 @@ mod tests {
         let mut o = orchestrator::new();
         assert!(!o.process_command(message)); //returns `false` to go on
         assert_eq!(o.tree_children.len(),9);
+
+        // A FEN that can't be read, or isn't a legal position, leaves the board alone
+        let mut message = message::new();
+        message.new_board(String::from("k7/p7/P7/8/8/6Bp/7P/7K w - - 0"));
+        assert!(!o.process_command(message));
+        let mut message = message::new();
+        message.new_board(String::from("k7/p7/P7/8/8/6Bp/7P/7K w KQ - 0 1"));
+        assert!(!o.process_command(message));
+        assert_eq!(o.tree_root.to_fen(),"k7/p7/P7/8/8/6Bp/7P/7K w - - 0 1");
+        assert_eq!(o.tree_children.len(),9);
+    }
+
+    #[test]
+    fn new_position() {
+        let start = String::from("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+        let moves = |text: &str| -> Vec<String> { text.split_whitespace().map(String::from).collect() };
+        let mut o = orchestrator::new();
+        let mut message = message::new();
+        message.new_position(start.clone(), moves("e2e4 e7e5 g1f3 b8c6 f1c4 g8f6 e1g1"));
+        assert!(!o.process_command(message));
+        assert_eq!(o.tree_root.to_fen(),"r1bqkb1r/pppp1ppp/2n2n2/4p3/2B1P3/5N2/PPPP1PPP/RNBQ1RK1 b kq - 5 4");
+
+        // Moves are played from the FEN given, and the search starts after them
+        let mut message = message::new();
+        message.new_position(start.clone(), moves("f2f3 e7e5 g2g4"));
+        assert!(!o.process_command(message));
+        let (result, report) = o.mate_search_report(1, false);
+        assert_eq!(result,Some(1));
+        assert_eq!(report[1],"move d8h4");
+
+        // An illegal move leaves the board alone
+        let mut message = message::new();
+        message.new_position(start, moves("e2e4 e2e4"));
+        assert!(!o.process_command(message));
+        assert_eq!(o.tree_root.to_fen(),"rnbqkbnr/pppp1ppp/8/4p3/6P1/5P2/PPPPP2P/RNBQKBNR b KQkq - 0 2");
+        assert_eq!(o.new_position("4k3/8/8/8/8/8/8/4K3 w - - 0 1".to_string(), &moves("e1e2 e8e7 e2e4")),Err("illegal move \"e2e4\" in 8/4k3/8/8/8/8/4K3/8 w - - 2 2".to_string()));
+        assert_eq!(o.note_line("illegal move"),"# illegal move");
+    }
+
+    #[test]
+    fn variant_change() {
+        use crate::reset::variant::Variant;
+        let mut o = orchestrator::new();
+        let mut message = message::new();
+        message.new_board(String::from("4k3/8/8/8/8/8/8/4K3 w - - 0 1"));
+        assert!(!o.process_command(message));
+        let mut message = message::new();
+        message.variant_change(Variant::Crazyhouse);
+        assert!(!o.process_command(message));
+        assert_eq!(o.tree_root.reset.variant(),Variant::Crazyhouse);
+        assert_eq!(o.tree_root.to_fen(),"4k3/8/8/8/8/8/8/4K3[] w - - 0 1");
+        assert_eq!(o.tree_children.len(),5);
+
+        // Later boards are played by the same rules
+        let mut message = message::new();
+        message.new_board(String::from("4k3/8/8/8/8/8/8/4K3 w - - 0 1"));
+        assert!(!o.process_command(message));
+        assert_eq!(o.tree_root.reset.variant(),Variant::Crazyhouse);
+    }
+
+    #[test]
+    fn mate_search() {
+        let mut message = message::new();
+        message.new_board(String::from("r5k1/5ppp/8/8/8/8/4RPPP/4R1K1 w - - 0 1"));
+        let mut o = orchestrator::new();
+        assert!(!o.process_command(message));
+        let mut message = message::new();
+        message.mate_search(3, false);
+        assert!(!o.process_command(message));
+        assert_eq!(o.tree_root.mating_line(),"e2e8 a8e8 e1e8");
+        assert_eq!(o.mate_search(1, false),None);
+    }
+
+    #[test]
+    fn mate_search_report() {
+        use crate::operator::CommunicationProtocol::UCI;
+        let mut o = orchestrator::new();
+        let mut message = message::new();
+        message.new_board(String::from("r5k1/5ppp/8/8/8/8/4RPPP/4R1K1 w - - 0 1"));
+        assert!(!o.process_command(message));
+        let (result, report) = o.mate_search_report(3, false);
+        assert_eq!(result,Some(2));
+        assert!(report[0].starts_with("3 100002 0 "));
+        assert!(report[0].ends_with(" e2e8 a8e8 e1e8"));
+        assert_eq!(report[1],"move e2e8");
+        let (result, report) = o.mate_search_report(1, false);
+        assert_eq!(result,None);
+        assert_eq!(report.len(),1);
+        assert!(report[0].starts_with("# No forced mate in 1 moves"));
+
+        let mut message = message::new();
+        message.protocol_change(UCI);
+        assert!(!o.process_command(message));
+        let (_, report) = o.mate_search_report(3, false);
+        assert!(report[0].starts_with("info depth 3 score mate 2 nodes "));
+        assert!(report[0].ends_with(" pv e2e8 a8e8 e1e8"));
+        assert_eq!(report[1],"bestmove e2e8");
+        let (_, report) = o.mate_search_report(1, false);
+        assert!(report[0].starts_with("info string No forced mate in 1 moves"));
+        assert_eq!(report[1],"bestmove 0000");
+    }
+
+    #[test]
+    fn book_move() {
+        use crate::book::BookEntry;
+        let fen = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+        let mut r = crate::reset::new();
+        r.init_from_fen(fen.to_string());
+        let path = std::env::temp_dir().join(format!("chessica_book_{}.bin",std::process::id()));
+        std::fs::write(&path, BookEntry { key: r.polyglot_key(), raw_move: (6 << 6) | 21, weight: 1, learn: 0 }.to_bytes()).unwrap();
+
+        let mut o = orchestrator::new();
+        let mut message = message::new();
+        message.new_board(fen.to_string());
+        assert!(!o.process_command(message));
+        let mut options = crate::book::options::new();
+        options.path = Some(path.to_string_lossy().to_string());
+        let mut message = message::new();
+        message.book_change(options.clone());
+        assert!(!o.process_command(message));
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(o.book_move(),Some("g1f3".to_string()));
+
+        options.max_depth = 0;
+        o.set_book_options(options);
+        assert_eq!(o.book_move(),None);
+
+        let mut options = crate::book::options::new();
+        options.path = Some("/nonexistent/book.bin".to_string());
+        o.set_book_options(options);
+        assert!(o.book.is_none());
+        assert_eq!(o.book_move(),None);
+    }
+
+    #[test]
+    fn mcts_engine() {
+        use crate::orchestrator::Engine;
+        let mut message = message::new();
+        message.new_board(String::from("6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1"));
+        let mut o = orchestrator::new();
+        assert!(!o.process_command(message));
+        let mut message = message::new();
+        message.engine_change(Engine::Mcts);
+        assert!(!o.process_command(message));
+        assert_eq!(o.engine,Engine::Mcts);
+        o.mcts_options.iterations = 2000;
+        assert_eq!(o.mcts_move(),Some("a1a8".to_string()));
     }
 
     #[test]
@@ -133,4 +531,36 @@ mod tests {
         assert!(!o.process_command(message)); //returns `false` to go on
     }
 
+    #[test]
+    fn determinism_change() {
+        let mut o = orchestrator::new();
+        let mut message = message::new();
+        message.determinism_change(true, Some(42));
+        assert!(!o.process_command(message));
+        assert_eq!(o.cogitator_thread_count,1);
+        assert_eq!(o.mcts_options.seed,42);
+        assert_eq!(o.book_rng,crate::utils::rng::new(42));
+        assert!(!crate::eval::noise_enabled());
+
+        // The same seed gives the same MCTS move, visits and node count
+        let mut message = message::new();
+        message.new_board(String::from("r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3"));
+        assert!(!o.process_command(message.clone()));
+        o.mcts_options.iterations = 300;
+        let mut first = 0;
+        let best = o.tree_root.mcts_search(&o.mcts_options, &mut first).unwrap();
+        let visits = o.tree_root.children[best].visits;
+        assert!(!o.process_command(message));
+        let mut second = 0;
+        assert_eq!(o.tree_root.mcts_search(&o.mcts_options, &mut second),Some(best));
+        assert_eq!(o.tree_root.children[best].visits,visits);
+        assert_eq!(first,second);
+
+        let mut message = message::new();
+        message.determinism_change(false, None);
+        assert!(!o.process_command(message));
+        assert_eq!(o.cogitator_thread_count,crate::orchestrator::r#const::SEARCH_THREADS_DEFAULT);
+        assert!(crate::eval::noise_enabled());
+    }
+
 }
\ No newline at end of file
@@ -5,11 +5,28 @@ use std::sync::mpsc::{Sender, Receiver};
 use crate::operator::message::OperatorMessage;
 use crate::tree;
 use tree::Tree;
+use tree::search_options::SearchOptions;
+use tree::mcts_options::MctsOptions;
+use crate::operator::CommunicationProtocol;
+use crate::book::Book;
+use crate::book::options::BookOptions;
+use crate::reset::variant::Variant;
+use crate::utils::rng;
+use crate::utils::rng::Rng;
 use std::sync::{Arc, Mutex};
 use std::thread::JoinHandle;
 use std::sync::atomic::{AtomicBool};
 use crate::orchestrator::r#const::SEARCH_THREADS_DEFAULT;
 
+/// Search engine used to pick the Orchestrator's moves
+#[derive(PartialEq,Eq,Copy,Clone,Hash,Debug)]
+pub enum Engine {
+    /// Parallel alpha-beta search run by the Cogitator threads
+    AlphaBeta,
+    /// Monte Carlo tree search (see `Tree::mcts_search`)
+    Mcts,
+}
+
 /// Data necessary the Orchestrator functionality to run successfully
 ///
 /// The Ochestrator will take game status from the Operator and will launch Cogitator threads as
@@ -23,10 +40,27 @@ pub struct Orchestrator {
     tree_children: Vec<Arc<Mutex<Tree>>>,
     cogitator_thread_count: u8,
     cogitator_handles:  Vec<JoinHandle<()>>,
+    pub search_options: SearchOptions,
+    pub engine: Engine,
+    pub mcts_options: MctsOptions,
+    communication_protocol: CommunicationProtocol,
+    pub book: Option<Book>,
+    pub book_options: BookOptions,
+    pub variant: Variant,
+    book_rng: Rng,
     red_light: Arc<AtomicBool>,
     exit_signal: Arc<AtomicBool>,
 }
 
+/// Seed for the book's move choice, so that games don't all follow the same line
+pub(crate) fn time_seed() -> u64 {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    match SystemTime::now().duration_since(UNIX_EPOCH) {
+        Ok(elapsed) => elapsed.as_nanos() as u64,
+        Err(_) => 0,
+    }
+}
+
 /// Constructs a new Orchestrator
 ///
 /// # Examples
@@ -47,6 +81,14 @@ pub fn new() -> Orchestrator {
         tree_children: Vec::new(),
         cogitator_thread_count: SEARCH_THREADS_DEFAULT,
         cogitator_handles: Vec::new(),
+        search_options: tree::search_options::new(),
+        engine: Engine::AlphaBeta,
+        mcts_options: tree::mcts_options::new(),
+        communication_protocol: CommunicationProtocol::ChessEngineCommunicationProtocol,
+        book: None,
+        book_options: crate::book::options::new(),
+        variant: Variant::Standard,
+        book_rng: rng::new(time_seed()),
         red_light: Arc::new(AtomicBool::new(false)),
         exit_signal: Arc::new(AtomicBool::new(false)),
     }
@@ -0,0 +1,233 @@
+use std::fmt;
+
+use crate::reference;
+use crate::reference::Board;
+use crate::reference::ReferenceMove;
+use crate::reset::fen;
+use crate::utils::rng;
+
+/// Positions the fuzzer's random games start from: the standard start, the perft positions
+/// full of castling, en passant and promotion tricks, and Chess960 starts
+pub const FUZZ_STARTS: [&str; 8] = [
+    "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+    "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+    "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+    "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9",
+    "nrbkqbrn/pppppppp/8/8/8/8/PPPPPPPP/NRBKQBRN w KQkq - 0 1",
+    "rk2r3/1pp2ppp/8/8/8/8/1PP2PPP/RK2R3 w KQkq - 0 1",
+];
+
+/// Where the move generator and the reference generator part ways in a position
+#[derive(PartialEq,Eq,Clone,Debug)]
+pub struct Mismatch {
+    pub fen: String,
+    /// Moves only the reference generator found, or found leading somewhere else
+    pub missing: Vec<ReferenceMove>,
+    /// Moves only the move generator found, or found leading somewhere else
+    pub extra: Vec<ReferenceMove>,
+    /// Why the move generator wouldn't set the position up at all
+    pub engine_error: Option<String>,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f,"{}",self.fen)?;
+        if let Some(error) = &self.engine_error {
+            write!(f,"\n  not accepted: {}",error)?;
+        }
+        for (label, moves) in [("missing", &self.missing), ("extra", &self.extra)] {
+            for m in moves.iter() {
+                write!(f,"\n  {} {} -> {}{}",label,m.text,m.fen,if m.check { " (check)" } else { "" })?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A failure found by `fuzz`: the position a random game reached, and the smallest position
+/// `shrink` could find that fails the same way
+#[derive(PartialEq,Eq,Clone,Debug)]
+pub struct FuzzFailure {
+    pub found: String,
+    pub minimal: Mismatch,
+}
+
+impl fmt::Display for FuzzFailure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f,"found at {}\nminimal {}",self.found,self.minimal)
+    }
+}
+
+/// The move generator's legal moves, written as the reference generator writes its own
+pub fn engine_moves(fen: &str) -> Result<Vec<ReferenceMove>, fen::FenError> {
+    let mut r = fen::from_fen(fen)?;
+    let mut child = crate::reset::new();
+    let mut moves = Vec::new();
+    while r.generate_next_move(&mut child) {
+        moves.push(ReferenceMove {
+            text: child.long_algebraic_text(),
+            fen: child.to_fen(),
+            check: child.in_check(),
+        });
+    }
+    Ok(moves)
+}
+
+/// Compare the move generator with the reference generator in one position
+///
+/// Both must find the same legal moves, leading to the same FENs, with the same check flags.
+///
+/// # Examples
+///
+/// ```
+/// use chessica::reference::fuzz::compare;
+/// assert_eq!(compare("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1"),Ok(()));
+/// ```
+pub fn compare(fen: &str) -> Result<(), Mismatch> {
+    let mut expected: Vec<ReferenceMove> = match reference::from_fen(fen) {
+        Ok(board) => board.legal_moves().into_iter().map(|(m, _)| m).collect(),
+        Err(_) => Vec::new(),
+    };
+    let mut found = match engine_moves(fen) {
+        Ok(found) => found,
+        Err(error) => return Err(Mismatch {
+            fen: fen.to_string(),
+            missing: expected,
+            extra: Vec::new(),
+            engine_error: Some(error.to_string()),
+        }),
+    };
+    expected.sort();
+    found.sort();
+    if expected == found {
+        return Ok(());
+    }
+    Err(Mismatch {
+        fen: fen.to_string(),
+        missing: expected.iter().filter(|m| !found.contains(m)).cloned().collect(),
+        extra: found.iter().filter(|m| !expected.contains(m)).cloned().collect(),
+        engine_error: None,
+    })
+}
+
+/// Cut a failing position down while it keeps failing
+///
+/// Pieces other than kings are taken away one at a time, then castling rights, then the en
+/// passant square and clocks, keeping each change that both generators accept and that still
+/// `fails`, until nothing more can go.
+///
+/// # Examples
+///
+/// ```
+/// use chessica::reference::fuzz::shrink;
+/// let minimal = shrink("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1", |fen| fen.contains('Q'));
+/// assert_eq!(minimal,"4k3/8/8/8/8/5Q2/8/4K3 w - - 0 1");
+/// ```
+pub fn shrink<F: Fn(&str) -> bool>(fen: &str, fails: F) -> String {
+    let mut board = match reference::from_fen(fen) {
+        Ok(board) => board,
+        Err(_) => return fen.to_string(),
+    };
+    let accepted = |candidate: &Board| {
+        let candidate_fen = candidate.to_fen();
+        fen::from_fen(&candidate_fen).is_ok() && fails(&candidate_fen)
+    };
+    loop {
+        let mut candidates = Vec::new();
+        for square in board.removable_squares() {
+            let mut candidate = board.clone();
+            candidate.remove_piece(square);
+            candidates.push(candidate);
+        }
+        for index in 0..4 {
+            let mut candidate = board.clone();
+            if candidate.remove_castling_right(index) {
+                candidates.push(candidate);
+            }
+        }
+        let mut candidate = board.clone();
+        if candidate.clear_history() {
+            candidates.push(candidate);
+        }
+        match candidates.into_iter().find(|candidate| accepted(candidate)) {
+            Some(smaller) => board = smaller,
+            None => return board.to_fen(),
+        }
+    }
+}
+
+/// Play random games, comparing the move generator with the reference generator at every
+/// position reached
+///
+/// Each game starts from one of `FUZZ_STARTS` and lasts up to `max_plies` moves, picked by a
+/// generator seeded with `seed`.  Returns the number of positions compared, or the first
+/// failure, shrunk.
+///
+/// # Examples
+///
+/// ```
+/// assert!(chessica::reference::fuzz::fuzz(7, 2, 20).is_ok());
+/// ```
+pub fn fuzz(seed: u64, games: u32, max_plies: u32) -> Result<u64, FuzzFailure> {
+    let mut rng = rng::new(seed);
+    let mut positions = 0;
+    for _ in 0..games {
+        let mut board = reference::from_fen(FUZZ_STARTS[rng.below(FUZZ_STARTS.len())]).unwrap();
+        for _ in 0..=max_plies {
+            let fen = board.to_fen();
+            positions += 1;
+            if compare(&fen).is_err() {
+                let minimal = shrink(&fen, |candidate| compare(candidate).is_err());
+                return Err(FuzzFailure {
+                    found: fen,
+                    minimal: compare(&minimal).unwrap_err(),
+                });
+            }
+            let mut moves = board.legal_moves();
+            if moves.is_empty() {
+                break;
+            }
+            board = moves.swap_remove(rng.below(moves.len())).1;
+        }
+    }
+    Ok(positions)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::reference::fuzz;
+    use crate::reference::fuzz::FUZZ_STARTS;
+
+    #[test]
+    fn fuzz_starts_agree() {
+        for fen in FUZZ_STARTS {
+            assert_eq!(fuzz::compare(fen),Ok(()),"{}",fen);
+        }
+    }
+
+    #[test]
+    fn fuzz_reports_mismatches() {
+        // The reference generator doesn't play Crazyhouse, so a drop looks like an extra move
+        let mismatch = fuzz::compare("4k3/8/8/8/8/8/8/4K3[N] w - - 0 1").unwrap_err();
+        assert!(mismatch.missing.is_empty());
+        assert!(mismatch.extra.iter().any(|m| m.text == "N@e4"));
+        let mismatch = fuzz::compare("4k3/8/8/8/8/8/8/4K3 w K - 0 1").unwrap_err();
+        assert!(mismatch.engine_error.is_some());
+    }
+
+    #[test]
+    fn fuzz_shrinks() {
+        // Keep the en passant capture: everything else goes
+        let minimal = fuzz::shrink("rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3", |fen| fen.contains(" f6 "));
+        assert_eq!(minimal,"4k3/8/8/4Pp2/8/8/8/4K3 w - f6 0 3");
+        let minimal = fuzz::shrink("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1", |fen| fen.contains('q'));
+        assert_eq!(minimal,"r3k3/8/8/8/8/8/8/4K3 w q - 0 1");
+    }
+
+    #[test]
+    fn fuzz_games() {
+        assert!(fuzz::fuzz(1, 4, 60).unwrap() > 4);
+    }
+}
@@ -0,0 +1,566 @@
+//! A slow, plain move generator to check the real one against
+//!
+//! Nothing here is shared with `Reset`: the board is an array of 64 squares (a1 = 0, b1 = 1 ...
+//! h8 = 63), moves are generated piece by piece as if no king were ever in check, and a move
+//! is legal when the mover's king isn't attacked once it is made.  Standard chess only, with
+//! Chess960 castling.
+
+pub mod fuzz;
+
+use std::fmt;
+
+/// Pieces are their FEN letters, upper case for white
+pub type Piece = char;
+
+/// A position the reference generator can play on
+#[derive(PartialEq,Eq,Clone,Debug)]
+pub struct Board {
+    squares: [Option<Piece>; 64],
+    white_to_move: bool,
+    /// Square of the rook each castling right belongs to: white kingside, white queenside,
+    /// black kingside, black queenside
+    castling: [Option<usize>; 4],
+    /// Square a pawn passed over by moving two squares on the last move
+    en_passant: Option<usize>,
+    halfmove_clock: u32,
+    fullmove_number: u32,
+}
+
+/// A legal move, and where it leads
+#[derive(PartialEq,Eq,PartialOrd,Ord,Clone,Debug)]
+pub struct ReferenceMove {
+    /// Long algebraic text, castling written as the king's move ("e1g1")
+    pub text: String,
+    /// FEN of the position after the move
+    pub fen: String,
+    /// Does the move give check?
+    pub check: bool,
+}
+
+/// Why a FEN can't be set up by the reference generator
+#[derive(PartialEq,Eq,Clone,Debug)]
+pub struct ReferenceError(pub String);
+
+impl fmt::Display for ReferenceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f,"{}",self.0)
+    }
+}
+
+impl std::error::Error for ReferenceError {}
+
+const KNIGHT_STEPS: [(i32, i32); 8] = [(1,2),(2,1),(2,-1),(1,-2),(-1,-2),(-2,-1),(-2,1),(-1,2)];
+const KING_STEPS: [(i32, i32); 8] = [(1,0),(1,1),(0,1),(-1,1),(-1,0),(-1,-1),(0,-1),(1,-1)];
+const ROOK_LINES: [(i32, i32); 4] = [(1,0),(0,1),(-1,0),(0,-1)];
+const BISHOP_LINES: [(i32, i32); 4] = [(1,1),(-1,1),(-1,-1),(1,-1)];
+
+fn file_of(square: usize) -> i32 {
+    (square % 8) as i32
+}
+
+fn rank_of(square: usize) -> i32 {
+    (square / 8) as i32
+}
+
+/// The square a (file, rank) step away, if it is on the board
+fn step(square: usize, (files, ranks): (i32, i32)) -> Option<usize> {
+    let (file, rank) = (file_of(square) + files, rank_of(square) + ranks);
+    if (0..8).contains(&file) && (0..8).contains(&rank) {
+        Some((rank * 8 + file) as usize)
+    } else {
+        None
+    }
+}
+
+fn square_name(square: usize) -> String {
+    format!("{}{}",(b'a' + file_of(square) as u8) as char,rank_of(square) + 1)
+}
+
+fn square_named(name: &str) -> Option<usize> {
+    let bytes = name.as_bytes();
+    if bytes.len() != 2 || !(b'a'..=b'h').contains(&bytes[0]) || !(b'1'..=b'8').contains(&bytes[1]) {
+        return None;
+    }
+    Some((bytes[1] - b'1') as usize * 8 + (bytes[0] - b'a') as usize)
+}
+
+fn is_white(piece: Piece) -> bool {
+    piece.is_ascii_uppercase()
+}
+
+/// Sets up a Board from a FEN
+///
+/// # Examples
+///
+/// ```
+/// let board = chessica::reference::from_fen("4k3/8/8/8/8/8/8/4K2R w K - 0 1").unwrap();
+/// assert_eq!(board.to_fen(),"4k3/8/8/8/8/8/8/4K2R w K - 0 1");
+/// assert!(chessica::reference::from_fen("4k3/8/8/8/8/8/8/4K2R[] w K - 0 1").is_err());
+/// ```
+pub fn from_fen(fen: &str) -> Result<Board, ReferenceError> {
+    let error = |what: &str| ReferenceError(format!("{} in \"{}\"",what,fen));
+    let fields: Vec<&str> = fen.split_whitespace().collect();
+    if fields.len() != 6 {
+        return Err(error("not six fields"));
+    }
+    let mut board = Board {
+        squares: [None; 64],
+        white_to_move: true,
+        castling: [None; 4],
+        en_passant: None,
+        halfmove_clock: 0,
+        fullmove_number: 1,
+    };
+    let rows: Vec<&str> = fields[0].split('/').collect();
+    if rows.len() != 8 {
+        return Err(error("not eight ranks"));
+    }
+    for (row, text) in rows.iter().enumerate() {
+        let rank = 7 - row;
+        let mut file = 0;
+        for c in text.chars() {
+            if let Some(empty) = c.to_digit(10) {
+                file += empty as usize;
+            } else if "pnbrqkPNBRQK".contains(c) && file < 8 {
+                board.squares[rank * 8 + file] = Some(c);
+                file += 1;
+            } else {
+                return Err(error("bad board"));
+            }
+        }
+        if file != 8 {
+            return Err(error("bad board"));
+        }
+    }
+    board.white_to_move = match fields[1] {
+        "w" => true,
+        "b" => false,
+        _ => return Err(error("bad side to move")),
+    };
+    if fields[2] != "-" {
+        for c in fields[2].chars() {
+            let white = is_white(c);
+            let king = board.king_square(white).ok_or_else(|| error("castling without a king"))?;
+            let back_rank = if white { 0 } else { 56 };
+            let rook = if white { 'R' } else { 'r' };
+            let (kingside, file) = match c.to_ascii_lowercase() {
+                'k' => (true, None),
+                'q' => (false, None),
+                f @ 'a'..='h' => (f as usize - 'a' as usize > file_of(king) as usize, Some(f as usize - 'a' as usize)),
+                _ => return Err(error("bad castling")),
+            };
+            // KQkq name the outermost rook on the wing
+            let file = match file {
+                Some(file) => file,
+                None if kingside => (0..8).rev().find(|f| board.squares[back_rank + f] == Some(rook)).ok_or_else(|| error("no castling rook"))?,
+                None => (0..8).find(|f| board.squares[back_rank + f] == Some(rook)).ok_or_else(|| error("no castling rook"))?,
+            };
+            if board.squares[back_rank + file] != Some(rook) || rank_of(king) != rank_of(back_rank) {
+                return Err(error("no castling rook"));
+            }
+            let index = if white { 0 } else { 2 } + if kingside { 0 } else { 1 };
+            board.castling[index] = Some(back_rank + file);
+        }
+    }
+    if fields[3] != "-" {
+        board.en_passant = Some(square_named(fields[3]).ok_or_else(|| error("bad en passant square"))?);
+    }
+    board.halfmove_clock = fields[4].parse().map_err(|_| error("bad halfmove clock"))?;
+    board.fullmove_number = fields[5].parse().map_err(|_| error("bad move number"))?;
+    Ok(board)
+}
+
+impl Board {
+
+    fn king_square(&self, white: bool) -> Option<usize> {
+        let king = if white { 'K' } else { 'k' };
+        (0..64).find(|&square| self.squares[square] == Some(king))
+    }
+
+    /// Is a square attacked by a side's pieces?
+    fn attacked(&self, square: usize, by_white: bool) -> bool {
+        let piece_of = |letter: char| if by_white { letter.to_ascii_uppercase() } else { letter };
+        let holds = |at: Option<usize>, letter: char| at.map_or(false, |at| self.squares[at] == Some(piece_of(letter)));
+        // A pawn attacks from the rank behind, as its side sees it
+        let pawn_rank = if by_white { -1 } else { 1 };
+        if holds(step(square, (-1, pawn_rank)), 'p') || holds(step(square, (1, pawn_rank)), 'p') {
+            return true;
+        }
+        if KNIGHT_STEPS.iter().any(|&s| holds(step(square, s), 'n')) ||
+            KING_STEPS.iter().any(|&s| holds(step(square, s), 'k')) {
+            return true;
+        }
+        for (lines, slider) in [(ROOK_LINES, 'r'), (BISHOP_LINES, 'b')] {
+            for direction in lines {
+                let mut at = step(square, direction);
+                while let Some(current) = at {
+                    if let Some(piece) = self.squares[current] {
+                        if piece == piece_of(slider) || piece == piece_of('q') {
+                            return true;
+                        }
+                        break;
+                    }
+                    at = step(current, direction);
+                }
+            }
+        }
+        false
+    }
+
+    /// Is the side to move in check?
+    pub fn in_check(&self) -> bool {
+        match self.king_square(self.white_to_move) {
+            Some(king) => self.attacked(king, !self.white_to_move),
+            None => false,
+        }
+    }
+
+    /// Every move of the side to move's pieces, ignoring whether it leaves the king in check,
+    /// as (from, to, promotion piece) and castling as (king, rook, 'c')
+    fn pseudo_legal_moves(&self) -> Vec<(usize, usize, Option<char>)> {
+        let mut moves = Vec::new();
+        let white = self.white_to_move;
+        let own = |square: usize| self.squares[square].map_or(false, |piece| is_white(piece) == white);
+        let enemy = |square: usize| self.squares[square].map_or(false, |piece| is_white(piece) != white);
+        for from in 0..64 {
+            let piece = match self.squares[from] {
+                Some(piece) if is_white(piece) == white => piece.to_ascii_lowercase(),
+                _ => continue,
+            };
+            match piece {
+                'p' => {
+                    let forward = if white { 1 } else { -1 };
+                    let last_rank = if white { 7 } else { 0 };
+                    let start_rank = if white { 1 } else { 6 };
+                    let mut targets = Vec::new();
+                    if let Some(to) = step(from, (0, forward)).filter(|&to| self.squares[to].is_none()) {
+                        targets.push(to);
+                        if rank_of(from) == start_rank {
+                            if let Some(two) = step(to, (0, forward)).filter(|&two| self.squares[two].is_none()) {
+                                targets.push(two);
+                            }
+                        }
+                    }
+                    for side in [-1, 1] {
+                        if let Some(to) = step(from, (side, forward)) {
+                            if enemy(to) || self.en_passant == Some(to) {
+                                targets.push(to);
+                            }
+                        }
+                    }
+                    for to in targets {
+                        if rank_of(to) == last_rank {
+                            for promotion in ['q', 'r', 'b', 'n'] {
+                                moves.push((from, to, Some(promotion)));
+                            }
+                        } else {
+                            moves.push((from, to, None));
+                        }
+                    }
+                },
+                'n' | 'k' => {
+                    let steps = if piece == 'n' { KNIGHT_STEPS } else { KING_STEPS };
+                    for s in steps {
+                        if let Some(to) = step(from, s).filter(|&to| !own(to)) {
+                            moves.push((from, to, None));
+                        }
+                    }
+                },
+                _ => {
+                    let lines: Vec<(i32, i32)> = match piece {
+                        'r' => ROOK_LINES.to_vec(),
+                        'b' => BISHOP_LINES.to_vec(),
+                        _ => ROOK_LINES.iter().chain(BISHOP_LINES.iter()).copied().collect(),
+                    };
+                    for direction in lines {
+                        let mut at = step(from, direction);
+                        while let Some(to) = at {
+                            if own(to) {
+                                break;
+                            }
+                            moves.push((from, to, None));
+                            if enemy(to) {
+                                break;
+                            }
+                            at = step(to, direction);
+                        }
+                    }
+                },
+            }
+        }
+        for (kingside, right) in [(true, 0), (false, 1)] {
+            let index = if white { right } else { right + 2 };
+            if let (Some(rook), Some(king)) = (self.castling[index], self.king_square(white)) {
+                if self.can_castle(king, rook, kingside) {
+                    moves.push((king, rook, Some('c')));
+                }
+            }
+        }
+        moves
+    }
+
+    /// Chess960 castling: the squares both king and rook cross must be empty but for the two of
+    /// them, and the king mustn't be in check or cross an attacked square
+    fn can_castle(&self, king: usize, rook: usize, kingside: bool) -> bool {
+        let back_rank = king - file_of(king) as usize;
+        let (king_to, rook_to) = if kingside { (back_rank + 6, back_rank + 5) } else { (back_rank + 2, back_rank + 3) };
+        let span = |a: usize, b: usize| if a < b { a..=b } else { b..=a };
+        let clear = span(king, king_to).chain(span(rook, rook_to))
+            .all(|square| square == king || square == rook || self.squares[square].is_none());
+        let mut without_king = self.clone();
+        without_king.squares[king] = None;
+        clear && !self.in_check() && span(king, king_to).all(|square| !without_king.attacked(square, !self.white_to_move))
+    }
+
+    /// The position after a pseudo-legal move
+    fn play(&self, (from, to, promotion): (usize, usize, Option<char>)) -> Board {
+        let mut next = self.clone();
+        let white = self.white_to_move;
+        let piece = self.squares[from].unwrap();
+        next.en_passant = None;
+        next.halfmove_clock += 1;
+        if promotion == Some('c') {
+            // King and rook leave together, then land on their castled squares
+            let back_rank = from - file_of(from) as usize;
+            let (king_to, rook_to) = if to > from { (back_rank + 6, back_rank + 5) } else { (back_rank + 2, back_rank + 3) };
+            let rook = self.squares[to];
+            next.squares[from] = None;
+            next.squares[to] = None;
+            next.squares[king_to] = Some(piece);
+            next.squares[rook_to] = rook;
+        } else {
+            if self.squares[to].is_some() {
+                next.halfmove_clock = 0;
+            }
+            if piece.eq_ignore_ascii_case(&'p') {
+                next.halfmove_clock = 0;
+                if Some(to) == self.en_passant {
+                    // The pawn taken en passant stands beside the one taking it
+                    next.squares[rank_of(from) as usize * 8 + file_of(to) as usize] = None;
+                }
+                if (rank_of(to) - rank_of(from)).abs() == 2 {
+                    next.en_passant = Some((from + to) / 2);
+                }
+            }
+            next.squares[from] = None;
+            next.squares[to] = Some(match promotion {
+                Some(promoted) if white => promoted.to_ascii_uppercase(),
+                Some(promoted) => promoted,
+                None => piece,
+            });
+        }
+        for index in 0..4 {
+            let right_is_white = index < 2;
+            let king_moved = piece.eq_ignore_ascii_case(&'k') && right_is_white == white;
+            if king_moved || next.castling[index] == Some(from) || next.castling[index] == Some(to) {
+                next.castling[index] = None;
+            }
+        }
+        next.white_to_move = !white;
+        if !white {
+            next.fullmove_number += 1;
+        }
+        next
+    }
+
+    /// Every legal move, with the position it leads to
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let board = chessica::reference::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").unwrap();
+    /// assert_eq!(board.legal_moves().len(),20);
+    /// ```
+    pub fn legal_moves(&self) -> Vec<(ReferenceMove, Board)> {
+        let mut moves = Vec::new();
+        for mv in self.pseudo_legal_moves() {
+            let next = self.play(mv);
+            let mover_king = next.king_square(self.white_to_move);
+            if mover_king.map_or(false, |king| next.attacked(king, !self.white_to_move)) {
+                continue;
+            }
+            let (from, to, promotion) = mv;
+            let mut text = square_name(from);
+            match promotion {
+                Some('c') => {
+                    let back_rank = from - file_of(from) as usize;
+                    text.push_str(&square_name(if to > from { back_rank + 6 } else { back_rank + 2 }));
+                },
+                Some(promoted) => {
+                    text.push_str(&square_name(to));
+                    text.push(promoted);
+                },
+                None => text.push_str(&square_name(to)),
+            }
+            let reference_move = ReferenceMove {
+                text,
+                fen: next.to_fen(),
+                check: next.in_check(),
+            };
+            moves.push((reference_move, next));
+        }
+        moves
+    }
+
+    /// Remove the piece on a square, along with any castling right or en passant square that
+    /// depended on it
+    pub fn remove_piece(&mut self, square: usize) {
+        self.squares[square] = None;
+        for index in 0..4 {
+            if self.castling[index] == Some(square) {
+                self.castling[index] = None;
+            }
+        }
+        let king_white = [true, true, false, false];
+        for (index, white) in king_white.iter().enumerate() {
+            if self.king_square(*white).is_none() {
+                self.castling[index] = None;
+            }
+        }
+        if let Some(passed) = self.en_passant {
+            let pushed = if self.white_to_move { passed - 8 } else { passed + 8 };
+            if pushed == square {
+                self.en_passant = None;
+            }
+        }
+    }
+
+    /// Squares holding a piece other than a king
+    pub fn removable_squares(&self) -> Vec<usize> {
+        (0..64).filter(|&square| self.squares[square].map_or(false, |piece| !piece.eq_ignore_ascii_case(&'k'))).collect()
+    }
+
+    /// Drop one castling right, returning whether there was one to drop
+    pub fn remove_castling_right(&mut self, index: usize) -> bool {
+        self.castling[index].take().is_some()
+    }
+
+    /// Forget the en passant square and reset the clocks, as simple as the FEN tail gets
+    pub fn clear_history(&mut self) -> bool {
+        let changed = self.en_passant.is_some() || self.halfmove_clock != 0 || self.fullmove_number != 1;
+        self.en_passant = None;
+        self.halfmove_clock = 0;
+        self.fullmove_number = 1;
+        changed
+    }
+
+    /// FEN, written the way `Reset::to_fen` writes it
+    ///
+    /// Castling rights held with the outermost rook on the wing are "KQkq", others the rook's
+    /// file; the en passant square is only given when a pawn stands ready to take en passant.
+    pub fn to_fen(&self) -> String {
+        let mut fen = String::new();
+        for rank in (0..8).rev() {
+            let mut empty = 0;
+            for file in 0..8 {
+                match self.squares[rank * 8 + file] {
+                    Some(piece) => {
+                        if empty > 0 {
+                            fen.push_str(&empty.to_string());
+                            empty = 0;
+                        }
+                        fen.push(piece);
+                    },
+                    None => empty += 1,
+                }
+            }
+            if empty > 0 {
+                fen.push_str(&empty.to_string());
+            }
+            if rank > 0 {
+                fen.push('/');
+            }
+        }
+        fen.push_str(if self.white_to_move { " w " } else { " b " });
+        let mut castling = String::new();
+        for (index, letter) in ['K', 'Q', 'k', 'q'].iter().enumerate() {
+            if let Some(rook) = self.castling[index] {
+                let white = index < 2;
+                let rook_piece = if white { 'R' } else { 'r' };
+                let back_rank = rook - file_of(rook) as usize;
+                let outer_files: Vec<usize> = if index % 2 == 0 { (file_of(rook) as usize + 1..8).collect() } else { (0..file_of(rook) as usize).collect() };
+                let outermost = outer_files.iter().all(|file| self.squares[back_rank + file] != Some(rook_piece));
+                if outermost {
+                    castling.push(*letter);
+                } else {
+                    let file = (b'a' + file_of(rook) as u8) as char;
+                    castling.push(if white { file.to_ascii_uppercase() } else { file });
+                }
+            }
+        }
+        if castling.is_empty() {
+            castling.push('-');
+        }
+        fen.push_str(&castling);
+        fen.push(' ');
+        let en_passant = self.en_passant.filter(|&passed| {
+            let (pushed, pawn) = if self.white_to_move { (passed - 8, 'P') } else { (passed + 8, 'p') };
+            [-1, 1].iter().any(|&side| step(pushed, (side, 0)).map_or(false, |beside| self.squares[beside] == Some(pawn)))
+        });
+        match en_passant {
+            Some(passed) => fen.push_str(&square_name(passed)),
+            None => fen.push('-'),
+        }
+        fen.push_str(&format!(" {} {}",self.halfmove_clock,self.fullmove_number));
+        fen
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::reference;
+    use crate::reference::Board;
+
+    fn perft(board: &Board, depth: u8) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+        board.legal_moves().iter().map(|(_, next)| perft(next, depth - 1)).sum()
+    }
+
+    #[test]
+    fn reference_perft() {
+        let positions = [
+            ("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1", 3, 8902),
+            ("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1", 3, 97862),
+            ("8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1", 4, 43238),
+            ("r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1", 3, 9467),
+            ("rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8", 3, 62379),
+            ("bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9", 3, 12189),
+        ];
+        for (fen, depth, count) in positions {
+            let board = reference::from_fen(fen).unwrap();
+            assert_eq!(perft(&board, depth),count,"{}",fen);
+        }
+    }
+
+    #[test]
+    fn reference_fen_round_trip() {
+        for fen in [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+            "1r2k1r1/8/8/8/8/8/8/R1R1K1R1 w Cq - 0 1",
+        ] {
+            assert_eq!(reference::from_fen(fen).unwrap().to_fen(),fen);
+        }
+        assert!(reference::from_fen("4k3/8/8/8/8/8/8/4K3 w K - 0 1").is_err());
+        assert!(reference::from_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 1 +0+0").is_err());
+    }
+
+    #[test]
+    fn reference_moves() {
+        // Castling through check, en passant exposing the king, and promotion
+        let board = reference::from_fen("4k3/8/8/8/8/8/8/R3K2R w KQ - 0 1").unwrap();
+        let castles: Vec<String> = board.legal_moves().into_iter().map(|(m, _)| m.text).filter(|t| t == "e1g1" || t == "e1c1").collect();
+        assert_eq!(castles.len(),2);
+        let board = reference::from_fen("4k3/8/8/8/8/8/5r2/R3K2R w KQ - 0 1").unwrap();
+        assert!(board.legal_moves().iter().all(|(m, _)| m.text != "e1g1"));
+        let board = reference::from_fen("8/8/8/KPp4r/8/8/8/7k w - c6 0 2").unwrap();
+        assert!(board.legal_moves().iter().all(|(m, _)| m.text != "b5c6"));
+        let board = reference::from_fen("7k/P7/8/8/8/8/8/K7 w - - 0 1").unwrap();
+        let promotion = board.legal_moves().into_iter().find(|(m, _)| m.text == "a7a8q").unwrap().0;
+        assert_eq!(promotion.fen,"Q6k/8/8/8/8/8/8/K7 b - - 0 1");
+        assert!(promotion.check);
+    }
+}
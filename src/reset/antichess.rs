@@ -0,0 +1,92 @@
+use crate::reset::Reset;
+
+impl Reset {
+
+    /// Can the side to move capture anything?  In Antichess it then has to.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chessica::reset::variant::Variant;
+    /// let mut r = chessica::reset::new();
+    /// r.init_from_fen("rnbqkbnr/ppp1pppp/8/3p4/4P3/8/PPPP1PPP/RNBQKBNR w - - 0 2".to_string());
+    /// r.set_variant(Variant::Antichess);
+    /// assert!(r.antichess_capture_available());
+    /// ```
+    pub fn antichess_capture_available(&mut self) -> bool {
+        let opponents_safe = if self.white_to_move() {
+            let b_black = self.b_black();
+            self.black_is_safe(b_black)
+        } else {
+            self.white_is_safe(self.b_white)
+        };
+        !opponents_safe || self.en_passant_capture_possible()
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::reset;
+    use crate::reset::Reset;
+    use crate::reset::variant::Variant;
+    use crate::reset::r#const::SCORE_WHITE_CHECKMATE;
+    use crate::reset::r#const::SCORE_BLACK_CHECKMATE;
+
+    fn prep_board(fen: &str) -> Reset {
+        let mut r = reset::new();
+        r.init_from_fen(fen.to_string());
+        r.set_variant(Variant::Antichess);
+        r
+    }
+
+    fn move_texts(r: &mut Reset) -> Vec<String> {
+        let mut texts = Vec::new();
+        let mut child = reset::new();
+        r.restart_move_generation();
+        while r.generate_next_move(&mut child) {
+            texts.push(child.uci_move_text());
+        }
+        texts
+    }
+
+    #[test]
+    fn antichess_captures_are_compulsory() {
+        let mut r = prep_board("rnbqkbnr/ppp1pppp/8/3p4/4P3/8/PPPP1PPP/RNBQKBNR w - - 0 2");
+        assert_eq!(move_texts(&mut r),vec!["e4d5"]);
+
+        // En passant counts, and any capture may be chosen
+        let mut r = prep_board("7n/8/8/3pP3/8/8/8/3R4 w - d6 0 1");
+        let mut texts = move_texts(&mut r);
+        texts.sort();
+        assert_eq!(texts,vec!["d1d5","e5d6"]);
+    }
+
+    #[test]
+    fn antichess_kings_are_ordinary_pieces() {
+        // The king may walk into attack, be captured, and a pawn may promote to one
+        let mut r = prep_board("8/8/8/8/8/8/8/r3K3 w - - 0 1");
+        assert_eq!(move_texts(&mut r).len(),5);
+        let mut r = prep_board("8/8/8/8/8/8/3k4/4K3 w - - 0 1");
+        assert_eq!(move_texts(&mut r),vec!["e1d2"]);
+        let mut r = prep_board("8/4P3/8/8/8/8/8/k7 w - - 0 1");
+        let mut texts = move_texts(&mut r);
+        texts.sort();
+        assert_eq!(texts,vec!["e7e8b","e7e8k","e7e8n","e7e8q","e7e8r"]);
+
+        // No castling
+        let mut r = prep_board("4k3/8/8/8/8/8/8/4K2R w K - 0 1");
+        assert!(r.child_from_san("O-O").is_none());
+    }
+
+    #[test]
+    fn antichess_losing_everything_wins() {
+        let mut r = prep_board("8/8/8/8/8/8/8/6k1 w - - 0 1");
+        assert_eq!(r.score(),SCORE_WHITE_CHECKMATE);
+        let mut r = prep_board("8/8/8/8/8/8/8/6K1 b - - 0 1");
+        assert_eq!(r.score(),SCORE_BLACK_CHECKMATE);
+        // Stalemate wins too
+        let mut r = prep_board("8/8/8/8/8/p7/P7/8 w - - 0 1");
+        assert_eq!(r.score(),SCORE_WHITE_CHECKMATE);
+    }
+}
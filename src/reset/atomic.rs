@@ -0,0 +1,149 @@
+use crate::reset::Reset;
+use crate::bitops;
+use crate::reset::r#const::B_NOT_N_EDGE;
+use crate::reset::r#const::B_NOT_NE_EDGE;
+use crate::reset::r#const::B_NOT_E_EDGE;
+use crate::reset::r#const::B_NOT_SE_EDGE;
+use crate::reset::r#const::B_NOT_S_EDGE;
+use crate::reset::r#const::B_NOT_SW_EDGE;
+use crate::reset::r#const::B_NOT_W_EDGE;
+use crate::reset::r#const::B_NOT_NW_EDGE;
+use crate::bitops::r#const::U8_NOT_BIT1_OR_BIT2;
+use crate::bitops::r#const::U8_NOT_BIT3_OR_BIT4;
+
+/// Bitstring of the squares a king's move away from any of a set of squares
+///
+/// # Examples
+///
+/// ```
+/// // h1 touches g1, g2 and h2
+/// assert_eq!(chessica::reset::atomic::b_king_neighbours(0x01),0x0302);
+/// ```
+pub fn b_king_neighbours(b_squares: u64) -> u64 {
+    ((b_squares & B_NOT_N_EDGE) << 8)
+        | ((b_squares & B_NOT_NE_EDGE) << 7)
+        | ((b_squares & B_NOT_E_EDGE) >> 1)
+        | ((b_squares & B_NOT_SE_EDGE) >> 9)
+        | ((b_squares & B_NOT_S_EDGE) >> 8)
+        | ((b_squares & B_NOT_SW_EDGE) >> 7)
+        | ((b_squares & B_NOT_W_EDGE) << 1)
+        | ((b_squares & B_NOT_NW_EDGE) << 9)
+}
+
+impl Reset {
+
+    /// Blow up the Atomic capture that produced this Reset
+    ///
+    /// Whatever stands on the capture square (the capturing piece) goes, along with every piece
+    /// but a pawn on the squares around it.  Castling rights go with any king or castling rook
+    /// caught in the blast.
+    pub fn explode(&mut self) {
+        let b_blast = self.b_all & (self.b_to | (b_king_neighbours(self.b_to) & !self.b_pawns));
+        let b_white_blast = b_blast & self.b_white;
+        let b_black_blast = b_blast & !self.b_white;
+        let balance = |b_pieces: u64| (b_pieces & b_white_blast).count_ones() as i8 - (b_pieces & b_black_blast).count_ones() as i8;
+        self.material -= balance(self.b_pawns)
+            + 3 * balance(self.b_knights)
+            + 3 * balance(self.b_bishops)
+            + 5 * balance(self.b_rooks)
+            + 9 * balance(self.b_queens());
+
+        let mut b_rooks = b_blast & self.b_rooks;
+        while b_rooks != 0 {
+            let b_rook = bitops::lowest_bit(b_rooks);
+            self.castle_bits &= !self.castle_rights_of_rook(bitops::get_bit_number(b_rook));
+            b_rooks &= !b_rook;
+        }
+        if b_white_blast & self.b_kings != 0 {
+            self.castle_bits &= U8_NOT_BIT1_OR_BIT2;
+        }
+        if b_black_blast & self.b_kings != 0 {
+            self.castle_bits &= U8_NOT_BIT3_OR_BIT4;
+        }
+
+        self.b_all &= !b_blast;
+        self.b_white &= !b_blast;
+        self.b_pawns &= !b_blast;
+        self.b_knights &= !b_blast;
+        self.b_bishops &= !b_blast;
+        self.b_rooks &= !b_blast;
+        self.b_kings &= !b_blast;
+    }
+
+    /// Are these squares safe for a side's king in Atomic chess?
+    ///
+    /// A square beside the enemy king always is: no capture there could help blowing up both
+    /// kings, the capturer's own included.
+    pub fn atomic_squares_are_safe(&mut self, white: bool, b_squares: u64) -> bool {
+        let b_enemy_king = self.b_kings & if white { self.b_black() } else { self.b_white };
+        let b_at_risk = b_squares & !b_king_neighbours(b_enemy_king);
+        b_at_risk == 0 || self.is_safe(b_at_risk, if white { 1 } else { 0 })
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::reset;
+    use crate::reset::Reset;
+    use crate::reset::variant::Variant;
+
+    fn prep_board(fen: &str) -> Reset {
+        let mut r = reset::new();
+        r.init_from_fen(fen.to_string());
+        r.set_variant(Variant::Atomic);
+        r
+    }
+
+    fn move_texts(r: &mut Reset) -> Vec<String> {
+        let mut texts = Vec::new();
+        let mut child = reset::new();
+        r.restart_move_generation();
+        while r.generate_next_move(&mut child) {
+            texts.push(child.uci_move_text());
+        }
+        texts
+    }
+
+    #[test]
+    fn atomic_explosions() {
+        // Nxd7 blows up the knight, the pawn taken and the bishop and queen beside it, but not the pawn on e7
+        let mut r = prep_board("r6k/3pp3/2bq4/4N3/8/8/8/4K3 w - - 0 1");
+        let mut child = r.child_from_san("Nxd7").unwrap();
+        assert_eq!(child.to_fen(),"r6k/4p3/8/8/8/8/8/4K3 b - - 0 1");
+        assert_eq!(child.material,-6);
+
+        // A rook caught in the blast takes its castling right with it
+        let mut r = prep_board("r3k2r/1p6/8/8/8/8/8/1R2K3 w kq - 0 1");
+        let mut child = r.child_from_san("Rxb7").unwrap();
+        assert_eq!(child.to_fen(),"4k2r/8/8/8/8/8/8/4K3 b k - 0 1");
+
+        // En passant explodes around the square the capturing pawn lands on
+        let mut r = prep_board("4k3/8/2n5/3pP3/8/8/8/4K3 w - d6 0 1");
+        let mut child = r.child_from_san("exd6").unwrap();
+        assert_eq!(child.to_fen(),"4k3/8/8/8/8/8/8/4K3 b - - 0 1");
+    }
+
+    #[test]
+    fn atomic_king_safety() {
+        // Kings can't capture
+        let mut r = prep_board("4k3/8/8/8/8/8/3p4/4K3 w - - 0 1");
+        assert!(!move_texts(&mut r).contains(&"e1d2".to_string()));
+
+        // Blowing up the enemy king wins, even from check, and leaves the loser without moves
+        let mut r = prep_board("3qk3/3p4/8/8/8/8/8/3QK2r w - - 0 1");
+        assert_eq!(move_texts(&mut r),vec!["e1e2","e1f2","e1d2","d1d7"]);
+        let mut child = r.child_from_san("Qxd7").unwrap();
+        assert_eq!(move_texts(&mut child),Vec::<String>::new());
+
+        // Capturing next to one's own king is suicide
+        let mut r = prep_board("4k3/8/8/8/8/8/2n5/2RK4 w - - 0 1");
+        assert!(r.child_from_san("Rxc2").is_none());
+        assert!(!move_texts(&mut r).contains(&"d1c2".to_string()));
+
+        // Kings side by side can't give check
+        let mut r = prep_board("8/8/8/8/3k4/8/3K4/3R4 w - - 0 1");
+        let child = r.child_from_san("Ke3").unwrap();
+        assert!(!child.in_check());
+    }
+}
@@ -1,13 +1,6 @@
 use crate::reset::Reset;
-use crate::reset::r#const::B_FOUR_CORNERS;
-use crate::reset::r#const::B_SE_CORNER;
-use crate::reset::r#const::B_SW_CORNER;
-use crate::reset::r#const::B_NE_CORNER;
-
-use crate::bitops::r#const::U8_NOT_BIT1;
-use crate::bitops::r#const::U8_NOT_BIT2;
-use crate::bitops::r#const::U8_NOT_BIT3;
-use crate::bitops::r#const::U8_NOT_BIT4;
+use crate::reset::PieceType;
+use crate::reset::variant::Variant;
 
 impl Reset {
 
@@ -15,6 +8,17 @@ impl Reset {
         child.capture = 1;
         child.halfmove_clock = 0; // Resets on capture
 
+        // Crazyhouse: the captured piece goes into the capturer's pocket, promoted pieces as pawns
+        if self.variant == Variant::Crazyhouse {
+            let captured = if child.b_promoted & child.b_to != 0 {
+                PieceType::Pawn
+            } else {
+                self.piece_type_at(child.b_to)
+            };
+            child.b_promoted &= !child.b_to;
+            child.add_to_pocket(self.white_to_move(), captured);
+        }
+
         child.b_all &= !child.b_to; // Useful for EP
         let material_multiplier: i8 = if self.white_to_move() {
             // Parent moved white
@@ -42,23 +46,12 @@ impl Reset {
             // Rooks
             child.b_rooks &= !child.b_to;
             child.material += material_multiplier * 5;
-            if child.b_to & B_FOUR_CORNERS != 0 {
-                if child.b_to & B_SE_CORNER != 0 {
-                    //white_castle_k = 0;
-                    child.castle_bits &= U8_NOT_BIT1;
-                } else if child.b_to & B_SW_CORNER != 0 {
-                    //white_castle_q = 0;
-                    child.castle_bits &= U8_NOT_BIT2;
-                } else if child.b_to & B_NE_CORNER != 0 {
-                    //black_castle_k = 0;
-                    child.castle_bits &= U8_NOT_BIT3;
-                } else { // B_NW_CORNER
-                    //black_castle_q = 0;
-                    child.castle_bits &= U8_NOT_BIT4;
-                }
-            }
+            child.castle_bits &= !self.castle_rights_of_rook(child.bi_to);
+        } else if child.b_to & child.b_kings != 0 {
+            // Kings (Antichess only, where they're ordinary pieces)
+            child.b_kings &= !child.b_to;
         } else {
-            // Queens (Default - no king captures)
+            // Queens (Default)
             child.material += material_multiplier * 9;
         }
     }
@@ -0,0 +1,106 @@
+use crate::reset::Reset;
+use crate::reset::variant::Variant;
+
+/// (square, piece code) pairs, as returned by `Reset::tablebase_pieces`
+pub type PieceList = Vec<(usize, u8)>;
+
+/// Square (a1 = 0 ... h8 = 63) of a single-bit board
+fn square_of(b_square: u64) -> usize {
+    let bit = b_square.trailing_zeros() as usize;
+    bit / 8 * 8 + 7 - bit % 8
+}
+
+impl Reset {
+
+    /// Pieces the move to `child` took off the board and put on it, as (square, piece code)
+    /// pairs counting a1 = 0 with the codes of `tablebase_pieces`
+    ///
+    /// Called on the parent.  Worked out from the child's `b_from`, `b_to`, capture and promotion
+    /// alone, so captures (en passant included), promotions and the rook's half of castling
+    /// are all listed.  A Crazyhouse drop only adds a piece; an Atomic capture takes off whatever
+    /// the explosion did; a null move changes nothing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut r = chessica::reset::new();
+    /// r.init_from_fen("4k3/8/8/8/8/8/8/4K2R w K - 0 1".to_string());
+    /// let mut castled = r.child_from_san("O-O").unwrap();
+    /// let (removed, added) = r.piece_changes(&castled);
+    /// assert_eq!(removed,vec![(4,6),(7,4)]);
+    /// assert_eq!(added,vec![(6,6),(5,4)]);
+    /// ```
+    pub fn piece_changes(&self, child: &Reset) -> (PieceList, PieceList) {
+        let mut removed = Vec::with_capacity(3);
+        let mut added = Vec::with_capacity(2);
+        if child.is_drop() {
+            let to = square_of(child.b_to);
+            if let Some(code) = child.tablebase_code(to) {
+                added.push((to, code));
+            }
+            return (removed, added);
+        }
+        if child.b_from == 0 || child.b_to == 0 {
+            return (removed, added);
+        }
+        if child.capture != 0 && child.variant == Variant::Atomic {
+            let before = self.tablebase_pieces();
+            let after = child.tablebase_pieces();
+            removed = before.iter().filter(|piece| !after.contains(piece)).copied().collect();
+            added = after.iter().filter(|piece| !before.contains(piece)).copied().collect();
+            return (removed, added);
+        }
+        let from = square_of(child.b_from);
+        let to = square_of(child.b_to);
+        let moved = match self.tablebase_code(from) {
+            Some(code) => code,
+            None => return (removed, added),
+        };
+        removed.push((from, moved));
+        if child.capture != 0 {
+            match self.tablebase_code(to) {
+                Some(captured) => removed.push((to, captured)),
+                // En passant: the pawn taken stands beside the capturing pawn
+                None => removed.push((from / 8 * 8 + to % 8, moved ^ 8)),
+            }
+        }
+        let arrived = if child.promotion != 0 { child.tablebase_code(to).unwrap_or(moved) } else { moved };
+        added.push((to, arrived));
+        if let Some((bi_rook_from, bi_rook_to)) = child.castled_rook_squares() {
+            removed.push((square_of(1 << (bi_rook_from - 1)), moved ^ 6 ^ 4));
+            added.push((square_of(1 << (bi_rook_to - 1)), moved ^ 6 ^ 4));
+        }
+        (removed, added)
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::reset;
+    use crate::reset::changes::PieceList;
+
+    fn changes(fen: &str, san: &str) -> (PieceList, PieceList) {
+        let mut r = reset::new();
+        r.init_from_fen(fen.to_string());
+        let child = r.child_from_san(san).unwrap();
+        r.piece_changes(&child)
+    }
+
+    #[test]
+    fn changes_captures_and_promotions() {
+        // e5xd6 en passant takes the pawn on d5
+        assert_eq!(changes("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1","exd6"),(vec![(36,1),(35,9)],vec![(43,1)]));
+        // Black pawn takes a rook on g1, promoting to a knight
+        assert_eq!(changes("4k3/8/8/8/8/8/7p/4K1R1 b - - 0 1","hxg1=N"),(vec![(15,9),(6,4)],vec![(6,10)]));
+        // Queenside castling for black
+        assert_eq!(changes("r3k3/8/8/8/8/8/8/4K3 b q - 0 1","O-O-O"),(vec![(60,14),(56,12)],vec![(58,14),(59,12)]));
+        assert_eq!(changes("4k3/8/8/8/8/8/8/4K1N1 w - - 0 1","Nf3"),(vec![(6,2)],vec![(21,2)]));
+
+        let mut r = reset::new();
+        r.init_from_fen("4k3/8/8/8/8/8/8/4K1N1 w - - 0 1".to_string());
+        let mut null_child = reset::new();
+        r.init_null_child(&mut null_child);
+        assert_eq!(r.piece_changes(&null_child),(vec![],vec![]));
+    }
+}
@@ -0,0 +1,227 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::reset::Reset;
+use crate::bitops::r#const::U8_BIT1;
+use crate::bitops::r#const::U8_BIT2;
+use crate::bitops::r#const::U8_BIT3;
+use crate::bitops::r#const::U8_BIT4;
+
+/// Whether the GUI asked for Chess960 move text (the `UCI_Chess960` option)
+static UCI_CHESS960: AtomicBool = AtomicBool::new(false);
+
+/// Write castling moves as the king taking its own rook ("e1h1") rather than as a two-square
+/// king move ("e1g1"), as UCI expects once `UCI_Chess960` is on
+pub fn set_enabled(enabled: bool) {
+    UCI_CHESS960.store(enabled, Ordering::Relaxed);
+}
+
+/// Are castling moves written king-takes-rook?
+pub fn enabled() -> bool {
+    UCI_CHESS960.load(Ordering::Relaxed)
+}
+
+/// Bitstring of the squares from one square number to another along a rank, both included
+///
+/// Square numbers are those of `bitops::get_bit_number` (h1 = 1 ... a8 = 64).
+///
+/// # Examples
+///
+/// ```
+/// // e1 (4) to g1 (2)
+/// assert_eq!(chessica::reset::chess960::b_span(4,2),0x0e);
+/// ```
+pub fn b_span(bi_a: u8, bi_b: u8) -> u64 {
+    let (low, high) = if bi_a < bi_b { (bi_a, bi_b) } else { (bi_b, bi_a) };
+    (u64::MAX >> (64 - high)) & (u64::MAX << (low - 1))
+}
+
+impl Reset {
+
+    /// Square number of the rook a side castles with on one wing
+    pub fn castle_rook_square(&self, white: bool, kingside: bool) -> u8 {
+        match (white, kingside) {
+            (true, true) => self.bi_castle_rook_wk,
+            (true, false) => self.bi_castle_rook_wq,
+            (false, true) => self.bi_castle_rook_bk,
+            (false, false) => self.bi_castle_rook_bq,
+        }
+    }
+
+    /// Castle bits of the rights that depend on a rook standing on a square
+    ///
+    /// A rook leaving, or captured on, that square takes those rights away.
+    pub fn castle_rights_of_rook(&self, bi_square: u8) -> u8 {
+        let mut bits = 0;
+        if self.bi_castle_rook_wk == bi_square { bits |= U8_BIT1; }
+        if self.bi_castle_rook_wq == bi_square { bits |= U8_BIT2; }
+        if self.bi_castle_rook_bk == bi_square { bits |= U8_BIT3; }
+        if self.bi_castle_rook_bq == bi_square { bits |= U8_BIT4; }
+        bits
+    }
+
+    /// For a child reached by castling, the square numbers the rook moved from and to
+    pub fn castled_rook_squares(&self) -> Option<(u8, u8)> {
+        if self.king_castled == 0 {
+            return None;
+        }
+        // The side that castled is the one not to move
+        let white = !self.white_to_move();
+        let rank: u8 = if white { 0 } else { 56 };
+        let kingside = self.bi_to == rank + 2;
+        let bi_rook_to = if kingside { rank + 3 } else { rank + 5 };
+        Some((self.castle_rook_square(white, kingside), bi_rook_to))
+    }
+
+    /// The rook furthest from the king on one wing of a side's back rank, if any
+    ///
+    /// This is the rook an X-FEN "K" or "Q" refers to.
+    fn outermost_rook(&self, white: bool, kingside: bool) -> Option<u8> {
+        let (rank, b_side, bi_king) = if white {
+            (0, self.b_white, self.white_king_square)
+        } else {
+            (56, self.b_black(), self.black_king_square)
+        };
+        let b_rooks = self.b_rooks & b_side;
+        let is_rook = |bi: &u8| b_rooks & (1 << (*bi - 1)) != 0;
+        if kingside {
+            (rank + 1..bi_king).find(is_rook)
+        } else {
+            (bi_king + 1..=rank + 8).rev().find(is_rook)
+        }
+    }
+
+    /// Grant the castling right named by one character of a FEN castling field
+    ///
+    /// Accepts "KQkq" (the outermost rook on that wing, as X-FEN reads them) and Shredder-FEN
+    /// rook files ("A"-"H" for white, "a"-"h" for black).  Returns `false` for anything else.
+    /// The king squares must already be set.
+    pub fn add_castle_right(&mut self, c: char) -> bool {
+        let (white, kingside, bi_rook) = match c {
+            'K' => (true, true, self.outermost_rook(true, true).unwrap_or(1)),
+            'Q' => (true, false, self.outermost_rook(true, false).unwrap_or(8)),
+            'k' => (false, true, self.outermost_rook(false, true).unwrap_or(57)),
+            'q' => (false, false, self.outermost_rook(false, false).unwrap_or(64)),
+            'A'..='H' => {
+                let bi_rook = 8 - (c as u8 - b'A');
+                (true, bi_rook < self.white_king_square, bi_rook)
+            },
+            'a'..='h' => {
+                let bi_rook = 64 - (c as u8 - b'a');
+                (false, bi_rook < self.black_king_square, bi_rook)
+            },
+            _ => return false,
+        };
+        match (white, kingside) {
+            (true, true) => { self.bi_castle_rook_wk = bi_rook; self.castle_bits |= U8_BIT1; },
+            (true, false) => { self.bi_castle_rook_wq = bi_rook; self.castle_bits |= U8_BIT2; },
+            (false, true) => { self.bi_castle_rook_bk = bi_rook; self.castle_bits |= U8_BIT3; },
+            (false, false) => { self.bi_castle_rook_bq = bi_rook; self.castle_bits |= U8_BIT4; },
+        }
+        true
+    }
+
+    /// FEN castling field
+    ///
+    /// Rights held with the outermost rook are written "KQkq", so standard chess reads as usual;
+    /// any other castling rook is written by its file, as X-FEN does.
+    pub fn castle_text(&self) -> String {
+        let rights = [
+            (self.white_castle_k(), true, true, 'K'),
+            (self.white_castle_q(), true, false, 'Q'),
+            (self.black_castle_k(), false, true, 'k'),
+            (self.black_castle_q(), false, false, 'q'),
+        ];
+        let mut text = String::new();
+        for (held, white, kingside, letter) in rights {
+            if !held {
+                continue;
+            }
+            let bi_rook = self.castle_rook_square(white, kingside);
+            match self.outermost_rook(white, kingside) {
+                Some(outermost) if outermost != bi_rook => {
+                    let file = (b'a' + 7 - (bi_rook - 1) % 8) as char;
+                    text.push(if white { file.to_ascii_uppercase() } else { file });
+                },
+                _ => text.push(letter),
+            }
+        }
+        if text.is_empty() {
+            text.push('-');
+        }
+        text
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::reset;
+    use crate::reset::chess960::b_span;
+
+    fn fen_round_trip(fen: &str) -> String {
+        let mut r = reset::new();
+        r.init_from_fen(fen.to_string());
+        r.to_fen()
+    }
+
+    #[test]
+    fn chess960_span() {
+        assert_eq!(b_span(4,2),0x0e);
+        assert_eq!(b_span(6,4),0x38);
+        assert_eq!(b_span(64,57),0xff00000000000000);
+        assert_eq!(b_span(5,5),0x10);
+    }
+
+    #[test]
+    fn chess960_fen_castling_fields() {
+        // Standard chess is unchanged
+        let fen = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+        assert_eq!(fen_round_trip(fen),fen);
+        // Shredder-FEN files for outermost rooks come back as "KQkq"
+        let fen = "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9";
+        assert_eq!(fen_round_trip(fen),"bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w KQkq - 2 9");
+        let mut r = reset::new();
+        r.init_from_fen(fen.to_string());
+        assert_eq!(r.castle_rook_square(true,true),1);
+        assert_eq!(r.castle_rook_square(true,false),3);
+        assert_eq!(r.castle_rook_square(false,true),57);
+        assert_eq!(r.castle_rook_square(false,false),59);
+        // An inner rook keeps its file
+        let fen = "4k3/8/8/8/8/8/8/R1R1K3 w C - 0 1";
+        assert_eq!(fen_round_trip(fen),fen);
+        let mut r = reset::new();
+        r.init_from_fen(fen.to_string());
+        assert_eq!(r.castle_rook_square(true,false),6);
+    }
+
+    #[test]
+    fn chess960_castling_moves() {
+        // King on b1, rook on a1: queenside castling leaves the king on c1 and the rook on d1
+        let mut r = reset::new();
+        r.init_from_fen("1k6/8/8/8/8/8/8/RK6 w A - 0 1".to_string());
+        let mut child = r.child_from_san("O-O-O").unwrap();
+        assert_eq!(child.to_fen(),"1k6/8/8/8/8/8/8/2KR4 b - - 1 1");
+        assert_eq!(child.uci_move_text(),"b1c1");
+        assert_eq!(child.castled_rook_squares(),Some((8,5)));
+
+        // King on f1, rook on g1: kingside castling swaps them over
+        let mut r = reset::new();
+        r.init_from_fen("4k3/8/8/8/8/8/8/5KR1 w G - 0 1".to_string());
+        let mut child = r.child_from_san("O-O").unwrap();
+        assert_eq!(child.to_fen(),"4k3/8/8/8/8/8/8/5RK1 b - - 1 1");
+
+        // The castling rook on b1 screens c1 from the rook on a1
+        let mut r = reset::new();
+        r.init_from_fen("1k6/8/8/8/8/8/8/rRK5 w B - 0 1".to_string());
+        assert!(r.child_from_san("O-O-O").is_none());
+    }
+
+    #[test]
+    fn chess960_uci_move_text() {
+        let mut r = reset::new();
+        r.init_from_fen("4k3/8/8/8/8/8/8/4K2R w K - 0 1".to_string());
+        let mut child = r.child_from_san("O-O").unwrap();
+        assert_eq!(child.uci_move_text(),"e1g1");
+        assert_eq!(child.chess960_move_text(),"e1h1");
+    }
+}
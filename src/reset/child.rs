@@ -26,22 +26,22 @@ impl Reset {
         child.b_bishops = self.b_bishops;
         child.b_rooks = self.b_rooks;
         child.b_kings = self.b_kings;
-        child.reserved_01 = self.reserved_01;
+        child.b_promoted = self.b_promoted;
+        child.pockets = self.pockets;
+        child.reserved_12 = self.reserved_12;
         child.material = self.material;
         child.halfmove_clock = self.halfmove_clock;
         child.fullmove_number = self.fullmove_number;
         child.white_king_square = self.white_king_square;
         child.black_king_square = self.black_king_square;
         child.castle_bits = self.castle_bits;
-        child.reserved_02 = self.reserved_02;
-        child.reserved_03 = self.reserved_03;
-        child.reserved_04 = self.reserved_04;
-        child.reserved_05 = self.reserved_05;
-        child.reserved_06 = self.reserved_06;
-        child.reserved_07 = self.reserved_07;
-        child.reserved_08 = self.reserved_08;
-        child.reserved_09 = self.reserved_09;
-        child.reserved_10 = self.reserved_10;
+        child.bi_castle_rook_wk = self.bi_castle_rook_wk;
+        child.bi_castle_rook_wq = self.bi_castle_rook_wq;
+        child.bi_castle_rook_bk = self.bi_castle_rook_bk;
+        child.bi_castle_rook_bq = self.bi_castle_rook_bq;
+        child.white_checks = self.white_checks;
+        child.black_checks = self.black_checks;
+        child.variant = self.variant;
         child.reserved_11 = self.reserved_11;
 
         child.b_current_piece = 0;
@@ -54,6 +54,7 @@ impl Reset {
         child.promotion = 0;
         child.king_castled = 0;
         child.game_over = 0;
+        child.null_move = 0;
 
         child.hash_value = 0;
         child.min = 0;
@@ -68,6 +69,30 @@ impl Reset {
         child.pin_dimension = PIN_DIMENSION_UNSET;
         child.current_piece_type = PieceType::Unknown;
     }
+
+    /// Initialize a "null move" child of this Reset
+    ///
+    /// The child has the same pieces as its parent, the other side to move and no en passant
+    /// square.  Null moves aren't legal chess; they're used by null-move pruning to ask "what if I
+    /// could pass?".  The caller must not do this while in check.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut r = chessica::reset::new();
+    /// r.init_from_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1".to_string());
+    /// let mut child = chessica::reset::new();
+    /// r.init_null_child(&mut child);
+    /// assert_eq!(child.to_fen(),"rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 1 2");
+    /// ```
+    pub fn init_null_child(&mut self, child: &mut Reset) {
+        self.init_child(child);
+        child.b_from = 0;
+        child.b_to = 0;
+        child.null_move = 1;
+        self.valid_child_post_processing(child);
+        child.initialize_move_generation();
+    }
 }
 
 #[cfg(test)]
@@ -119,6 +144,7 @@ mod tests {
         r.promotion = 1;
         r.king_castled = 1;
         r.game_over = 1;
+        r.null_move = 1;
         r.init_child(&mut child);
         assert_eq!(child.b_all,123);
         assert_eq!(child.b_white,234);
@@ -143,6 +169,28 @@ mod tests {
         assert_eq!(child.promotion,0); // Cleared
         assert_eq!(child.king_castled,0); // Cleared
         assert_eq!(child.game_over,0); // Cleared
+        assert_eq!(child.null_move,0); // Cleared
+    }
+
+    #[test]
+    fn reset_init_null_child() {
+        let mut r = reset::new();
+        r.init_from_fen("rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq e6 0 2".to_string());
+        let mut child = reset::new();
+        r.init_null_child(&mut child);
+        assert_eq!(child.to_fen(),"rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 1 2");
+        assert!(child.is_null_move());
+        assert!(!child.in_check());
+        // A position straight from FEN has no move either, but wasn't reached by a null move
+        assert!(!r.is_null_move());
+
+        let mut grandchild = reset::new();
+        let mut count = 0;
+        child.conditionally_complete_move_initialization();
+        while child.generate_next_move(&mut grandchild) {
+            count += 1;
+        }
+        assert_eq!(count,29);
     }
 
     #[test]
@@ -9,22 +9,22 @@ pub fn clone_from(source: &mut Reset) -> Reset {
         b_bishops: source.b_bishops,
         b_rooks: source.b_rooks,
         b_kings: source.b_kings,
-        reserved_01: source.reserved_01,
+        b_promoted: source.b_promoted,
+        pockets: source.pockets,
+        reserved_12: source.reserved_12,
         material: source.material,
         halfmove_clock: source.halfmove_clock,
         fullmove_number: source.fullmove_number,
         white_king_square: source.white_king_square,
         black_king_square: source.black_king_square,
         castle_bits: source.castle_bits,
-        reserved_02: source.reserved_02,
-        reserved_03: source.reserved_03,
-        reserved_04: source.reserved_04,
-        reserved_05: source.reserved_05,
-        reserved_06: source.reserved_06,
-        reserved_07: source.reserved_07,
-        reserved_08: source.reserved_08,
-        reserved_09: source.reserved_09,
-        reserved_10: source.reserved_10,
+        bi_castle_rook_wk: source.bi_castle_rook_wk,
+        bi_castle_rook_wq: source.bi_castle_rook_wq,
+        bi_castle_rook_bk: source.bi_castle_rook_bk,
+        bi_castle_rook_bq: source.bi_castle_rook_bq,
+        white_checks: source.white_checks,
+        black_checks: source.black_checks,
+        variant: source.variant,
         reserved_11: source.reserved_11,
 
         b_current_piece: source.b_current_piece,
@@ -37,6 +37,7 @@ pub fn clone_from(source: &mut Reset) -> Reset {
         promotion: source.promotion,
         king_castled: source.king_castled,
         game_over: source.game_over,
+        null_move: source.null_move,
 
         b_from: source.b_from,
         b_to: source.b_to,
@@ -52,6 +53,10 @@ pub fn clone_from(source: &mut Reset) -> Reset {
         bi_current_piece: source.bi_current_piece,
         pin_dimension: source.pin_dimension,
         current_piece_type: source.current_piece_type,
+        drop_piece: source.drop_piece,
+        drop_square: source.drop_square,
+        must_capture: source.must_capture,
+        move_filter: source.move_filter,
     }
 }
 
@@ -79,7 +84,9 @@ impl Reset {
         clone.b_bishops = self.b_bishops;
         clone.b_rooks = self.b_rooks;
         clone.b_kings = self.b_kings;
-        clone.reserved_01 = self.reserved_01;
+        clone.b_promoted = self.b_promoted;
+        clone.pockets = self.pockets;
+        clone.reserved_12 = self.reserved_12;
         clone.b_current_piece = self.b_current_piece;
         clone.b_en_passant = self.b_en_passant;
         clone.b_from = self.b_from;
@@ -96,15 +103,13 @@ impl Reset {
         clone.white_king_square = self.white_king_square;
         clone.black_king_square = self.black_king_square;
         clone.castle_bits = self.castle_bits;
-        clone.reserved_02 = self.reserved_02;
-        clone.reserved_03 = self.reserved_03;
-        clone.reserved_04 = self.reserved_04;
-        clone.reserved_05 = self.reserved_05;
-        clone.reserved_06 = self.reserved_06;
-        clone.reserved_07 = self.reserved_07;
-        clone.reserved_08 = self.reserved_08;
-        clone.reserved_09 = self.reserved_09;
-        clone.reserved_10 = self.reserved_10;
+        clone.bi_castle_rook_wk = self.bi_castle_rook_wk;
+        clone.bi_castle_rook_wq = self.bi_castle_rook_wq;
+        clone.bi_castle_rook_bk = self.bi_castle_rook_bk;
+        clone.bi_castle_rook_bq = self.bi_castle_rook_bq;
+        clone.white_checks = self.white_checks;
+        clone.black_checks = self.black_checks;
+        clone.variant = self.variant;
         clone.reserved_11 = self.reserved_11;
 
         clone.move_id = self.move_id;
@@ -114,6 +119,7 @@ impl Reset {
         clone.promotion = self.promotion;
         clone.king_castled = self.king_castled;
         clone.game_over = self.game_over;
+        clone.null_move = self.null_move;
 
         clone.bi_from = self.bi_from;
         clone.bi_to = self.bi_to;
@@ -124,6 +130,10 @@ impl Reset {
         clone.bi_current_piece = self.bi_current_piece;
         clone.pin_dimension = self.pin_dimension;
         clone.current_piece_type = self.current_piece_type;
+        clone.drop_piece = self.drop_piece;
+        clone.drop_square = self.drop_square;
+        clone.must_capture = self.must_capture;
+        clone.move_filter = self.move_filter;
     }
 }
 
@@ -181,6 +191,7 @@ mod tests {
         r.promotion = 1;
         r.king_castled = 1;
         r.game_over = 1;
+        r.null_move = 1;
         r.must_check_safety = 1;
         r.bi_current_piece = 132;
         r.pin_dimension = 14;
@@ -209,6 +220,7 @@ mod tests {
         assert_eq!(child.promotion,1);
         assert_eq!(child.king_castled,1);
         assert_eq!(child.game_over,1);
+        assert_eq!(child.null_move,1);
         assert_eq!(child.must_check_safety,1);
         assert_eq!(child.bi_current_piece,132);
         assert_eq!(child.pin_dimension,14);
@@ -35,23 +35,12 @@ pub const B_KNIGHT_CAN_MOVE_0800: u64 = 0x3f3f3f3f3f3f3f00;
 pub const B_KNIGHT_CAN_MOVE_1000: u64 = 0x003f3f3f3f3f3f3f;
 pub const B_KNIGHT_CAN_MOVE_1100: u64 = 0x00007f7f7f7f7f7f;
 
-pub const B_WHITE_CASTLEK_SAFETY: u64 = 0x000000000000000e;
-pub const B_WHITE_CASTLEQ_SAFETY: u64 = 0x0000000000000038;
-pub const B_BLACK_CASTLEK_SAFETY: u64 = 0x0e00000000000000;
-pub const B_BLACK_CASTLEQ_SAFETY: u64 = 0x3800000000000000;
-
-pub const B_WHITE_CASTLEK_EMPTY: u64 = 0x0000000000000006;
-pub const B_WHITE_CASTLEQ_EMPTY: u64 = 0x0000000000000070;
-pub const B_BLACK_CASTLEK_EMPTY: u64 = 0x0600000000000000;
-pub const B_BLACK_CASTLEQ_EMPTY: u64 = 0x7000000000000000;
-
-pub const B_WHITE_CASTLEK_DESTINATION: u64 = 0x0000000000000002;
-pub const B_WHITE_CASTLEQ_DESTINATION: u64 = 0x0000000000000020;
-pub const B_BLACK_CASTLEK_DESTINATION: u64 = 0x0200000000000000;
-pub const B_BLACK_CASTLEQ_DESTINATION: u64 = 0x2000000000000000;
-
 pub const SCORE_MIN: i32 = -500000000;
 pub const SCORE_MAX: i32 = 500000000;
 pub const SCORE_STALEMATE: i32 = 0;
 pub const SCORE_BLACK_CHECKMATE: i32 = -127000000;
 pub const SCORE_WHITE_CHECKMATE: i32 = 127000000;
+pub const SCORE_MATE_PLY_LIMIT: i32 = 1000;
+pub const SCORE_PAWN: i32 = 1000000;
+pub const SCORE_TABLEBASE_WIN: i32 = 100000000;
+pub const SCORE_KNOWN_WIN: i32 = 50000000;
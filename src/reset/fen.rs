@@ -1,14 +1,104 @@
+use std::fmt;
+
 use crate::reset::Reset;
 use crate::utils;
 use crate::bitops;
+use crate::reset::PieceType;
+use crate::reset::r#const::B_RANK_1;
+use crate::reset::r#const::B_RANK_8;
+use crate::reset::variant::Variant;
+use crate::reset::goals::CHECKS_TO_WIN;
+
+/// Why a FEN couldn't be read, or doesn't describe a legal position
+#[derive(PartialEq,Eq,Clone,Debug)]
+pub enum FenError {
+    /// Not the six fields a FEN has (checks given in Three-check aside)
+    FieldCount(usize),
+    /// Piece placement that doesn't have eight ranks
+    RankCount(usize),
+    /// A rank (numbered 8 down to 1, in the order they're written) that doesn't cover eight squares
+    RankLength(u8),
+    /// A character in the piece placement that isn't a piece, a digit or a promotion mark
+    InvalidPiece(char),
+    /// Something other than "w" or "b" to move
+    BadSideToMove(String),
+    /// A castling field that isn't "-", "KQkq" letters or rook files
+    BadCastling(String),
+    /// An en passant field that isn't "-" or a square a pawn could just have passed over
+    BadEnPassant(String),
+    /// A halfmove clock or fullmove number that isn't a number in range
+    BadClock(String),
+    /// Crazyhouse pocket letters that aren't droppable pieces
+    BadPocket(String),
+    /// Three-check counts that aren't numbers
+    BadChecks(String),
+    /// A side (white if `true`) without exactly one king
+    KingCount(bool),
+    /// A pawn on the first or eighth rank
+    PawnOnBackRank(String),
+    /// The side that isn't to move is in check, so its king could be taken
+    OpponentInCheck,
+    /// A castling right ("K", "Q", "k" or "q") whose king or rook isn't where castling needs it
+    CastlingRights(char),
+}
+
+impl fmt::Display for FenError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FenError::FieldCount(count) => write!(f, "expected 6 fields, found {}", count),
+            FenError::RankCount(count) => write!(f, "expected 8 ranks, found {}", count),
+            FenError::RankLength(rank) => write!(f, "rank {} doesn't cover 8 squares", rank),
+            FenError::InvalidPiece(c) => write!(f, "invalid piece \"{}\"", c),
+            FenError::BadSideToMove(text) => write!(f, "invalid side to move \"{}\"", text),
+            FenError::BadCastling(text) => write!(f, "invalid castling field \"{}\"", text),
+            FenError::BadEnPassant(text) => write!(f, "invalid en passant field \"{}\"", text),
+            FenError::BadClock(text) => write!(f, "invalid move clock \"{}\"", text),
+            FenError::BadPocket(text) => write!(f, "invalid pocket \"{}\"", text),
+            FenError::BadChecks(text) => write!(f, "invalid check counts \"{}\"", text),
+            FenError::KingCount(white) => write!(f, "{} doesn't have exactly one king", if *white { "white" } else { "black" }),
+            FenError::PawnOnBackRank(square) => write!(f, "pawn on {}", square),
+            FenError::OpponentInCheck => write!(f, "the side not to move is in check"),
+            FenError::CastlingRights(c) => write!(f, "castling right \"{}\" without its king and rook in place", c),
+        }
+    }
+}
 
-use crate::bitops::r#const::U8_BIT1;
-use crate::bitops::r#const::U8_BIT2;
-use crate::bitops::r#const::U8_BIT3;
-use crate::bitops::r#const::U8_BIT4;
+impl std::error::Error for FenError {}
+
+/// Read a FEN from outside the engine, checking both its form and that it describes a legal
+/// position
+///
+/// # Examples
+///
+/// ```
+/// use chessica::reset::fen::{from_fen, FenError};
+/// let mut r = from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").unwrap();
+/// assert_eq!(r.to_fen(),"rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+/// assert_eq!(from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq -").err(),Some(FenError::FieldCount(4)));
+/// assert_eq!(from_fen("8/8/8/8/8/8/8/4K3 w - - 0 1").err(),Some(FenError::KingCount(false)));
+/// ```
+pub fn from_fen(fen: &str) -> Result<Reset, FenError> {
+    let mut r = crate::reset::new();
+    r.parse_fen(fen)?;
+    r.validate_position()?;
+    Ok(r)
+}
+
+/// Can an en passant field name this square, with this side to move?
+fn en_passant_square_is_valid(square: &str, white_to_move: bool) -> bool {
+    let mut chars = square.chars();
+    let file = chars.next();
+    let rank = chars.next();
+    matches!(file, Some('a'..='h')) &&
+        rank == Some(if white_to_move { '6' } else { '3' }) &&
+        chars.next().is_none()
+}
 
 impl Reset {
     /// Initialize a Reset from FEN notation
+    ///
+    /// For FENs known to be well formed, such as the engine's own; panics on one that can't be
+    /// read.  Anything from outside should go through `fen::from_fen` instead.
     /// 
     /// # Examples
     ///
@@ -19,20 +109,85 @@ impl Reset {
     /// r.init_from_fen(fen);
     /// ```
     pub fn init_from_fen(&mut self, fen: String) {
-        let chunks:Vec<&str>= fen.split(' ').collect();
+        if let Err(error) = self.parse_fen(&fen) {
+            panic!("Invalid FEN \"{}\": {}",fen,error);
+        }
+    }
+
+    /// Initialize a Reset from FEN notation, reporting the first field that can't be read
+    ///
+    /// Only the form of the FEN is checked; `validate_position` checks that it makes sense.  On
+    /// an error the Reset is left part filled, and should be thrown away.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chessica::reset::fen::FenError;
+    /// let mut r = chessica::reset::new();
+    /// assert_eq!(r.parse_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNX w KQkq - 0 1"),Err(FenError::InvalidPiece('X')));
+    /// ```
+    pub fn parse_fen(&mut self, fen: &str) -> Result<(), FenError> {
+        let mut chunks:Vec<&str>= fen.split_whitespace().collect();
+
+        // Three-check counts come as checks given after the move number ("+1+0"), or as checks
+        // remaining before the clocks ("2+3")
+        if let Some(pos) = chunks.iter().position(|chunk| chunk.chars().skip(1).any(|c| c == '+')) {
+            let text = chunks[pos];
+            let counts: Vec<&str> = text.split('+').filter(|count| !count.is_empty()).collect();
+            let counts: Vec<u8> = match counts.iter().map(|count| count.parse()).collect::<Result<Vec<u8>, _>>() {
+                Ok(counts) if counts.len() == 2 => counts,
+                _ => return Err(FenError::BadChecks(text.to_string())),
+            };
+            self.variant = Variant::ThreeCheck;
+            if text.starts_with('+') {
+                self.set_checks_given(counts[0], counts[1]);
+            } else {
+                self.set_checks_given(CHECKS_TO_WIN.saturating_sub(counts[0]), CHECKS_TO_WIN.saturating_sub(counts[1]));
+            }
+            chunks.remove(pos);
+        }
+        if chunks.len() != 6 {
+            return Err(FenError::FieldCount(chunks.len()));
+        }
 
         // PROCESS THE PIECE POSITIONS (Chunk 0)
-        let rows:Vec<&str>= chunks[0].split('/').collect();
-        #[allow(clippy::needless_range_loop)]
-        for y in 0..8 {
+        // Crazyhouse pockets follow the board, in brackets ("...RNBQKBNR[Qp]") or as a ninth rank
+        let (board, pocket) = match chunks[0].split_once('[') {
+            Some((board, pocket)) => (board, Some(pocket.trim_end_matches(']'))),
+            None => match chunks[0].split('/').nth(8) {
+                Some(pocket) => (&chunks[0][..chunks[0].len() - pocket.len() - 1], Some(pocket)),
+                None => (chunks[0], None),
+            },
+        };
+        if let Some(pocket) = pocket {
+            self.variant = Variant::Crazyhouse;
+            if !self.set_pockets(pocket) {
+                return Err(FenError::BadPocket(pocket.to_string()));
+            }
+        }
+        let rows:Vec<&str>= board.split('/').collect();
+        if rows.len() != 8 {
+            return Err(FenError::RankCount(rows.len()));
+        }
+        for (y, row) in rows.iter().enumerate() {
+            let rank = 8 - y as u8;
             let mut x = 0;
-            for c in rows[y].chars() {
+            let mut b_last: u64 = 0;
+            for c in row.chars() {
                 let mut bit: u64 = 1; 
                 match c {
                     '1'|'2'|'3'|'4'|'5'|'6'|'7'|'8' => {
                         x += c as u32 - '0' as u32;
+                        b_last = 0;
+                    },
+                    '~' if b_last != 0 => {
+                        // The piece before was promoted
+                        self.b_promoted |= b_last;
                     },
                     'k'|'q'|'r'|'b'|'n'|'p'|'K'|'Q'|'R'|'B'|'N'|'P' => {
+                        if x >= 8 {
+                            return Err(FenError::RankLength(rank));
+                        }
                         bit <<= 7 - x + 8*(7 - y as u32);
                         self.b_all |= bit;
                         let material_multiplier: i8 = match c {
@@ -68,11 +223,15 @@ impl Reset {
                                 self.material += material_multiplier;
                             },
                         }
+                        b_last = bit;
                         x += 1;
                     },
-                    _ => println!("I don't know what to do with {}",c),
+                    _ => return Err(FenError::InvalidPiece(c)),
                 }
             }
+            if x != 8 {
+                return Err(FenError::RankLength(rank));
+            }
         }
         
         // PROCESS WHO'S MOVE IT IS (Chunk 1)
@@ -83,48 +242,40 @@ impl Reset {
             "w" => {
                 self.to_move = 0;
             },
-            _ => println!("I don't know what to do with {}",chunks[1]),
+            _ => return Err(FenError::BadSideToMove(chunks[1].to_string())),
         }
 
+        // Set king squares appropriately
+        self.white_king_square = bitops::get_bit_number(self.b_white & self.b_kings);
+        self.black_king_square = bitops::get_bit_number(self.b_black() & self.b_kings);
+
         // PROCESS CASTLE ELIGIBILITY (Chunk 2)
-        for c in chunks[2].chars() {
-            match c {
-                '-' => {},
-                'K' => {
-                    //white_castle_k = 1;
-                    self.castle_bits |= U8_BIT1;
-                },
-                'Q' => {
-                    //white_castle_q = 1;
-                    self.castle_bits |= U8_BIT2;
-                },
-                'k' => {
-                    //black_castle_k = 1;
-                    self.castle_bits |= U8_BIT3;
-                },
-                'q' => {
-                    //black_castle_q = 1;
-                    self.castle_bits |= U8_BIT4;
-                },
-                _ => println!("I don't know what to do with {}",c),
-            }
+        if chunks[2] != "-" && !chunks[2].chars().all(|c| self.add_castle_right(c)) {
+            return Err(FenError::BadCastling(chunks[2].to_string()));
         }
 
         // PROCESS EN PASSANT SQUARE (Chunk 3)
         if chunks[3] != "-" {
+            if !en_passant_square_is_valid(chunks[3], self.white_to_move()) {
+                return Err(FenError::BadEnPassant(chunks[3].to_string()));
+            }
             self.b_en_passant = utils::convert_square_to_bitstring(chunks[3].to_string());
         }
 
         // PROCESS HALFMOVE CLOCK (Chunk 4)
-        self.halfmove_clock = chunks[4].parse().unwrap();
+        self.halfmove_clock = chunks[4].parse().map_err(|_| FenError::BadClock(chunks[4].to_string()))?;
 
         // PROCESS MOVE NUMBER (Chunk 5)
-        self.fullmove_number = chunks[5].parse().unwrap();
+        self.fullmove_number = chunks[5].parse().map_err(|_| FenError::BadClock(chunks[5].to_string()))?;
 
-        // Set king squares appropriately
-        self.white_king_square = bitops::get_bit_number(self.b_white & self.b_kings);
-        self.black_king_square = bitops::get_bit_number(self.b_black() & self.b_kings);
+        self.complete_setup();
+        Ok(())
+    }
 
+    /// Derive the check indicator and game state of a freshly placed position, and get it ready
+    /// to generate moves
+    pub fn complete_setup(&mut self) {
+        self.null_move = 0;
         // Set check indicator appropriately
         if self.white_to_move() {
             if !self.white_is_safe(self.b_kings & self.b_white) {
@@ -133,10 +284,72 @@ impl Reset {
         } else if !self.black_is_safe(self.b_kings & self.b_black()) {
             self.in_check = 1;
         }
+        self.update_game_over();
         self.initialize_move_generation();
         self.complete_move_initialization();
     }
 
+    /// Check that the position is one a game could reach, by the rules of its variant
+    ///
+    /// Each side needs exactly one king (any number in Antichess), no pawn may stand on the
+    /// first or eighth rank, the side not to move can't be in check, and each castling right
+    /// needs its king on the back rank and its rook beside it on the right wing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chessica::reset::fen::FenError;
+    /// let mut r = chessica::reset::new();
+    /// r.init_from_fen("4k3/8/8/8/8/8/8/R3K3 b Q - 0 1".to_string());
+    /// assert_eq!(r.validate_position(),Ok(()));
+    /// let mut r = chessica::reset::new();
+    /// r.init_from_fen("4k3/8/8/8/8/8/8/R3K3 b K - 0 1".to_string());
+    /// assert_eq!(r.validate_position(),Err(FenError::CastlingRights('K')));
+    /// ```
+    pub fn validate_position(&mut self) -> Result<(), FenError> {
+        if self.variant != Variant::Antichess {
+            for white in [true, false] {
+                if self.count_of(PieceType::King, white) != 1 {
+                    return Err(FenError::KingCount(white));
+                }
+            }
+        }
+        let b_misplaced_pawns = self.b_pawns & (B_RANK_1 | B_RANK_8);
+        if b_misplaced_pawns != 0 {
+            return Err(FenError::PawnOnBackRank(utils::convert_bitstring_to_square(bitops::lowest_bit(b_misplaced_pawns))));
+        }
+        if !self.king_is_safe(!self.white_to_move()) {
+            return Err(FenError::OpponentInCheck);
+        }
+        if self.variant != Variant::Antichess {
+            let rights = [
+                (self.white_castle_k(), true, true, 'K'),
+                (self.white_castle_q(), true, false, 'Q'),
+                (self.black_castle_k(), false, true, 'k'),
+                (self.black_castle_q(), false, false, 'q'),
+            ];
+            for (held, white, kingside, letter) in rights {
+                if held && !self.castle_pieces_in_place(white, kingside) {
+                    return Err(FenError::CastlingRights(letter));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Are the king and rook a castling right needs where castling would find them?
+    fn castle_pieces_in_place(&self, white: bool, kingside: bool) -> bool {
+        let (rank, b_side, bi_king) = if white {
+            (0, self.b_white, self.white_king_square)
+        } else {
+            (56, self.b_black(), self.black_king_square)
+        };
+        let bi_rook = self.castle_rook_square(white, kingside);
+        let king_on_back_rank = bi_king > rank && bi_king <= rank + 8;
+        let rook_on_wing = if kingside { bi_rook < bi_king } else { bi_rook > bi_king };
+        king_on_back_rank && rook_on_wing && self.b_rooks & b_side & (1 << (bi_rook - 1)) != 0
+    }
+
     /// Generate a FEN notation string from a reset
     /// 
     /// # Examples
@@ -202,6 +415,9 @@ impl Reset {
                             fen.push('k');
                         }
                     }
+                    if pointer & self.b_promoted != 0 {
+                        fen.push('~');
+                    }
                 } else {
                     emptycount += 1;
                 }
@@ -214,6 +430,11 @@ impl Reset {
                 fen.push('/');
             }
         }
+        if self.variant == Variant::Crazyhouse {
+            fen.push('[');
+            fen.push_str(&self.pocket_text());
+            fen.push(']');
+        }
         
         // PROCESS WHO'S MOVE IT IS (Chunk 1)
         fen.push(' ');
@@ -225,30 +446,11 @@ impl Reset {
 
         // PROCESS CASTLE ELIGIBILITY (Chunk 2)
         fen.push(' ');
-        let mut any_castle = 0;
-        if self.white_castle_k() {
-            fen.push('K');
-            any_castle = 1;
-        }
-        if self.white_castle_q() {
-            fen.push('Q');
-            any_castle = 1;
-        }
-        if self.black_castle_k() {
-            fen.push('k');
-            any_castle = 1;
-        }
-        if self.black_castle_q() {
-            fen.push('q');
-            any_castle = 1;
-        }
-        if any_castle == 0 {
-          fen.push('-');
-        }
+        fen.push_str(&self.castle_text());
 
         // PROCESS EN PASSANT SQUARE (Chunk 3)
         fen.push(' ');
-        if self.b_en_passant == 0 {
+        if !self.en_passant_capture_possible() {
             fen.push('-');
         } else {
             fen.push_str(&utils::convert_bitstring_to_square(self.b_en_passant));
@@ -262,13 +464,35 @@ impl Reset {
         fen.push(' ');
         fen.push_str(&self.fullmove_number.to_string());
 
+        // CHECKS GIVEN (Three-check only)
+        if self.variant == Variant::ThreeCheck {
+            fen.push_str(&format!(" +{}+{}", self.white_checks, self.black_checks));
+        }
+
         fen
     }
+
+    /// Generate an EPD line from a reset: the first four FEN fields, with the clocks as the
+    /// `hmvc` and `fmvn` operations
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut r = chessica::reset::fen::from_fen("4k3/8/8/8/8/8/4P3/4K3 b - - 3 40").unwrap();
+    /// assert_eq!(r.to_epd(),"4k3/8/8/8/8/8/4P3/4K3 b - - hmvc 3; fmvn 40;");
+    /// ```
+    pub fn to_epd(&mut self) -> String {
+        let fen = self.to_fen();
+        let fields: Vec<&str> = fen.split(' ').take(4).collect();
+        format!("{} hmvc {}; fmvn {};",fields.join(" "),self.halfmove_clock,self.fullmove_number)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::reset;
+    use crate::reset::fen::from_fen;
+    use crate::reset::fen::FenError;
     #[test]
     fn fen_init_from_fen_starting_position() {
         let mut r = reset::new();
@@ -402,6 +626,54 @@ mod tests {
         }
     }
 
+    /// Every position of a game survives a trip through `from_fen` and `to_fen`, and its FEN only
+    /// names an en passant square when the capture is really on
+    fn assert_game_round_trips(start: &str, moves: &str) -> reset::Reset {
+        let mut r = from_fen(start).unwrap();
+        for san in moves.split_whitespace() {
+            let fen = r.to_fen();
+            assert_eq!(from_fen(&fen).unwrap().to_fen(),fen,"FEN->Reset->FEN");
+            assert_eq!(fen.split(' ').nth(3) != Some("-"),r.en_passant_capture_possible(),"{}",fen);
+            r = r.child_from_san(san).unwrap_or_else(|| panic!("{} is illegal in {}", san, fen));
+        }
+        let fen = r.to_fen();
+        assert_eq!(from_fen(&fen).unwrap().to_fen(),fen,"FEN->Reset->FEN");
+        r
+    }
+
+    #[test]
+    fn fen_round_trip_real_games() {
+        let start = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+        let games = [
+            // Morphy - Duke of Brunswick and Count Isouard, Paris 1858
+            "e4 e5 Nf3 d6 d4 Bg4 dxe5 Bxf3 Qxf3 dxe5 Bc4 Nf6 Qb3 Qe7 Nc3 c6 Bg5 b5 Nxb5 cxb5 Bxb5+ Nbd7 \
+             O-O-O Rd8 Rxd7 Rxd7 Rd1 Qe6 Bxd7+ Nxd7 Qb8+ Nxb8 Rd8#",
+            // Anderssen - Kieseritzky, London 1851
+            "e4 e5 f4 exf4 Bc4 Qh4+ Kf1 b5 Bxb5 Nf6 Nf3 Qh6 d3 Nh5 Nh4 Qg5 Nf5 c6 g4 Nf6 Rg1 cxb5 h4 Qg6 \
+             h5 Qg5 Qf3 Ng8 Bxf4 Qf6 Nc3 Bc5 Nd5 Qxb2 Bd6 Bxg1 e5 Qxa1+ Ke2 Na6 Nxg7+ Kd8 Qf6+ Nxf6 Be7#",
+            // Alekhine's Defence with an en passant capture
+            "e4 Nf6 e5 d5 exd6 cxd6 d4 g6 c4 Bg7 Nc3 O-O Be2 Nc6 d5 Ne5 f4 Ned7 Nf3 e5 dxe6 fxe6",
+            // En passant captures on both sides of the queen's wing
+            "d4 e6 d5 c5 dxc6 dxc6 a4 Nf6 a5 b5 axb6 axb6 Rxa8",
+        ];
+        for moves in games.iter() {
+            assert_game_round_trips(start, moves);
+        }
+
+        // Long games count past 255 moves and 255 quiet halfmoves
+        let mut child = assert_game_round_trips("8/8/4k3/8/8/4K3/8/8 w - - 250 300", "Kd3 Kd6 Kc3 Kc6 Kb3 Kb6");
+        assert_eq!(child.to_fen(),"8/8/1k6/8/8/1K6/8/8 w - - 256 303");
+        let mut r = from_fen("8/8/4k3/8/8/4K3/8/8 b - - 65535 65535").unwrap();
+        assert_eq!(r.child_from_san("Kd6").unwrap().to_fen(),"8/8/3k4/8/8/4K3/8/8 w - - 65535 65535");
+        assert_eq!(from_fen("8/8/4k3/8/8/4K3/8/8 w - - 0 65536").err(),Some(FenError::BadClock("65536".to_string())));
+
+        // An en passant square no pawn can capture on is dropped
+        let mut r = from_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1").unwrap();
+        assert_eq!(r.to_fen(),"rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1");
+        let mut r = from_fen("rnbqkbnr/ppp1pppp/8/8/3pP3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 2").unwrap();
+        assert_eq!(r.to_fen(),"rnbqkbnr/ppp1pppp/8/8/3pP3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 2");
+    }
+
     #[test]
     fn fen_init_from_fen_white_in_check() {
         let mut r = reset::new();
@@ -417,4 +689,70 @@ mod tests {
         r.init_from_fen(fen);
         assert_eq!(r.in_check,1,"in_check");
     }
+
+    #[test]
+    fn fen_malformed() {
+        let errors = [
+            ("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0", FenError::FieldCount(5)),
+            ("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1 x", FenError::FieldCount(7)),
+            ("rnbqkbnr/pppppppp/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1", FenError::RankCount(7)),
+            ("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPP/RNBQKBNR w KQkq - 0 1", FenError::RankLength(2)),
+            ("rnbqkbnr/pppppppp/9/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1", FenError::InvalidPiece('9')),
+            ("rnbqkbnr/pppppppp/54/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1", FenError::RankLength(6)),
+            ("rnbqkbnrr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1", FenError::RankLength(8)),
+            ("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNX w KQkq - 0 1", FenError::InvalidPiece('X')),
+            ("rnbqkbnr/pppppppp/~7/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1", FenError::InvalidPiece('~')),
+            ("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR x KQkq - 0 1", FenError::BadSideToMove("x".to_string())),
+            ("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkx - 0 1", FenError::BadCastling("KQkx".to_string())),
+            ("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq e3 0 1", FenError::BadEnPassant("e3".to_string())),
+            ("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq i6 0 1", FenError::BadEnPassant("i6".to_string())),
+            ("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - x 1", FenError::BadClock("x".to_string())),
+            ("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 -1", FenError::BadClock("-1".to_string())),
+            ("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR[K] w KQkq - 0 1", FenError::BadPocket("K".to_string())),
+            ("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1 +x+0", FenError::BadChecks("+x+0".to_string())),
+        ];
+        for (fen, error) in errors {
+            assert_eq!(from_fen(fen).err(),Some(error),"{}",fen);
+        }
+    }
+
+    #[test]
+    fn fen_non_ascii() {
+        let errors = [
+            ("é+ w - - 0 1", FenError::BadChecks("é+".to_string())),
+            ("é w - - 0 1", FenError::RankCount(1)),
+            ("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNé w KQkq - 0 1", FenError::InvalidPiece('é')),
+            ("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq é6 0 1", FenError::BadEnPassant("é6".to_string())),
+            ("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR[é] w KQkq - 0 1", FenError::BadPocket("é".to_string())),
+            ("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1 +é+0", FenError::BadChecks("+é+0".to_string())),
+        ];
+        for (fen, error) in errors {
+            assert_eq!(from_fen(fen).err(),Some(error),"{}",fen);
+        }
+    }
+
+    #[test]
+    fn fen_illegal_positions() {
+        let errors = [
+            ("4k3/8/8/8/8/8/8/8 w - - 0 1", FenError::KingCount(true)),
+            ("4k3/8/8/8/8/8/8/3KK3 w - - 0 1", FenError::KingCount(true)),
+            ("8/8/8/8/8/8/8/4K3 w - - 0 1", FenError::KingCount(false)),
+            ("4k3/8/8/8/8/8/8/P3K3 w - - 0 1", FenError::PawnOnBackRank("a1".to_string())),
+            ("4k2p/8/8/8/8/8/8/4K3 w - - 0 1", FenError::PawnOnBackRank("h8".to_string())),
+            ("4k3/8/8/8/8/8/8/4K2r b - - 0 1", FenError::OpponentInCheck),
+            ("4k3/8/8/8/8/8/8/4K3 w K - 0 1", FenError::CastlingRights('K')),
+            ("4k3/8/8/8/8/8/8/4KR2 w Q - 0 1", FenError::CastlingRights('Q')),
+            ("r3k3/8/8/8/8/8/8/4K3 w k - 0 1", FenError::CastlingRights('k')),
+            ("r7/4k3/8/8/8/8/8/4K3 w q - 0 1", FenError::CastlingRights('q')),
+        ];
+        for (fen, error) in errors {
+            assert_eq!(from_fen(fen).err(),Some(error),"{}",fen);
+        }
+
+        // Antichess allows any number of kings, and no king is ever in check
+        let mut r = reset::new();
+        r.parse_fen("8/8/8/8/8/8/8/2K1K2r w - - 0 1").unwrap();
+        r.set_variant(reset::variant::Variant::Antichess);
+        assert_eq!(r.validate_position(),Ok(()));
+    }
 }
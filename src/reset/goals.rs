@@ -0,0 +1,219 @@
+use crate::reset::Reset;
+use crate::reset::r#const::B_RANK_7;
+use crate::reset::r#const::B_RANK_8;
+use crate::reset::r#const::SCORE_PAWN;
+use crate::reset::r#const::SCORE_STALEMATE;
+use crate::reset::r#const::SCORE_BLACK_CHECKMATE;
+use crate::reset::r#const::SCORE_WHITE_CHECKMATE;
+use crate::reset::variant::Variant;
+
+/// The four centre squares (d4, e4, d5, e5) a King of the Hill king races for
+pub const B_CENTER: u64 = 0x0000001818000000;
+
+/// Checks that win a Three-check game
+pub const CHECKS_TO_WIN: u8 = 3;
+
+/// How many king moves a square number is from the nearest centre square (0-3)
+fn centre_distance(bi_square: u8) -> i32 {
+    let distance = |i: i32| (3 - i).max(i - 4).max(0);
+    let index = bi_square.max(1) as i32 - 1;
+    distance(index % 8).max(distance(index / 8))
+}
+
+/// Rank (0-7) of a square number
+fn rank_of(bi_square: u8) -> i32 {
+    (bi_square.max(1) as i32 - 1) / 8
+}
+
+impl Reset {
+
+    /// Has the game ended by a variant's goal being reached?
+    pub fn game_over(&self) -> bool {
+        self.game_over != 0
+    }
+
+    /// Number of checks a side has given (Three-check)
+    pub fn checks_given(&self, white: bool) -> u8 {
+        if white { self.white_checks } else { self.black_checks }
+    }
+
+    /// Set the number of checks each side has given, as a Three-check FEN records them
+    pub fn set_checks_given(&mut self, white_checks: u8, black_checks: u8) {
+        self.white_checks = white_checks;
+        self.black_checks = black_checks;
+    }
+
+    /// Count a check given by a side
+    pub fn count_check(&mut self, white: bool) {
+        if white {
+            self.white_checks += 1;
+        } else {
+            self.black_checks += 1;
+        }
+    }
+
+    /// Score of a game the variant's goal has ended, from white's point of view, or `None` if it
+    /// hasn't been reached
+    ///
+    /// A king on a centre square wins King of the Hill, a third check wins Three-check, and a
+    /// king on the eighth rank wins Racing Kings.  When white gets there first black has one
+    /// move left to follow (possible only from the seventh rank), which draws.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chessica::reset::variant::Variant;
+    /// use chessica::reset::r#const::SCORE_WHITE_CHECKMATE;
+    /// let mut r = chessica::reset::new();
+    /// r.init_from_fen("7k/8/8/8/4K3/8/8/8 b - - 0 1".to_string());
+    /// assert_eq!(r.goal_score(),None);
+    /// r.set_variant(Variant::KingOfTheHill);
+    /// assert_eq!(r.goal_score(),Some(SCORE_WHITE_CHECKMATE));
+    /// ```
+    pub fn goal_score(&self) -> Option<i32> {
+        let b_white_king = self.b_kings & self.b_white;
+        let b_black_king = self.b_kings & self.b_black();
+        match self.variant {
+            Variant::KingOfTheHill => {
+                if b_white_king & B_CENTER != 0 {
+                    Some(SCORE_WHITE_CHECKMATE)
+                } else if b_black_king & B_CENTER != 0 {
+                    Some(SCORE_BLACK_CHECKMATE)
+                } else {
+                    None
+                }
+            },
+            Variant::ThreeCheck => {
+                if self.white_checks >= CHECKS_TO_WIN {
+                    Some(SCORE_WHITE_CHECKMATE)
+                } else if self.black_checks >= CHECKS_TO_WIN {
+                    Some(SCORE_BLACK_CHECKMATE)
+                } else {
+                    None
+                }
+            },
+            Variant::RacingKings => {
+                match (b_white_king & B_RANK_8 != 0, b_black_king & B_RANK_8 != 0) {
+                    (true, true) => Some(SCORE_STALEMATE),
+                    (false, true) => Some(SCORE_BLACK_CHECKMATE),
+                    (true, false) if self.white_to_move() || b_black_king & B_RANK_7 == 0 => Some(SCORE_WHITE_CHECKMATE),
+                    _ => None,
+                }
+            },
+            _ => None,
+        }
+    }
+
+    /// Set `game_over` from whether the variant's goal has been reached
+    pub fn update_game_over(&mut self) {
+        self.game_over = if self.goal_score().is_some() { 1 } else { 0 };
+    }
+
+    /// Evaluation of the progress each side has made toward the variant's goal, from white's
+    /// point of view
+    ///
+    /// Half a pawn for each step a king has taken toward the centre (King of the Hill) or up the
+    /// board (Racing Kings), and a pawn for each check given (Three-check).
+    pub fn goal_progress(&self) -> i32 {
+        match self.variant {
+            Variant::KingOfTheHill => {
+                (centre_distance(self.black_king_square) - centre_distance(self.white_king_square)) * SCORE_PAWN / 2
+            },
+            Variant::ThreeCheck => (self.white_checks as i32 - self.black_checks as i32) * SCORE_PAWN,
+            Variant::RacingKings => {
+                (rank_of(self.white_king_square) - rank_of(self.black_king_square)) * SCORE_PAWN / 2
+            },
+            _ => 0,
+        }
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::reset;
+    use crate::reset::Reset;
+    use crate::reset::variant::Variant;
+    use crate::reset::r#const::SCORE_STALEMATE;
+    use crate::reset::r#const::SCORE_BLACK_CHECKMATE;
+    use crate::reset::r#const::SCORE_WHITE_CHECKMATE;
+
+    fn prep_board(fen: &str, variant: Variant) -> Reset {
+        let mut r = reset::new();
+        r.init_from_fen(fen.to_string());
+        r.set_variant(variant);
+        r
+    }
+
+    fn move_count(r: &mut Reset) -> usize {
+        let mut child = reset::new();
+        let mut count = 0;
+        r.restart_move_generation();
+        while r.generate_next_move(&mut child) {
+            count += 1;
+        }
+        count
+    }
+
+    #[test]
+    fn goals_king_of_the_hill() {
+        let mut r = prep_board("7k/8/8/8/8/4K3/8/8 w - - 0 1", Variant::KingOfTheHill);
+        assert!(!r.game_over());
+        let mut child = r.child_from_san("Kd4").unwrap();
+        assert!(child.game_over());
+        assert_eq!(child.goal_score(),Some(SCORE_WHITE_CHECKMATE));
+        assert_eq!(child.terminal_score(),SCORE_WHITE_CHECKMATE);
+        assert_eq!(move_count(&mut child),0);
+        let mut child = r.child_from_san("Kf3").unwrap();
+        assert!(!child.game_over());
+        assert!(move_count(&mut child) > 0);
+    }
+
+    #[test]
+    fn goals_three_check() {
+        let mut r = prep_board("4k3/8/8/8/8/8/8/R3K3 w - - 0 1 +2+0", Variant::ThreeCheck);
+        assert_eq!(r.checks_given(true),2);
+        let mut child = r.child_from_san("Ra8").unwrap();
+        assert_eq!(child.checks_given(true),3);
+        assert!(child.game_over());
+        assert_eq!(child.terminal_score(),SCORE_WHITE_CHECKMATE);
+        assert_eq!(child.to_fen(),"R3k3/8/8/8/8/8/8/4K3 b - - 1 1 +3+0");
+        let child = r.child_from_san("Ra7").unwrap();
+        assert_eq!(child.checks_given(true),2);
+        assert!(!child.game_over());
+
+        // Checks remaining, before the clocks, are read as well
+        let mut r = reset::new();
+        r.init_from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 2+3 0 1".to_string());
+        assert_eq!(r.variant(),Variant::ThreeCheck);
+        assert_eq!(r.to_fen(),"4k3/8/8/8/8/8/8/R3K3 w - - 0 1 +1+0");
+    }
+
+    #[test]
+    fn goals_racing_kings() {
+        // Checks may not be given
+        let mut r = prep_board("8/8/8/8/8/8/krbnNBRK/qrbnNBRQ w - - 0 1", Variant::RacingKings);
+        assert_eq!(move_count(&mut r),21);
+        let mut r = prep_board("8/8/8/8/8/8/k7/6RK w - - 0 1", Variant::RacingKings);
+        assert!(r.child_from_san("Ra1").is_none());
+        assert!(r.child_from_san("Rg3").is_some());
+
+        // White reaching the eighth rank wins, unless black can follow
+        let mut r = prep_board("8/6K1/8/k7/8/8/8/8 w - - 0 1", Variant::RacingKings);
+        let child = r.child_from_san("Kg8").unwrap();
+        assert_eq!(child.goal_score(),Some(SCORE_WHITE_CHECKMATE));
+        let mut r = prep_board("8/k5K1/8/8/8/8/8/8 w - - 0 1", Variant::RacingKings);
+        let mut child = r.child_from_san("Kg8").unwrap();
+        assert!(!child.game_over());
+        let grandchild = child.child_from_san("Kb8").unwrap();
+        assert_eq!(grandchild.goal_score(),Some(SCORE_STALEMATE));
+        let mut grandchild = child.child_from_san("Kb7").unwrap();
+        assert_eq!(grandchild.goal_score(),Some(SCORE_WHITE_CHECKMATE));
+        assert_eq!(move_count(&mut grandchild),0);
+
+        // Black getting there first wins
+        let mut r = prep_board("8/k7/8/8/8/6K1/8/8 b - - 0 1", Variant::RacingKings);
+        let child = r.child_from_san("Ka8").unwrap();
+        assert_eq!(child.goal_score(),Some(SCORE_BLACK_CHECKMATE));
+    }
+}
@@ -12,7 +12,7 @@ impl Reset {
     /// r.init_from_fen(fen);
     /// assert_eq!(r.child_hash(),0x0000000000000000);
     /// ```
-    pub fn child_hash(&mut self) -> u32 {
+    pub fn child_hash(&self) -> u32 {
         let mut child_hash: u32 = 0x0;
         child_hash |= (self.bi_from as u32) << 16;
         child_hash |= (self.bi_to as u32) << 8;
@@ -14,6 +14,87 @@ impl Reset {
     pub fn white_to_move(&self) -> bool {
         self.to_move == 0
     }
+
+    /// Bit index (1-64) of the square the last move came from
+    pub fn bi_from(&self) -> u8 {
+        self.bi_from
+    }
+
+    /// Bit index (1-64) of the square the last move went to
+    pub fn bi_to(&self) -> u8 {
+        self.bi_to
+    }
+
+    /// Half moves since the last capture or pawn move, for the fifty move rule
+    pub fn halfmove_clock(&self) -> u16 {
+        self.halfmove_clock
+    }
+
+    /// Full move number, starting at 1 and incremented after each black move
+    pub fn fullmove_number(&self) -> u16 {
+        self.fullmove_number
+    }
+
+    /// Plies played since the start of the game, derived from the full move number
+    ///
+    /// # Examples
+    /// ```
+    /// let mut r = chessica::reset::new();
+    /// r.init_from_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1".to_string());
+    /// assert_eq!(r.game_ply(),1);
+    /// ```
+    pub fn game_ply(&self) -> u16 {
+        let moves = self.fullmove_number.max(1) - 1;
+        moves * 2 + if self.white_to_move() { 0 } else { 1 }
+    }
+
+    /// Was this Reset reached by a null move ("pass") rather than a real move?
+    ///
+    /// Set by `init_null_child` and `make_null_move`.  A Reset built straight from FEN isn't
+    /// one, even though it has no move either.
+    pub fn is_null_move(&self) -> bool {
+        self.null_move != 0
+    }
+
+    /// Was the move that produced this Reset quiet (no capture, promotion or check)?
+    pub fn is_quiet(&self) -> bool {
+        self.capture == 0 && self.promotion == 0 && self.in_check == 0
+    }
+
+    /// Does the side to move have anything besides king and pawns?
+    ///
+    /// # Examples
+    /// ```
+    /// let mut r = chessica::reset::new();
+    /// r.init_from_fen("4k3/pppp4/8/8/8/8/4PPPP/4K2R b - - 0 1".to_string());
+    /// assert!(!r.has_non_pawn_material());
+    /// ```
+    pub fn has_non_pawn_material(&self) -> bool {
+        let b_side = if self.white_to_move() {
+            self.b_white
+        } else {
+            self.b_black()
+        };
+        b_side & !(self.b_pawns | self.b_kings) != 0
+    }
+
+    /// Is the king the only piece `white` (or black) has left?
+    ///
+    /// # Examples
+    /// ```
+    /// let mut r = chessica::reset::new();
+    /// r.init_from_fen("4k3/8/8/8/8/8/8/4K2R b - - 0 1".to_string());
+    /// assert!(r.has_lone_king(false));
+    /// assert!(!r.has_lone_king(true));
+    /// ```
+    pub fn has_lone_king(&self, white: bool) -> bool {
+        let b_side = if white {
+            self.b_white
+        } else {
+            self.b_black()
+        };
+        b_side & !self.b_kings == 0
+    }
 }
 
 #[cfg(test)]
@@ -28,5 +109,26 @@ mod tests {
         r.to_move = 1;
         assert!(!r.white_to_move());
     }
+
+    #[test]
+    fn helpers_has_non_pawn_material() {
+        let mut r = reset::new();
+        r.init_from_fen("4k3/pppp4/8/8/8/8/4PPPP/4K2R w - - 0 1".to_string());
+        assert!(r.has_non_pawn_material());
+        let mut r = reset::new();
+        r.init_from_fen("4k3/pppp4/8/8/8/8/4PPPP/4K2R b - - 0 1".to_string());
+        assert!(!r.has_non_pawn_material());
+    }
+
+    #[test]
+    fn helpers_is_quiet() {
+        let mut r = reset::new();
+        assert!(r.is_quiet());
+        r.capture = 1;
+        assert!(!r.is_quiet());
+        r.capture = 0;
+        r.in_check = 1;
+        assert!(!r.is_quiet());
+    }
 }
 
@@ -7,18 +7,10 @@ use crate::reset::r#const::B_NOT_S_EDGE;
 use crate::reset::r#const::B_NOT_SW_EDGE;
 use crate::reset::r#const::B_NOT_W_EDGE;
 use crate::reset::r#const::B_NOT_NW_EDGE;
-use crate::reset::r#const::B_WHITE_CASTLEK_SAFETY;
-use crate::reset::r#const::B_WHITE_CASTLEQ_SAFETY;
-use crate::reset::r#const::B_BLACK_CASTLEK_SAFETY;
-use crate::reset::r#const::B_BLACK_CASTLEQ_SAFETY;
-use crate::reset::r#const::B_WHITE_CASTLEK_EMPTY;
-use crate::reset::r#const::B_WHITE_CASTLEQ_EMPTY;
-use crate::reset::r#const::B_BLACK_CASTLEK_EMPTY;
-use crate::reset::r#const::B_BLACK_CASTLEQ_EMPTY;
-use crate::reset::r#const::B_WHITE_CASTLEK_DESTINATION;
-use crate::reset::r#const::B_WHITE_CASTLEQ_DESTINATION;
-use crate::reset::r#const::B_BLACK_CASTLEK_DESTINATION;
-use crate::reset::r#const::B_BLACK_CASTLEQ_DESTINATION;
+use crate::reset::chess960::b_span;
+use crate::reset::variant::Variant;
+use crate::bitops::r#const::U8_NOT_BIT1_OR_BIT2;
+use crate::bitops::r#const::U8_NOT_BIT3_OR_BIT4;
 
 impl Reset {
     pub fn generate_next_king_move(&mut self, child: &mut Reset) -> bool {
@@ -125,93 +117,96 @@ impl Reset {
             }
         }
 
-        if self.white_to_move() {
+        // Castle Kingside
+        let castle_k = if self.white_to_move() { self.white_castle_k() } else { self.black_castle_k() };
+        if self.move_id < 100 && castle_k && self.add_castle_move_if_valid(child, true) {
+            self.move_id = 100;
+            self.valid_child_post_processing(child);
+            return true;
+        }
 
-            // White Castle Kingside
-            if self.move_id < 100 && 
-                self.white_castle_k() &&
-                (self.b_all & B_WHITE_CASTLEK_EMPTY == 0) &&
-                self.white_is_safe(B_WHITE_CASTLEK_SAFETY)
-            {
-                self.add_move_unconditional(child, B_WHITE_CASTLEK_DESTINATION);
-                child.b_all &= 0xfffffffffffffffe;
-                child.b_white &= 0xfffffffffffffffe;
-                child.b_rooks &= 0xfffffffffffffffe;
-                child.b_all |= 0x0000000000000004;
-                child.b_white |= 0x0000000000000004;
-                child.b_rooks |= 0x0000000000000004;
-                if !child.black_is_safe(child.b_kings & child.b_black()) {
-                    child.in_check = 1;
-                }
-                self.move_id = 100;
-                self.valid_child_post_processing(child);
-                return true;
-            }
+        // Castle Queenside
+        let castle_q = if self.white_to_move() { self.white_castle_q() } else { self.black_castle_q() };
+        if self.move_id < 110 && castle_q && self.add_castle_move_if_valid(child, false) {
+            self.consider_next_moveable_piece();
+            self.valid_child_post_processing(child);
+            return true;
+        }
 
-            // White Castle Queenside
-            if self.move_id < 110 && 
-                self.white_castle_q() &&
-                (self.b_all & B_WHITE_CASTLEQ_EMPTY == 0) &&
-                self.white_is_safe(B_WHITE_CASTLEQ_SAFETY)
-            {
-                self.add_move_unconditional(child, B_WHITE_CASTLEQ_DESTINATION);
-                child.b_all &= 0xffffffffffffff7f;
-                child.b_white &= 0xffffffffffffff7f;
-                child.b_rooks &= 0xffffffffffffff7f;
-                child.b_all |= 0x0000000000000010;
-                child.b_white |= 0x0000000000000010;
-                child.b_rooks |= 0x0000000000000010;
-                if !child.black_is_safe(child.b_kings & child.b_black()) {
-                    child.in_check = 1;
-                }
-                self.consider_next_moveable_piece();
-                self.valid_child_post_processing(child);
-                return true;
-            }
+        self.consider_next_moveable_piece();
+        false
+    }
 
+    /// Castles the moving side on one wing into the child, if the rules allow it
+    ///
+    /// The king ends on the g- or c-file and the rook beside it on the f- or d-file, wherever
+    /// they started (Chess960).  The castling rook may have been screening the king's
+    /// destination along the rank, so the child still has its king safety settled.
+    pub fn add_castle_move_if_valid(&mut self, child: &mut Reset, kingside: bool) -> bool {
+        if !self.castle_is_open(kingside) {
+            return false;
+        }
+        let white = self.white_to_move();
+        let rank: u8 = if white { 0 } else { 56 };
+        let bi_king = if white { self.white_king_square } else { self.black_king_square };
+        let bi_rook = self.castle_rook_square(white, kingside);
+        let (bi_king_to, bi_rook_to) = if kingside { (rank + 2, rank + 3) } else { (rank + 6, rank + 5) };
+        let b_king: u64 = 1 << (bi_king - 1);
+        let b_rook: u64 = 1 << (bi_rook - 1);
+        let b_king_to: u64 = 1 << (bi_king_to - 1);
+        let b_rook_to: u64 = 1 << (bi_rook_to - 1);
+
+        self.init_child(child);
+        child.b_from = b_king;
+        child.b_to = b_king_to;
+        child.bi_from = bi_king;
+        child.bi_to = bi_king_to;
+        child.b_all = (child.b_all & !(b_king | b_rook)) | b_king_to | b_rook_to;
+        child.b_kings = (child.b_kings & !b_king) | b_king_to;
+        child.b_rooks = (child.b_rooks & !b_rook) | b_rook_to;
+        child.king_castled = 1;
+        if white {
+            child.b_white = (child.b_white & !(b_king | b_rook)) | b_king_to | b_rook_to;
+            child.white_king_square = bi_king_to;
+            child.castle_bits &= U8_NOT_BIT1_OR_BIT2;
         } else {
+            child.black_king_square = bi_king_to;
+            child.castle_bits &= U8_NOT_BIT3_OR_BIT4;
+        }
 
-            // Black Castle Kingside
-            if self.move_id < 100 && 
-                self.black_castle_k() &&
-                (self.b_all & B_BLACK_CASTLEK_EMPTY == 0) &&
-                self.black_is_safe(B_BLACK_CASTLEK_SAFETY)
-            {
-                self.add_move_unconditional(child, B_BLACK_CASTLEK_DESTINATION);
-                child.b_all &= 0xfeffffffffffffff;
-                child.b_rooks &= 0xfeffffffffffffff;
-                child.b_all |= 0x0400000000000000;
-                child.b_rooks |= 0x0400000000000000;
-                if !child.white_is_safe(child.b_kings & child.b_white) {
-                    child.in_check = 1;
-                }
-                self.move_id = 100;
-                self.valid_child_post_processing(child);
-                return true;
-            }
+        child.settle_king_safety(white)
+    }
 
-            // Black Castle Queenside
-            if self.move_id < 110 && 
-                self.black_castle_q() &&
-                (self.b_all & B_BLACK_CASTLEQ_EMPTY == 0) &&
-                self.black_is_safe(B_BLACK_CASTLEQ_SAFETY)
-            {
-                self.add_move_unconditional(child, B_BLACK_CASTLEQ_DESTINATION);
-                child.b_all &= 0x7fffffffffffffff;
-                child.b_rooks &= 0x7fffffffffffffff;
-                child.b_all |= 0x1000000000000000;
-                child.b_rooks |= 0x1000000000000000;
-                if !child.white_is_safe(child.b_kings & child.b_white) {
-                    child.in_check = 1;
-                }
-                self.consider_next_moveable_piece();
-                self.valid_child_post_processing(child);
-                return true;
-            }
+    /// Could the moving side castle on one wing, given the rights?
+    ///
+    /// Every square the king or rook passes over or lands on must be empty but for the two of
+    /// them, and the king may not start in, pass through or land in check.  Antichess has no
+    /// castling.
+    pub fn castle_is_open(&mut self, kingside: bool) -> bool {
+        if self.variant == Variant::Antichess {
+            return false;
+        }
+        let white = self.white_to_move();
+        let rank: u8 = if white { 0 } else { 56 };
+        let bi_king = if white { self.white_king_square } else { self.black_king_square };
+        let bi_rook = self.castle_rook_square(white, kingside);
+        let (bi_king_to, bi_rook_to) = if kingside { (rank + 2, rank + 3) } else { (rank + 6, rank + 5) };
+        let b_king: u64 = 1 << (bi_king - 1);
+        let b_rook: u64 = 1 << (bi_rook - 1);
+        let b_side = if white { self.b_white } else { self.b_black() };
+
+        let b_king_path = b_span(bi_king, bi_king_to);
+        let b_empty = (b_king_path | b_span(bi_rook, bi_rook_to)) & !(b_king | b_rook);
+        if self.b_rooks & b_side & b_rook == 0 || self.b_all & b_empty != 0 {
+            return false;
+        }
+        if self.variant == Variant::Atomic {
+            self.atomic_squares_are_safe(white, b_king_path)
+        } else if white {
+            self.white_is_safe(b_king_path)
+        } else {
+            self.black_is_safe(b_king_path)
         }
-
-        self.consider_next_moveable_piece();
-        false
     }
 
 }
@@ -0,0 +1,968 @@
+use crate::reset::Reset;
+use crate::reset::PieceType;
+use crate::reset::moves::MoveFilter;
+use crate::reset::pocket::drop_material;
+use crate::reset::pocket::POCKET_PIECES;
+use crate::reset::variant::Variant;
+use crate::reset::pinned::PIN_DIMENSION_NONE;
+use crate::reset::pinned::PIN_MATCH_NS;
+use crate::reset::pinned::PIN_MATCH_EW;
+use crate::reset::pinned::PIN_MATCH_NESW;
+use crate::reset::pinned::PIN_MATCH_SENW;
+use crate::reset::pinned::PIN_MATCH_NONE;
+use crate::reset::r#const::BLACK;
+use crate::reset::r#const::WHITE;
+use crate::reset::r#const::B_NOT_N_EDGE;
+use crate::reset::r#const::B_NOT_NE_EDGE;
+use crate::reset::r#const::B_NOT_E_EDGE;
+use crate::reset::r#const::B_NOT_SE_EDGE;
+use crate::reset::r#const::B_NOT_S_EDGE;
+use crate::reset::r#const::B_NOT_SW_EDGE;
+use crate::reset::r#const::B_NOT_W_EDGE;
+use crate::reset::r#const::B_NOT_NW_EDGE;
+use crate::reset::r#const::B_KNIGHT_CAN_MOVE_0100;
+use crate::reset::r#const::B_KNIGHT_CAN_MOVE_0200;
+use crate::reset::r#const::B_KNIGHT_CAN_MOVE_0400;
+use crate::reset::r#const::B_KNIGHT_CAN_MOVE_0500;
+use crate::reset::r#const::B_KNIGHT_CAN_MOVE_0700;
+use crate::reset::r#const::B_KNIGHT_CAN_MOVE_0800;
+use crate::reset::r#const::B_KNIGHT_CAN_MOVE_1000;
+use crate::reset::r#const::B_KNIGHT_CAN_MOVE_1100;
+use crate::reset::r#const::B_RANK_1;
+use crate::reset::r#const::B_RANK_2;
+use crate::reset::r#const::B_RANK_7;
+use crate::reset::r#const::B_RANK_8;
+use crate::bitops;
+use crate::bitops::r#const::U8_NOT_BIT1_OR_BIT2;
+use crate::bitops::r#const::U8_NOT_BIT3_OR_BIT4;
+
+const MOVE_CAPTURE: u8 = 0x01;
+const MOVE_CHECK: u8 = 0x02;
+const MOVE_CASTLE: u8 = 0x04;
+
+/// One step of a piece: the shift to the square it reaches (left if positive, right if
+/// negative), the squares it can take that step from, and the pins it can't be under to take it
+type Step = (i8, u64, u8);
+
+const KNIGHT_STEPS: [Step; 8] = [
+    (15, B_KNIGHT_CAN_MOVE_0100, PIN_MATCH_NONE), (6, B_KNIGHT_CAN_MOVE_0200, PIN_MATCH_NONE),
+    (-10, B_KNIGHT_CAN_MOVE_0400, PIN_MATCH_NONE), (-17, B_KNIGHT_CAN_MOVE_0500, PIN_MATCH_NONE),
+    (-15, B_KNIGHT_CAN_MOVE_0700, PIN_MATCH_NONE), (-6, B_KNIGHT_CAN_MOVE_0800, PIN_MATCH_NONE),
+    (10, B_KNIGHT_CAN_MOVE_1000, PIN_MATCH_NONE), (17, B_KNIGHT_CAN_MOVE_1100, PIN_MATCH_NONE),
+];
+const BISHOP_STEPS: [Step; 4] = [
+    (7, B_NOT_NE_EDGE, PIN_MATCH_NESW), (-9, B_NOT_SE_EDGE, PIN_MATCH_SENW),
+    (-7, B_NOT_SW_EDGE, PIN_MATCH_NESW), (9, B_NOT_NW_EDGE, PIN_MATCH_SENW),
+];
+const ROOK_STEPS: [Step; 4] = [
+    (8, B_NOT_N_EDGE, PIN_MATCH_NS), (-8, B_NOT_S_EDGE, PIN_MATCH_NS),
+    (1, B_NOT_W_EDGE, PIN_MATCH_EW), (-1, B_NOT_E_EDGE, PIN_MATCH_EW),
+];
+/// Queen lines and king steps, clockwise from north
+const ROYAL_STEPS: [Step; 8] = [
+    (8, B_NOT_N_EDGE, PIN_MATCH_NS), (7, B_NOT_NE_EDGE, PIN_MATCH_NESW),
+    (-1, B_NOT_E_EDGE, PIN_MATCH_EW), (-9, B_NOT_SE_EDGE, PIN_MATCH_SENW),
+    (-8, B_NOT_S_EDGE, PIN_MATCH_NS), (-7, B_NOT_SW_EDGE, PIN_MATCH_NESW),
+    (1, B_NOT_W_EDGE, PIN_MATCH_EW), (9, B_NOT_NW_EDGE, PIN_MATCH_SENW),
+];
+const PROMOTION_PIECES: [PieceType; 5] = [
+    PieceType::Knight,
+    PieceType::Bishop,
+    PieceType::Rook,
+    PieceType::Queen,
+    PieceType::King,
+];
+
+fn step(b_square: u64, shift: i8) -> u64 {
+    if shift > 0 { b_square << shift } else { b_square >> -shift }
+}
+
+/// A legal move, small enough to keep lists of
+///
+/// Moves come from `Reset::generate_moves` (or `Reset::move_to`, for a child the move generator
+/// produced), so they are known to be legal, and whether they give check is already settled.
+#[derive(PartialEq,Eq,Copy,Clone,Debug)]
+pub struct Move {
+    /// Square number the piece moved from, 0 for a Crazyhouse drop
+    bi_from: u8,
+    /// Square number the piece (the king, when castling) moved to
+    bi_to: u8,
+    /// Piece that moved, or was dropped
+    piece_type: PieceType,
+    /// Piece a pawn promoted to, `PieceType::Unknown` if it didn't
+    promotion_piece: PieceType,
+    flags: u8,
+}
+
+impl Move {
+
+    /// Did the move capture, promote or give check?  (See `Reset::is_quiet`.)
+    pub fn is_quiet(&self) -> bool {
+        self.flags & (MOVE_CAPTURE | MOVE_CHECK) == 0 && self.promotion_piece == PieceType::Unknown
+    }
+
+    /// Does the move capture or promote?  (See `Reset::is_capture_or_promotion`.)
+    pub fn is_capture_or_promotion(&self) -> bool {
+        self.flags & MOVE_CAPTURE != 0 || self.promotion_piece != PieceType::Unknown
+    }
+
+    /// Does the move capture?
+    pub fn is_capture(&self) -> bool {
+        self.flags & MOVE_CAPTURE != 0
+    }
+
+    /// Square number moved from, 0 for a Crazyhouse drop
+    pub fn bi_from(&self) -> u8 {
+        self.bi_from
+    }
+
+    /// Square number moved to
+    pub fn bi_to(&self) -> u8 {
+        self.bi_to
+    }
+
+    /// Piece that moved, or was dropped
+    pub fn piece_type(&self) -> PieceType {
+        self.piece_type
+    }
+
+    /// Piece a pawn promoted to, `PieceType::Unknown` if it didn't
+    pub fn promotion_piece(&self) -> PieceType {
+        self.promotion_piece
+    }
+
+    /// `Reset::child_hash` of the child the move leads to
+    pub fn child_hash(&self) -> u32 {
+        let piece = if self.bi_from == 0 { self.piece_type } else { self.promotion_piece };
+        (self.bi_from as u32) << 16 | (self.bi_to as u32) << 8 | piece as u32
+    }
+
+}
+
+/// What `Reset::make_move` overwrote, for `Reset::unmake_move` to put back
+///
+/// Only the position and the record of the move that reached it are kept, not the state of the
+/// move generator: moves should all be generated (`Reset::legal_moves`) before any is made.
+pub struct Undo {
+    b_all: u64,
+    b_white: u64,
+    b_pawns: u64,
+    b_knights: u64,
+    b_bishops: u64,
+    b_rooks: u64,
+    b_kings: u64,
+    b_promoted: u64,
+    pockets: u64,
+    b_en_passant: u64,
+    b_from: u64,
+    b_to: u64,
+    halfmove_clock: u16,
+    fullmove_number: u16,
+    material: i8,
+    white_king_square: u8,
+    black_king_square: u8,
+    castle_bits: u8,
+    white_checks: u8,
+    black_checks: u8,
+    to_move: u8,
+    capture: u8,
+    in_check: u8,
+    promotion: u8,
+    king_castled: u8,
+    game_over: u8,
+    null_move: u8,
+    bi_from: u8,
+    bi_to: u8,
+    promotion_piece: PieceType,
+}
+
+/// Where the move generator puts the moves it keeps, and which it keeps
+struct MoveCandidates<'a> {
+    filter: MoveFilter,
+    must_capture: bool,
+    /// The standard rules apply, so pins and the squares moved from and to settle most moves
+    /// (see `Variant::needs_full_legality_check`)
+    standard: bool,
+    /// How the piece being moved is pinned to its king
+    pin_dimension: u8,
+    moves: &'a mut Vec<Move>,
+}
+
+impl Reset {
+
+    /// The move from this Reset to a child the move generator produced
+    pub fn move_to(&self, child: &Reset) -> Move {
+        let mut flags = 0;
+        if child.capture != 0 { flags |= MOVE_CAPTURE; }
+        if child.in_check != 0 { flags |= MOVE_CHECK; }
+        if child.king_castled != 0 { flags |= MOVE_CASTLE; }
+        Move {
+            bi_from: child.bi_from,
+            bi_to: child.bi_to,
+            piece_type: if child.is_drop() { child.promotion_piece } else { self.piece_type_at(child.b_from) },
+            promotion_piece: if child.promotion != 0 { child.promotion_piece } else { PieceType::Unknown },
+            flags,
+        }
+    }
+
+    /// Append every legal move to `moves`, in move generation order
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut r = chessica::reset::new();
+    /// r.init_from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1".to_string());
+    /// let mut moves = Vec::new();
+    /// r.legal_moves(&mut moves);
+    /// assert_eq!(moves.len(),20);
+    /// ```
+    pub fn legal_moves(&mut self, moves: &mut Vec<Move>) {
+        self.generate_moves(MoveFilter::All, moves);
+    }
+
+    /// Append the legal moves that get past `filter` to `moves`, in move generation order
+    ///
+    /// Unlike `generate_next_move` no children are built.  The moves come straight from the
+    /// bitboards, in the order `generate_next_move` would produce them, and each is checked for
+    /// legality by placing it on this Reset and taking it back again.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chessica::reset::moves::MoveFilter;
+    /// let mut r = chessica::reset::fen::from_fen("4k3/8/8/3q4/4P3/8/8/4K3 w - - 0 1").unwrap();
+    /// let mut moves = Vec::new();
+    /// r.generate_moves(MoveFilter::Captures, &mut moves);
+    /// assert_eq!(moves.len(),1);
+    /// r.make_move(moves[0]);
+    /// assert_eq!(r.move_text(),"e4d5");
+    /// ```
+    pub fn generate_moves(&mut self, filter: MoveFilter, moves: &mut Vec<Move>) {
+        let white = self.white_to_move();
+        let b_side = if white { self.b_white } else { self.b_black() };
+        let moves_pieces = self.game_over == 0 &&
+            !(self.variant == Variant::Atomic && self.b_kings & b_side == 0);
+        let must_capture = self.variant == Variant::Antichess && self.antichess_capture_available();
+        let standard = !self.variant.needs_full_legality_check();
+        let mut b_pieces = if moves_pieces { b_side } else { 0 };
+        while b_pieces != 0 {
+            let b_from = bitops::lowest_bit(b_pieces);
+            b_pieces &= !b_from;
+            let piece_type = self.piece_type_at(b_from);
+            let pin_dimension = if standard && piece_type != PieceType::King {
+                self.pin_dimension_of(b_from, bitops::get_bit_number(b_from))
+            } else {
+                PIN_DIMENSION_NONE
+            };
+            let mut candidates = MoveCandidates { filter, must_capture, standard, pin_dimension, moves };
+            match piece_type {
+                PieceType::Pawn => self.generate_pawn_moves(white, b_from, &mut candidates),
+                PieceType::Knight => self.generate_steps(b_from, piece_type, &KNIGHT_STEPS, false, &mut candidates),
+                PieceType::Bishop => self.generate_steps(b_from, piece_type, &BISHOP_STEPS, true, &mut candidates),
+                PieceType::Rook => self.generate_steps(b_from, piece_type, &ROOK_STEPS, true, &mut candidates),
+                PieceType::Queen => self.generate_steps(b_from, piece_type, &ROYAL_STEPS, true, &mut candidates),
+                PieceType::King => {
+                    self.generate_steps(b_from, piece_type, &ROYAL_STEPS, false, &mut candidates);
+                    self.generate_castles(white, b_from, &mut candidates);
+                },
+                PieceType::Unknown => {},
+            }
+        }
+        // Crazyhouse drops come after the moves of the pieces on the board
+        if self.pockets != 0 && filter != MoveFilter::Captures {
+            self.generate_drops(white, moves);
+        }
+    }
+
+    /// Moves of a piece that steps, or slides along lines if `slides`, in the order of `steps`
+    fn generate_steps(&mut self, b_from: u64, piece_type: PieceType, steps: &[Step], slides: bool, candidates: &mut MoveCandidates) {
+        let b_side = if self.white_to_move() { self.b_white } else { self.b_black() };
+        for &(shift, b_can_step, pin_match) in steps {
+            let mut b_target = b_from;
+            while b_target & b_can_step != 0 {
+                b_target = step(b_target, shift);
+                if b_target & b_side != 0 {
+                    break;
+                }
+                self.add_move_if_legal(candidates, b_from, b_target, piece_type, PieceType::Unknown, pin_match);
+                if !slides || b_target & self.b_all != 0 {
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Pawn moves, as `generate_next_white_pawn_move` and `generate_next_black_pawn_move` order
+    /// them: forward one (with promotions), forward two, the captures, then en passant
+    fn generate_pawn_moves(&mut self, white: bool, b_from: u64, candidates: &mut MoveCandidates) {
+        let (forward, b_can_advance, b_start_rank) = if white {
+            (8, B_NOT_N_EDGE, B_RANK_2)
+        } else {
+            (-8, B_NOT_S_EDGE, B_RANK_7)
+        };
+        let captures: [Step; 2] = if white {
+            [(9, B_NOT_NW_EDGE, PIN_MATCH_SENW), (7, B_NOT_NE_EDGE, PIN_MATCH_NESW)]
+        } else {
+            [(-9, B_NOT_SE_EDGE, PIN_MATCH_SENW), (-7, B_NOT_SW_EDGE, PIN_MATCH_NESW)]
+        };
+        let b_opponents = if white { self.b_black() } else { self.b_white };
+
+        let b_forward_one = step(b_from, forward);
+        if b_from & b_can_advance != 0 && b_forward_one & self.b_all == 0 {
+            self.add_pawn_move_if_legal(candidates, b_from, b_forward_one, PIN_MATCH_NS);
+        }
+        let b_forward_two = step(b_from, 2 * forward);
+        if b_from & b_start_rank != 0 && (b_forward_one | b_forward_two) & self.b_all == 0 {
+            self.add_pawn_move_if_legal(candidates, b_from, b_forward_two, PIN_MATCH_NS);
+        }
+        for &(shift, b_can_capture, pin_match) in captures.iter() {
+            let b_target = step(b_from, shift);
+            if b_from & b_can_capture != 0 && b_target & b_opponents != 0 {
+                self.add_pawn_move_if_legal(candidates, b_from, b_target, pin_match);
+            }
+        }
+        if self.b_en_passant == 0 {
+            return;
+        }
+        for &(shift, b_can_capture, _) in captures.iter() {
+            if b_from & b_can_capture != 0 && step(b_from, shift) == self.b_en_passant {
+                let mut mv = self.pseudo_move(b_from, self.b_en_passant, PieceType::Pawn, PieceType::Unknown);
+                mv.flags |= MOVE_CAPTURE;
+                // Taking en passant clears two squares, which a pin can't account for
+                self.add_if_legal(candidates, mv, false);
+                // Only one pawn move is taken en passant per pawn
+                return;
+            }
+        }
+    }
+
+    /// A pawn move, or each of its promotions if it reaches the last rank
+    fn add_pawn_move_if_legal(&mut self, candidates: &mut MoveCandidates, b_from: u64, b_to: u64, pin_match: u8) {
+        if b_to & (B_RANK_1 | B_RANK_8) == 0 {
+            self.add_move_if_legal(candidates, b_from, b_to, PieceType::Pawn, PieceType::Unknown, pin_match);
+            return;
+        }
+        let promotions = if self.variant == Variant::Antichess { 5 } else { 4 };
+        for &promotion_piece in PROMOTION_PIECES[..promotions].iter() {
+            self.add_move_if_legal(candidates, b_from, b_to, PieceType::Pawn, promotion_piece, pin_match);
+        }
+    }
+
+    /// Castling kingside, then queenside, where the rights are held and the way is open
+    fn generate_castles(&mut self, white: bool, b_king: u64, candidates: &mut MoveCandidates) {
+        let rank: u8 = if white { 0 } else { 56 };
+        for kingside in [true, false] {
+            let held = match (white, kingside) {
+                (true, true) => self.white_castle_k(),
+                (true, false) => self.white_castle_q(),
+                (false, true) => self.black_castle_k(),
+                (false, false) => self.black_castle_q(),
+            };
+            if held && self.castle_is_open(kingside) {
+                let bi_king_to = if kingside { rank + 2 } else { rank + 6 };
+                let mut mv = self.pseudo_move(b_king, 1 << (bi_king_to - 1), PieceType::King, PieceType::Unknown);
+                mv.flags = MOVE_CASTLE;
+                self.add_if_legal(candidates, mv, false);
+            }
+        }
+    }
+
+    /// Crazyhouse drops, as `generate_next_drop` orders them
+    fn generate_drops(&mut self, white: bool, moves: &mut Vec<Move>) {
+        for piece_type in POCKET_PIECES {
+            if self.pocket_count(white, piece_type) == 0 {
+                continue;
+            }
+            let b_available = if piece_type == PieceType::Pawn {
+                !self.b_all & !(B_RANK_1 | B_RANK_8)
+            } else {
+                !self.b_all
+            };
+            for square in 0..64 {
+                let b_to: u64 = 1 << square;
+                if b_to & b_available == 0 {
+                    continue;
+                }
+                let mut mv = Move {
+                    bi_from: 0,
+                    bi_to: square + 1,
+                    piece_type,
+                    promotion_piece: PieceType::Unknown,
+                    flags: 0,
+                };
+                if self.settle_move(&mut mv) {
+                    moves.push(mv);
+                }
+            }
+        }
+    }
+
+    /// A move from `b_from` to `b_to`, flagged as a capture if it lands on a piece
+    fn pseudo_move(&self, b_from: u64, b_to: u64, piece_type: PieceType, promotion_piece: PieceType) -> Move {
+        Move {
+            bi_from: bitops::get_bit_number(b_from),
+            bi_to: bitops::get_bit_number(b_to),
+            piece_type,
+            promotion_piece,
+            flags: if b_to & self.b_all != 0 { MOVE_CAPTURE } else { 0 },
+        }
+    }
+
+    /// A piece moving along a line `pin_match` describes, as `add_move_if_valid` takes it
+    fn add_move_if_legal(&mut self, candidates: &mut MoveCandidates, b_from: u64, b_to: u64, piece_type: PieceType, promotion_piece: PieceType, pin_match: u8) {
+        // Atomic kings can't capture, and can't be captured directly (see `add_variant_move`)
+        if self.variant == Variant::Atomic &&
+            b_to & self.b_all != 0 &&
+            (piece_type == PieceType::King || b_to & self.b_kings != 0)
+        {
+            return;
+        }
+        if candidates.pin_dimension & pin_match != 0 {
+            return;
+        }
+        let mv = self.pseudo_move(b_from, b_to, piece_type, promotion_piece);
+        // A promoted piece may give check from anywhere it now reaches
+        let standard = candidates.standard && promotion_piece == PieceType::Unknown;
+        self.add_if_legal(candidates, mv, standard);
+    }
+
+    /// Keep a move that gets past the filter and leaves the mover's king safe
+    ///
+    /// A `standard` move is one that pins have already been ruled out for.
+    fn add_if_legal(&mut self, candidates: &mut MoveCandidates, mut mv: Move, standard: bool) {
+        let admitted = match candidates.filter {
+            MoveFilter::All => true,
+            MoveFilter::Captures => mv.is_capture_or_promotion(),
+            MoveFilter::Quiet => !mv.is_capture_or_promotion(),
+        };
+        // Antichess: when a capture is possible, nothing else is
+        if !admitted || (candidates.must_capture && !mv.is_capture()) {
+            return;
+        }
+        let legal = if standard { self.settle_standard_move(&mut mv) } else { self.settle_move(&mut mv) };
+        if legal {
+            candidates.moves.push(mv);
+        }
+    }
+
+    /// `settle_move` for a move that pins have already been ruled out for, under the standard
+    /// rules
+    ///
+    /// Only a king move, or any move out of check, needs the mover's king looked at again; and
+    /// the only checks the move can give are from the piece that moved, or revealed behind it.
+    /// None of that looks past where the pieces stand, so only the bitboards are moved and put
+    /// back.
+    fn settle_standard_move(&mut self, mv: &mut Move) -> bool {
+        let white = self.white_to_move();
+        let saved = (self.b_all, self.b_white, self.b_pawns, self.b_knights, self.b_bishops, self.b_rooks, self.b_kings, self.b_to);
+        let b_from: u64 = 1 << (mv.bi_from - 1);
+        let b_to: u64 = 1 << (mv.bi_to - 1);
+        self.b_pawns &= !b_to;
+        self.b_knights &= !b_to;
+        self.b_bishops &= !b_to;
+        self.b_rooks &= !b_to;
+        self.b_kings &= !b_to;
+        match mv.piece_type {
+            PieceType::Pawn => self.b_pawns = (self.b_pawns & !b_from) | b_to,
+            PieceType::Knight => self.b_knights = (self.b_knights & !b_from) | b_to,
+            PieceType::Bishop => self.b_bishops = (self.b_bishops & !b_from) | b_to,
+            PieceType::Rook => self.b_rooks = (self.b_rooks & !b_from) | b_to,
+            PieceType::King => self.b_kings = (self.b_kings & !b_from) | b_to,
+            _ => {},
+        }
+        self.b_all = (self.b_all & !b_from) | b_to;
+        self.b_white = if white { (self.b_white & !b_from) | b_to } else { self.b_white & !b_to };
+        self.b_to = b_to;
+
+        let legal = !((self.in_check != 0 || mv.piece_type == PieceType::King) && !self.king_is_safe(white));
+        if legal {
+            let (king_square, king_color) = if white { (self.black_king_square, BLACK) } else { (self.white_king_square, WHITE) };
+            if !self.is_safe_from_revealed_check(king_square, mv.bi_from, king_color) ||
+                !self.is_safe_from_direct_check(king_square, mv.bi_to, king_color)
+            {
+                mv.flags |= MOVE_CHECK;
+            }
+        }
+        (self.b_all, self.b_white, self.b_pawns, self.b_knights, self.b_bishops, self.b_rooks, self.b_kings, self.b_to) = saved;
+        legal
+    }
+
+    /// Is a move legal?  Places it on this Reset, settles king safety as a child would (flagging
+    /// the move if it gives check), then takes it back.
+    fn settle_move(&mut self, mv: &mut Move) -> bool {
+        let undo = self.undo_record();
+        let white = self.white_to_move();
+        self.place_move(white, *mv);
+        self.in_check = 0;
+        let legal = self.settle_king_safety(white);
+        if self.in_check != 0 {
+            mv.flags |= MOVE_CHECK;
+        }
+        self.unmake_move(&undo);
+        legal
+    }
+
+    fn undo_record(&self) -> Undo {
+        Undo {
+            b_all: self.b_all,
+            b_white: self.b_white,
+            b_pawns: self.b_pawns,
+            b_knights: self.b_knights,
+            b_bishops: self.b_bishops,
+            b_rooks: self.b_rooks,
+            b_kings: self.b_kings,
+            b_promoted: self.b_promoted,
+            pockets: self.pockets,
+            b_en_passant: self.b_en_passant,
+            b_from: self.b_from,
+            b_to: self.b_to,
+            halfmove_clock: self.halfmove_clock,
+            fullmove_number: self.fullmove_number,
+            material: self.material,
+            white_king_square: self.white_king_square,
+            black_king_square: self.black_king_square,
+            castle_bits: self.castle_bits,
+            white_checks: self.white_checks,
+            black_checks: self.black_checks,
+            to_move: self.to_move,
+            capture: self.capture,
+            in_check: self.in_check,
+            promotion: self.promotion,
+            king_castled: self.king_castled,
+            game_over: self.game_over,
+            null_move: self.null_move,
+            bi_from: self.bi_from,
+            bi_to: self.bi_to,
+            promotion_piece: self.promotion_piece,
+        }
+    }
+
+    /// Play a move on this Reset, rather than into a child
+    ///
+    /// The Reset ends up as the child the move generator produced for the move would have been,
+    /// ready to generate its own moves.  `unmake_move` with the returned `Undo` takes it back.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut r = chessica::reset::new();
+    /// r.init_from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1".to_string());
+    /// let mut moves = Vec::new();
+    /// r.legal_moves(&mut moves);
+    /// let undo = r.make_move(moves[0]);
+    /// assert_eq!(r.to_fen(),"rnbqkbnr/pppppppp/8/8/8/7N/PPPPPPPP/RNBQKB1R b KQkq - 1 1");
+    /// r.unmake_move(&undo);
+    /// assert_eq!(r.to_fen(),"rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+    /// ```
+    pub fn make_move(&mut self, mv: Move) -> Undo {
+        let undo = self.undo_record();
+        let white = self.white_to_move();
+        self.place_move(white, mv);
+        self.in_check = if mv.flags & MOVE_CHECK != 0 { 1 } else { 0 };
+        self.finish_made_move(white);
+        undo
+    }
+
+    /// Put a move's pieces where they go, recording it as the last move played
+    fn place_move(&mut self, white: bool, mv: Move) {
+        let b_en_passant = self.b_en_passant;
+        let b_from: u64 = if mv.bi_from == 0 { 0 } else { 1 << (mv.bi_from - 1) };
+        let b_to: u64 = 1 << (mv.bi_to - 1);
+        self.b_en_passant = 0;
+        self.b_from = b_from;
+        self.b_to = b_to;
+        self.bi_from = mv.bi_from;
+        self.bi_to = mv.bi_to;
+        self.capture = 0;
+        self.promotion = 0;
+        self.king_castled = 0;
+        self.null_move = 0;
+        self.promotion_piece = PieceType::Unknown;
+
+        if mv.bi_from == 0 {
+            self.make_drop(white, mv.piece_type, b_to);
+        } else if mv.flags & MOVE_CASTLE != 0 {
+            self.make_castle(white, b_from, mv.bi_to);
+        } else {
+            self.make_piece_move(white, mv, b_from, b_to, b_en_passant);
+        }
+    }
+
+    /// Let the side to move pass, as `init_null_child` does into a child
+    pub fn make_null_move(&mut self) -> Undo {
+        let undo = self.undo_record();
+        let white = self.white_to_move();
+        self.b_en_passant = 0;
+        self.b_from = 0;
+        self.b_to = 0;
+        self.bi_from = 0;
+        self.bi_to = 0;
+        self.capture = 0;
+        self.promotion = 0;
+        self.king_castled = 0;
+        self.null_move = 1;
+        self.promotion_piece = PieceType::Unknown;
+        self.in_check = 0;
+        self.finish_made_move(white);
+        undo
+    }
+
+    /// Take back the move `make_move` (or `make_null_move`) returned this `Undo` for
+    pub fn unmake_move(&mut self, undo: &Undo) {
+        self.b_all = undo.b_all;
+        self.b_white = undo.b_white;
+        self.b_pawns = undo.b_pawns;
+        self.b_knights = undo.b_knights;
+        self.b_bishops = undo.b_bishops;
+        self.b_rooks = undo.b_rooks;
+        self.b_kings = undo.b_kings;
+        self.b_promoted = undo.b_promoted;
+        self.pockets = undo.pockets;
+        self.b_en_passant = undo.b_en_passant;
+        self.b_from = undo.b_from;
+        self.b_to = undo.b_to;
+        self.halfmove_clock = undo.halfmove_clock;
+        self.fullmove_number = undo.fullmove_number;
+        self.material = undo.material;
+        self.white_king_square = undo.white_king_square;
+        self.black_king_square = undo.black_king_square;
+        self.castle_bits = undo.castle_bits;
+        self.white_checks = undo.white_checks;
+        self.black_checks = undo.black_checks;
+        self.to_move = undo.to_move;
+        self.capture = undo.capture;
+        self.in_check = undo.in_check;
+        self.promotion = undo.promotion;
+        self.king_castled = undo.king_castled;
+        self.game_over = undo.game_over;
+        self.null_move = undo.null_move;
+        self.bi_from = undo.bi_from;
+        self.bi_to = undo.bi_to;
+        self.promotion_piece = undo.promotion_piece;
+    }
+
+    /// Clocks, side to move and variant goals after a move, as `valid_child_post_processing`
+    /// sets them in a child
+    fn finish_made_move(&mut self, white: bool) {
+        if self.capture != 0 || self.b_to & self.b_pawns != 0 || self.promotion != 0 {
+            self.halfmove_clock = 0;
+        } else {
+            self.halfmove_clock = self.halfmove_clock.saturating_add(1);
+        }
+        if white {
+            self.to_move = 1;
+        } else {
+            self.to_move = 0;
+            self.fullmove_number = self.fullmove_number.saturating_add(1);
+        }
+        self.game_over = 0;
+        if self.variant.has_win_conditions() {
+            if self.variant == Variant::ThreeCheck && self.in_check != 0 {
+                self.count_check(white);
+            }
+            self.update_game_over();
+        }
+        self.current_piece_type = PieceType::Unknown;
+        self.initialize_move_generation();
+    }
+
+    /// A piece moving on the board, as `add_move_unconditional` and the pawn moves build it
+    fn make_piece_move(&mut self, white: bool, mv: Move, b_from: u64, b_to: u64, b_en_passant: u64) {
+        if b_to & self.b_all != 0 {
+            self.capture_in_place(white, b_to);
+        }
+        if self.b_promoted & b_from != 0 {
+            self.b_promoted ^= b_from | b_to;
+        }
+        self.b_all = (self.b_all & !b_from) | b_to;
+        if white {
+            self.b_white = (self.b_white & !b_from) | b_to;
+        }
+        match mv.piece_type {
+            PieceType::Pawn => self.b_pawns = (self.b_pawns & !b_from) | b_to,
+            PieceType::Knight => self.b_knights = (self.b_knights & !b_from) | b_to,
+            PieceType::Bishop => self.b_bishops = (self.b_bishops & !b_from) | b_to,
+            PieceType::Rook => {
+                self.b_rooks = (self.b_rooks & !b_from) | b_to;
+                self.castle_bits &= !self.castle_rights_of_rook(mv.bi_from);
+            },
+            PieceType::King => {
+                self.b_kings = (self.b_kings & !b_from) | b_to;
+                if white {
+                    self.white_king_square = mv.bi_to;
+                    self.castle_bits &= U8_NOT_BIT1_OR_BIT2;
+                } else {
+                    self.black_king_square = mv.bi_to;
+                    self.castle_bits &= U8_NOT_BIT3_OR_BIT4;
+                }
+            },
+            _ => {},
+        }
+        if self.capture != 0 && self.variant == Variant::Atomic {
+            self.explode();
+        }
+        if mv.piece_type != PieceType::Pawn {
+            return;
+        }
+
+        if mv.flags & MOVE_CAPTURE != 0 && self.capture == 0 && b_to == b_en_passant {
+            // En passant: the pawn taken stands behind the square moved to
+            let b_taken = if white { b_to >> 8 } else { b_to << 8 };
+            self.b_all &= !b_taken;
+            self.b_white &= !b_taken;
+            self.b_pawns &= !b_taken;
+            self.material += if white { 1 } else { -1 };
+            self.capture = 1;
+            if self.variant == Variant::Crazyhouse {
+                self.add_to_pocket(white, PieceType::Pawn);
+            }
+            if self.variant == Variant::Atomic {
+                self.explode();
+            }
+        }
+        if mv.promotion_piece != PieceType::Unknown {
+            self.promote_in_place(white, mv.promotion_piece, b_to);
+        } else if mv.bi_to.abs_diff(mv.bi_from) == 16 {
+            self.b_en_passant = if white { b_from << 8 } else { b_from >> 8 };
+        }
+    }
+
+    /// Take the piece on a square, as `capture_processing` does in a child
+    fn capture_in_place(&mut self, white: bool, b_to: u64) {
+        self.capture = 1;
+        if self.variant == Variant::Crazyhouse {
+            let captured = if self.b_promoted & b_to != 0 {
+                PieceType::Pawn
+            } else {
+                self.piece_type_at(b_to)
+            };
+            self.b_promoted &= !b_to;
+            self.add_to_pocket(white, captured);
+        }
+        self.b_all &= !b_to;
+        let material_multiplier: i8 = if white {
+            1
+        } else {
+            self.b_white &= !b_to;
+            -1
+        };
+        if b_to & self.b_pawns != 0 {
+            self.b_pawns &= !b_to;
+            self.material += material_multiplier;
+        } else if b_to & self.b_knights != 0 {
+            self.b_knights &= !b_to;
+            self.material += material_multiplier * 3;
+        } else if b_to & self.b_bishops != 0 {
+            self.b_bishops &= !b_to;
+            self.material += material_multiplier * 3;
+        } else if b_to & self.b_rooks != 0 {
+            self.b_rooks &= !b_to;
+            self.material += material_multiplier * 5;
+            self.castle_bits &= !self.castle_rights_of_rook(self.bi_to);
+        } else if b_to & self.b_kings != 0 {
+            self.b_kings &= !b_to;
+        } else {
+            self.material += material_multiplier * 9;
+        }
+    }
+
+    /// Turn the pawn that just arrived on the last rank into another piece, as
+    /// `generate_promotion_moves` does in a child
+    fn promote_in_place(&mut self, white: bool, piece_type: PieceType, b_to: u64) {
+        self.promotion = 1;
+        self.promotion_piece = piece_type;
+        self.b_pawns &= !b_to;
+        // An Atomic capture may have blown the pawn up, leaving nothing to promote
+        let b_placed = b_to & self.b_all;
+        let multiplier = if b_placed == 0 {
+            0
+        } else if white {
+            1
+        } else {
+            -1
+        };
+        match piece_type {
+            PieceType::Knight => {
+                self.b_knights |= b_placed;
+                self.material += 2 * multiplier;
+            },
+            PieceType::Bishop => {
+                self.b_bishops |= b_placed;
+                self.material += 2 * multiplier;
+            },
+            PieceType::Rook => {
+                self.b_rooks |= b_placed;
+                self.material += 4 * multiplier;
+            },
+            PieceType::King => {
+                self.b_kings |= b_placed;
+                self.material -= multiplier;
+            },
+            _ => self.material += 8 * multiplier,
+        }
+        if self.variant == Variant::Crazyhouse {
+            self.b_promoted |= b_to;
+        }
+    }
+
+    /// Castle, as `add_castle_move_if_valid` does in a child
+    fn make_castle(&mut self, white: bool, b_king: u64, bi_king_to: u8) {
+        let rank: u8 = if white { 0 } else { 56 };
+        let kingside = bi_king_to == rank + 2;
+        let bi_rook = self.castle_rook_square(white, kingside);
+        let bi_rook_to = if kingside { rank + 3 } else { rank + 5 };
+        let b_rook: u64 = 1 << (bi_rook - 1);
+        let b_king_to: u64 = 1 << (bi_king_to - 1);
+        let b_rook_to: u64 = 1 << (bi_rook_to - 1);
+        self.b_all = (self.b_all & !(b_king | b_rook)) | b_king_to | b_rook_to;
+        self.b_kings = (self.b_kings & !b_king) | b_king_to;
+        self.b_rooks = (self.b_rooks & !b_rook) | b_rook_to;
+        self.king_castled = 1;
+        if white {
+            self.b_white = (self.b_white & !(b_king | b_rook)) | b_king_to | b_rook_to;
+            self.white_king_square = bi_king_to;
+            self.castle_bits &= U8_NOT_BIT1_OR_BIT2;
+        } else {
+            self.black_king_square = bi_king_to;
+            self.castle_bits &= U8_NOT_BIT3_OR_BIT4;
+        }
+    }
+
+    /// Drop a piece from the mover's pocket, as `add_drop` does in a child
+    fn make_drop(&mut self, white: bool, piece_type: PieceType, b_to: u64) {
+        self.b_all |= b_to;
+        if white {
+            self.b_white |= b_to;
+        }
+        match piece_type {
+            PieceType::Pawn => self.b_pawns |= b_to,
+            PieceType::Knight => self.b_knights |= b_to,
+            PieceType::Bishop => self.b_bishops |= b_to,
+            PieceType::Rook => self.b_rooks |= b_to,
+            _ => {},
+        }
+        self.material += if white { drop_material(piece_type) } else { -drop_material(piece_type) };
+        self.take_from_pocket(white, piece_type);
+        self.promotion_piece = piece_type;
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::reset;
+    use crate::reset::Reset;
+    use crate::reset::moves::MoveFilter;
+    use crate::reset::variant::Variant;
+
+    fn prep_board(fen: &str, variant: Variant) -> Reset {
+        let mut r = reset::new();
+        r.init_from_fen(fen.to_string());
+        r.set_variant(variant);
+        r
+    }
+
+    /// Everything about a Reset that a made move has to get right
+    fn summary(r: &mut Reset) -> String {
+        format!("{} material={} check={} over={} last={} {} {} {} {} {} {:?}",
+            r.to_fen(), r.material, r.in_check, r.game_over, r.b_from, r.b_to, r.bi_from, r.bi_to,
+            r.capture, r.king_castled, r.promotion_piece)
+    }
+
+    /// Check that making each move matches the child the generator built, and that unmaking it
+    /// restores the parent, `depth` plies deep
+    fn assert_make_matches_children(r: &mut Reset, depth: u8) {
+        if depth == 0 {
+            return;
+        }
+        let before = summary(r);
+        let mut moves = Vec::new();
+        r.legal_moves(&mut moves);
+        let mut child = reset::new();
+        r.restart_move_generation();
+        let mut index = 0;
+        while r.generate_next_move(&mut child) {
+            let mut made = reset::clone::clone_from(r);
+            let undo = made.make_move(moves[index]);
+            assert_eq!(summary(&mut made),summary(&mut child),"{} from {}",child.move_text(),before);
+            assert_make_matches_children(&mut made, depth - 1);
+            made.unmake_move(&undo);
+            assert_eq!(summary(&mut made),before,"unmaking {}",child.move_text());
+            index += 1;
+        }
+        assert_eq!(index,moves.len());
+    }
+
+    #[test]
+    fn make_matches_children() {
+        let positions = [
+            ("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1", Variant::Standard),
+            ("8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1", Variant::Standard),
+            ("r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1", Variant::Standard),
+            ("rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8", Variant::Standard),
+            ("rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3", Variant::Standard),
+            ("bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9", Variant::Standard),
+            ("1r2k1r1/8/8/8/8/8/8/1R2K1R1 w GBgb - 0 1", Variant::Standard),
+            ("r1b1k2r/ppp2ppp/2n5/3q4/1b1P4/2N2N2/PP3PPP/R2QKB1R w KQkq - 0 1", Variant::Crazyhouse),
+            ("4k3/1P6/8/8/8/8/6p1/4K3[QRNBPqrnbp] w - - 0 1", Variant::Crazyhouse),
+            ("r3k2r/1p6/8/3pP3/8/8/1p6/R3K2R w KQkq d6 0 1", Variant::Atomic),
+            ("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1", Variant::Atomic),
+            ("8/1P6/8/8/3pP3/8/1p6/8 b - e3 0 1", Variant::Antichess),
+            ("8/8/8/8/8/4K3/8/7k w - - 0 1", Variant::KingOfTheHill),
+            ("4k3/8/8/8/8/8/8/R3K3 w - - 0 1 +2+0", Variant::ThreeCheck),
+            ("8/8/8/8/8/8/krbnNBRK/qrbnNBRQ w - - 0 1", Variant::RacingKings),
+        ];
+        for (fen, variant) in positions {
+            let mut r = prep_board(fen, variant);
+            assert_make_matches_children(&mut r, 2);
+        }
+    }
+
+    #[test]
+    fn generate_moves_filters_match_children() {
+        let positions = [
+            ("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1", Variant::Standard),
+            ("n1n5/PPPk4/8/8/8/8/4Kppp/5N1N b - - 0 1", Variant::Standard),
+            ("8/8/3p4/KPp4r/1R3p1k/8/4P1P1/8 w - c6 0 2", Variant::Standard),
+            ("2k5/8/8/8/8/8/8/4K3[QRBNPqrbnp] w - - 0 1", Variant::Crazyhouse),
+            ("r3k2r/1p6/8/3pP3/8/8/1p6/R3K2R w KQkq d6 0 1", Variant::Atomic),
+            ("rnbqkbnr/ppp1pppp/8/3p4/4P3/8/PPPP1PPP/RNBQKBNR w - - 0 2", Variant::Antichess),
+            ("8/8/8/8/8/8/krbnNBRK/qrbnNBRQ w - - 0 1", Variant::RacingKings),
+        ];
+        for (fen, variant) in positions {
+            let mut r = prep_board(fen, variant);
+            for filter in [MoveFilter::All, MoveFilter::Captures, MoveFilter::Quiet] {
+                let mut children = Vec::new();
+                let mut child = reset::new();
+                r.restart_move_generation();
+                r.set_move_filter(filter);
+                while r.generate_next_move(&mut child) {
+                    children.push(summary(&mut child));
+                }
+                r.restart_move_generation();
+                let mut moves = Vec::new();
+                r.generate_moves(filter, &mut moves);
+                let made: Vec<String> = moves.iter().map(|mv| {
+                    let mut made = reset::clone::clone_from(&mut r);
+                    made.make_move(*mv);
+                    summary(&mut made)
+                }).collect();
+                assert_eq!(made,children,"{} {:?}",fen,filter);
+            }
+        }
+    }
+
+    #[test]
+    fn make_null_move() {
+        let mut r = prep_board("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1", Variant::Standard);
+        let mut null_child = reset::new();
+        r.init_null_child(&mut null_child);
+        let before = r.to_fen();
+        let undo = r.make_null_move();
+        assert_eq!(r.to_fen(),null_child.to_fen());
+        assert!(r.is_null_move());
+        r.unmake_move(&undo);
+        assert_eq!(r.to_fen(),before);
+        assert!(!r.is_null_move());
+    }
+}
@@ -0,0 +1,134 @@
+use crate::reset::r#const::SCORE_BLACK_CHECKMATE;
+use crate::reset::r#const::SCORE_WHITE_CHECKMATE;
+use crate::reset::r#const::SCORE_MATE_PLY_LIMIT;
+
+/// Score of a checkmate found `ply` half-moves below the root of the search
+///
+/// Mate scores shrink toward zero by one for every ply, so a search always prefers the shortest
+/// mate for the winning side and the longest resistance for the losing side.
+///
+/// # Examples
+///
+/// ```
+/// use chessica::reset::mate::checkmate_score;
+/// use chessica::reset::r#const::SCORE_WHITE_CHECKMATE;
+/// assert_eq!(checkmate_score(false, 3),SCORE_WHITE_CHECKMATE - 3);
+/// assert!(checkmate_score(false, 1) > checkmate_score(false, 5));
+/// ```
+pub fn checkmate_score(white_is_mated: bool, ply: u8) -> i32 {
+    if white_is_mated {
+        SCORE_BLACK_CHECKMATE + ply as i32
+    } else {
+        SCORE_WHITE_CHECKMATE - ply as i32
+    }
+}
+
+/// Convert a score from `Reset::score`, which knows nothing about the search, into one that is
+/// adjusted for the ply it was found at.  Non-mate scores are returned unchanged.
+pub fn adjust_for_ply(score: i32, ply: u8) -> i32 {
+    if score == SCORE_WHITE_CHECKMATE {
+        checkmate_score(false, ply)
+    } else if score == SCORE_BLACK_CHECKMATE {
+        checkmate_score(true, ply)
+    } else {
+        score
+    }
+}
+
+/// Mate-distance pruning: narrow a search window to the scores that are still possible `ply`
+/// half-moves below the root
+///
+/// `min` is the upper bound and `max` the lower bound, as in the alpha-beta searches.  Nothing
+/// found below this node can beat a mate delivered right here, so once a shorter mate is already
+/// known the window closes (`min <= max`) and the node can be cut off without searching.
+///
+/// # Examples
+///
+/// ```
+/// use chessica::reset::mate::{checkmate_score, mate_distance_window};
+/// use chessica::reset::r#const::{SCORE_MAX, SCORE_MIN};
+/// let (min, max) = mate_distance_window(SCORE_MAX, SCORE_MIN, 4);
+/// assert_eq!(min,checkmate_score(false, 4));
+/// assert_eq!(max,checkmate_score(true, 4));
+///
+/// // White already has a mate in 1 ply, so a node 4 plies down can't improve on it
+/// let (min, max) = mate_distance_window(SCORE_MAX, checkmate_score(false, 1), 4);
+/// assert!(min <= max);
+/// ```
+pub fn mate_distance_window(min: i32, max: i32, ply: u8) -> (i32, i32) {
+    (min.min(checkmate_score(false, ply)), max.max(checkmate_score(true, ply)))
+}
+
+/// Is this score a (ply-adjusted) checkmate?
+pub fn is_mate_score(score: i32) -> bool {
+    !(SCORE_BLACK_CHECKMATE + SCORE_MATE_PLY_LIMIT..=SCORE_WHITE_CHECKMATE - SCORE_MATE_PLY_LIMIT).contains(&score)
+}
+
+/// Number of plies until checkmate: positive when white delivers it, negative when black does
+///
+/// # Examples
+///
+/// ```
+/// use chessica::reset::mate::{checkmate_score, mate_in_plies};
+/// assert_eq!(mate_in_plies(checkmate_score(true, 4)),Some(-4));
+/// assert_eq!(mate_in_plies(1500000),None);
+/// ```
+pub fn mate_in_plies(score: i32) -> Option<i32> {
+    if !is_mate_score(score) {
+        None
+    } else if score > 0 {
+        Some(SCORE_WHITE_CHECKMATE - score)
+    } else {
+        Some(-(score - SCORE_BLACK_CHECKMATE))
+    }
+}
+
+/// Number of moves until checkmate from the point of view of the side to move, as used by "mate N"
+/// in UCI: positive if the side to move mates, negative if it gets mated.
+///
+/// # Examples
+///
+/// ```
+/// use chessica::reset::mate::{checkmate_score, mate_in_moves};
+/// // White to move, mate delivered on white's 2nd move (ply 3)
+/// assert_eq!(mate_in_moves(checkmate_score(false, 3), true),Some(2));
+/// assert_eq!(mate_in_moves(checkmate_score(false, 3), false),Some(-2));
+/// ```
+pub fn mate_in_moves(score: i32, white_to_move: bool) -> Option<i32> {
+    let plies = mate_in_plies(score)?;
+    let moves = (plies.abs() + 1) / 2;
+    let side_to_move_mates = (plies > 0) == white_to_move;
+    if side_to_move_mates {
+        Some(moves)
+    } else {
+        Some(-moves)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::reset::mate;
+    use crate::reset::r#const::SCORE_BLACK_CHECKMATE;
+    use crate::reset::r#const::SCORE_WHITE_CHECKMATE;
+    use crate::reset::r#const::SCORE_STALEMATE;
+
+    #[test]
+    fn mate_adjust_for_ply() {
+        assert_eq!(mate::adjust_for_ply(SCORE_WHITE_CHECKMATE, 5),SCORE_WHITE_CHECKMATE - 5);
+        assert_eq!(mate::adjust_for_ply(SCORE_BLACK_CHECKMATE, 5),SCORE_BLACK_CHECKMATE + 5);
+        assert_eq!(mate::adjust_for_ply(SCORE_STALEMATE, 5),SCORE_STALEMATE);
+        assert_eq!(mate::adjust_for_ply(-3000123, 5),-3000123);
+    }
+
+    #[test]
+    fn mate_distances() {
+        assert!(!mate::is_mate_score(39000998));
+        assert!(mate::is_mate_score(mate::checkmate_score(true, 0)));
+        assert_eq!(mate::mate_in_plies(mate::checkmate_score(false, 1)),Some(1));
+        assert_eq!(mate::mate_in_moves(mate::checkmate_score(false, 1), true),Some(1));
+        // Black to move at the root, white mates after black's reply: mated in 1
+        assert_eq!(mate::mate_in_moves(mate::checkmate_score(false, 2), false),Some(-1));
+        assert_eq!(mate::mate_in_moves(mate::checkmate_score(true, 2), true),Some(-1));
+        assert_eq!(mate::mate_in_moves(mate::checkmate_score(true, 5), false),Some(3));
+    }
+}
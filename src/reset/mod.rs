@@ -20,8 +20,26 @@ pub mod profiling;
 pub mod test_helpers;
 pub mod score;
 pub mod hash;
+pub mod ordering;
+pub mod mate;
+pub mod polyglot;
+pub mod san;
+pub mod syzygy;
+pub mod changes;
+pub mod chess960;
+pub mod variant;
+pub mod pocket;
+pub mod atomic;
+pub mod antichess;
+pub mod goals;
+pub mod packed;
+pub mod make;
+pub mod symmetry;
+pub mod random;
 
 use crate::reset::pinned::PIN_DIMENSION_UNSET;
+use crate::reset::variant::Variant;
+use crate::reset::moves::MoveFilter;
 
 #[derive(PartialEq,Eq,Copy,Clone,Hash,Debug)]
 pub enum PieceType {
@@ -70,51 +88,56 @@ pub enum PieceType {
 /// | b_rooks            | u64  | copy   |   40   | Bitstring representing the presence of rooks |
 /// |                    |      |        |        | Note that there is no `b_queens` - a user must call `b_queens()` to derive this value. |
 /// | b_kings            | u64  | copy   |   48   | Bitstring representing the presence of kings |
-/// | reserved_01        | u64  | copy   |   56   | Reserved |
-/// | b_current_piece    | u64  | clear  |   64   | Bitstring representing the piece currently under consideration for move generation |
-/// | b_en_passant       | u64  | clear  |   72   | Bitstring representing a piece that is eligible for en passant capture.  This is an entire bitstring to represent a single bit, which seems wasteful. |
-/// | b_from             | u64  | whatev |   80   | Bitstring representing where the last piece was moved from |
-/// | b_to               | u64  | whatev |   88   | Bitstring representing where the last piece was moved to |
-/// | score              | i32  | clear  |   96   | Score of this reset.  White is positive, Black negative.  If white is up exactly a pawn, the score will be 1,000,000.  Checkmate for Black is -128,000,000. |
-/// | hash_value         | u32  | whatev |  100   | Hash value for this reset |
-/// | min                | i32  | whatev |  104   | Min value used for move searching |
-/// | max                | i32  | whatev |  108   | Max value used for move searching |
-/// | material           | i8   | copy   |  112   | Material score of this board |
-/// | halfmove_clock     | u8   | copy   |  113   | Halfmoves elapsed since last pawn move or capture |
-/// | fullmove_number    | u8   | copy   |  114   | Full moves elapsed since beginning of the game |
-/// | white_king_square  | u8   | copy   |  115   | Square number of the white king |
-/// | black_king_square  | u8   | copy   |  116   | Square number of the black king |
-/// | castle_bits        | u8   | copy   |  117   | `1` if white is eligible to castle queenside, `0` if not |
+/// | b_promoted         | u64  | copy   |   56   | Bitstring of pieces that arrived by promotion (Crazyhouse demotes them to pawns when captured) |
+/// | pockets            | u64  | copy   |   64   | Crazyhouse pocket counts, six bits for each side and droppable piece type |
+/// | reserved_12        | u64  | copy   |   72   | Reserved |
+/// | b_current_piece    | u64  | clear  |   80   | Bitstring representing the piece currently under consideration for move generation |
+/// | b_en_passant       | u64  | clear  |   88   | Bitstring representing a piece that is eligible for en passant capture.  This is an entire bitstring to represent a single bit, which seems wasteful. |
+/// | b_from             | u64  | whatev |   96   | Bitstring representing where the last piece was moved from |
+/// | b_to               | u64  | whatev |  104   | Bitstring representing where the last piece was moved to |
+/// | score              | i32  | clear  |  112   | Score of this reset.  White is positive, Black negative.  If white is up exactly a pawn, the score will be 1,000,000.  Checkmate for Black is -128,000,000. |
+/// | hash_value         | u32  | whatev |  116   | Hash value for this reset |
+/// | min                | i32  | whatev |  120   | Min value used for move searching |
+/// | max                | i32  | whatev |  124   | Max value used for move searching |
+/// | halfmove_clock     | u16  | copy   |  128   | Halfmoves elapsed since last pawn move or capture |
+/// | fullmove_number    | u16  | copy   |  130   | Full moves elapsed since beginning of the game |
+/// | material           | i8   | copy   |  132   | Material score of this board |
+/// | white_king_square  | u8   | copy   |  133   | Square number of the white king |
+/// | black_king_square  | u8   | copy   |  134   | Square number of the black king |
+/// | castle_bits        | u8   | copy   |  135   | `1` if white is eligible to castle queenside, `0` if not |
 /// | white_castle_k     |      |        |        | 0x01: `1` if white is eligible to castle kingside, `0` if not |
 /// | white_castle_q     |      |        |        | 0x02: `1` if white is eligible to castle queenside, `0` if not |
 /// | black_castle_k     |      |        |        | 0x04: `1` if black is eligible to castle kingside, `0` if not |
 /// | black_castle_q     |      |        |        | 0x08: `1` if black is eligible to castle queenside, `0` if not |
-/// | reserved_02        | u8   | whatev |  143   | Reserved |
-/// | reserved_03        | u8   | copy   |  119   | Reserved |
-/// | reserved_04        | u8   | copy   |  120   | Reserved |
-/// | reserved_05        | u8   | copy   |  121   | Reserved |
-/// | reserved_06        | u8   | copy   |  122   | Reserved |
-/// | reserved_07        | u8   | copy   |  123   | Reserved |
-/// | reserved_08        | u8   | copy   |  124   | Reserved |
-/// | reserved_09        | u8   | copy   |  125   | Reserved |
-/// | reserved_10        | u8   | copy   |  126   | Reserved |
-/// | reserved_11        | u8   | copy   |  127   | Reserved |
-/// | move_id            | u8   | clear  |  128   | ID of tne next move to be considered for a given piece type |
-/// | to_move            | u8   | clear  |  129   | `0` if it is white's move, `1` if it is black's move |
-/// | capture            | u8   | clear  |  130   | `1` if the last move was a capture, `0` otherwise |
-/// | in_check           | u8   | clear  |  131   | `1` if the side moving is currently in check, `0` otherwise |
-/// | promotion          | u8   | clear  |  132   | `1` if the last move was a promotion, `0` otherwise |
-/// | king_castled       | u8   | clear  |  133   | `1` if the last move was a castle, `0` otherwise |
-/// | game_over          | u8   | clear  |  134   | `1` if the game is over |
-/// | bi_from            | u8   | whatev |  135   | Bit index of the move's originating square |
-/// | bi_to              | u8   | whatev |  136   | Bit index of the move's destination square |
-/// | score_depth        | u8   | whatev |  137   | Search depth from which score was obtained |
-/// | promotion_piece    | u8   | whatev |  138   | `PieceType` of the new piece if promoted |
-/// | times_seen         | u8   | whatev |  139   | Number of times this reset has been seen in the current game |
-/// | must_check_safety  | u8   | whatev |  140   | 1 if we must check king safety after this move, 0 otherwise.  I believe this is used for odd moves, like EP captures, castling, and promotions. |
-/// | bi_current_piece   | u8   | whatev |  141   | Bit index for b_current_piece |
-/// | pin_dimension      | u8   | whatev |  142   | Dimension in which the piece under evaluation is pinned to the king |
-/// | current_piece_type | u8   | copy   |  118   | Reserved |
+/// | bi_castle_rook_wk  | u8   | copy   |  136   | Square number of the rook white castles kingside with |
+/// | bi_castle_rook_wq  | u8   | copy   |  137   | Square number of the rook white castles queenside with |
+/// | bi_castle_rook_bk  | u8   | copy   |  138   | Square number of the rook black castles kingside with |
+/// | bi_castle_rook_bq  | u8   | copy   |  139   | Square number of the rook black castles queenside with |
+/// | white_checks       | u8   | copy   |  140   | Checks white has given (Three-check) |
+/// | black_checks       | u8   | copy   |  141   | Checks black has given (Three-check) |
+/// | variant            | u8   | copy   |  142   | `Variant` whose rules the game is played by |
+/// | reserved_11        | u8   | copy   |  143   | Reserved |
+/// | move_id            | u8   | clear  |  144   | ID of tne next move to be considered for a given piece type |
+/// | to_move            | u8   | clear  |  145   | `0` if it is white's move, `1` if it is black's move |
+/// | capture            | u8   | clear  |  146   | `1` if the last move was a capture, `0` otherwise |
+/// | in_check           | u8   | clear  |  147   | `1` if the side moving is currently in check, `0` otherwise |
+/// | promotion          | u8   | clear  |  148   | `1` if the last move was a promotion, `0` otherwise |
+/// | king_castled       | u8   | clear  |  149   | `1` if the last move was a castle, `0` otherwise |
+/// | game_over          | u8   | clear  |  150   | `1` if the game is over |
+/// | null_move          | u8   | clear  |  151   | `1` if this Reset was reached by a null move ("pass"), `0` otherwise |
+/// | bi_from            | u8   | whatev |  152   | Bit index of the move's originating square |
+/// | bi_to              | u8   | whatev |  153   | Bit index of the move's destination square |
+/// | score_depth        | u8   | whatev |  154   | Search depth from which score was obtained |
+/// | times_seen         | u8   | whatev |  155   | Number of times this reset has been seen in the current game |
+/// | must_check_safety  | u8   | whatev |  156   | 1 if we must check king safety after this move, 0 otherwise.  I believe this is used for odd moves, like EP captures, castling, and promotions. |
+/// | bi_current_piece   | u8   | whatev |  157   | Bit index for b_current_piece |
+/// | pin_dimension      | u8   | whatev |  158   | Dimension in which the piece under evaluation is pinned to the king |
+/// | drop_piece         | u8   | whatev |  159   | Index into `POCKET_PIECES` of the next Crazyhouse drop to be considered |
+/// | drop_square        | u8   | whatev |  160   | Bit index of the next Crazyhouse drop to be considered |
+/// | must_capture       | u8   | whatev |  161   | `1` if Antichess obliges the side to move to capture, `0` otherwise |
+/// | promotion_piece    | u8   | whatev |  162   | `PieceType` of the new piece if promoted |
+/// | current_piece_type | u8   | clear  |  163   | `PieceType` of the piece currently under consideration for move generation |
+/// | move_filter        | u8   | whatev |  164   | `MoveFilter` of the moves `generate_next_move` hands out (all, captures or quiet) |
 pub struct Reset {
     //Fields passed from parent to child
     b_all: u64,
@@ -124,22 +147,22 @@ pub struct Reset {
     b_bishops: u64,
     b_rooks: u64,
     b_kings: u64,
-    reserved_01: u64,
+    b_promoted: u64,
+    pockets: u64,
+    reserved_12: u64,
     material: i8,
-    halfmove_clock: u8,
-    fullmove_number: u8,
+    halfmove_clock: u16,
+    fullmove_number: u16,
     white_king_square: u8,
     black_king_square: u8,
     castle_bits: u8,
-    reserved_02: u8,
-    reserved_03: u8,
-    reserved_04: u8,
-    reserved_05: u8,
-    reserved_06: u8,
-    reserved_07: u8,
-    reserved_08: u8,
-    reserved_09: u8,
-    reserved_10: u8,
+    bi_castle_rook_wk: u8,
+    bi_castle_rook_wq: u8,
+    bi_castle_rook_bk: u8,
+    bi_castle_rook_bq: u8,
+    white_checks: u8,
+    black_checks: u8,
+    variant: Variant,
     reserved_11: u8,
 
     //Fields cleared in a new child
@@ -153,6 +176,7 @@ pub struct Reset {
     promotion: u8,
     king_castled: u8,
     game_over: u8,
+    null_move: u8,
 
     //Fields that can be garbage in a new child
     b_from: u64,
@@ -169,6 +193,10 @@ pub struct Reset {
     bi_current_piece: u8,
     pin_dimension: u8,
     current_piece_type: PieceType,
+    drop_piece: u8,
+    drop_square: u8,
+    must_capture: u8,
+    move_filter: MoveFilter,
 }
 
 /// Constructs a new Reset
@@ -188,22 +216,22 @@ pub fn new() -> Reset {
         b_bishops: 0,
         b_rooks: 0,
         b_kings: 0,
-        reserved_01: 0,
+        b_promoted: 0,
+        pockets: 0,
+        reserved_12: 0,
         material: 0,
         halfmove_clock: 0,
         fullmove_number: 0,
         white_king_square: 0,
         black_king_square: 0,
         castle_bits: 0,
-        reserved_02: 0,
-        reserved_03: 0,
-        reserved_04: 0,
-        reserved_05: 0,
-        reserved_06: 0,
-        reserved_07: 0,
-        reserved_08: 0,
-        reserved_09: 0,
-        reserved_10: 0,
+        bi_castle_rook_wk: 1,
+        bi_castle_rook_wq: 8,
+        bi_castle_rook_bk: 57,
+        bi_castle_rook_bq: 64,
+        white_checks: 0,
+        black_checks: 0,
+        variant: Variant::Standard,
         reserved_11: 0,
 
         b_current_piece: 0,
@@ -216,6 +244,7 @@ pub fn new() -> Reset {
         promotion: 0,
         king_castled: 0,
         game_over: 0,
+        null_move: 0,
 
         b_from: 0,
         b_to: 0,
@@ -231,6 +260,10 @@ pub fn new() -> Reset {
         bi_current_piece: 0,
         pin_dimension: PIN_DIMENSION_UNSET,
         current_piece_type: PieceType::Unknown,
+        drop_piece: 0,
+        drop_square: 0,
+        must_capture: 0,
+        move_filter: MoveFilter::All,
     }
 }
 
@@ -327,4 +360,3 @@ mod tests {
         assert!(!r.in_check());
     }
 }
-
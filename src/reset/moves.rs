@@ -3,24 +3,70 @@ use crate::reset::Reset;
 use crate::reset::PieceType;
 use crate::bitops;
 use crate::reset::pinned::PIN_DIMENSION_UNSET;
-
-use crate::reset::r#const::B_FOUR_CORNERS;
-use crate::reset::r#const::B_SE_CORNER;
-use crate::reset::r#const::B_SW_CORNER;
-use crate::reset::r#const::B_NE_CORNER;
+use crate::reset::variant::Variant;
 
 use crate::reset::r#const::BLACK;
 use crate::reset::r#const::WHITE;
 
-use crate::bitops::r#const::U8_NOT_BIT1;
-use crate::bitops::r#const::U8_NOT_BIT2;
-use crate::bitops::r#const::U8_NOT_BIT3;
-use crate::bitops::r#const::U8_NOT_BIT4;
 use crate::bitops::r#const::U8_NOT_BIT1_OR_BIT2;
 use crate::bitops::r#const::U8_NOT_BIT3_OR_BIT4;
 
+/// Which of a position's moves `generate_next_move` hands out
+///
+/// A staged move picker asks for the captures first, and for the quiet moves only if none of
+/// the captures cut the search off.
+#[derive(PartialEq,Eq,Clone,Copy,Debug)]
+pub enum MoveFilter {
+    /// Every legal move
+    All,
+    /// Captures and promotions
+    Captures,
+    /// Everything else: quiet moves, castling and Crazyhouse drops
+    Quiet,
+}
+
 impl Reset {
 
+    /// Restrict `generate_next_move` to some of the moves, until move generation is next
+    /// initialized
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chessica::reset::moves::MoveFilter;
+    /// let mut r = chessica::reset::fen::from_fen("4k3/8/8/3q4/4P3/8/8/4K3 w - - 0 1").unwrap();
+    /// let mut child = chessica::reset::new();
+    /// r.set_move_filter(MoveFilter::Captures);
+    /// assert!(r.generate_next_move(&mut child));
+    /// assert_eq!(child.move_text(),"e4d5");
+    /// assert!(!r.generate_next_move(&mut child));
+    /// ```
+    pub fn set_move_filter(&mut self, filter: MoveFilter) {
+        self.move_filter = filter;
+    }
+
+    /// Could a move to `b_destination` by the current piece get past the move filter?
+    ///
+    /// Lets the piece generators skip building a child the filter is bound to throw away.  Only
+    /// pawns reach an empty square by capturing (en passant) or promoting.
+    pub fn move_filter_admits(&self, b_destination: u64) -> bool {
+        let b_opponents = if self.white_to_move() { self.b_black() } else { self.b_white };
+        match self.move_filter {
+            MoveFilter::All => true,
+            MoveFilter::Captures => b_destination & b_opponents != 0 || self.current_piece_type == PieceType::Pawn,
+            MoveFilter::Quiet => b_destination & b_opponents == 0,
+        }
+    }
+
+    /// Does a child get past the move filter?
+    fn move_filter_accepts(&self, child: &Reset) -> bool {
+        match self.move_filter {
+            MoveFilter::All => true,
+            MoveFilter::Captures => child.is_capture_or_promotion(),
+            MoveFilter::Quiet => !child.is_capture_or_promotion(),
+        }
+    }
+
     /// Set the PieceType of current_piece
     pub fn set_current_piece_type(&mut self) {
         if self.b_current_piece & self.b_pawns != 0 { // Pawn
@@ -50,11 +96,37 @@ impl Reset {
     /// r.initialize_move_generation();
     /// ```
     pub fn initialize_move_generation(&mut self) {
+        self.drop_piece = 0;
+        self.drop_square = 0;
+        self.must_capture = 0;
+        self.move_filter = MoveFilter::All;
         if self.white_to_move() {
             self.b_current_piece = bitops::lowest_bit(self.b_white);
         } else {
             self.b_current_piece = bitops::lowest_bit(self.b_black());
         }
+        if self.variant.has_capture_rules() {
+            self.initialize_variant_move_generation();
+        }
+        if self.game_over != 0 {
+            // A game won by a variant's goal has no moves left to play
+            self.b_current_piece = 0;
+        }
+    }
+
+    /// Apply the variant rules that restrict a whole position's moves
+    ///
+    /// An Atomic side whose king has exploded has lost, so has no moves; an Antichess side that
+    /// can capture must.
+    fn initialize_variant_move_generation(&mut self) {
+        if self.variant == Variant::Atomic {
+            let b_side = if self.white_to_move() { self.b_white } else { self.b_black() };
+            if self.b_kings & b_side == 0 {
+                self.b_current_piece = 0;
+            }
+        } else if self.variant == Variant::Antichess && self.antichess_capture_available() {
+            self.must_capture = 1;
+        }
     }
 
     /// Complete move generation initialization
@@ -73,6 +145,15 @@ impl Reset {
         }
     }
 
+    /// Start move generation over from the first piece
+    ///
+    /// Searches that visit the same child twice (e.g., a reduced search followed by a full-depth
+    /// re-search) use this to rewind the child's move generator.
+    pub fn restart_move_generation(&mut self) {
+        self.initialize_move_generation();
+        self.complete_move_initialization();
+    }
+
     /// Consider the next moveable piece
     ///
     /// # Examples
@@ -132,11 +213,17 @@ impl Reset {
                     found_move = self.generate_next_king_move(child);
                 },
             }
-            if found_move {
+            // Antichess: when a capture is possible, nothing else is
+            if found_move && (self.must_capture == 0 || child.capture != 0) && self.move_filter_accepts(child) {
                 child.initialize_move_generation();
                 return true;
             }
         }
+        // Crazyhouse drops come after the moves of the pieces on the board
+        if self.pockets != 0 && self.move_filter != MoveFilter::Captures && self.generate_next_drop(child) {
+            child.initialize_move_generation();
+            return true;
+        }
         false
     }
 
@@ -153,7 +240,7 @@ impl Reset {
 
         if child.b_to & child.b_all != 0 { // Capture
             #[cfg(debug_assertions)]
-            if child.b_to & self.b_kings != 0 {
+            if child.b_to & self.b_kings != 0 && self.variant != Variant::Antichess {
                 println!("King was captured!?!?!");
                 println!("Self:");
                 self.print_all();
@@ -163,6 +250,9 @@ impl Reset {
             }
             self.capture_processing(child);
         }
+        if child.b_promoted & child.b_from != 0 {
+            child.b_promoted ^= child.b_from | child.b_to;
+        }
         child.b_all &= !child.b_from;
         child.b_all |= child.b_to;
         if self.white_to_move() {
@@ -187,21 +277,7 @@ impl Reset {
             PieceType::Rook => {
                 child.b_rooks &= !child.b_from;
                 child.b_rooks |= child.b_to;
-                if child.b_from & B_FOUR_CORNERS != 0 {
-                    if child.b_from & B_SE_CORNER != 0 {
-                        //white_castle_k = 0;
-                        child.castle_bits &= U8_NOT_BIT1;
-                    } else if child.b_from & B_SW_CORNER != 0 {
-                        //white_castle_q = 0;
-                        child.castle_bits &= U8_NOT_BIT2;
-                    } else if child.b_from & B_NE_CORNER != 0 {
-                        //black_castle_k = 0;
-                        child.castle_bits &= U8_NOT_BIT3;
-                    } else { // B_NW_CORNER
-                        //black_castle_q = 0;
-                        child.castle_bits &= U8_NOT_BIT4;
-                    }
-                }
+                child.castle_bits &= !self.castle_rights_of_rook(child.bi_from);
             },
             PieceType::Queen => {
             },
@@ -219,6 +295,9 @@ impl Reset {
             PieceType::Unknown => {
             },
         }
+        if child.capture != 0 && self.variant == Variant::Atomic {
+            child.explode();
+        }
     }
 
     /// Adds a move to the specified child reset if valid
@@ -226,6 +305,13 @@ impl Reset {
     ///
     pub fn add_move_if_valid(&mut self, child: &mut Reset, b_destination: u64, pin_match: u8) -> bool {
 
+        if !self.move_filter_admits(b_destination) {
+            return false;
+        }
+
+        if self.variant.needs_full_legality_check() {
+            return self.add_variant_move(child, b_destination);
+        }
         if self.pin_dimension == PIN_DIMENSION_UNSET {
             self.set_current_piece_pin_dimension();
         } 
@@ -262,6 +348,13 @@ impl Reset {
     ///
     pub fn add_move_full_safety_check(&mut self, child: &mut Reset, b_destination: u64) -> bool {
 
+        if !self.move_filter_admits(b_destination) {
+            return false;
+        }
+
+        if self.variant.needs_full_legality_check() {
+            return self.add_variant_move(child, b_destination);
+        }
         self.add_move_unconditional(child, b_destination);
 
         // Move is invalid if I'm moving into check
@@ -287,18 +380,41 @@ impl Reset {
         true
     }
 
+    /// Adds a move to the specified child reset if valid under Atomic, Antichess or Racing Kings rules
+    /// Forces a full king safety check (see `Variant::needs_full_legality_check`)
+    ///
+    pub fn add_variant_move(&mut self, child: &mut Reset, b_destination: u64) -> bool {
+
+        // Atomic kings can't capture (they'd go up with the blast), and one only stands attacked
+        // beside the capturer's own king, so can't be captured directly either
+        if self.variant == Variant::Atomic &&
+            b_destination & self.b_all != 0 &&
+            (self.current_piece_type == PieceType::King || b_destination & self.b_kings != 0)
+        {
+            return false;
+        }
+        self.add_move_unconditional(child, b_destination);
+        child.settle_king_safety(self.white_to_move())
+    }
+
     /// Processing to be done on a valid child before returning the move
     pub fn valid_child_post_processing(&mut self, child: &mut Reset) {
         if child.capture != 0 || child.b_to & child.b_pawns != 0 || child.promotion != 0 {
             child.halfmove_clock = 0;
         } else {
-            child.halfmove_clock += 1;
+            child.halfmove_clock = child.halfmove_clock.saturating_add(1);
         }
         if self.white_to_move() {
             child.to_move = 1;
         } else {
             //child.to_move = 0; This was already initialized to zero
-            child.fullmove_number += 1;
+            child.fullmove_number = child.fullmove_number.saturating_add(1);
+        }
+        if self.variant.has_win_conditions() {
+            if self.variant == Variant::ThreeCheck && child.in_check != 0 {
+                child.count_check(self.white_to_move());
+            }
+            child.update_game_over();
         }
     }
 }
@@ -572,35 +688,35 @@ mod tests {
         let _result = r.generate_next_move(&mut child);
         assert_eq!(child.to_fen(),"rnbqkbnr/pppppppp/8/8/8/7P/PPPPPPP1/RNBQKBNR b KQkq - 0 1");
         let _result = r.generate_next_move(&mut child);
-        assert_eq!(child.to_fen(),"rnbqkbnr/pppppppp/8/8/7P/8/PPPPPPP1/RNBQKBNR b KQkq h3 0 1");
+        assert_eq!(child.to_fen(),"rnbqkbnr/pppppppp/8/8/7P/8/PPPPPPP1/RNBQKBNR b KQkq - 0 1");
         let _result = r.generate_next_move(&mut child);
         assert_eq!(child.to_fen(),"rnbqkbnr/pppppppp/8/8/8/6P1/PPPPPP1P/RNBQKBNR b KQkq - 0 1");
         let _result = r.generate_next_move(&mut child);
-        assert_eq!(child.to_fen(),"rnbqkbnr/pppppppp/8/8/6P1/8/PPPPPP1P/RNBQKBNR b KQkq g3 0 1");
+        assert_eq!(child.to_fen(),"rnbqkbnr/pppppppp/8/8/6P1/8/PPPPPP1P/RNBQKBNR b KQkq - 0 1");
         let _result = r.generate_next_move(&mut child);
         assert_eq!(child.to_fen(),"rnbqkbnr/pppppppp/8/8/8/5P2/PPPPP1PP/RNBQKBNR b KQkq - 0 1");
         let _result = r.generate_next_move(&mut child);
-        assert_eq!(child.to_fen(),"rnbqkbnr/pppppppp/8/8/5P2/8/PPPPP1PP/RNBQKBNR b KQkq f3 0 1");
+        assert_eq!(child.to_fen(),"rnbqkbnr/pppppppp/8/8/5P2/8/PPPPP1PP/RNBQKBNR b KQkq - 0 1");
         let _result = r.generate_next_move(&mut child);
         assert_eq!(child.to_fen(),"rnbqkbnr/pppppppp/8/8/8/4P3/PPPP1PPP/RNBQKBNR b KQkq - 0 1");
         let _result = r.generate_next_move(&mut child);
-        assert_eq!(child.to_fen(),"rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1");
+        assert_eq!(child.to_fen(),"rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1");
         let _result = r.generate_next_move(&mut child);
         assert_eq!(child.to_fen(),"rnbqkbnr/pppppppp/8/8/8/3P4/PPP1PPPP/RNBQKBNR b KQkq - 0 1");
         let _result = r.generate_next_move(&mut child);
-        assert_eq!(child.to_fen(),"rnbqkbnr/pppppppp/8/8/3P4/8/PPP1PPPP/RNBQKBNR b KQkq d3 0 1");
+        assert_eq!(child.to_fen(),"rnbqkbnr/pppppppp/8/8/3P4/8/PPP1PPPP/RNBQKBNR b KQkq - 0 1");
         let _result = r.generate_next_move(&mut child);
         assert_eq!(child.to_fen(),"rnbqkbnr/pppppppp/8/8/8/2P5/PP1PPPPP/RNBQKBNR b KQkq - 0 1");
         let _result = r.generate_next_move(&mut child);
-        assert_eq!(child.to_fen(),"rnbqkbnr/pppppppp/8/8/2P5/8/PP1PPPPP/RNBQKBNR b KQkq c3 0 1");
+        assert_eq!(child.to_fen(),"rnbqkbnr/pppppppp/8/8/2P5/8/PP1PPPPP/RNBQKBNR b KQkq - 0 1");
         let _result = r.generate_next_move(&mut child);
         assert_eq!(child.to_fen(),"rnbqkbnr/pppppppp/8/8/8/1P6/P1PPPPPP/RNBQKBNR b KQkq - 0 1");
         let _result = r.generate_next_move(&mut child);
-        assert_eq!(child.to_fen(),"rnbqkbnr/pppppppp/8/8/1P6/8/P1PPPPPP/RNBQKBNR b KQkq b3 0 1");
+        assert_eq!(child.to_fen(),"rnbqkbnr/pppppppp/8/8/1P6/8/P1PPPPPP/RNBQKBNR b KQkq - 0 1");
         let _result = r.generate_next_move(&mut child);
         assert_eq!(child.to_fen(),"rnbqkbnr/pppppppp/8/8/8/P7/1PPPPPPP/RNBQKBNR b KQkq - 0 1");
         let _result = r.generate_next_move(&mut child);
-        assert_eq!(child.to_fen(),"rnbqkbnr/pppppppp/8/8/P7/8/1PPPPPPP/RNBQKBNR b KQkq a3 0 1");
+        assert_eq!(child.to_fen(),"rnbqkbnr/pppppppp/8/8/P7/8/1PPPPPPP/RNBQKBNR b KQkq - 0 1");
         let result = r.generate_next_move(&mut child);
         assert!(!result);
     }
@@ -625,7 +741,7 @@ mod tests {
         let _result = r.generate_next_move(&mut child);
         assert_eq!(child.to_fen(),"1rb2rqk/4R1pp/pp6/5BP1/5P1Q/8/P4N1P/R5K1 w - - 0 2");
         let _result = r.generate_next_move(&mut child);
-        assert_eq!(child.to_fen(),"1rb2rqk/4R1pp/1p6/p4BP1/5P1Q/8/P4N1P/R5K1 w - a6 0 2");
+        assert_eq!(child.to_fen(),"1rb2rqk/4R1pp/1p6/p4BP1/5P1Q/8/P4N1P/R5K1 w - - 0 2");
         let _result = r.generate_next_move(&mut child);
         assert_eq!(child.to_fen(),"1rb2r1k/p3Rqpp/1p6/5BP1/5P1Q/8/P4N1P/R5K1 w - - 1 2");
         let _result = r.generate_next_move(&mut child);
@@ -0,0 +1,158 @@
+use crate::reset::Reset;
+use crate::reset::PieceType;
+use crate::reset::make::Move;
+
+/// Relative value of a piece for move ordering purposes
+///
+/// These only need to be in the right order - they are not used for scoring a position.
+pub fn piece_order_value(piece: PieceType) -> i32 {
+    match piece {
+        PieceType::Pawn => 1,
+        PieceType::Knight => 3,
+        PieceType::Bishop => 3,
+        PieceType::Rook => 5,
+        PieceType::Queen => 9,
+        PieceType::King => 100,
+        PieceType::Unknown => 0,
+    }
+}
+
+impl Reset {
+
+    /// The PieceType found on a single-bit square, or `PieceType::Unknown` if it is empty
+    ///
+    /// # Examples
+    /// ```
+    /// use chessica::reset::PieceType;
+    /// let mut r = chessica::reset::new();
+    /// r.init_from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1".to_string());
+    /// assert_eq!(r.piece_type_at(0x0000000000000010),PieceType::Queen);
+    /// assert_eq!(r.piece_type_at(0x0000000000010000),PieceType::Unknown);
+    /// ```
+    pub fn piece_type_at(&self, b_square: u64) -> PieceType {
+        if b_square & self.b_all == 0 {
+            PieceType::Unknown
+        } else if b_square & self.b_pawns != 0 {
+            PieceType::Pawn
+        } else if b_square & self.b_knights != 0 {
+            PieceType::Knight
+        } else if b_square & self.b_bishops != 0 {
+            PieceType::Bishop
+        } else if b_square & self.b_rooks != 0 {
+            PieceType::Rook
+        } else if b_square & self.b_kings != 0 {
+            PieceType::King
+        } else {
+            PieceType::Queen
+        }
+    }
+
+    /// Did the move that produced this Reset capture or promote?
+    pub fn is_capture_or_promotion(&self) -> bool {
+        self.capture != 0 || self.promotion != 0
+    }
+
+    /// MVV-LVA (Most Valuable Victim, Least Valuable Attacker) ordering value of a child move
+    ///
+    /// Called on the parent.  Captures of big pieces by small pieces come first; promotions are
+    /// treated as capturing the piece they promote to.  Returns 0 for quiet moves.
+    ///
+    /// # Examples
+    /// ```
+    /// let mut r = chessica::reset::new();
+    /// r.init_from_fen("4k3/8/8/3q4/2P1P3/8/8/4K3 w - - 0 1".to_string());
+    /// let mut child = chessica::reset::new();
+    /// let mut best = 0;
+    /// while r.generate_next_move(&mut child) {
+    ///     best = best.max(r.mvv_lva(&child));
+    /// }
+    /// assert_eq!(best,9*16-1);
+    /// ```
+    pub fn mvv_lva(&self, child: &Reset) -> i32 {
+        let mut value: i32 = 0;
+        if child.capture != 0 {
+            let victim = match self.piece_type_at(child.b_to) {
+                PieceType::Unknown => PieceType::Pawn, // En passant
+                piece => piece,
+            };
+            let attacker = self.piece_type_at(child.b_from);
+            value += piece_order_value(victim) * 16 - piece_order_value(attacker);
+        }
+        if child.promotion != 0 {
+            value += piece_order_value(child.promotion_piece) * 16;
+        }
+        value
+    }
+
+    /// `mvv_lva` of a move to be made on this Reset
+    pub fn move_mvv_lva(&self, mv: &Move) -> i32 {
+        let mut value: i32 = 0;
+        if mv.is_capture() {
+            let victim = match self.piece_type_at(1 << (mv.bi_to() - 1)) {
+                PieceType::Unknown => PieceType::Pawn, // En passant
+                piece => piece,
+            };
+            value += piece_order_value(victim) * 16 - piece_order_value(mv.piece_type());
+        }
+        if mv.promotion_piece() != PieceType::Unknown {
+            value += piece_order_value(mv.promotion_piece()) * 16;
+        }
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::reset;
+    use crate::reset::PieceType;
+    use crate::reset::Reset;
+
+    fn best_capture(fen: &str) -> (String, i32) {
+        let mut r = reset::new();
+        r.init_from_fen(fen.to_string());
+        let mut child: Reset = reset::new();
+        let mut best_value = 0;
+        let mut best_move = String::new();
+        while r.generate_next_move(&mut child) {
+            let value = r.mvv_lva(&child);
+            if value > best_value {
+                best_value = value;
+                best_move = child.move_text();
+            }
+        }
+        (best_move, best_value)
+    }
+
+    #[test]
+    fn ordering_piece_type_at() {
+        let mut r = reset::new();
+        r.init_from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1".to_string());
+        assert_eq!(r.piece_type_at(0x0000000000000080),PieceType::Rook);
+        assert_eq!(r.piece_type_at(0x0000000000000040),PieceType::Knight);
+        assert_eq!(r.piece_type_at(0x0000000000000020),PieceType::Bishop);
+        assert_eq!(r.piece_type_at(0x0000000000000008),PieceType::King);
+        assert_eq!(r.piece_type_at(0x0000000000000800),PieceType::Pawn);
+        assert_eq!(r.piece_type_at(0x0000000000100000),PieceType::Unknown);
+    }
+
+    #[test]
+    fn ordering_mvv_lva() {
+        // Pawn takes queen beats queen takes queen
+        let (best_move, _) = best_capture("4k3/8/8/3q4/4P3/8/8/3QK3 w - - 0 1");
+        assert_eq!(best_move,"e4d5");
+
+        // Rook takes rook beats rook takes knight
+        let (best_move, _) = best_capture("4k3/8/8/r6n/8/8/8/R3K2R w - - 0 1");
+        assert_eq!(best_move,"a1a5");
+
+        // En passant counts as capturing a pawn
+        let (best_move, value) = best_capture("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1");
+        assert_eq!(best_move,"e5d6");
+        assert_eq!(value,15);
+
+        // Promotion to queen comes first
+        let (best_move, value) = best_capture("8/4P3/8/8/8/8/k7/4K3 w - - 0 1");
+        assert_eq!(best_move,"e7e8");
+        assert_eq!(value,144);
+    }
+}
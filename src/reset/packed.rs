@@ -0,0 +1,424 @@
+use std::fmt;
+
+use crate::bitops;
+use crate::utils;
+use crate::reset::Reset;
+use crate::reset::fen::FenError;
+use crate::reset::r#const::B_RANK_4;
+use crate::reset::r#const::B_RANK_5;
+use crate::reset::variant::VARIANTS;
+
+/// Positions packed into 32 bytes
+///
+/// Training data, books and caches hold far too many positions to keep them as FENs or whole
+/// `Reset`s.  A packed position is 32 bytes, and each position has exactly one packing, so the
+/// bytes can be compared, hashed and deduplicated directly:
+///
+/// | bytes | meaning |
+/// | ----- | ------- |
+/// | 0-7   | `b_all`, little endian |
+/// | 8-23  | a 4-bit code for each occupied square, lowest square (h1) first, two to a byte (low nibble first); nibbles past the last piece are zero |
+/// | 24    | bit 0: `1` if black is to move; bits 1-3: index of the variant in `VARIANTS`; bits 4-7: zero |
+/// | 25    | Three-check checks given: white in the low nibble, black in the high nibble |
+/// | 26-27 | halfmove clock, little endian |
+/// | 28-29 | fullmove number, little endian |
+/// | 30-31 | zero |
+///
+/// Codes 0-7 are white's pieces and 8-15 black's: pawn, knight, bishop, rook, queen, king, a
+/// rook its side may still castle with, and a pawn that has just moved two squares and can be
+/// taken en passant.  Castling rights and the en passant square thus ride on the pieces they
+/// belong to, which also covers Chess960 rooks.  As with FEN output, an en passant square is only
+/// recorded when a pawn could actually capture there.
+pub const PACKED_SIZE: usize = 32;
+
+/// A position packed by `Reset::to_packed`
+pub type PackedPosition = [u8; PACKED_SIZE];
+
+const CODE_PAWN: u8 = 0;
+const CODE_KNIGHT: u8 = 1;
+const CODE_BISHOP: u8 = 2;
+const CODE_ROOK: u8 = 3;
+const CODE_QUEEN: u8 = 4;
+const CODE_KING: u8 = 5;
+const CODE_CASTLING_ROOK: u8 = 6;
+const CODE_EN_PASSANT_PAWN: u8 = 7;
+const CODE_BLACK: u8 = 8;
+
+const OFFSET_PIECES: usize = 8;
+const OFFSET_FLAGS: usize = 24;
+const OFFSET_CHECKS: usize = 25;
+const OFFSET_HALFMOVE_CLOCK: usize = 26;
+const OFFSET_FULLMOVE_NUMBER: usize = 28;
+const OFFSET_PADDING: usize = 30;
+
+/// Bits of the flags byte with no meaning
+const FLAGS_SPARE: u8 = 0xf0;
+
+/// Pieces there are codes for
+const MAX_PIECES: u32 = 32;
+
+/// Why packed bytes don't describe a position
+#[derive(PartialEq,Eq,Clone,Debug)]
+pub enum PackedError {
+    /// More occupied squares than there are piece codes
+    TooManyPieces(u32),
+    /// A variant index past the end of `VARIANTS`
+    BadVariant(u8),
+    /// A castling rook off its back rank, or a second one on the same wing
+    BadCastlingRook(String),
+    /// En passant pawns that can't be taken en passant: more than one, on the wrong rank, of the
+    /// side to move, or with no pawn beside them
+    BadEnPassant(String),
+    /// Pieces that don't make a legal position
+    Illegal(FenError),
+    /// Bits the format leaves unused set in a byte, so the bytes aren't the position's packing
+    NotZero(usize),
+}
+
+impl fmt::Display for PackedError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PackedError::TooManyPieces(count) => write!(f, "{} pieces, at most {} can be packed", count, MAX_PIECES),
+            PackedError::BadVariant(index) => write!(f, "invalid variant {}", index),
+            PackedError::BadCastlingRook(square) => write!(f, "invalid castling rook on {}", square),
+            PackedError::BadEnPassant(square) => write!(f, "invalid en passant pawn on {}", square),
+            PackedError::Illegal(error) => write!(f, "{}", error),
+            PackedError::NotZero(offset) => write!(f, "unused bits set in byte {}", offset),
+        }
+    }
+}
+
+impl std::error::Error for PackedError {}
+
+/// Unpack a position packed by `Reset::to_packed`, checking that it's legal as `fen::from_fen`
+/// does
+///
+/// Bytes that unpack are exactly the packing of the position they unpack to: any bit the format
+/// leaves unused must be zero.
+///
+/// # Examples
+///
+/// ```
+/// use chessica::reset::packed::{from_packed, PackedError};
+/// let r = chessica::reset::fen::from_fen("4k3/8/8/8/8/8/8/4K2R w K - 3 40").unwrap();
+/// let packed = r.to_packed().unwrap();
+/// assert_eq!(from_packed(&packed).unwrap().to_fen(),"4k3/8/8/8/8/8/8/4K2R w K - 3 40");
+/// assert_eq!(from_packed(&[0; 32]).err().map(|e| e.to_string()),Some("white doesn't have exactly one king".to_string()));
+/// ```
+pub fn from_packed(packed: &PackedPosition) -> Result<Reset, PackedError> {
+    let mut r = crate::reset::new();
+    let b_all = u64::from_le_bytes(packed[..OFFSET_PIECES].try_into().unwrap());
+    if b_all.count_ones() > MAX_PIECES {
+        return Err(PackedError::TooManyPieces(b_all.count_ones()));
+    }
+    let unused_nibbles = if b_all.count_ones() >= MAX_PIECES { 0 } else { !0u128 << (4 * b_all.count_ones()) };
+    let codes = u128::from_le_bytes(packed[OFFSET_PIECES..OFFSET_FLAGS].try_into().unwrap());
+    if codes & unused_nibbles != 0 {
+        return Err(PackedError::NotZero(OFFSET_PIECES + (codes & unused_nibbles).trailing_zeros() as usize / 8));
+    }
+    let flags = packed[OFFSET_FLAGS];
+    if flags & FLAGS_SPARE != 0 {
+        return Err(PackedError::NotZero(OFFSET_FLAGS));
+    }
+    if let Some(offset) = (OFFSET_PADDING..PACKED_SIZE).find(|offset| packed[*offset] != 0) {
+        return Err(PackedError::NotZero(offset));
+    }
+    r.to_move = flags & 1;
+    r.variant = *VARIANTS.get((flags >> 1) as usize).ok_or(PackedError::BadVariant(flags >> 1))?;
+    r.set_checks_given(packed[OFFSET_CHECKS] & 0x0f, packed[OFFSET_CHECKS] >> 4);
+    r.halfmove_clock = u16::from_le_bytes([packed[OFFSET_HALFMOVE_CLOCK], packed[OFFSET_HALFMOVE_CLOCK + 1]]);
+    r.fullmove_number = u16::from_le_bytes([packed[OFFSET_FULLMOVE_NUMBER], packed[OFFSET_FULLMOVE_NUMBER + 1]]);
+
+    // Place the pieces
+    let mut b_castling_rooks = 0;
+    let mut b_en_passant_pawns = 0;
+    let mut b_remaining = b_all;
+    let mut index = 0;
+    while b_remaining != 0 {
+        let b_piece = bitops::lowest_bit(b_remaining);
+        b_remaining &= !b_piece;
+        let code = (packed[OFFSET_PIECES + index / 2] >> (4 * (index % 2))) & 0x0f;
+        index += 1;
+        r.b_all |= b_piece;
+        if code & CODE_BLACK == 0 {
+            r.b_white |= b_piece;
+        }
+        match code & !CODE_BLACK {
+            CODE_PAWN => r.b_pawns |= b_piece,
+            CODE_KNIGHT => r.b_knights |= b_piece,
+            CODE_BISHOP => r.b_bishops |= b_piece,
+            CODE_ROOK => r.b_rooks |= b_piece,
+            CODE_QUEEN => (),
+            CODE_KING => r.b_kings |= b_piece,
+            CODE_CASTLING_ROOK => {
+                r.b_rooks |= b_piece;
+                b_castling_rooks |= b_piece;
+            },
+            _ => {
+                r.b_pawns |= b_piece;
+                b_en_passant_pawns |= b_piece;
+            },
+        }
+    }
+    let balance = |b_pieces: u64| (b_pieces & r.b_white).count_ones() as i8 - (b_pieces & r.b_black()).count_ones() as i8;
+    r.material = balance(r.b_pawns) + 3 * balance(r.b_knights | r.b_bishops) + 5 * balance(r.b_rooks) + 9 * balance(r.b_queens());
+    r.white_king_square = bitops::get_bit_number(r.b_white & r.b_kings);
+    r.black_king_square = bitops::get_bit_number(r.b_black() & r.b_kings);
+
+    // Castling rights, named by their rook's file as Shredder-FEN does
+    let mut b_remaining = b_castling_rooks;
+    while b_remaining != 0 {
+        let b_rook = bitops::lowest_bit(b_remaining);
+        b_remaining &= !b_rook;
+        let bi_rook = bitops::get_bit_number(b_rook);
+        let white = b_rook & r.b_white != 0;
+        let on_back_rank = if white { bi_rook <= 8 } else { bi_rook > 56 };
+        let file = (b'a' + 7 - (bi_rook - 1) % 8) as char;
+        let castle_bits = r.castle_bits;
+        if !on_back_rank || !r.add_castle_right(if white { file.to_ascii_uppercase() } else { file }) || r.castle_bits == castle_bits {
+            return Err(PackedError::BadCastlingRook(utils::convert_bitstring_to_square(b_rook)));
+        }
+    }
+
+    // The en passant square is the one the pawn passed over
+    if b_en_passant_pawns != 0 {
+        let (b_rank, b_side) = if r.white_to_move() {
+            r.b_en_passant = b_en_passant_pawns << 8;
+            (B_RANK_5, r.b_black())
+        } else {
+            r.b_en_passant = b_en_passant_pawns >> 8;
+            (B_RANK_4, r.b_white)
+        };
+        if b_en_passant_pawns.count_ones() != 1 || b_en_passant_pawns & b_rank & b_side == 0 || !r.en_passant_capture_possible() {
+            return Err(PackedError::BadEnPassant(utils::convert_bitstring_to_square(bitops::lowest_bit(b_en_passant_pawns))));
+        }
+    }
+
+    r.complete_setup();
+    r.validate_position().map_err(PackedError::Illegal)?;
+    Ok(r)
+}
+
+impl Reset {
+
+    /// Pack the position into 32 bytes, or `None` if it holds more than the format can: over 32
+    /// pieces, Crazyhouse pockets or promoted pieces, or more than 15 checks given
+    ///
+    /// Two positions that `to_fen` writes the same pack the same.  The position should be legal,
+    /// as one from `fen::from_fen` is; a castling right without its rook is lost.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let r = chessica::reset::fen::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").unwrap();
+    /// let packed = r.to_packed().unwrap();
+    /// assert_eq!(packed.len(),32);
+    /// assert_eq!(packed[..8],[0xff, 0xff, 0, 0, 0, 0, 0xff, 0xff]);
+    /// // h1 is a castling rook, g1 a knight
+    /// assert_eq!(packed[8],0x16);
+    /// ```
+    pub fn to_packed(&self) -> Option<PackedPosition> {
+        if self.pockets != 0 || self.b_promoted != 0 || self.b_all.count_ones() > MAX_PIECES ||
+            self.white_checks > 0x0f || self.black_checks > 0x0f
+        {
+            return None;
+        }
+        let mut packed = [0; PACKED_SIZE];
+        packed[..OFFSET_PIECES].copy_from_slice(&self.b_all.to_le_bytes());
+
+        let rights = [
+            (self.white_castle_k(), true, true),
+            (self.white_castle_q(), true, false),
+            (self.black_castle_k(), false, true),
+            (self.black_castle_q(), false, false),
+        ];
+        let mut b_castling_rooks = 0;
+        for (held, white, kingside) in rights {
+            if held {
+                b_castling_rooks |= 1 << (self.castle_rook_square(white, kingside) - 1);
+            }
+        }
+        let b_en_passant_pawn = if !self.en_passant_capture_possible() {
+            0
+        } else if self.white_to_move() {
+            self.b_en_passant >> 8
+        } else {
+            self.b_en_passant << 8
+        };
+
+        let mut b_remaining = self.b_all;
+        let mut index = 0;
+        while b_remaining != 0 {
+            let b_piece = bitops::lowest_bit(b_remaining);
+            b_remaining &= !b_piece;
+            let code = if b_piece & b_en_passant_pawn != 0 {
+                CODE_EN_PASSANT_PAWN
+            } else if b_piece & self.b_pawns != 0 {
+                CODE_PAWN
+            } else if b_piece & self.b_knights != 0 {
+                CODE_KNIGHT
+            } else if b_piece & self.b_bishops != 0 {
+                CODE_BISHOP
+            } else if b_piece & self.b_rooks & b_castling_rooks != 0 {
+                CODE_CASTLING_ROOK
+            } else if b_piece & self.b_rooks != 0 {
+                CODE_ROOK
+            } else if b_piece & self.b_kings != 0 {
+                CODE_KING
+            } else {
+                CODE_QUEEN
+            };
+            let code = if b_piece & self.b_white != 0 { code } else { code | CODE_BLACK };
+            packed[OFFSET_PIECES + index / 2] |= code << (4 * (index % 2));
+            index += 1;
+        }
+
+        let variant = VARIANTS.iter().position(|variant| *variant == self.variant).unwrap_or(0) as u8;
+        packed[OFFSET_FLAGS] = self.to_move | variant << 1;
+        packed[OFFSET_CHECKS] = self.white_checks | self.black_checks << 4;
+        packed[OFFSET_HALFMOVE_CLOCK..OFFSET_HALFMOVE_CLOCK + 2].copy_from_slice(&self.halfmove_clock.to_le_bytes());
+        packed[OFFSET_FULLMOVE_NUMBER..OFFSET_FULLMOVE_NUMBER + 2].copy_from_slice(&self.fullmove_number.to_le_bytes());
+        Some(packed)
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::reset;
+    use crate::reset::fen::from_fen;
+    use crate::reset::fen::FenError;
+    use crate::reset::packed::from_packed;
+    use crate::reset::packed::PackedError;
+    use crate::reset::variant::Variant;
+
+    /// Pack a position and unpack it again, checking it comes back as the same FEN
+    fn assert_round_trips(fen: &str, variant: Variant) -> [u8; 32] {
+        let mut r = reset::new();
+        r.parse_fen(fen).unwrap();
+        r.set_variant(variant);
+        r.validate_position().unwrap();
+        let packed = r.to_packed().unwrap();
+        let mut unpacked = from_packed(&packed).unwrap();
+        assert_eq!(unpacked.to_fen(),r.to_fen(),"{}",fen);
+        assert_eq!(unpacked.variant(),variant,"{}",fen);
+        assert_eq!(unpacked.to_packed(),Some(packed),"{}",fen);
+        packed
+    }
+
+    #[test]
+    fn packed_round_trip() {
+        let fens = [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+            "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+            "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+            "rnbqkbnr/p1pppppp/8/8/PpP4P/8/1P1PPPP1/RNBQKBNR b KQkq c3 0 3",
+            "8/8/4k3/8/8/4K3/8/8 b - - 65535 65535",
+            // Chess960 castling with an inner rook
+            "1r2k1r1/8/8/8/8/8/8/1R2K1R1 w GBgb - 0 1",
+            "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9",
+        ];
+        for fen in fens {
+            assert_round_trips(fen, Variant::Standard);
+        }
+        for variant in [Variant::Atomic, Variant::KingOfTheHill, Variant::RacingKings, Variant::Crazyhouse] {
+            assert_round_trips("8/8/8/8/8/8/krbnNBRK/qrbnNBRQ w - - 0 1", variant);
+        }
+        assert_round_trips("8/8/8/8/8/8/8/2K1K2r w - - 0 1", Variant::Antichess);
+        assert_round_trips("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1 +2+1", Variant::ThreeCheck);
+
+        // Every position of a game
+        let mut r = from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").unwrap();
+        for san in "e4 Nf6 e5 d5 exd6 cxd6 d4 g6 c4 Bg7 Nc3 O-O Be2 Nc6 d5 Ne5 f4 Ned7 Nf3 e5 dxe6 fxe6 O-O".split(' ') {
+            r = r.child_from_san(san).unwrap();
+            assert_round_trips(&r.to_fen(), Variant::Standard);
+        }
+    }
+
+    #[test]
+    fn packed_canonical() {
+        // An en passant square no pawn can capture on isn't packed
+        let with = assert_round_trips("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1", Variant::Standard);
+        let without = assert_round_trips("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1", Variant::Standard);
+        assert_eq!(with,without);
+        // Nor does it matter how a castling right was written
+        let x_fen = assert_round_trips("4k3/8/8/8/8/8/8/R3K2R w KQ - 0 1", Variant::Standard);
+        let shredder = assert_round_trips("4k3/8/8/8/8/8/8/R3K2R w HA - 0 1", Variant::Standard);
+        assert_eq!(x_fen,shredder);
+
+        // Too much to pack
+        let mut r = reset::new();
+        r.init_from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR[Qp] w KQkq - 0 1".to_string());
+        assert_eq!(r.to_packed(),None);
+        let mut r = reset::new();
+        r.init_from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1 +16+0".to_string());
+        assert_eq!(r.to_packed(),None);
+    }
+
+    #[test]
+    fn packed_errors() {
+        let start = from_fen("4k3/8/8/3pP3/8/8/8/R3K3 w Q d6 0 1").unwrap().to_packed().unwrap();
+        let mut packed = start;
+        packed[..8].copy_from_slice(&[0xff; 8]);
+        assert_eq!(from_packed(&packed).err(),Some(PackedError::TooManyPieces(64)));
+        let mut packed = start;
+        packed[24] = 7 << 1;
+        assert_eq!(from_packed(&packed).err(),Some(PackedError::BadVariant(7)));
+        // Black to move, so the d5 pawn's double step can't be the last move
+        let mut packed = start;
+        packed[24] |= 1;
+        assert_eq!(from_packed(&packed).err(),Some(PackedError::BadEnPassant("d5".to_string())));
+        // The a1 rook moved to h2, still marked as castling
+        let r = from_fen("4k3/8/8/8/8/8/8/R3K3 w Q - 0 1").unwrap();
+        let mut packed = r.to_packed().unwrap();
+        packed[..8].copy_from_slice(&(1u64 << 3 | 1 << 8 | 1 << 59).to_le_bytes());
+        assert_eq!(from_packed(&packed).err(),Some(PackedError::BadCastlingRook("h2".to_string())));
+        assert_eq!(from_packed(&[0; 32]).err(),Some(PackedError::Illegal(FenError::KingCount(true))));
+
+        // Unused bits
+        let mut packed = start;
+        packed[31] = 1;
+        assert_eq!(from_packed(&packed).err(),Some(PackedError::NotZero(31)));
+        let mut packed = start;
+        packed[24] |= 0x10;
+        assert_eq!(from_packed(&packed).err(),Some(PackedError::NotZero(24)));
+        // Five pieces fill bytes 8 and 9 and the low nibble of byte 10
+        let mut packed = start;
+        packed[10] |= 0x10;
+        assert_eq!(from_packed(&packed).err(),Some(PackedError::NotZero(10)));
+        let mut packed = start;
+        packed[23] = 0x80;
+        assert_eq!(from_packed(&packed).err(),Some(PackedError::NotZero(23)));
+    }
+
+    #[test]
+    fn packed_decodes_canonically() {
+        // Whatever unpacks packs back to the same bytes: try every value of every byte of a few
+        // packings
+        let positions = [
+            ("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1", Variant::Standard),
+            ("4k3/8/8/3pP3/8/8/8/R3K3 w Q d6 0 1", Variant::Standard),
+            ("1r2k1r1/8/8/8/8/8/8/1R2K1R1 w GBgb - 0 1", Variant::Standard),
+            ("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1 +2+1", Variant::ThreeCheck),
+            ("8/8/8/8/8/8/krbnNBRK/qrbnNBRQ w - - 0 1", Variant::RacingKings),
+        ];
+        let mut decoded = 0;
+        for (fen, variant) in positions {
+            let packed = assert_round_trips(fen, variant);
+            for offset in 0..packed.len() {
+                for value in 0..=255 {
+                    let mut bytes = packed;
+                    bytes[offset] = value;
+                    if let Ok(r) = from_packed(&bytes) {
+                        assert_eq!(r.to_packed(),Some(bytes),"{} byte {} = {:#04x}",fen,offset,value);
+                        decoded += 1;
+                    }
+                }
+            }
+        }
+        assert!(decoded > 1000,"{}",decoded);
+    }
+}
@@ -11,32 +11,37 @@ use crate::reset::r#const::B_NOT_SE_EDGE;
 use crate::reset::r#const::B_RANK_2;
 use crate::reset::r#const::B_RANK_7;
 use crate::reset::PieceType;
+use crate::reset::variant::Variant;
 
 impl Reset {
 
     pub fn generate_promotion_moves(&mut self, child: &mut Reset, move_base: u8) {
         child.promotion = 1;
         child.b_pawns &= !child.b_to;
-        let multiplier = if self.white_to_move() {
+        // An Atomic capture may have blown the pawn up, leaving nothing to promote
+        let b_to = child.b_to & child.b_all;
+        let multiplier = if b_to == 0 {
+            0
+        } else if self.white_to_move() {
             1
         } else {
             -1
         };
         match self.move_id % 10 {
             0 => { // Promote to knight
-                child.b_knights |= child.b_to;
+                child.b_knights |= b_to;
                 child.material += 2 * multiplier;
                 child.promotion_piece = PieceType::Knight;
                 self.move_id = move_base + 1;
             },
             1 => { // Promote to bishop
-                child.b_bishops |= child.b_to;
+                child.b_bishops |= b_to;
                 child.material += 2 * multiplier;
                 child.promotion_piece = PieceType::Bishop;
                 self.move_id = move_base + 2;
             },
             2 => { // Promote to rook
-                child.b_rooks |= child.b_to;
+                child.b_rooks |= b_to;
                 child.material += 4 * multiplier;
                 child.promotion_piece = PieceType::Rook;
                 self.move_id = move_base + 3;
@@ -44,15 +49,20 @@ impl Reset {
             3 => { // Promote to queen
                 child.material += 8 * multiplier;
                 child.promotion_piece = PieceType::Queen;
+                self.move_id = if self.variant == Variant::Antichess { move_base + 4 } else { move_base + 10 };
+            },
+            4 => { // Promote to king (Antichess)
+                child.b_kings |= b_to;
+                child.material -= multiplier;
+                child.promotion_piece = PieceType::King;
                 self.move_id = move_base + 10;
             },
             _ => panic!("Shouldn't get here!"),
         }
-        if self.white_to_move() {
-            if !child.black_is_safe(child.b_kings & child.b_black()) {
-                child.in_check = 1;
-            }
-        } else if !child.white_is_safe(child.b_kings & child.b_white) {
+        if self.variant == Variant::Crazyhouse {
+            child.b_promoted |= child.b_to;
+        }
+        if !child.king_is_safe(!self.white_to_move()) {
             child.in_check = 1;
         }
     }
@@ -63,11 +73,14 @@ impl Reset {
         child.b_pawns &= !b_pawn_to_remove;
         child.material += 1;
         child.capture = 1;
-        if !child.white_is_safe(child.b_kings & child.b_white) {
-            return false;
+        if self.variant == Variant::Crazyhouse {
+            child.add_to_pocket(true, PieceType::Pawn);
         }
-        if !child.black_is_safe(child.b_kings & child.b_black()) {
-            child.in_check = 1;
+        if self.variant == Variant::Atomic {
+            child.explode();
+        }
+        if !child.settle_king_safety(true) {
+            return false;
         }
         self.valid_child_post_processing(child);
         true
@@ -177,11 +190,14 @@ impl Reset {
         child.b_pawns &= !b_pawn_to_remove;
         child.material -= 1;
         child.capture = 1;
-        if !child.black_is_safe(child.b_kings & child.b_black()) {
-            return false;
+        if self.variant == Variant::Crazyhouse {
+            child.add_to_pocket(false, PieceType::Pawn);
         }
-        if !child.white_is_safe(child.b_kings & child.b_white) {
-            child.in_check = 1;
+        if self.variant == Variant::Atomic {
+            child.explode();
+        }
+        if !child.settle_king_safety(false) {
+            return false;
         }
         self.valid_child_post_processing(child);
         true
@@ -322,7 +338,7 @@ mod tests {
         assert_eq!(r.move_id,20);
 
         // h2 to h4
-        let fen = String::from("rnbqkbnr/pppppppp/8/8/7P/8/PPPPPPP1/RNBQKBNR b KQkq h3 0 1");
+        let fen = String::from("rnbqkbnr/pppppppp/8/8/7P/8/PPPPPPP1/RNBQKBNR b KQkq - 0 1");
         let retval = r.generate_next_pawn_move(&mut child);
         assert!(retval);
         assert_eq!(child.to_fen(),fen);
@@ -344,7 +360,7 @@ mod tests {
         assert_eq!(r.move_id,20);
 
         // g2 to g4
-        let fen = String::from("rnbqkbnr/pppppppp/8/8/6P1/8/PPPPPP1P/RNBQKBNR b KQkq g3 0 1");
+        let fen = String::from("rnbqkbnr/pppppppp/8/8/6P1/8/PPPPPP1P/RNBQKBNR b KQkq - 0 1");
         let retval = r.generate_next_pawn_move(&mut child);
         assert!(retval);
         assert_eq!(child.to_fen(),fen);
@@ -366,7 +382,7 @@ mod tests {
         assert_eq!(r.move_id,20);
 
         // f2 to f4
-        let fen = String::from("rnbqkbnr/pppppppp/8/8/5P2/8/PPPPP1PP/RNBQKBNR b KQkq f3 0 1");
+        let fen = String::from("rnbqkbnr/pppppppp/8/8/5P2/8/PPPPP1PP/RNBQKBNR b KQkq - 0 1");
         let retval = r.generate_next_pawn_move(&mut child);
         assert!(retval);
         assert_eq!(child.to_fen(),fen);
@@ -388,7 +404,7 @@ mod tests {
         assert_eq!(r.move_id,20);
 
         // e2 to e4
-        let fen = String::from("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1");
+        let fen = String::from("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1");
         let retval = r.generate_next_pawn_move(&mut child);
         assert!(retval);
         assert_eq!(child.to_fen(),fen);
@@ -410,7 +426,7 @@ mod tests {
         assert_eq!(r.move_id,20);
 
         // d2 to d4
-        let fen = String::from("rnbqkbnr/pppppppp/8/8/3P4/8/PPP1PPPP/RNBQKBNR b KQkq d3 0 1");
+        let fen = String::from("rnbqkbnr/pppppppp/8/8/3P4/8/PPP1PPPP/RNBQKBNR b KQkq - 0 1");
         let retval = r.generate_next_pawn_move(&mut child);
         assert!(retval);
         assert_eq!(child.to_fen(),fen);
@@ -432,7 +448,7 @@ mod tests {
         assert_eq!(r.move_id,20);
 
         // c2 to c4
-        let fen = String::from("rnbqkbnr/pppppppp/8/8/2P5/8/PP1PPPPP/RNBQKBNR b KQkq c3 0 1");
+        let fen = String::from("rnbqkbnr/pppppppp/8/8/2P5/8/PP1PPPPP/RNBQKBNR b KQkq - 0 1");
         let retval = r.generate_next_pawn_move(&mut child);
         assert!(retval);
         assert_eq!(child.to_fen(),fen);
@@ -454,7 +470,7 @@ mod tests {
         assert_eq!(r.move_id,20);
 
         // b2 to b4
-        let fen = String::from("rnbqkbnr/pppppppp/8/8/1P6/8/P1PPPPPP/RNBQKBNR b KQkq b3 0 1");
+        let fen = String::from("rnbqkbnr/pppppppp/8/8/1P6/8/P1PPPPPP/RNBQKBNR b KQkq - 0 1");
         let retval = r.generate_next_pawn_move(&mut child);
         assert!(retval);
         assert_eq!(child.to_fen(),fen);
@@ -476,7 +492,7 @@ mod tests {
         assert_eq!(r.move_id,20);
 
         // a2 to a4
-        let fen = String::from("rnbqkbnr/pppppppp/8/8/P7/8/1PPPPPPP/RNBQKBNR b KQkq a3 0 1");
+        let fen = String::from("rnbqkbnr/pppppppp/8/8/P7/8/1PPPPPPP/RNBQKBNR b KQkq - 0 1");
         let retval = r.generate_next_pawn_move(&mut child);
         assert!(retval);
         assert_eq!(child.to_fen(),fen);
@@ -505,7 +521,7 @@ mod tests {
         assert_eq!(r.move_id,20);
 
         // h7 to h5
-        let fen = String::from("rnbqkbnr/ppppppp1/8/7p/8/8/PPPPPPPP/RNBQKBNR w KQkq h6 0 2");
+        let fen = String::from("rnbqkbnr/ppppppp1/8/7p/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 2");
         let retval = r.generate_next_pawn_move(&mut child);
         assert!(retval);
         assert_eq!(child.to_fen(),fen);
@@ -527,7 +543,7 @@ mod tests {
         assert_eq!(r.move_id,20);
 
         // g7 to g5
-        let fen = String::from("rnbqkbnr/pppppp1p/8/6p1/8/8/PPPPPPPP/RNBQKBNR w KQkq g6 0 2");
+        let fen = String::from("rnbqkbnr/pppppp1p/8/6p1/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 2");
         let retval = r.generate_next_pawn_move(&mut child);
         assert!(retval);
         assert_eq!(child.to_fen(),fen);
@@ -549,7 +565,7 @@ mod tests {
         assert_eq!(r.move_id,20);
 
         // f7 to f5
-        let fen = String::from("rnbqkbnr/ppppp1pp/8/5p2/8/8/PPPPPPPP/RNBQKBNR w KQkq f6 0 2");
+        let fen = String::from("rnbqkbnr/ppppp1pp/8/5p2/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 2");
         let retval = r.generate_next_pawn_move(&mut child);
         assert!(retval);
         assert_eq!(child.to_fen(),fen);
@@ -571,7 +587,7 @@ mod tests {
         assert_eq!(r.move_id,20);
 
         // e7 to e5
-        let fen = String::from("rnbqkbnr/pppp1ppp/8/4p3/8/8/PPPPPPPP/RNBQKBNR w KQkq e6 0 2");
+        let fen = String::from("rnbqkbnr/pppp1ppp/8/4p3/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 2");
         let retval = r.generate_next_pawn_move(&mut child);
         assert!(retval);
         assert_eq!(child.to_fen(),fen);
@@ -593,7 +609,7 @@ mod tests {
         assert_eq!(r.move_id,20);
 
         // d7 to d5
-        let fen = String::from("rnbqkbnr/ppp1pppp/8/3p4/8/8/PPPPPPPP/RNBQKBNR w KQkq d6 0 2");
+        let fen = String::from("rnbqkbnr/ppp1pppp/8/3p4/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 2");
         let retval = r.generate_next_pawn_move(&mut child);
         assert!(retval);
         assert_eq!(child.to_fen(),fen);
@@ -615,7 +631,7 @@ mod tests {
         assert_eq!(r.move_id,20);
 
         // c7 to c5
-        let fen = String::from("rnbqkbnr/pp1ppppp/8/2p5/8/8/PPPPPPPP/RNBQKBNR w KQkq c6 0 2");
+        let fen = String::from("rnbqkbnr/pp1ppppp/8/2p5/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 2");
         let retval = r.generate_next_pawn_move(&mut child);
         assert!(retval);
         assert_eq!(child.to_fen(),fen);
@@ -637,7 +653,7 @@ mod tests {
         assert_eq!(r.move_id,20);
 
         // b7 to b5
-        let fen = String::from("rnbqkbnr/p1pppppp/8/1p6/8/8/PPPPPPPP/RNBQKBNR w KQkq b6 0 2");
+        let fen = String::from("rnbqkbnr/p1pppppp/8/1p6/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 2");
         let retval = r.generate_next_pawn_move(&mut child);
         assert!(retval);
         assert_eq!(child.to_fen(),fen);
@@ -659,7 +675,7 @@ mod tests {
         assert_eq!(r.move_id,20);
 
         // a7 to a5
-        let fen = String::from("rnbqkbnr/1ppppppp/8/p7/8/8/PPPPPPPP/RNBQKBNR w KQkq a6 0 2");
+        let fen = String::from("rnbqkbnr/1ppppppp/8/p7/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 2");
         let retval = r.generate_next_pawn_move(&mut child);
         assert!(retval);
         assert_eq!(child.to_fen(),fen);
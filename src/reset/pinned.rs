@@ -37,6 +37,12 @@ impl Reset {
     /// Someday, king_square won't be needed by this method, but for now it's there for performance
     /// reasons.
     pub fn set_current_piece_pin_dimension(&mut self) {
+        self.pin_dimension = self.pin_dimension_of(self.b_current_piece, self.bi_current_piece);
+    }
+
+    /// The dimension in which the side to move's piece on a square is pinned to its king, or
+    /// `PIN_DIMENSION_NONE`
+    pub fn pin_dimension_of(&self, b_piece: u64, bi_piece: u8) -> u8 {
         let mut b_opponents: u64 = if self.white_to_move() {
             self.b_black()
         } else {
@@ -47,18 +53,17 @@ impl Reset {
         } else {
             self.black_king_square
         };
-        let from_square = self.bi_current_piece as usize;
+        let from_square = bi_piece as usize;
 
         let search_type = &REVEALED_CHECK_ROUTES[king_square as usize][from_square];
         if matches!(search_type,RevealedCheckSearchType::DoNotSearch) {
-            self.pin_dimension = PIN_DIMENSION_NONE;
-            return;
+            return PIN_DIMENSION_NONE;
         }
 
         let b_others: u64 = self.b_pawns | self.b_knights | self.b_kings;
         let index: u8 = match search_type {
             RevealedCheckSearchType::DoNotSearch => {
-                return; //Will not get here
+                return PIN_DIMENSION_NONE; //Will not get here
             },
             RevealedCheckSearchType::FromN => {
                 b_opponents &= !(b_others | self.b_bishops);
@@ -95,15 +100,13 @@ impl Reset {
         };
         let b_attacks = REVEALED_CHECK_BITMAPS[king_square as usize][index as usize];
         if b_attacks & b_opponents == 0 {
-            self.pin_dimension = PIN_DIMENSION_NONE;
-            return;
+            return PIN_DIMENSION_NONE;
         }
-        let b_board: u64 = self.b_all & !self.b_current_piece;
+        let b_board: u64 = self.b_all & !b_piece;
         if !(IS_SAFE_FROM_REVEALED_CHECK_FUNCTIONS[index as usize])(king_square,b_board,b_opponents) {
-            self.pin_dimension = PIN_DIMENSIONS[index as usize];
-            return;
+            return PIN_DIMENSIONS[index as usize];
         }
-        self.pin_dimension = PIN_DIMENSION_NONE;
+        PIN_DIMENSION_NONE
     }
 }
 
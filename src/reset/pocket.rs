@@ -0,0 +1,289 @@
+use crate::reset::Reset;
+use crate::reset::PieceType;
+use crate::reset::r#const::B_RANK_1;
+use crate::reset::r#const::B_RANK_8;
+use crate::bitops;
+
+/// Pieces that can be held in a Crazyhouse pocket, in the order their counts are packed
+pub const POCKET_PIECES: [PieceType; 5] = [
+    PieceType::Pawn,
+    PieceType::Knight,
+    PieceType::Bishop,
+    PieceType::Rook,
+    PieceType::Queen,
+];
+
+const POCKET_BITS: u32 = 6;
+const POCKET_MASK: u64 = 0x3f;
+/// Bits of `pockets` holding one side's counts, white's first
+pub const POCKET_SIDE_BITS: u32 = 5 * POCKET_BITS;
+
+/// Position of a side's count of a piece type within `pockets`
+fn pocket_shift(white: bool, piece_type: PieceType) -> u32 {
+    let side: u32 = if white { 0 } else { POCKET_SIDE_BITS };
+    side + (piece_type as u32 - 1) * POCKET_BITS
+}
+
+/// Material value, in pawns, of a piece that can be dropped
+pub fn drop_material(piece_type: PieceType) -> i8 {
+    match piece_type {
+        PieceType::Pawn => 1,
+        PieceType::Knight | PieceType::Bishop => 3,
+        PieceType::Rook => 5,
+        _ => 9,
+    }
+}
+
+/// FEN letter of a white piece
+fn piece_letter(piece_type: PieceType) -> char {
+    match piece_type {
+        PieceType::Pawn => 'P',
+        PieceType::Knight => 'N',
+        PieceType::Bishop => 'B',
+        PieceType::Rook => 'R',
+        PieceType::Queen => 'Q',
+        _ => 'K',
+    }
+}
+
+impl Reset {
+
+    /// Number of pieces of a type a side holds in its Crazyhouse pocket
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chessica::reset::PieceType;
+    /// let mut r = chessica::reset::new();
+    /// r.init_from_fen("4k3/8/8/8/8/8/8/4K3[NNp] w - - 0 1".to_string());
+    /// assert_eq!(r.pocket_count(true,PieceType::Knight),2);
+    /// assert_eq!(r.pocket_count(false,PieceType::Pawn),1);
+    /// ```
+    pub fn pocket_count(&self, white: bool, piece_type: PieceType) -> u8 {
+        ((self.pockets >> pocket_shift(white, piece_type)) & POCKET_MASK) as u8
+    }
+
+    /// Put a piece in a side's pocket
+    pub fn add_to_pocket(&mut self, white: bool, piece_type: PieceType) {
+        self.pockets += 1 << pocket_shift(white, piece_type);
+    }
+
+    /// Take a piece out of a side's pocket
+    pub fn take_from_pocket(&mut self, white: bool, piece_type: PieceType) {
+        self.pockets -= 1 << pocket_shift(white, piece_type);
+    }
+
+    /// Was this Reset reached by dropping a piece from a pocket?
+    pub fn is_drop(&self) -> bool {
+        self.b_from == 0 && self.b_to != 0
+    }
+
+    /// Pocket contents as the bracketed FEN suffix lists them, white's first (e.g., "QNpp")
+    pub fn pocket_text(&self) -> String {
+        let mut text = String::new();
+        for white in [true, false] {
+            for piece_type in POCKET_PIECES.iter().rev() {
+                let letter = piece_letter(*piece_type);
+                let letter = if white { letter } else { letter.to_ascii_lowercase() };
+                for _ in 0..self.pocket_count(white, *piece_type) {
+                    text.push(letter);
+                }
+            }
+        }
+        text
+    }
+
+    /// Fill the pockets from the letters of a FEN pocket (e.g., "QNpp")
+    ///
+    /// Returns `false`, leaving the pockets as they were, if a letter isn't a droppable piece.
+    pub fn set_pockets(&mut self, text: &str) -> bool {
+        let mut pockets: u64 = 0;
+        for c in text.chars() {
+            let piece_type = match POCKET_PIECES.iter().find(|piece_type| piece_letter(**piece_type) == c.to_ascii_uppercase()) {
+                Some(piece_type) => *piece_type,
+                None => return false,
+            };
+            pockets += 1 << pocket_shift(c.is_ascii_uppercase(), piece_type);
+        }
+        self.pockets = pockets;
+        true
+    }
+
+    /// Generate the next Crazyhouse drop
+    ///
+    /// Drops follow the piece moves: each piece in the mover's pocket, in `POCKET_PIECES` order, on
+    /// each empty square (pawns only on ranks 2-7).  A drop can't uncover a check, so when not in
+    /// check every one is legal; when in check only the drops that leave the king safe (the ones
+    /// that block) are kept.
+    pub fn generate_next_drop(&mut self, child: &mut Reset) -> bool {
+        let white = self.white_to_move();
+        while (self.drop_piece as usize) < POCKET_PIECES.len() {
+            let piece_type = POCKET_PIECES[self.drop_piece as usize];
+            if self.drop_square >= 64 || self.pocket_count(white, piece_type) == 0 {
+                self.drop_piece += 1;
+                self.drop_square = 0;
+                continue;
+            }
+            let b_destination: u64 = 1 << self.drop_square;
+            self.drop_square += 1;
+            let b_available = if piece_type == PieceType::Pawn {
+                !self.b_all & !(B_RANK_1 | B_RANK_8)
+            } else {
+                !self.b_all
+            };
+            if b_destination & b_available == 0 {
+                continue;
+            }
+            self.add_drop(child, piece_type, b_destination);
+            if white {
+                if self.in_check != 0 && !child.white_is_safe(child.b_kings & child.b_white) {
+                    continue;
+                }
+                if !child.black_is_safe(child.b_kings & child.b_black()) {
+                    child.in_check = 1;
+                }
+            } else {
+                if self.in_check != 0 && !child.black_is_safe(child.b_kings & child.b_black()) {
+                    continue;
+                }
+                if !child.white_is_safe(child.b_kings & child.b_white) {
+                    child.in_check = 1;
+                }
+            }
+            self.valid_child_post_processing(child);
+            return true;
+        }
+        false
+    }
+
+    /// Drop a piece from the mover's pocket into the child
+    ///
+    /// Drops have no `b_from`.  The dropped piece goes in `promotion_piece` (with `promotion`
+    /// left at 0) so that `child_hash` tells drops of different pieces apart.
+    fn add_drop(&mut self, child: &mut Reset, piece_type: PieceType, b_destination: u64) {
+        let white = self.white_to_move();
+        self.init_child(child);
+        child.b_from = 0;
+        child.b_to = b_destination;
+        child.bi_from = 0;
+        child.bi_to = bitops::get_bit_number(b_destination);
+        child.b_all |= b_destination;
+        if white {
+            child.b_white |= b_destination;
+        }
+        match piece_type {
+            PieceType::Pawn => child.b_pawns |= b_destination,
+            PieceType::Knight => child.b_knights |= b_destination,
+            PieceType::Bishop => child.b_bishops |= b_destination,
+            PieceType::Rook => child.b_rooks |= b_destination,
+            _ => {},
+        }
+        let multiplier: i8 = if white { 1 } else { -1 };
+        child.material += multiplier * drop_material(piece_type);
+        child.take_from_pocket(white, piece_type);
+        child.promotion_piece = piece_type;
+    }
+
+    /// Move text of a drop, e.g. "N@f3"
+    pub fn drop_text(&self) -> String {
+        let mut text = String::new();
+        text.push(piece_letter(self.piece_type_at(self.b_to)));
+        text.push('@');
+        text.push_str(&crate::utils::convert_bitstring_to_square(self.b_to));
+        text
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::reset;
+    use crate::reset::Reset;
+    use crate::reset::PieceType;
+    use crate::reset::variant::Variant;
+
+    fn prep_board(fen: &str) -> Reset {
+        let mut r = reset::new();
+        r.init_from_fen(fen.to_string());
+        r
+    }
+
+    fn move_texts(r: &mut Reset) -> Vec<String> {
+        let mut texts = Vec::new();
+        let mut child = reset::new();
+        r.restart_move_generation();
+        while r.generate_next_move(&mut child) {
+            texts.push(child.uci_move_text());
+        }
+        texts
+    }
+
+    #[test]
+    fn pocket_fen_round_trip() {
+        let fen = "r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R[QRbnp] w KQkq - 2 3";
+        let mut r = prep_board(fen);
+        assert_eq!(r.variant(),Variant::Crazyhouse);
+        assert_eq!(r.to_fen(),fen);
+        assert_eq!(r.pocket_count(true,PieceType::Queen),1);
+        assert_eq!(r.pocket_count(false,PieceType::Bishop),1);
+
+        // The pocket can also be written as a ninth rank
+        let fen = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR/Qp w KQkq - 0 1";
+        assert_eq!(prep_board(fen).to_fen(),"rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR[Qp] w KQkq - 0 1");
+
+        // Promoted pieces are marked with "~"
+        let fen = "4k3/8/8/8/8/8/8/Q~3K3[] w - - 0 1";
+        assert_eq!(prep_board(fen).to_fen(),fen);
+
+        // Standard FENs are unchanged
+        let fen = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+        let mut r = prep_board(fen);
+        assert_eq!(r.variant(),Variant::Standard);
+        assert_eq!(r.to_fen(),fen);
+    }
+
+    #[test]
+    fn pocket_captures() {
+        // A capture pockets the piece for the capturer
+        let mut r = prep_board("4k3/8/8/3n4/4P3/8/8/4K3[] w - - 0 1");
+        let mut child = r.child_from_san("exd5").unwrap();
+        assert_eq!(child.to_fen(),"4k3/8/8/3P4/8/8/8/4K3[N] b - - 0 1");
+
+        // A promoted piece goes back to being a pawn
+        let mut r = prep_board("4k3/8/8/3q~4/4P3/8/8/4K3[] w - - 0 1");
+        let mut child = r.child_from_san("exd5").unwrap();
+        assert_eq!(child.to_fen(),"4k3/8/8/3P4/8/8/8/4K3[P] b - - 0 1");
+
+        // Promotion marks the new piece, which carries its mark as it moves
+        let mut r = prep_board("8/4P3/8/8/8/8/8/k3K3[] w - - 0 1");
+        let mut child = r.child_from_san("e8=Q").unwrap();
+        assert_eq!(child.to_fen(),"4Q~3/8/8/8/8/8/8/k3K3[] b - - 0 1");
+    }
+
+    #[test]
+    fn pocket_drops() {
+        // Five king moves and a knight on each of the 62 empty squares
+        let mut r = prep_board("4k3/8/8/8/8/8/8/4K3[N] w - - 0 1");
+        let texts = move_texts(&mut r);
+        assert_eq!(texts.len(),67);
+        assert!(texts.contains(&"N@f3".to_string()));
+
+        // No pawns on the first or last rank
+        let mut r = prep_board("4k3/8/8/8/8/8/8/4K3[P] w - - 0 1");
+        let texts = move_texts(&mut r);
+        assert_eq!(texts.len(),53);
+        assert!(!texts.contains(&"P@a8".to_string()));
+
+        // In check, only blocking drops are legal
+        let mut r = prep_board("4k3/8/8/8/8/8/8/r3K3[N] w - - 0 1");
+        let mut texts = move_texts(&mut r);
+        texts.sort();
+        assert_eq!(texts,vec!["N@b1","N@c1","N@d1","e1d2","e1e2","e1f2"]);
+
+        // The dropped piece leaves the pocket and can give check
+        let mut r = prep_board("4k3/8/8/8/8/8/8/4K3[Q] w - - 0 1");
+        let mut child = r.child_from_san("Q@e7").unwrap();
+        assert_eq!(child.to_fen(),"4k3/4Q3/8/8/8/8/8/4K3[] b - - 1 1");
+        assert!(child.in_check());
+    }
+}
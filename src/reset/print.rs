@@ -114,6 +114,11 @@ impl Reset {
     }
 
     pub fn print_board_big(&mut self) {
+        self.print_board_big_beside(&[]);
+    }
+
+    /// `print_board_big` with a line of `side` text printed to the right of each row of squares
+    pub fn print_board_big_beside(&mut self, side: &[String]) {
         use ansi_term::Colour;
         let mut b_index: u64 = 0x8000000000000000;
         let mut increment: u8 = 0;
@@ -123,8 +128,6 @@ impl Reset {
         self.print();
         while row > 0 {
             let mut style;
-            if col == 1 {
-            }
             if (row + col) % 2 == 0 { // Black Square
                 style = Colour::RGB(0,0,0).on(Colour::RGB(110,110,110));
                 print!("{}", style.paint(" "));
@@ -195,6 +198,9 @@ impl Reset {
             increment += 1;
             col += 1;
             if increment % 8 == 0 {
+                if let Some(text) = side.get(increment as usize / 8 - 1) {
+                    print!("   {}",text);
+                }
                 println!();
                 level += 1;
                 if level > 3 {
@@ -206,7 +212,16 @@ impl Reset {
         }
     }
 
+    /// The big board with the evaluation breakdown (`eval::trace`) beside it
+    pub fn print_eval(&mut self) {
+        let lines = crate::eval::trace::trace(self).lines();
+        self.print_board_big_beside(&lines);
+    }
+
     pub fn move_text(&mut self) -> String {
+        if self.is_drop() {
+            return self.drop_text();
+        }
         let from_text = convert_bitstring_to_square(self.b_from);
         let to_text = convert_bitstring_to_square(self.b_to);
         let mut return_string =  String::new();
@@ -215,6 +230,45 @@ impl Reset {
         return_string
     }
 
+    /// Move text in UCI long algebraic notation, including the promotion piece (e.g., "e7e8q")
+    ///
+    /// Castling is written king-takes-rook once `UCI_Chess960` is on.
+    pub fn uci_move_text(&mut self) -> String {
+        if crate::reset::chess960::enabled() {
+            return self.chess960_move_text();
+        }
+        self.long_algebraic_text()
+    }
+
+    /// Move text in UCI long algebraic notation, writing castling as the king taking its own rook
+    /// (e.g., "e1h1")
+    pub fn chess960_move_text(&mut self) -> String {
+        match self.castled_rook_squares() {
+            Some((bi_rook, _)) => {
+                let mut return_string = convert_bitstring_to_square(self.b_from);
+                return_string.push_str(&convert_bitstring_to_square(1 << (bi_rook - 1)));
+                return_string
+            },
+            None => self.long_algebraic_text(),
+        }
+    }
+
+    /// Move text in long algebraic notation, castling written as the king's move (e.g., "e1g1")
+    pub fn long_algebraic_text(&mut self) -> String {
+        use crate::reset::PieceType;
+        let mut return_string = self.move_text();
+        if self.promotion != 0 {
+            match self.promotion_piece {
+                PieceType::Knight => return_string.push('n'),
+                PieceType::Bishop => return_string.push('b'),
+                PieceType::Rook => return_string.push('r'),
+                PieceType::King => return_string.push('k'),
+                _ => return_string.push('q'),
+            }
+        }
+        return_string
+    }
+
     pub fn print_all(&mut self) {
         self.print_board_big();
         println!("==");
@@ -272,4 +326,18 @@ mod tests {
         r.print_board_small();
     }
 
+    #[test]
+    fn test_uci_move_text() {
+        let mut r = reset::new();
+        r.init_from_fen("4k3/1P6/8/8/8/8/8/4K3 w - - 0 1".to_string());
+        let mut child = reset::new();
+        let mut moves = Vec::new();
+        while r.generate_next_move(&mut child) {
+            moves.push(child.uci_move_text());
+        }
+        assert!(moves.contains(&"b7b8q".to_string()));
+        assert!(moves.contains(&"b7b8n".to_string()));
+        assert!(moves.contains(&"e1d1".to_string()));
+    }
+
 }
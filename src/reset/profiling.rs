@@ -1,4 +1,6 @@
 use crate::reset::Reset;
+use crate::reset::variant::Variant;
+use crate::reset::make::Move;
 
 pub fn perft(fen: &str, depth: u8) {
     use crate::utils::convert_bitstring_to_square;
@@ -29,7 +31,37 @@ pub fn count_possible_games(fen: &str, depth: u8) -> u64 {
     move_count
 }
 
+/// Count the games of a given length from a position played by a variant's rules
+///
+/// For variants a FEN doesn't name, such as Atomic chess and Antichess.
+pub fn count_possible_variant_games(fen: &str, variant: Variant, depth: u8) -> u64 {
+    let mut move_count: u64 = 0;
+    let mut r: Reset = crate::reset::new();
+    let fen = String::from(fen);
+    r.init_from_fen(fen);
+    r.set_variant(variant);
+    r.in_place_move_tree(depth, &mut move_count);
+    move_count
+}
+
+/// Count the games of a given length by making and unmaking moves on one Reset
+///
+/// Gives the same counts as `count_possible_variant_games`, which builds a child Reset for
+/// every move instead.
+pub fn count_possible_games_make_unmake(fen: &str, variant: Variant, depth: u8) -> u64 {
+    let mut move_count: u64 = 0;
+    let mut r: Reset = crate::reset::new();
+    let fen = String::from(fen);
+    r.init_from_fen(fen);
+    r.set_variant(variant);
+    r.make_unmake_move_tree(depth, &mut Vec::new(), &mut move_count);
+    move_count
+}
+
 pub fn burn() {
+    // Copy-make, compared with burn_make_unmake 1: 14m44.218s
+    // Copy-make, compared with burn_make_unmake 2: 11m16.766s
+
     // PieceType 1: 6m21.456s
     // PieceType 2: 6m20.028s
     // PieceType 3: 6m19.498s
@@ -75,6 +107,22 @@ pub fn burn() {
 
 }
 
+/// `burn`, making and unmaking moves rather than building children
+pub fn burn_make_unmake() {
+    // First make/unmake version 1: 21m25.677s
+    // One search over a MoveStack for both modes 1: 17m36.283s
+    // Moves generated from the bitboards 1: 17m37.167s
+
+    let fen = String::from("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+    assert_eq!(count_possible_games_make_unmake(&fen,Variant::Standard,7),3195901860,"starting position, ply=7");
+
+    let fen = String::from("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1");
+    assert_eq!(count_possible_games_make_unmake(&fen,Variant::Standard,6),8031647685,"Position 2 - Kiwipete, ply=6");
+
+    let fen = String::from("r3kb1r/p2nqppp/5n2/1B2p1B1/4P3/1Q6/PPP2PPP/R3K2R w KQkq - 1 12");
+    assert_eq!(count_possible_games_make_unmake(&fen,Variant::Standard,6),1547156972,"Morphy-Isouard 1858, ply=6");
+}
+
 impl Reset {
 
 
@@ -90,6 +138,23 @@ impl Reset {
         }
     }
 
+    /// `in_place_move_tree` by make/unmake, with every ply's moves kept on one shared stack
+    pub fn make_unmake_move_tree(&mut self, depth: u8, moves: &mut Vec<Move>, move_count: &mut u64) {
+        if depth == 0 {
+            *move_count += 1;
+            return
+        }
+        let first = moves.len();
+        self.legal_moves(moves);
+        let last = moves.len();
+        for index in first..last {
+            let undo = self.make_move(moves[index]);
+            self.make_unmake_move_tree(depth - 1, moves, move_count);
+            self.unmake_move(&undo);
+        }
+        moves.truncate(first);
+    }
+
 }
 
 #[cfg(test)]
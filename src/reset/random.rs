@@ -0,0 +1,325 @@
+use std::fmt;
+
+use crate::reset;
+use crate::reset::Reset;
+use crate::reset::fen;
+use crate::reset::fen::FenError;
+use crate::utils::rng;
+use crate::utils::rng::Rng;
+
+/// Tries at one position before a generator decides its source can't produce any more
+pub const MAX_ATTEMPTS: u32 = 10000;
+
+/// Where random positions come from
+#[derive(PartialEq,Eq,Clone,Debug)]
+pub enum PositionSource {
+    /// Random legal moves played from a FEN, stopping after a number of plies picked from
+    /// `min_plies..=max_plies`
+    Playout { fen: String, min_plies: u16, max_plies: u16 },
+    /// The pieces of a material signature ("KRPvKR", as `Reset::material_signature` writes
+    /// them) dropped on random squares
+    Placement { signature: String },
+}
+
+/// How generated positions are written out
+#[derive(PartialEq,Eq,Clone,Copy,Debug)]
+pub enum PositionFormat {
+    Fen,
+    Epd,
+}
+
+impl PositionFormat {
+    /// Write a position in this format
+    pub fn write(&self, r: &mut Reset) -> String {
+        match self {
+            PositionFormat::Fen => r.to_fen(),
+            PositionFormat::Epd => r.to_epd(),
+        }
+    }
+}
+
+/// Why a generator couldn't be set up
+#[derive(PartialEq,Eq,Clone,Debug)]
+pub enum RandomPositionError {
+    /// The playout start isn't a legal position
+    Fen(FenError),
+    /// A ply range whose minimum is over its maximum
+    PlyRange(u16, u16),
+    /// A material signature that isn't a king and up to fifteen more pieces (no more than eight
+    /// of them pawns) for each side
+    Signature(String),
+}
+
+impl fmt::Display for RandomPositionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RandomPositionError::Fen(error) => write!(f, "invalid start position: {}", error),
+            RandomPositionError::PlyRange(min, max) => write!(f, "invalid ply range {}-{}", min, max),
+            RandomPositionError::Signature(text) => write!(f, "invalid material signature \"{}\"", text),
+        }
+    }
+}
+
+impl std::error::Error for RandomPositionError {}
+
+/// Produces random legal positions, the same ones for the same source and seed
+///
+/// Every position has the side to move with at least one legal move, and the game not over.
+/// The generator is an iterator, which ends only if `MAX_ATTEMPTS` tries in a row fail, as they
+/// will for material that can't stand on a board legally.
+pub struct PositionGenerator {
+    source: PositionSource,
+    pieces: Vec<char>,
+    rng: Rng,
+}
+
+/// Constructs a new PositionGenerator, checking its source
+///
+/// # Examples
+///
+/// ```
+/// use chessica::reset::random::{self, PositionSource};
+/// let source = PositionSource::Playout { fen: "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1".to_string(), min_plies: 8, max_plies: 12 };
+/// let mut positions = random::new(source, 7).unwrap();
+/// let mut r = positions.next().unwrap();
+/// assert!(r.fullmove_number() >= 5);
+/// let source = PositionSource::Placement { signature: "KQvKR".to_string() };
+/// let mut r = random::new(source, 7).unwrap().next().unwrap();
+/// assert_eq!(r.material_signature(),"KQvKR");
+/// assert!(random::new(PositionSource::Placement { signature: "KQvQ".to_string() }, 7).is_err());
+/// ```
+pub fn new(source: PositionSource, seed: u64) -> Result<PositionGenerator, RandomPositionError> {
+    let mut pieces = Vec::new();
+    match &source {
+        PositionSource::Playout { fen, min_plies, max_plies } => {
+            fen::from_fen(fen).map_err(RandomPositionError::Fen)?;
+            if min_plies > max_plies {
+                return Err(RandomPositionError::PlyRange(*min_plies, *max_plies));
+            }
+        },
+        PositionSource::Placement { signature } => {
+            pieces = signature_pieces(signature).ok_or_else(|| RandomPositionError::Signature(signature.clone()))?;
+        },
+    }
+    Ok(PositionGenerator {
+        source,
+        pieces,
+        rng: rng::new(seed),
+    })
+}
+
+/// FEN letters of the pieces of a material signature, white's upper case and black's lower
+fn signature_pieces(signature: &str) -> Option<Vec<char>> {
+    let (white, black) = signature.split_once('v')?;
+    let mut pieces = Vec::new();
+    for (side, white) in [(white, true), (black, false)] {
+        let mut letters = side.chars();
+        if letters.next() != Some('K') || side.len() > 16 || side.matches('P').count() > 8 {
+            return None;
+        }
+        for letter in letters {
+            if !"QRBNP".contains(letter) {
+                return None;
+            }
+            pieces.push(if white { letter } else { letter.to_ascii_lowercase() });
+        }
+    }
+    // Kings last, as they fit anywhere the others leave
+    pieces.push('K');
+    pieces.push('k');
+    Some(pieces)
+}
+
+/// Can the side to move make a move?
+fn has_legal_move(r: &mut Reset) -> bool {
+    let mut child = reset::new();
+    let found = r.generate_next_move(&mut child);
+    r.restart_move_generation();
+    found
+}
+
+impl PositionGenerator {
+
+    /// One try at a position from a random playout: `None` if the game ends before the chosen
+    /// number of plies
+    fn playout(&mut self, fen: &str, min_plies: u16, max_plies: u16) -> Option<Reset> {
+        let plies = min_plies as usize + self.rng.below((max_plies - min_plies) as usize + 1);
+        let mut current = fen::from_fen(fen).ok()?;
+        let mut child = reset::new();
+        let mut moves: Vec<Reset> = Vec::new();
+        for _ply in 0..plies {
+            if current.game_over() {
+                return None;
+            }
+            moves.clear();
+            current.restart_move_generation();
+            while current.generate_next_move(&mut child) {
+                moves.push(reset::clone::clone_from(&mut child));
+            }
+            if moves.is_empty() {
+                return None;
+            }
+            current = moves.swap_remove(self.rng.below(moves.len()));
+        }
+        current.restart_move_generation();
+        if current.game_over() || !has_legal_move(&mut current) {
+            return None;
+        }
+        Some(current)
+    }
+
+    /// One try at a position from random placement: `None` unless the position is legal, with
+    /// the side to move not in check (a check could be one no move gives) and able to move
+    fn placement(&mut self) -> Option<Reset> {
+        // Squares count a8 = 0 ... h1 = 63, the order a FEN lists them in
+        let mut squares: [Option<char>; 64] = [None; 64];
+        for piece in self.pieces.iter() {
+            let pawn = piece.eq_ignore_ascii_case(&'p');
+            let empty: Vec<usize> = (0..64).filter(|&square| squares[square].is_none() && (!pawn || (8..56).contains(&square))).collect();
+            if empty.is_empty() {
+                return None;
+            }
+            squares[empty[self.rng.below(empty.len())]] = Some(*piece);
+        }
+        let mut placement = String::new();
+        for rank in squares.chunks(8) {
+            let mut empty_count = 0;
+            for square in rank.iter() {
+                match square {
+                    Some(piece) => {
+                        if empty_count > 0 {
+                            placement.push_str(&empty_count.to_string());
+                            empty_count = 0;
+                        }
+                        placement.push(*piece);
+                    },
+                    None => empty_count += 1,
+                }
+            }
+            if empty_count > 0 {
+                placement.push_str(&empty_count.to_string());
+            }
+            placement.push('/');
+        }
+        placement.pop();
+        let side = if self.rng.below(2) == 0 { 'w' } else { 'b' };
+        let mut r = fen::from_fen(&format!("{} {} - - 0 1",placement,side)).ok()?;
+        if r.in_check() || r.game_over() || !has_legal_move(&mut r) {
+            return None;
+        }
+        Some(r)
+    }
+
+}
+
+impl Iterator for PositionGenerator {
+    type Item = Reset;
+
+    fn next(&mut self) -> Option<Reset> {
+        for _attempt in 0..MAX_ATTEMPTS {
+            let found = match self.source.clone() {
+                PositionSource::Playout { fen, min_plies, max_plies } => self.playout(&fen, min_plies, max_plies),
+                PositionSource::Placement { .. } => self.placement(),
+            };
+            if found.is_some() {
+                return found;
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::reset::fen;
+    use crate::reset::random;
+    use crate::reset::random::PositionFormat;
+    use crate::reset::random::PositionSource;
+    use crate::reset::random::RandomPositionError;
+
+    const START: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+    fn playout(min_plies: u16, max_plies: u16) -> PositionSource {
+        PositionSource::Playout { fen: START.to_string(), min_plies, max_plies }
+    }
+
+    fn placement(signature: &str) -> PositionSource {
+        PositionSource::Placement { signature: signature.to_string() }
+    }
+
+    #[test]
+    fn random_playouts() {
+        let fens: Vec<String> = random::new(playout(6, 20), 3).unwrap().take(50).map(|mut r| r.to_fen()).collect();
+        for fen in fens.iter() {
+            let mut r = fen::from_fen(fen).unwrap();
+            // Plies 6 to 20 end on moves 4 to 11
+            assert!((4..=11).contains(&r.fullmove_number()),"{}",fen);
+            assert!(!r.game_over());
+            assert_eq!(&r.to_fen(),fen);
+        }
+        let mut distinct = fens.clone();
+        distinct.sort();
+        distinct.dedup();
+        assert!(distinct.len() > 40);
+
+        // An exact ply count lands on the same side to move every time
+        for mut r in random::new(playout(5, 5), 4).unwrap().take(20) {
+            assert!(!r.white_to_move(),"{}",r.to_fen());
+            assert_eq!(r.fullmove_number(),3);
+        }
+        assert_eq!(random::new(playout(0, 0), 4).unwrap().next().unwrap().to_fen(),START);
+    }
+
+    #[test]
+    fn random_placements() {
+        for signature in ["KvK", "KQvKR", "KRPvKR", "KPPPPPPPPvKPPPPPPPP", "KQRRBBNNvKQRRBBNN"] {
+            let mut seen = Vec::new();
+            for mut r in random::new(placement(signature), 5).unwrap().take(30) {
+                let fen = r.to_fen();
+                assert_eq!(r.material_signature(),signature,"{}",fen);
+                assert!(!r.in_check(),"{}",fen);
+                assert!(fen::from_fen(&fen).is_ok(),"{}",fen);
+                seen.push(fen);
+            }
+            assert_eq!(seen.len(),30);
+            seen.sort();
+            seen.dedup();
+            assert!(seen.len() > 25,"{}",signature);
+        }
+    }
+
+    #[test]
+    fn random_repeatable() {
+        for source in [playout(10, 30), placement("KBNvK")] {
+            let a: Vec<String> = random::new(source.clone(), 11).unwrap().take(10).map(|mut r| r.to_fen()).collect();
+            let b: Vec<String> = random::new(source.clone(), 11).unwrap().take(10).map(|mut r| r.to_fen()).collect();
+            let c: Vec<String> = random::new(source, 12).unwrap().take(10).map(|mut r| r.to_fen()).collect();
+            assert_eq!(a,b);
+            assert_ne!(a,c);
+        }
+    }
+
+    #[test]
+    fn random_gives_up() {
+        // Fool's mate: every game from here is already over
+        let source = PositionSource::Playout { fen: "rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3".to_string(), min_plies: 0, max_plies: 10 };
+        assert!(random::new(source, 1).unwrap().next().is_none());
+    }
+
+    #[test]
+    fn random_bad_sources() {
+        assert_eq!(random::new(playout(9, 8), 1).err(),Some(RandomPositionError::PlyRange(9, 8)));
+        assert!(matches!(random::new(PositionSource::Playout { fen: "8/8/8 w - - 0 1".to_string(), min_plies: 0, max_plies: 1 }, 1),Err(RandomPositionError::Fen(_))));
+        for signature in ["KQ", "QKvK", "KvKX", "KvKPPPPPPPPP", "KvkQ", "KQQQQQQQQQQQQQQQQvK"] {
+            assert_eq!(random::new(placement(signature), 1).err(),Some(RandomPositionError::Signature(signature.to_string())));
+        }
+    }
+
+    #[test]
+    fn random_formats() {
+        let mut r = fen::from_fen("8/8/4k3/8/8/8/4P3/4K3 b - - 3 40").unwrap();
+        assert_eq!(PositionFormat::Fen.write(&mut r),"8/8/4k3/8/8/8/4P3/4K3 b - - 3 40");
+        assert_eq!(PositionFormat::Epd.write(&mut r),"8/8/4k3/8/8/8/4P3/4K3 b - - hmvc 3; fmvn 40;");
+    }
+
+}
@@ -1,4 +1,5 @@
 use crate::reset::Reset;
+use crate::reset::variant::Variant;
 
 impl Reset {
 
@@ -9,6 +10,49 @@ impl Reset {
     pub fn black_is_safe(&mut self, b_squares: u64) -> bool {
         self.is_safe(b_squares, 0)
     }
+
+    /// Is a side's king safe from capture, by the rules of the variant being played?
+    ///
+    /// Antichess kings are ordinary pieces, never in check.  In Atomic chess a king beside the
+    /// enemy king can't be captured, and a king that has already exploded is in no check (its side
+    /// has lost).
+    pub fn king_is_safe(&mut self, white: bool) -> bool {
+        let b_king = self.b_kings & if white { self.b_white } else { self.b_black() };
+        match self.variant {
+            Variant::Antichess => true,
+            Variant::Atomic => b_king == 0 || self.atomic_squares_are_safe(white, b_king),
+            _ => self.is_safe(b_king, if white { 1 } else { 0 }),
+        }
+    }
+
+    /// Settle king safety in a child once a move has been made into it
+    ///
+    /// Returns `false` if the move left the mover's own king capturable, or blew it up, making it
+    /// illegal.  Otherwise sets `in_check` if the other king is attacked (or, in Racing Kings,
+    /// returns `false`).  Blowing up the enemy king wins an Atomic game outright, whatever else
+    /// the move leaves behind.
+    pub fn settle_king_safety(&mut self, white_moved: bool) -> bool {
+        if self.variant == Variant::Atomic {
+            let b_mover = if white_moved { self.b_white } else { self.b_black() };
+            if self.b_kings & b_mover == 0 {
+                return false;
+            }
+            if self.b_kings & !b_mover == 0 {
+                return true;
+            }
+        }
+        if !self.king_is_safe(white_moved) {
+            return false;
+        }
+        if !self.king_is_safe(!white_moved) {
+            if self.variant == Variant::RacingKings {
+                // Racing Kings forbids giving check
+                return false;
+            }
+            self.in_check = 1;
+        }
+        true
+    }
         
     /// Determine whether whether a set of squares in a Reset is safe from an opponent's attack
     ///
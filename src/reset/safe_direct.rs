@@ -285,7 +285,7 @@ impl Reset {
                 }
             },
             DirectCheckSearchType::LocalStraight => {
-                let b_attackers: u64 = self.b_all & !(self.b_pawns & self.b_knights & self.b_bishops);
+                let b_attackers: u64 = self.b_all & !(self.b_pawns | self.b_knights | self.b_bishops);
                 if self.b_to & b_opponents & b_attackers != 0 {
                     return false;
                 }
@@ -293,23 +293,23 @@ impl Reset {
             DirectCheckSearchType::LocalDiagonal => {
                 if king_color == WHITE {
                     if attack_square > king_square {
-                        let b_attackers: u64 = self.b_all & !(self.b_knights & self.b_rooks);
+                        let b_attackers: u64 = self.b_all & !(self.b_knights | self.b_rooks);
                         if self.b_to & b_opponents & b_attackers != 0 {
                             return false;
                         }
                     } else {
-                        let b_attackers: u64 = self.b_all & !(self.b_pawns & self.b_knights & self.b_rooks);
+                        let b_attackers: u64 = self.b_all & !(self.b_pawns | self.b_knights | self.b_rooks);
                         if self.b_to & b_opponents & b_attackers != 0 {
                             return false;
                         }
                     }
                 } else if attack_square < king_square {
-                    let b_attackers: u64 = self.b_all & !(self.b_knights & self.b_rooks);
+                    let b_attackers: u64 = self.b_all & !(self.b_knights | self.b_rooks);
                     if self.b_to & b_opponents & b_attackers != 0 {
                         return false;
                     }
                 } else {
-                    let b_attackers: u64 = self.b_all & !(self.b_pawns & self.b_knights & self.b_rooks);
+                    let b_attackers: u64 = self.b_all & !(self.b_pawns | self.b_knights | self.b_rooks);
                     if self.b_to & b_opponents & b_attackers != 0 {
                         return false;
                     }
@@ -695,4 +695,29 @@ mod tests {
         assert!(!r.is_safe_from_direct_check(king,attacker,WHITE));
     }
 
+    #[test]
+    fn direct_check_local() {
+        // Only a piece that moves that way checks from the next square
+        for (fen, king, attacker, color, safe) in [
+            ("8/8/8/K7/6Pk/8/8/8 b - - 0 1", "h4", "g4", BLACK, true),
+            ("8/8/8/K7/6Rk/8/8/8 b - - 0 1", "h4", "g4", BLACK, false),
+            ("8/8/8/K7/6Bk/8/8/8 b - - 0 1", "h4", "g4", BLACK, true),
+            ("3Nk3/8/8/8/8/8/8/K7 b - - 0 1", "e8", "d8", BLACK, true),
+            ("4k3/3N4/8/8/8/8/8/K7 b - - 0 1", "e8", "d7", BLACK, true),
+            ("4k3/3B4/8/8/8/8/8/K7 b - - 0 1", "e8", "d7", BLACK, false),
+            ("4k3/3R4/8/8/8/8/8/K7 b - - 0 1", "e8", "d7", BLACK, true),
+            ("4k3/3Q4/8/8/8/8/8/K7 b - - 0 1", "e8", "d7", BLACK, false),
+            ("4k3/3P4/8/8/8/8/8/K7 b - - 0 1", "e8", "d7", BLACK, false),
+            ("4k3/8/8/8/8/8/3p4/4K3 w - - 0 1", "e1", "d2", WHITE, false),
+            ("4k3/8/8/8/8/8/8/3bK3 w - - 0 1", "e1", "d1", WHITE, true),
+            ("4k3/8/8/8/8/8/8/3qK3 w - - 0 1", "e1", "d1", WHITE, false),
+        ] {
+            let mut r = prep_board(fen);
+            let king = utils::convert_square_to_number(king.to_string());
+            r.b_to = utils::convert_square_to_bitstring(attacker.to_string());
+            let attacker = utils::convert_square_to_number(attacker.to_string());
+            assert_eq!(r.is_safe_from_direct_check(king,attacker,color),safe,"{}",fen);
+        }
+    }
+
 }
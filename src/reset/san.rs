@@ -0,0 +1,189 @@
+use crate::reset;
+use crate::reset::Reset;
+use crate::reset::PieceType;
+
+impl Reset {
+
+    /// The legal child reached by a move written in Standard Algebraic Notation (e.g. "Nbd7",
+    /// "exd6", "e8=Q+", "O-O-O", or a Crazyhouse drop such as "N@f3"), or `None` if the move is malformed, illegal or ambiguous
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut r = chessica::reset::new();
+    /// r.init_from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1".to_string());
+    /// let mut child = r.child_from_san("Nf3").unwrap();
+    /// assert_eq!(child.uci_move_text(),"g1f3");
+    /// assert!(r.child_from_san("Nd2").is_none());
+    /// ```
+    pub fn child_from_san(&mut self, san: &str) -> Option<Reset> {
+        let text = san.trim_end_matches(['+', '#', '!', '?']);
+        let castle = match text {
+            "O-O" | "0-0" => Some(true),
+            "O-O-O" | "0-0-0" => Some(false),
+            _ => None,
+        };
+        if let Some((letter, square)) = text.split_once('@') {
+            return self.child_from_drop_text(&format!("{}@{}", if letter.is_empty() { "P" } else { letter }, square));
+        }
+
+        let mut chars: Vec<char> = text.chars().collect();
+        let mut piece = PieceType::Pawn;
+        let mut promotion = PieceType::Unknown;
+        if castle.is_some() {
+            piece = PieceType::King;
+        } else {
+            if let Some(first) = chars.first() {
+                if let Some(found) = san_piece(*first) {
+                    piece = found;
+                    chars.remove(0);
+                }
+            }
+            // Promotion, written "e8=Q" or "e8Q"
+            if piece == PieceType::Pawn && chars.len() > 2 {
+                if let Some(found) = san_piece(chars[chars.len() - 1]) {
+                    promotion = found;
+                    chars.pop();
+                    if chars.last() == Some(&'=') {
+                        chars.pop();
+                    }
+                }
+            }
+            chars.retain(|c| *c != 'x' && *c != ':' && *c != '-');
+            if chars.len() < 2 {
+                return None;
+            }
+        }
+        let destination: String = if castle.is_some() { String::new() } else { chars[chars.len() - 2..].iter().collect() };
+        let qualifiers: Vec<char> = if castle.is_some() { Vec::new() } else { chars[..chars.len() - 2].to_vec() };
+
+        let mut found: Option<Reset> = None;
+        self.restart_move_generation();
+        let mut child = reset::new();
+        while self.generate_next_move(&mut child) {
+            if self.piece_type_at(child.b_from) != piece {
+                continue;
+            }
+            let move_text = child.move_text();
+            let (from_text, to_text) = move_text.split_at(2);
+            let matched = match castle {
+                Some(kingside) => child.king_castled != 0 && (to_text.as_bytes()[0] == b'g') == kingside,
+                None => {
+                    let promoted = if child.promotion == 0 { PieceType::Unknown } else { child.promotion_piece };
+                    to_text == destination
+                        && promoted == promotion
+                        && qualifiers.iter().all(|c| from_text.contains(*c))
+                },
+            };
+            if matched {
+                if found.is_some() {
+                    return None;
+                }
+                found = Some(reset::clone::clone_from(&mut child));
+            }
+        }
+        found
+    }
+
+    /// The legal child reached by a move in UCI long algebraic notation (e.g. "e2e4", "e7e8q" or
+    /// "N@f3"), or `None` if there is no such legal move
+    ///
+    /// Castling may be written as the king's move ("e1g1") or as the king taking its rook ("e1h1"),
+    /// whether or not `UCI_Chess960` is on.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut r = chessica::reset::new();
+    /// r.init_from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1".to_string());
+    /// let mut child = r.child_from_uci("e1g1").unwrap();
+    /// assert_eq!(child.to_fen(),"r3k2r/8/8/8/8/8/8/R4RK1 b kq - 1 1");
+    /// assert!(r.child_from_uci("e1h1").is_some());
+    /// assert!(r.child_from_uci("e1e3").is_none());
+    /// ```
+    pub fn child_from_uci(&mut self, text: &str) -> Option<Reset> {
+        self.restart_move_generation();
+        let mut child = reset::new();
+        let mut found = None;
+        while self.generate_next_move(&mut child) {
+            if child.long_algebraic_text() == text || child.chess960_move_text() == text {
+                found = Some(reset::clone::clone_from(&mut child));
+                break;
+            }
+        }
+        self.restart_move_generation();
+        found
+    }
+
+    /// The legal child reached by dropping a piece, written as `drop_text` writes it ("N@f3")
+    fn child_from_drop_text(&mut self, drop_text: &str) -> Option<Reset> {
+        self.restart_move_generation();
+        let mut child = reset::new();
+        while self.generate_next_move(&mut child) {
+            if child.is_drop() && child.drop_text() == drop_text {
+                return Some(reset::clone::clone_from(&mut child));
+            }
+        }
+        None
+    }
+
+}
+
+/// Piece named by a SAN piece letter
+fn san_piece(letter: char) -> Option<PieceType> {
+    match letter {
+        'N' => Some(PieceType::Knight),
+        'B' => Some(PieceType::Bishop),
+        'R' => Some(PieceType::Rook),
+        'Q' => Some(PieceType::Queen),
+        'K' => Some(PieceType::King),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::reset;
+
+    fn san_to_uci(fen: &str, san: &str) -> Option<String> {
+        let mut r = reset::new();
+        r.init_from_fen(fen.to_string());
+        r.child_from_san(san).map(|mut child| child.uci_move_text())
+    }
+
+    #[test]
+    fn san_simple_moves() {
+        let fen = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+        assert_eq!(san_to_uci(fen,"e4"),Some("e2e4".to_string()));
+        assert_eq!(san_to_uci(fen,"e3"),Some("e2e3".to_string()));
+        assert_eq!(san_to_uci(fen,"Nc3!?"),Some("b1c3".to_string()));
+        assert_eq!(san_to_uci(fen,"e5"),None);
+        assert_eq!(san_to_uci(fen,"Be2"),None);
+        assert_eq!(san_to_uci(fen,"x"),None);
+    }
+
+    #[test]
+    fn san_captures_and_disambiguation() {
+        // Knights on b1 and f3 can both reach d2; rooks on a1 and a5 can both reach a3
+        let fen = "4k3/8/8/R2pP3/8/5N2/8/RN2K3 w - d6 0 1";
+        assert_eq!(san_to_uci(fen,"Nd2"),None);
+        assert_eq!(san_to_uci(fen,"Nbd2"),Some("b1d2".to_string()));
+        assert_eq!(san_to_uci(fen,"Nfd2"),Some("f3d2".to_string()));
+        assert_eq!(san_to_uci(fen,"R1a3"),Some("a1a3".to_string()));
+        assert_eq!(san_to_uci(fen,"R5a3"),Some("a5a3".to_string()));
+        assert_eq!(san_to_uci(fen,"exd6"),Some("e5d6".to_string()));
+        assert_eq!(san_to_uci(fen,"Rxd5"),Some("a5d5".to_string()));
+    }
+
+    #[test]
+    fn san_castling_and_promotion() {
+        let fen = "r3k2r/1P6/8/8/8/8/8/R3K2R w KQkq - 0 1";
+        assert_eq!(san_to_uci(fen,"O-O"),Some("e1g1".to_string()));
+        assert_eq!(san_to_uci(fen,"O-O-O+"),Some("e1c1".to_string()));
+        assert_eq!(san_to_uci(fen,"0-0"),Some("e1g1".to_string()));
+        assert_eq!(san_to_uci(fen,"bxa8=Q+"),Some("b7a8q".to_string()));
+        assert_eq!(san_to_uci(fen,"bxa8N"),Some("b7a8n".to_string()));
+        assert_eq!(san_to_uci(fen,"b8=R"),Some("b7b8r".to_string()));
+        assert_eq!(san_to_uci(fen,"b8"),None);
+    }
+}
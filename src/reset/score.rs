@@ -1,36 +1,147 @@
 use crate::reset::Reset;
+use crate::reset::PieceType;
+use crate::reset::r#const::SCORE_PAWN;
 use crate::reset::r#const::SCORE_STALEMATE;
 use crate::reset::r#const::SCORE_BLACK_CHECKMATE;
 use crate::reset::r#const::SCORE_WHITE_CHECKMATE;
+use crate::eval;
+use crate::eval::EvalParams;
+use crate::nnue;
+use crate::nnue::accumulator;
+use crate::nnue::accumulator::Accumulator;
+use crate::reset::variant::Variant;
 
 impl Reset {
 
     pub fn score(&mut self) -> i32 {
+        self.score_with(None)
+    }
+
+    /// Score the position, using the NNUE network when one is loaded
+    ///
+    /// The accumulator, when the caller has kept one up to date, saves building it again; it
+    /// also fixes the network used.  Without one the shared network is used, if any.
+    pub fn score_with(&mut self, accumulator: Option<&Accumulator>) -> i32 {
         let mut child = crate::reset::new();
         let mut clone = crate::reset::clone::clone_from(self);
         clone.initialize_move_generation();
         clone.complete_move_initialization();
         if !clone.generate_next_move(&mut child) {
-            if clone.in_check() {
-                if clone.white_to_move() {
-                    //println!("Found Black Checkmate???");
-                    self.score = SCORE_BLACK_CHECKMATE;
-                } else {
-                    //println!("Found White Checkmate???");
-                    self.score = SCORE_WHITE_CHECKMATE;
-                }
-            } else {
-                //println!("Found Stalemate???");
-                self.score = SCORE_STALEMATE;
+            self.score = clone.terminal_score();
+            return self.score;
+        }
+        let params = eval::params();
+        if self.variant.has_capture_rules() {
+            // Endgame knowledge and the network only know standard chess, and Antichess turns
+            // material on its head
+            self.score = self.material_with(&params);
+            if self.variant == Variant::Antichess {
+                self.score = -self.score;
             }
+            self.score += self.eval_noise();
+            return self.score;
+        }
+        if self.variant.has_win_conditions() {
+            // Material, plus how close each side is to the variant's goal
+            self.score = self.material_with(&params) + self.goal_progress() + self.eval_noise();
+            return self.score;
+        }
+        if let Some(score) = crate::endgame::evaluate_with(self, &params) {
+            self.score = score;
+            return self.score;
+        }
+        if let Some(accumulator) = accumulator {
+            self.score = accumulator.network.evaluate(self, accumulator);
             return self.score;
         }
-        self.score = self.material as i32 * 1000000;
-        let randomfactor: i32 = (self.b_all % 1997) as i32 - 998;
-        self.score += randomfactor;
+        if let Some(network) = nnue::network() {
+            self.score = network.evaluate(self, &accumulator::refresh(&network, self));
+            return self.score;
+        }
+        self.score = self.material_with(&params);
+        self.score += self.eval_noise();
         self.score
     }
 
+    /// Score of a position in which the side to move has no moves, from white's point of view
+    ///
+    /// Checkmate or stalemate, but for the variants: an Antichess side that can't move (having
+    /// lost all its pieces, or been stalemated) has won, an Atomic side whose king has exploded
+    /// has lost, and a game ended by a variant's goal scores as `goal_score` has it.
+    pub fn terminal_score(&self) -> i32 {
+        if let Some(score) = self.goal_score() {
+            return score;
+        }
+        let white = self.white_to_move();
+        let loses = match self.variant {
+            Variant::Antichess => false,
+            Variant::Atomic if self.b_kings & if white { self.b_white } else { self.b_black() } == 0 => true,
+            _ => self.in_check(),
+        };
+        if loses {
+            if white { SCORE_BLACK_CHECKMATE } else { SCORE_WHITE_CHECKMATE }
+        } else if self.variant == Variant::Antichess {
+            if white { SCORE_WHITE_CHECKMATE } else { SCORE_BLACK_CHECKMATE }
+        } else {
+            SCORE_STALEMATE
+        }
+    }
+
+    /// Static evaluation with the given parameters, from white's point of view
+    ///
+    /// Endgame knowledge first, then material.  Mates and stalemates are left to `score`.
+    pub fn evaluate_with(&self, params: &EvalParams) -> i32 {
+        match crate::endgame::evaluate_with(self, params) {
+            Some(score) => score,
+            None => self.material_with(params),
+        }
+    }
+
+    /// Tie-breaking noise added to the material scores of this position (see `eval::noise`)
+    pub fn eval_noise(&self) -> i32 {
+        eval::noise(self.b_all)
+    }
+
+    /// Material balance valued with the given parameters, from white's point of view
+    pub fn material_with(&self, params: &EvalParams) -> i32 {
+        let balance = |piece_type: PieceType| self.count_of(piece_type, true) as i32 - self.count_of(piece_type, false) as i32;
+        let centipawns = balance(PieceType::Pawn) * params.pawn
+            + balance(PieceType::Knight) * params.knight
+            + balance(PieceType::Bishop) * params.bishop
+            + balance(PieceType::Rook) * params.rook
+            + balance(PieceType::Queen) * params.queen
+            + self.pocket_balance(params);
+        centipawns * (SCORE_PAWN / 100)
+    }
+
+    /// Crazyhouse pocket balance valued with the given parameters, in centipawns
+    fn pocket_balance(&self, params: &EvalParams) -> i32 {
+        if self.pockets == 0 {
+            return 0;
+        }
+        let balance = |piece_type: PieceType| self.pocket_count(true, piece_type) as i32 - self.pocket_count(false, piece_type) as i32;
+        balance(PieceType::Pawn) * params.pawn
+            + balance(PieceType::Knight) * params.knight
+            + balance(PieceType::Bishop) * params.bishop
+            + balance(PieceType::Rook) * params.rook
+            + balance(PieceType::Queen) * params.queen
+    }
+
+    /// Number of one side's pieces of a type
+    pub fn count_of(&self, piece_type: PieceType, white: bool) -> u32 {
+        let b_pieces = match piece_type {
+            PieceType::Pawn => self.b_pawns,
+            PieceType::Knight => self.b_knights,
+            PieceType::Bishop => self.b_bishops,
+            PieceType::Rook => self.b_rooks,
+            PieceType::Queen => self.b_queens(),
+            PieceType::King => self.b_kings,
+            PieceType::Unknown => 0,
+        };
+        let b_side = if white { self.b_white } else { self.b_black() };
+        (b_pieces & b_side).count_ones()
+    }
+
 }
 
 
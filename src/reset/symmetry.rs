@@ -0,0 +1,225 @@
+use crate::reset::Reset;
+use crate::reset::variant::Variant;
+use crate::bitops;
+
+/// A way of turning a position into an equivalent one
+///
+/// | symmetry    | applies to                                    | score   |
+/// | ----------- | --------------------------------------------- | ------- |
+/// | ColorFlip   | anything but Racing Kings                     | negated |
+/// | MirrorFiles | positions without castling rights             | same    |
+/// | Rotate90    | pawnless positions without castling rights    | same    |
+/// | Rotate180   | pawnless positions without castling rights    | same    |
+/// | Rotate270   | pawnless positions without castling rights    | same    |
+///
+/// ColorFlip mirrors the ranks and swaps the colours, so white's pieces, rights, pocket and
+/// checks become black's and black is to move if white was.  Rotations are clockwise, as seen
+/// from white's side of the board, and don't apply to Crazyhouse, where pawns may be dropped, or
+/// Racing Kings, where the kings race for the eighth rank.
+#[derive(PartialEq,Eq,Copy,Clone,Debug)]
+pub enum Symmetry {
+    ColorFlip,
+    MirrorFiles,
+    Rotate90,
+    Rotate180,
+    Rotate270,
+}
+
+/// Every symmetry, for looping over
+pub const SYMMETRIES: [Symmetry; 5] = [
+    Symmetry::ColorFlip,
+    Symmetry::MirrorFiles,
+    Symmetry::Rotate90,
+    Symmetry::Rotate180,
+    Symmetry::Rotate270,
+];
+
+impl Symmetry {
+
+    /// Where a square number (h1 = 1 ... a8 = 64) goes
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chessica::reset::symmetry::Symmetry;
+    /// // a1 goes to a8, h1 and a8
+    /// assert_eq!(Symmetry::ColorFlip.square(8),64);
+    /// assert_eq!(Symmetry::MirrorFiles.square(8),1);
+    /// assert_eq!(Symmetry::Rotate90.square(8),64);
+    /// ```
+    pub fn square(&self, bi_square: u8) -> u8 {
+        let index = bi_square - 1;
+        // Files count from h (0) to a (7), ranks from 1 (0) to 8 (7)
+        let (file, rank) = (index % 8, index / 8);
+        let index = match self {
+            Symmetry::ColorFlip => index ^ 56,
+            Symmetry::MirrorFiles => index ^ 7,
+            Symmetry::Rotate90 => file * 8 + (7 - rank),
+            Symmetry::Rotate180 => index ^ 63,
+            Symmetry::Rotate270 => (7 - file) * 8 + rank,
+        };
+        index + 1
+    }
+
+    /// Where every square of a bitstring goes
+    pub fn bitstring(&self, b_squares: u64) -> u64 {
+        let mut b_result = 0;
+        let mut b_remaining = b_squares;
+        while b_remaining != 0 {
+            let b_square = bitops::lowest_bit(b_remaining);
+            b_remaining &= !b_square;
+            b_result |= 1 << (self.square(bitops::get_bit_number(b_square)) - 1);
+        }
+        b_result
+    }
+
+    /// Does this symmetry turn the position into one played by the same rules?
+    pub fn applies_to(&self, reset: &Reset) -> bool {
+        match self {
+            Symmetry::ColorFlip => reset.variant != Variant::RacingKings,
+            Symmetry::MirrorFiles => reset.castle_bits == 0,
+            _ => reset.castle_bits == 0 && reset.b_pawns == 0 &&
+                reset.variant != Variant::Crazyhouse && reset.variant != Variant::RacingKings,
+        }
+    }
+
+}
+
+impl Reset {
+
+    /// The position this one becomes under a symmetry, or `None` if the symmetry doesn't apply
+    ///
+    /// Perft counts are the same for both positions, and so is the score, but for ColorFlip,
+    /// which negates it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chessica::reset::symmetry::Symmetry;
+    /// let mut r = chessica::reset::new();
+    /// r.init_from_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1".to_string());
+    /// let mut flipped = r.transformed(Symmetry::ColorFlip).unwrap();
+    /// assert_eq!(flipped.to_fen(),"rnbqkbnr/pppp1ppp/8/4p3/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+    /// assert!(r.transformed(Symmetry::MirrorFiles).is_none());
+    /// ```
+    pub fn transformed(&self, symmetry: Symmetry) -> Option<Reset> {
+        if !symmetry.applies_to(self) {
+            return None;
+        }
+        let mut r = crate::reset::new();
+        let map = |b_squares: u64| symmetry.bitstring(b_squares);
+        r.variant = self.variant;
+        r.b_all = map(self.b_all);
+        r.b_pawns = map(self.b_pawns);
+        r.b_knights = map(self.b_knights);
+        r.b_bishops = map(self.b_bishops);
+        r.b_rooks = map(self.b_rooks);
+        r.b_kings = map(self.b_kings);
+        r.b_promoted = map(self.b_promoted);
+        r.b_en_passant = map(self.b_en_passant);
+        r.halfmove_clock = self.halfmove_clock;
+        r.fullmove_number = self.fullmove_number;
+        if symmetry == Symmetry::ColorFlip {
+            r.b_white = map(self.b_black());
+            r.to_move = self.to_move ^ 1;
+            r.material = -self.material;
+            r.set_checks_given(self.checks_given(false), self.checks_given(true));
+            // Each side's pocket counts are a run of bits, white's in the low half
+            let half = crate::reset::pocket::POCKET_SIDE_BITS;
+            r.pockets = ((self.pockets & ((1 << half) - 1)) << half) | (self.pockets >> half);
+            // Castle bits 1 and 2 are white's, 3 and 4 black's
+            r.castle_bits = ((self.castle_bits & 0x03) << 2) | ((self.castle_bits >> 2) & 0x03);
+            r.bi_castle_rook_wk = symmetry.square(self.bi_castle_rook_bk);
+            r.bi_castle_rook_wq = symmetry.square(self.bi_castle_rook_bq);
+            r.bi_castle_rook_bk = symmetry.square(self.bi_castle_rook_wk);
+            r.bi_castle_rook_bq = symmetry.square(self.bi_castle_rook_wq);
+        } else {
+            r.b_white = map(self.b_white);
+            r.to_move = self.to_move;
+            r.material = self.material;
+            r.set_checks_given(self.checks_given(true), self.checks_given(false));
+            r.pockets = self.pockets;
+        }
+        r.white_king_square = bitops::get_bit_number(r.b_white & r.b_kings);
+        r.black_king_square = bitops::get_bit_number(r.b_black() & r.b_kings);
+        r.complete_setup();
+        Some(r)
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::reset;
+    use crate::reset::Reset;
+    use crate::reset::symmetry::Symmetry;
+    use crate::reset::symmetry::SYMMETRIES;
+    use crate::reset::variant::Variant;
+
+    fn prep_board(fen: &str, variant: Variant) -> Reset {
+        let mut r = reset::new();
+        r.init_from_fen(fen.to_string());
+        r.set_variant(variant);
+        r
+    }
+
+    fn transformed_fen(fen: &str, variant: Variant, symmetry: Symmetry) -> Option<String> {
+        prep_board(fen, variant).transformed(symmetry).map(|mut r| r.to_fen())
+    }
+
+    #[test]
+    fn symmetry_squares() {
+        for symmetry in SYMMETRIES {
+            let mut squares: Vec<u8> = (1..=64).map(|bi| symmetry.square(bi)).collect();
+            squares.sort_unstable();
+            assert_eq!(squares,(1..=64).collect::<Vec<u8>>(),"{:?} is a permutation",symmetry);
+        }
+        // Four quarter turns, or two flips, are no turn at all
+        for bi in 1..=64 {
+            let quarter = Symmetry::Rotate90;
+            assert_eq!(quarter.square(quarter.square(quarter.square(quarter.square(bi)))),bi);
+            assert_eq!(Symmetry::Rotate180.square(bi),quarter.square(quarter.square(bi)));
+            assert_eq!(Symmetry::Rotate270.square(bi),quarter.square(Symmetry::Rotate180.square(bi)));
+            assert_eq!(Symmetry::ColorFlip.square(Symmetry::ColorFlip.square(bi)),bi);
+            assert_eq!(Symmetry::MirrorFiles.square(Symmetry::MirrorFiles.square(bi)),bi);
+        }
+        // e2 goes to e7, d2, b4, d7 and g5
+        assert_eq!(Symmetry::ColorFlip.square(12),52);
+        assert_eq!(Symmetry::MirrorFiles.square(12),13);
+        assert_eq!(Symmetry::Rotate90.square(12),31);
+        assert_eq!(Symmetry::Rotate180.square(12),53);
+        assert_eq!(Symmetry::Rotate270.square(12),34);
+    }
+
+    #[test]
+    fn symmetry_color_flip() {
+        assert_eq!(transformed_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1", Variant::Standard, Symmetry::ColorFlip),
+            Some(String::from("r3k2r/pppbbppp/2n2q1P/1P2p3/3pn3/BN2PNP1/P1PPQPB1/R3K2R b KQkq - 0 1")));
+        assert_eq!(transformed_fen("rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3", Variant::Standard, Symmetry::ColorFlip),
+            Some(String::from("rnbqkbnr/pppp1ppp/8/8/3PpP2/8/PPP1P1PP/RNBQKBNR b KQkq f3 0 3")));
+        assert_eq!(transformed_fen("1r2k1r1/8/8/8/8/8/8/R1R1K1R1 w Cq - 0 1", Variant::Standard, Symmetry::ColorFlip),
+            Some(String::from("r1r1k1r1/8/8/8/8/8/8/1R2K1R1 b Qc - 0 1")));
+        assert_eq!(transformed_fen("r3k3/8/8/8/8/8/8/4K3[Qnn] b q - 0 1", Variant::Crazyhouse, Symmetry::ColorFlip),
+            Some(String::from("4k3/8/8/8/8/8/8/R3K3[NNq] w Q - 0 1")));
+        assert_eq!(transformed_fen("4k3/8/8/8/8/8/8/R3K3 w - - 0 1 +2+0", Variant::ThreeCheck, Symmetry::ColorFlip),
+            Some(String::from("r3k3/8/8/8/8/8/8/4K3 b - - 0 1 +0+2")));
+        assert_eq!(transformed_fen("8/8/8/8/8/8/krbnNBRK/qrbnNBRQ w - - 0 1", Variant::RacingKings, Symmetry::ColorFlip),None);
+    }
+
+    #[test]
+    fn symmetry_mirror_and_rotate() {
+        assert_eq!(transformed_fen("8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1", Variant::Standard, Symmetry::MirrorFiles),
+            Some(String::from("8/5p2/4p3/r5PK/k1p3R1/8/1P1P4/8 w - - 0 1")));
+        assert_eq!(transformed_fen("8/8/8/8/8/8/krbnNBRK/qrbnNBRQ w - - 0 1", Variant::RacingKings, Symmetry::MirrorFiles),
+            Some(String::from("8/8/8/8/8/8/KRBNnbrk/QRBNnbrq w - - 0 1")));
+        assert_eq!(transformed_fen("8/kr6/r7/8/8/8/6RK/7R w - - 0 1", Variant::Standard, Symmetry::Rotate90),
+            Some(String::from("5rk1/6r1/8/8/8/8/1R6/RK6 w - - 0 1")));
+        assert_eq!(transformed_fen("8/kr6/r7/8/8/8/6RK/7R w - - 0 1", Variant::Standard, Symmetry::Rotate180),
+            Some(String::from("R7/KR6/8/8/8/7r/6rk/8 w - - 0 1")));
+        assert_eq!(transformed_fen("8/kr6/r7/8/8/8/6RK/7R w - - 0 1", Variant::Standard, Symmetry::Rotate270),
+            Some(String::from("6KR/6R1/8/8/8/8/1r6/1kr5 w - - 0 1")));
+        assert_eq!(transformed_fen("4k3/8/8/8/8/8/8/R3K3 w Q - 0 1", Variant::Standard, Symmetry::MirrorFiles),None);
+        assert_eq!(transformed_fen("4k3/p7/8/8/8/8/8/4K3 w - - 0 1", Variant::Standard, Symmetry::Rotate90),None);
+        assert_eq!(transformed_fen("4k3/8/8/8/8/8/8/4K3[N] w - - 0 1", Variant::Crazyhouse, Symmetry::Rotate180),None);
+    }
+}
@@ -0,0 +1,116 @@
+use crate::reset::Reset;
+use crate::reset::PieceType;
+
+/// Piece letters in the order Syzygy file names list them
+const SIGNATURE_PIECES: [(PieceType, char); 6] = [
+    (PieceType::King, 'K'),
+    (PieceType::Queen, 'Q'),
+    (PieceType::Rook, 'R'),
+    (PieceType::Bishop, 'B'),
+    (PieceType::Knight, 'N'),
+    (PieceType::Pawn, 'P'),
+];
+
+impl Reset {
+
+    /// Number of pieces (kings and pawns included) on the board
+    pub fn piece_count(&self) -> u32 {
+        self.b_all.count_ones()
+    }
+
+    /// Did the move that produced this Reset capture something (en passant included)?
+    pub fn is_capture(&self) -> bool {
+        self.capture != 0
+    }
+
+    /// Does either side still have a castling right?
+    pub fn any_castle(&self) -> bool {
+        self.white_castle_k() || self.white_castle_q() || self.black_castle_k() || self.black_castle_q()
+    }
+
+    /// Material signature in Syzygy file name style: white's pieces, "v", black's pieces, each
+    /// listed king first and pawns last
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut r = chessica::reset::new();
+    /// r.init_from_fen("8/8/4k3/8/2n5/8/1P2K3/5R2 w - - 0 1".to_string());
+    /// assert_eq!(r.material_signature(),"KRPvKN");
+    /// ```
+    pub fn material_signature(&self) -> String {
+        let mut signature = String::new();
+        for b_side in [self.b_white, self.b_black()].iter() {
+            if !signature.is_empty() {
+                signature.push('v');
+            }
+            for (piece, letter) in SIGNATURE_PIECES.iter() {
+                let b_pieces = match piece {
+                    PieceType::King => self.b_kings,
+                    PieceType::Queen => self.b_queens(),
+                    PieceType::Rook => self.b_rooks,
+                    PieceType::Bishop => self.b_bishops,
+                    PieceType::Knight => self.b_knights,
+                    _ => self.b_pawns,
+                };
+                for _ in 0..(b_pieces & b_side).count_ones() {
+                    signature.push(*letter);
+                }
+            }
+        }
+        signature
+    }
+
+    /// Every piece on the board as (square, piece code) pairs, in ascending square order
+    ///
+    /// Squares count a1 = 0, b1 = 1 ... h8 = 63.  Piece codes are the ones used inside Syzygy
+    /// files: 1-6 for a white pawn, knight, bishop, rook, queen and king, 9-14 for black.
+    pub fn tablebase_pieces(&self) -> Vec<(usize, u8)> {
+        (0..64).filter_map(|square| self.tablebase_code(square).map(|code| (square, code))).collect()
+    }
+
+    /// Piece code (as in `tablebase_pieces`) of whatever stands on a square, counting a1 = 0
+    pub fn tablebase_code(&self, square: usize) -> Option<u8> {
+        let b_square: u64 = 1 << (square / 8 * 8 + 7 - square % 8);
+        let code = match self.piece_type_at(b_square) {
+            PieceType::Pawn => 1,
+            PieceType::Knight => 2,
+            PieceType::Bishop => 3,
+            PieceType::Rook => 4,
+            PieceType::Queen => 5,
+            PieceType::King => 6,
+            PieceType::Unknown => return None,
+        };
+        Some(if b_square & self.b_white != 0 { code } else { code | 8 })
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::reset;
+
+    #[test]
+    fn syzygy_position_helpers() {
+        let mut r = reset::new();
+        r.init_from_fen("7R/8/8/3k4/8/8/8/4K2q w - - 0 1".to_string());
+        assert_eq!(r.piece_count(),4);
+        assert!(!r.any_castle());
+        assert_eq!(r.material_signature(),"KRvKQ");
+        // e1 king, h1 queen, d5 king, h8 rook
+        assert_eq!(r.tablebase_pieces(),vec![(4,6),(7,13),(35,14),(63,4)]);
+
+        let mut child = reset::new();
+        let mut captures = 0;
+        while r.generate_next_move(&mut child) {
+            if child.is_capture() {
+                captures += 1;
+            }
+        }
+        // Rxh1 is the only capture
+        assert_eq!(captures,1);
+
+        r.init_from_fen("8/8/8/3k4/8/8/8/R3K3 w Q - 0 1".to_string());
+        assert!(r.any_castle());
+    }
+}
@@ -0,0 +1,144 @@
+use crate::reset::Reset;
+
+/// Rules the game is played by
+#[derive(PartialEq,Eq,Copy,Clone,Hash,Debug)]
+pub enum Variant {
+    Standard,
+    Crazyhouse,
+    Atomic,
+    Antichess,
+    KingOfTheHill,
+    ThreeCheck,
+    RacingKings,
+}
+
+/// Every variant, in the order the protocols list them
+pub const VARIANTS: [Variant; 7] = [
+    Variant::Standard,
+    Variant::Crazyhouse,
+    Variant::Atomic,
+    Variant::Antichess,
+    Variant::KingOfTheHill,
+    Variant::ThreeCheck,
+    Variant::RacingKings,
+];
+
+impl Variant {
+
+    /// Name of the variant as UCI's `UCI_Variant` option spells it
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chessica::reset::variant::Variant;
+    /// assert_eq!(Variant::Crazyhouse.name(),"crazyhouse");
+    /// ```
+    pub fn name(&self) -> &'static str {
+        match self {
+            Variant::Standard => "chess",
+            Variant::Crazyhouse => "crazyhouse",
+            Variant::Atomic => "atomic",
+            Variant::Antichess => "antichess",
+            Variant::KingOfTheHill => "kingofthehill",
+            Variant::ThreeCheck => "3check",
+            Variant::RacingKings => "racingkings",
+        }
+    }
+
+    /// Name of the variant as CECP's `variant` command spells it
+    pub fn cecp_name(&self) -> &'static str {
+        match self {
+            Variant::Standard => "normal",
+            Variant::Antichess => "giveaway",
+            _ => self.name(),
+        }
+    }
+
+    /// The variant with a UCI or CECP name
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chessica::reset::variant::Variant;
+    /// assert_eq!(Variant::from_name("normal"),Some(Variant::Standard));
+    /// assert_eq!(Variant::from_name("crazyhouse"),Some(Variant::Crazyhouse));
+    /// assert_eq!(Variant::from_name("giveaway"),Some(Variant::Antichess));
+    /// assert_eq!(Variant::from_name("bughouse"),None);
+    /// ```
+    pub fn from_name(name: &str) -> Option<Variant> {
+        match name {
+            "standard" => Some(Variant::Standard),
+            _ => VARIANTS.iter().copied().find(|variant| variant.name() == name || variant.cecp_name() == name),
+        }
+    }
+
+    /// Do captures follow rules of their own in this variant?
+    ///
+    /// Atomic captures explode and Antichess has no check, so neither can use the pin-based
+    /// shortcuts the move generator takes to tell legal moves from illegal ones.
+    pub fn has_capture_rules(&self) -> bool {
+        matches!(self, Variant::Atomic | Variant::Antichess)
+    }
+
+    /// Must every move be checked for legality in full, rather than with the pin-based shortcuts?
+    ///
+    /// True of the variants with capture rules, and of Racing Kings, where a move that gives
+    /// check is illegal.
+    pub fn needs_full_legality_check(&self) -> bool {
+        self.has_capture_rules() || *self == Variant::RacingKings
+    }
+
+    /// Can this variant's game end other than by checkmate or stalemate, with moves still left?
+    ///
+    /// A king reaching the centre (King of the Hill) or the eighth rank (Racing Kings), or a
+    /// third check (Three-check), ends the game.
+    pub fn has_win_conditions(&self) -> bool {
+        matches!(self, Variant::KingOfTheHill | Variant::ThreeCheck | Variant::RacingKings)
+    }
+
+}
+
+impl Reset {
+
+    /// Rules this game is played by
+    pub fn variant(&self) -> Variant {
+        self.variant
+    }
+
+    /// Play this game by another variant's rules
+    ///
+    /// Children inherit the variant, so this is set on the root of a game.  Move generation
+    /// starts over, as the moves available may differ, and the game may already be over.
+    pub fn set_variant(&mut self, variant: Variant) {
+        self.variant = variant;
+        self.update_game_over();
+        self.restart_move_generation();
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::reset;
+    use crate::reset::variant::Variant;
+    use crate::reset::variant::VARIANTS;
+
+    #[test]
+    fn variant_names_round_trip() {
+        for variant in VARIANTS {
+            assert_eq!(Variant::from_name(variant.name()),Some(variant));
+            assert_eq!(Variant::from_name(variant.cecp_name()),Some(variant));
+        }
+    }
+
+    #[test]
+    fn variant_inherited_by_children() {
+        let mut r = reset::new();
+        r.init_from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1".to_string());
+        assert_eq!(r.variant(),Variant::Standard);
+        r.set_variant(Variant::Crazyhouse);
+        let mut child = reset::new();
+        assert!(r.generate_next_move(&mut child));
+        assert_eq!(child.variant(),Variant::Crazyhouse);
+    }
+}
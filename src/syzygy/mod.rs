@@ -0,0 +1,218 @@
+pub mod table;
+pub mod probe;
+
+use crate::syzygy::table::Table;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+
+/// Win/draw/loss for the side to move, as stored in Syzygy WDL tables
+///
+/// Cursed wins and blessed losses are wins and losses that the fifty-move rule turns into draws.
+#[derive(PartialEq,Eq,PartialOrd,Ord,Copy,Clone,Hash,Debug)]
+pub enum Wdl {
+    Loss,
+    BlessedLoss,
+    Draw,
+    CursedWin,
+    Win,
+}
+
+impl Wdl {
+
+    /// Convert the -2..2 value used inside the tables
+    pub fn from_value(value: i8) -> Wdl {
+        match value {
+            -2 => Wdl::Loss,
+            -1 => Wdl::BlessedLoss,
+            0 => Wdl::Draw,
+            1 => Wdl::CursedWin,
+            _ => Wdl::Win,
+        }
+    }
+
+    /// The -2..2 value used inside the tables
+    pub fn value(&self) -> i8 {
+        match self {
+            Wdl::Loss => -2,
+            Wdl::BlessedLoss => -1,
+            Wdl::Draw => 0,
+            Wdl::CursedWin => 1,
+            Wdl::Win => 2,
+        }
+    }
+
+}
+
+/// A table file that is read from disk the first time it is probed
+enum Slot {
+    Unloaded(PathBuf),
+    Loaded(Arc<Table>),
+    Broken,
+}
+
+/// The Syzygy tables available for probing, by material signature (e.g. "KRPvKR")
+pub struct Tablebases {
+    wdl: HashMap<String, Mutex<Slot>>,
+    dtz: HashMap<String, Mutex<Slot>>,
+    max_pieces: u32,
+}
+
+/// Constructs an empty set of Tablebases
+///
+/// # Examples
+///
+/// ```
+/// let tablebases = chessica::syzygy::new();
+/// assert_eq!(tablebases.max_pieces(),0);
+/// ```
+pub fn new() -> Tablebases {
+    Tablebases {
+        wdl: HashMap::new(),
+        dtz: HashMap::new(),
+        max_pieces: 0,
+    }
+}
+
+impl Tablebases {
+
+    /// Register every `.rtbw` and `.rtbz` file in a directory, returning the number of WDL tables
+    ///
+    /// Files are only read when first probed.
+    pub fn add_directory(&mut self, path: &str) -> io::Result<usize> {
+        let mut found = 0;
+        for entry in fs::read_dir(path)? {
+            let path = entry?.path();
+            let (name, extension) = match (path.file_stem(), path.extension()) {
+                (Some(name), Some(extension)) => (name.to_string_lossy().to_string(), extension.to_string_lossy().to_string()),
+                _ => continue,
+            };
+            let tables = match extension.as_str() {
+                "rtbw" => {
+                    found += 1;
+                    self.max_pieces = self.max_pieces.max(name.chars().filter(|c| *c != 'v').count() as u32);
+                    &mut self.wdl
+                },
+                "rtbz" => &mut self.dtz,
+                _ => continue,
+            };
+            tables.insert(name, Mutex::new(Slot::Unloaded(path)));
+        }
+        Ok(found)
+    }
+
+    /// Register a table that is already in memory
+    pub fn add_table(&mut self, name: &str, table: Table) {
+        if !table.dtz {
+            self.max_pieces = self.max_pieces.max(name.chars().filter(|c| *c != 'v').count() as u32);
+        }
+        let tables = if table.dtz { &mut self.dtz } else { &mut self.wdl };
+        tables.insert(name.to_string(), Mutex::new(Slot::Loaded(Arc::new(table))));
+    }
+
+    /// Largest number of pieces covered by the WDL tables
+    pub fn max_pieces(&self) -> u32 {
+        self.max_pieces
+    }
+
+    /// The table for a material signature, and whether it is stored with the colours swapped
+    pub fn table(&self, signature: &str, dtz: bool) -> Option<(Arc<Table>, bool)> {
+        let tables = if dtz { &self.dtz } else { &self.wdl };
+        let (slot, swapped) = match tables.get(signature) {
+            Some(slot) => (slot, false),
+            None => {
+                let mut sides = signature.split('v');
+                let white = sides.next()?;
+                let black = sides.next()?;
+                (tables.get(&format!("{}v{}",black,white))?, true)
+            },
+        };
+        let mut slot = slot.lock().ok()?;
+        if let Slot::Unloaded(path) = &*slot {
+            let name = path.file_stem()?.to_string_lossy().to_string();
+            *slot = match fs::read(path).and_then(|bytes| table::from_bytes(&name, bytes, dtz)) {
+                Ok(table) => Slot::Loaded(Arc::new(table)),
+                Err(e) => {
+                    println!("# could not read tablebase {}: {}",path.display(),e);
+                    Slot::Broken
+                },
+            };
+        }
+        match &*slot {
+            Slot::Loaded(table) => Some((Arc::clone(table), swapped)),
+            _ => None,
+        }
+    }
+
+}
+
+lazy_static! {
+    /// The tables used by the searches and the Orchestrator
+    pub static ref TABLEBASES: RwLock<Tablebases> = RwLock::new(new());
+}
+
+/// Copy of `TABLEBASES.max_pieces()` that searches can check without taking the lock
+static MAX_PIECES: AtomicU32 = AtomicU32::new(0);
+
+/// Replace the shared tables with the ones found in `path`, returning the number of WDL tables
+///
+/// An empty path removes all tables.
+pub fn init(path: &str) -> io::Result<usize> {
+    let mut tablebases = new();
+    let found = if path.is_empty() { 0 } else { tablebases.add_directory(path)? };
+    let max_pieces = tablebases.max_pieces();
+    match TABLEBASES.write() {
+        Ok(mut shared) => *shared = tablebases,
+        Err(_) => return Err(io::Error::new(io::ErrorKind::Other, "tablebase lock poisoned")),
+    }
+    MAX_PIECES.store(max_pieces, Ordering::Relaxed);
+    Ok(found)
+}
+
+/// Largest number of pieces covered by the shared tables (0 when none are loaded)
+pub fn max_pieces() -> u32 {
+    MAX_PIECES.load(Ordering::Relaxed)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::reset;
+    use crate::syzygy;
+    use crate::syzygy::Wdl;
+    use crate::syzygy::probe;
+    use crate::syzygy::table;
+
+    #[test]
+    fn syzygy_add_directory() {
+        let directory = std::env::temp_dir().join(format!("chessica_syzygy_{}",std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        // Single-value KQvK table: white to move wins, black to move loses
+        let mut bytes = table::WDL_MAGIC.to_vec();
+        bytes.extend_from_slice(&[0x01, 0x00, 0x66, 0x55, 0xEE, 0x00, 0x80, 4, 0x80, 0]);
+        bytes.resize(64, 0);
+        std::fs::write(directory.join("KQvK.rtbw"), &bytes).unwrap();
+        std::fs::write(directory.join("KRvK.rtbw"), &bytes[..20]).unwrap();
+        std::fs::write(directory.join("README.txt"), b"not a table").unwrap();
+
+        let mut tablebases = syzygy::new();
+        assert_eq!(tablebases.add_directory(directory.to_str().unwrap()).unwrap(),2);
+        assert_eq!(tablebases.max_pieces(),3);
+        assert!(tablebases.table("KQvK", true).is_none());
+        let (_, swapped) = tablebases.table("KvKQ", false).unwrap();
+        assert!(swapped);
+
+        let mut r = reset::new();
+        r.init_from_fen("8/8/8/4k3/8/8/8/KQ6 w - - 0 1".to_string());
+        assert_eq!(probe::wdl_with(&tablebases, &r),Some(Wdl::Win));
+        // The truncated KRvK file is reported once and then left alone
+        r.init_from_fen("8/8/8/4k3/8/8/8/KR6 w - - 0 1".to_string());
+        assert_eq!(probe::wdl_with(&tablebases, &r),None);
+        assert_eq!(probe::wdl_with(&tablebases, &r),None);
+
+        std::fs::remove_dir_all(&directory).unwrap();
+        assert!(syzygy::new().add_directory(directory.to_str().unwrap()).is_err());
+    }
+}
@@ -0,0 +1,356 @@
+use crate::reset;
+use crate::reset::Reset;
+use crate::reset::r#const::SCORE_TABLEBASE_WIN;
+use crate::reset::variant::Variant;
+use crate::syzygy;
+use crate::syzygy::Tablebases;
+use crate::syzygy::TABLEBASES;
+use crate::syzygy::Wdl;
+
+/// Ranks used to order root moves, as large as any DTZ a table can store
+const MAX_DTZ: i32 = 1 << 18;
+
+/// How a probe went, and what it found out beyond the value it returned
+#[derive(PartialEq,Eq,Copy,Clone,Debug)]
+enum ProbeState {
+    /// A table was missing or unreadable
+    Fail,
+    Ok,
+    /// The DTZ table only stores the other side to move
+    ChangeStm,
+    /// The best move captures or moves a pawn
+    ZeroingBestMove,
+}
+
+/// DTZ of a position whose best move resets the fifty-move counter
+fn dtz_before_zeroing(wdl: Wdl) -> i32 {
+    match wdl {
+        Wdl::Win => 1,
+        Wdl::CursedWin => 101,
+        Wdl::BlessedLoss => -101,
+        Wdl::Loss => -1,
+        Wdl::Draw => 0,
+    }
+}
+
+/// Look a position up in its own table.  The position must have no captures to play out and no
+/// castling rights.  For DTZ tables `wdl` is the position's already known WDL value.
+fn probe_table(tablebases: &Tablebases, reset: &Reset, dtz: bool, wdl: Wdl, state: &mut ProbeState) -> i32 {
+    if reset.piece_count() == 2 {
+        return 0;
+    }
+    let (table, black_stronger) = match tablebases.table(&reset.material_signature(), dtz) {
+        Some(found) => found,
+        None => {
+            *state = ProbeState::Fail;
+            return 0;
+        },
+    };
+    let black_to_move = !reset.white_to_move();
+    let flip = (table.symmetric && black_to_move) || black_stronger;
+    let stm = (flip ^ black_to_move) as usize;
+    let mut pieces = reset.tablebase_pieces();
+    if flip {
+        for (square, code) in pieces.iter_mut() {
+            *square ^= 56;
+            *code ^= 8;
+        }
+    }
+    match table.probe(&pieces, stm, wdl.value()) {
+        Ok(Some(value)) => value,
+        Ok(None) => {
+            *state = ProbeState::ChangeStm;
+            0
+        },
+        Err(_) => {
+            *state = ProbeState::Fail;
+            0
+        },
+    }
+}
+
+/// WDL value of a position, playing out captures (and with `check_zeroing` pawn moves too) since
+/// the tables assume the side to move has made the best of them
+fn search(tablebases: &Tablebases, reset: &mut Reset, check_zeroing: bool, state: &mut ProbeState) -> Wdl {
+    let mut best = Wdl::Loss;
+    let mut total_count = 0;
+    let mut move_count = 0;
+    let mut child = reset::new();
+    reset.restart_move_generation();
+    while reset.generate_next_move(&mut child) {
+        total_count += 1;
+        if !(child.is_capture() || (check_zeroing && child.halfmove_clock() == 0)) {
+            continue;
+        }
+        move_count += 1;
+        child.restart_move_generation();
+        let value = Wdl::from_value(-search(tablebases, &mut child, false, state).value());
+        if *state == ProbeState::Fail {
+            return Wdl::Draw;
+        }
+        if value > best {
+            best = value;
+            if value == Wdl::Win {
+                *state = ProbeState::ZeroingBestMove;
+                return value;
+            }
+        }
+    }
+
+    let no_more_moves = move_count > 0 && move_count == total_count;
+    let value = if no_more_moves {
+        best
+    } else {
+        let value = probe_table(tablebases, reset, false, Wdl::Draw, state);
+        if *state == ProbeState::Fail {
+            return Wdl::Draw;
+        }
+        Wdl::from_value(value as i8)
+    };
+    if best >= value {
+        *state = if best > Wdl::Draw || no_more_moves { ProbeState::ZeroingBestMove } else { ProbeState::Ok };
+        return best;
+    }
+    *state = ProbeState::Ok;
+    value
+}
+
+/// WDL value for the side to move
+fn probe_wdl(tablebases: &Tablebases, reset: &mut Reset) -> Option<Wdl> {
+    if reset.any_castle() {
+        return None;
+    }
+    let mut state = ProbeState::Ok;
+    let wdl = search(tablebases, reset, false, &mut state);
+    if state == ProbeState::Fail { None } else { Some(wdl) }
+}
+
+/// Distance to the next capture or pawn move (in plies) for the side to move: positive when
+/// winning, negative when losing, 0 for a draw.  Wins and losses spoiled by the fifty-move rule
+/// are counted 100 plies further away.
+fn probe_dtz(tablebases: &Tablebases, reset: &mut Reset, state: &mut ProbeState) -> i32 {
+    *state = ProbeState::Ok;
+    let wdl = search(tablebases, reset, true, state);
+    if *state == ProbeState::Fail || wdl == Wdl::Draw {
+        return 0;
+    }
+    if *state == ProbeState::ZeroingBestMove {
+        return dtz_before_zeroing(wdl);
+    }
+    let dtz = probe_table(tablebases, reset, true, wdl, state);
+    if *state == ProbeState::Fail {
+        return 0;
+    }
+    let sign = wdl.value().signum() as i32;
+    if *state != ProbeState::ChangeStm {
+        let cursed = wdl == Wdl::BlessedLoss || wdl == Wdl::CursedWin;
+        return (dtz + if cursed { 100 } else { 0 }) * sign;
+    }
+
+    // The DTZ table only stores the other side to move, so look one ply ahead
+    let mut min_dtz = 0xFFFF;
+    let mut child = reset::new();
+    reset.restart_move_generation();
+    while reset.generate_next_move(&mut child) {
+        let zeroing = child.halfmove_clock() == 0;
+        child.restart_move_generation();
+        let mut dtz = if zeroing {
+            -dtz_before_zeroing(search(tablebases, &mut child, false, state))
+        } else {
+            -probe_dtz(tablebases, &mut child, state)
+        };
+        if *state == ProbeState::Fail {
+            return 0;
+        }
+        if dtz == 1 && is_checkmate(&mut child) {
+            min_dtz = 1;
+        }
+        if !zeroing {
+            dtz += dtz.signum();
+        }
+        if dtz < min_dtz && dtz.signum() == sign {
+            min_dtz = dtz;
+        }
+    }
+    if min_dtz == 0xFFFF { -1 } else { min_dtz }
+}
+
+/// Is the side to move checkmated?
+fn is_checkmate(reset: &mut Reset) -> bool {
+    if !reset.in_check() {
+        return false;
+    }
+    let mut child = reset::new();
+    reset.restart_move_generation();
+    !reset.generate_next_move(&mut child)
+}
+
+/// WDL value of a position for the side to move, or `None` if it has castling rights or its
+/// tables aren't available
+///
+/// # Examples
+///
+/// ```
+/// let mut r = chessica::reset::new();
+/// r.init_from_fen("8/8/8/4k3/8/8/8/K6Q w - - 0 1".to_string());
+/// // No tables have been loaded
+/// assert_eq!(chessica::syzygy::probe::wdl(&r),None);
+/// ```
+pub fn wdl(reset: &Reset) -> Option<Wdl> {
+    let tablebases = TABLEBASES.read().ok()?;
+    wdl_with(&tablebases, reset)
+}
+
+/// `wdl`, using a given set of tables
+pub fn wdl_with(tablebases: &Tablebases, reset: &Reset) -> Option<Wdl> {
+    let mut probed = reset::new();
+    reset.clone_to(&mut probed);
+    probe_wdl(tablebases, &mut probed)
+}
+
+/// DTZ of a position for the side to move, or `None` if it has castling rights or its tables
+/// aren't available
+pub fn dtz(reset: &Reset) -> Option<i32> {
+    let tablebases = TABLEBASES.read().ok()?;
+    dtz_with(&tablebases, reset)
+}
+
+/// `dtz`, using a given set of tables
+pub fn dtz_with(tablebases: &Tablebases, reset: &Reset) -> Option<i32> {
+    if reset.any_castle() {
+        return None;
+    }
+    let mut probed = reset::new();
+    reset.clone_to(&mut probed);
+    let mut state = ProbeState::Ok;
+    let dtz = probe_dtz(tablebases, &mut probed, &mut state);
+    if state == ProbeState::Fail { None } else { Some(dtz) }
+}
+
+/// The DTZ-optimal move at the root, with its DTZ counted from the root position
+///
+/// Winning moves that keep the win inside the fifty-move rule are preferred, fastest first;
+/// losing moves resist as long as possible.
+pub fn root_move(reset: &Reset) -> Option<(Reset, i32)> {
+    let tablebases = TABLEBASES.read().ok()?;
+    root_move_with(&tablebases, reset)
+}
+
+/// `root_move`, using a given set of tables
+pub fn root_move_with(tablebases: &Tablebases, reset: &Reset) -> Option<(Reset, i32)> {
+    if reset.any_castle() {
+        return None;
+    }
+    let count50 = i32::from(reset.halfmove_clock());
+    let mut parent = reset::new();
+    reset.clone_to(&mut parent);
+    parent.restart_move_generation();
+    let mut child = reset::new();
+    let mut best: Option<((i32, i32), Reset, i32)> = None;
+    while parent.generate_next_move(&mut child) {
+        let mut probed = reset::clone::clone_from(&mut child);
+        probed.restart_move_generation();
+        let mut state = ProbeState::Ok;
+        let mut dtz = if probed.halfmove_clock() == 0 {
+            let wdl = search(tablebases, &mut probed, false, &mut state);
+            dtz_before_zeroing(Wdl::from_value(-wdl.value()))
+        } else {
+            let dtz = -probe_dtz(tablebases, &mut probed, &mut state);
+            dtz + dtz.signum()
+        };
+        if state == ProbeState::Fail {
+            return None;
+        }
+        if dtz == 2 && is_checkmate(&mut probed) {
+            dtz = 1;
+        }
+        let rank = if dtz > 0 {
+            if dtz + count50 <= 99 { MAX_DTZ } else { MAX_DTZ - (dtz + count50) }
+        } else if dtz < 0 {
+            if -dtz * 2 + count50 < 100 { -MAX_DTZ } else { -MAX_DTZ + (-dtz + count50) }
+        } else {
+            0
+        };
+        let key = (rank, -dtz);
+        if best.as_ref().map_or(true, |(best_key, _, _)| key > *best_key) {
+            best = Some((key, reset::clone::clone_from(&mut child), dtz));
+        }
+    }
+    best.map(|(_, child, dtz)| (child, dtz))
+}
+
+/// Score for a search node that the tables can settle, from white's point of view
+///
+/// Only standard chess positions just after a capture or pawn move, with no castling rights and
+/// few enough pieces, are probed.  The root (`ply` 0) is left to the search so that it still
+/// picks a move.
+/// Wins score below checkmates and shrink with `ply`, so the search heads for the nearest one;
+/// cursed wins and blessed losses are draws.
+pub fn search_score(reset: &Reset, ply: u8) -> Option<i32> {
+    if ply == 0
+        || reset.variant() != Variant::Standard
+        || reset.piece_count() > syzygy::max_pieces()
+        || reset.halfmove_clock() != 0
+        || reset.any_castle() {
+        return None;
+    }
+    let score = match wdl(reset)? {
+        Wdl::Win => SCORE_TABLEBASE_WIN - ply as i32,
+        Wdl::Loss => -SCORE_TABLEBASE_WIN + ply as i32,
+        _ => 0,
+    };
+    Some(if reset.white_to_move() { score } else { -score })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::reset;
+    use crate::syzygy;
+    use crate::syzygy::Wdl;
+    use crate::syzygy::probe;
+    use crate::syzygy::table;
+
+    /// A KQvK WDL table whose every position is a win for white to move and a loss for black to
+    /// move (stored as a single value per side, so no compressed data is needed)
+    pub fn single_value_kqvk() -> table::Table {
+        let mut bytes = table::WDL_MAGIC.to_vec();
+        // Not split, no pawns; piece order and pieces for white to move, then black
+        bytes.extend_from_slice(&[0x01, 0x00, 0x66, 0x55, 0xEE]);
+        bytes.push(0);
+        // Single value flag with the value + 2 for each side
+        bytes.extend_from_slice(&[0x80, 4, 0x80, 0]);
+        bytes.resize(64, 0);
+        table::from_bytes("KQvK", bytes, false).unwrap()
+    }
+
+    fn probe_fen(tablebases: &syzygy::Tablebases, fen: &str) -> Option<Wdl> {
+        let mut r = reset::new();
+        r.init_from_fen(fen.to_string());
+        probe::wdl_with(tablebases, &r)
+    }
+
+    #[test]
+    fn syzygy_single_value_table() {
+        let mut tablebases = syzygy::new();
+        tablebases.add_table("KQvK", single_value_kqvk());
+        assert_eq!(tablebases.max_pieces(),3);
+        assert_eq!(probe_fen(&tablebases,"8/8/8/4k3/8/8/8/KQ6 w - - 0 1"),Some(Wdl::Win));
+        assert_eq!(probe_fen(&tablebases,"8/8/8/4k3/8/8/8/KQ6 b - - 0 1"),Some(Wdl::Loss));
+        // Black holds the queen, so the table is read with the colours swapped
+        assert_eq!(probe_fen(&tablebases,"8/8/8/4K3/8/8/8/kq6 b - - 0 1"),Some(Wdl::Win));
+        assert_eq!(probe_fen(&tablebases,"8/8/8/4K3/8/8/8/kq6 w - - 0 1"),Some(Wdl::Loss));
+        // Black takes the queen, leaving a KvK draw
+        assert_eq!(probe_fen(&tablebases,"8/8/8/8/8/8/1k6/1Q5K b - - 0 1"),Some(Wdl::Draw));
+        // No table for this material, and castling rights are never probed
+        assert_eq!(probe_fen(&tablebases,"8/8/8/4k3/8/8/8/KR6 w - - 0 1"),None);
+        assert_eq!(probe_fen(&tablebases,"4k3/8/8/8/8/8/8/4K2Q w K - 0 1"),None);
+    }
+
+    #[test]
+    fn syzygy_search_score() {
+        let mut r = reset::new();
+        r.init_from_fen("8/8/8/4k3/8/8/8/KQ6 b - - 0 1".to_string());
+        // Nothing loaded
+        assert_eq!(probe::search_score(&r,1),None);
+    }
+}
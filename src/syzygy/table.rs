@@ -0,0 +1,904 @@
+use std::io;
+
+/// First four bytes of every `.rtbw` file
+pub const WDL_MAGIC: [u8; 4] = [0x71, 0xE8, 0x23, 0x5D];
+/// First four bytes of every `.rtbz` file
+pub const DTZ_MAGIC: [u8; 4] = [0xD7, 0x66, 0x0C, 0xA5];
+
+/// Most pieces a Syzygy table can hold
+pub const TB_PIECES: usize = 7;
+
+// Per-table flags
+const FLAG_STM: u8 = 1;
+const FLAG_MAPPED: u8 = 2;
+const FLAG_WIN_PLIES: u8 = 4;
+const FLAG_LOSS_PLIES: u8 = 8;
+const FLAG_WIDE: u8 = 16;
+const FLAG_SINGLE_VALUE: u8 = 128;
+
+/// Squares are numbered a1 = 0, b1 = 1 ... h8 = 63, as in the tables themselves
+fn file_of(square: usize) -> usize {
+    square & 7
+}
+
+fn rank_of(square: usize) -> usize {
+    square >> 3
+}
+
+/// Rank minus file: negative below the a1-h8 diagonal, zero on it, positive above it
+fn off_diagonal(square: usize) -> i32 {
+    rank_of(square) as i32 - file_of(square) as i32
+}
+
+fn king_attacks(from: usize, to: usize) -> bool {
+    let file_distance = (file_of(from) as i32 - file_of(to) as i32).abs();
+    let rank_distance = (rank_of(from) as i32 - rank_of(to) as i32).abs();
+    file_distance <= 1 && rank_distance <= 1
+}
+
+/// Lookup tables shared by every Syzygy table's index encoding
+pub struct Encoding {
+    /// Squares a2-h7 numbered so that the leading pawn (nearest the edge, lowest rank) is highest
+    pub map_pawns: [usize; 64],
+    /// Squares below the a1-h8 diagonal numbered 0..27
+    pub map_b1h1h7: [usize; 64],
+    /// Squares of the a1-d1-d4 triangle numbered 0..9, diagonal squares last
+    pub map_a1d1d4: [usize; 64],
+    /// The 462 legal, non-mirrored placements of two kings, the first in the a1-d1-d4 triangle
+    pub map_kk: [[usize; 64]; 10],
+    /// binomial[k][n]: ways to choose k of n squares
+    pub binomial: [[u64; 64]; TB_PIECES],
+    pub lead_pawn_idx: [[u64; 64]; 6],
+    pub lead_pawns_size: [[u64; 4]; 6],
+}
+
+fn new_encoding() -> Encoding {
+    let mut e = Encoding {
+        map_pawns: [0; 64],
+        map_b1h1h7: [0; 64],
+        map_a1d1d4: [0; 64],
+        map_kk: [[0; 64]; 10],
+        binomial: [[0; 64]; TB_PIECES],
+        lead_pawn_idx: [[0; 64]; 6],
+        lead_pawns_size: [[0; 4]; 6],
+    };
+
+    let mut code = 0;
+    for square in 0..64 {
+        if off_diagonal(square) < 0 {
+            e.map_b1h1h7[square] = code;
+            code += 1;
+        }
+    }
+
+    let mut diagonal = Vec::new();
+    code = 0;
+    for square in [0, 1, 2, 3, 8, 9, 10, 11, 16, 17, 18, 19, 24, 25, 26, 27].iter() {
+        if off_diagonal(*square) < 0 {
+            e.map_a1d1d4[*square] = code;
+            code += 1;
+        } else if off_diagonal(*square) == 0 {
+            diagonal.push(*square);
+        }
+    }
+    for square in diagonal.iter() {
+        e.map_a1d1d4[*square] = code;
+        code += 1;
+    }
+
+    let mut both_on_diagonal = Vec::new();
+    code = 0;
+    for idx in 0..10 {
+        for s1 in 0..28 {
+            // b1 is mapped to 0 as well as a1, but only b1 is a real placement for index 0
+            if e.map_a1d1d4[s1] == idx && (idx != 0 || s1 == 1) && file_of(s1) < 4 && rank_of(s1) < 4 {
+                for s2 in 0..64 {
+                    if king_attacks(s1, s2) || (off_diagonal(s1) == 0 && off_diagonal(s2) > 0) {
+                        continue;
+                    } else if off_diagonal(s1) == 0 && off_diagonal(s2) == 0 {
+                        both_on_diagonal.push((idx, s2));
+                    } else {
+                        e.map_kk[idx][s2] = code;
+                        code += 1;
+                    }
+                }
+            }
+        }
+    }
+    for (idx, s2) in both_on_diagonal.iter() {
+        e.map_kk[*idx][*s2] = code;
+        code += 1;
+    }
+
+    e.binomial[0][0] = 1;
+    for n in 1..64 {
+        for k in 0..TB_PIECES.min(n + 1) {
+            e.binomial[k][n] = if k > 0 { e.binomial[k - 1][n - 1] } else { 0 }
+                + if k < n { e.binomial[k][n - 1] } else { 0 };
+        }
+    }
+
+    let mut available_squares = 47;
+    for lead_pawns_count in 1..=5 {
+        for file in 0..4 {
+            let mut idx = 0;
+            for rank in 1..7 {
+                let square = rank * 8 + file;
+                if lead_pawns_count == 1 {
+                    e.map_pawns[square] = available_squares;
+                    available_squares -= 1;
+                    e.map_pawns[square ^ 7] = available_squares;
+                    available_squares = available_squares.saturating_sub(1);
+                }
+                e.lead_pawn_idx[lead_pawns_count][square] = idx;
+                idx += e.binomial[lead_pawns_count - 1][e.map_pawns[square]];
+            }
+            e.lead_pawns_size[lead_pawns_count][file] = idx;
+        }
+    }
+    e
+}
+
+lazy_static! {
+    pub static ref ENCODING: Encoding = new_encoding();
+}
+
+/// Decoding data for one side to move (and, with pawns, one leading pawn file) of a table
+#[derive(Clone,Default)]
+pub struct PairsData {
+    pub flags: u8,
+    pub block_size: u64,
+    pub span: u64,
+    pub num_blocks: u32,
+    pub max_sym_len: u8,
+    pub min_sym_len: u8,
+    pub lowest_sym: usize,
+    pub btree: usize,
+    pub block_length: usize,
+    pub block_length_size: u32,
+    pub sparse_index: usize,
+    pub sparse_index_size: u64,
+    pub data: usize,
+    pub base64: Vec<u64>,
+    pub symlen: Vec<u8>,
+    pub pieces: [u8; TB_PIECES],
+    pub group_idx: [u64; TB_PIECES + 1],
+    pub group_len: [usize; TB_PIECES + 1],
+    pub map_idx: [u16; 4],
+}
+
+/// One Syzygy WDL or DTZ file, read into memory
+pub struct Table {
+    pub bytes: Vec<u8>,
+    pub dtz: bool,
+    pub piece_count: usize,
+    pub has_pawns: bool,
+    pub has_unique_pieces: bool,
+    /// Both sides have the same material (e.g. "KRvKR"), so only white to move is stored
+    pub symmetric: bool,
+    /// Pawns of the leading colour, then of the other colour
+    pub pawn_count: [usize; 2],
+    /// Indexed by `side * 4 + file`
+    pub items: Vec<PairsData>,
+    pub map: usize,
+}
+
+fn out_of_range() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "tablebase file is truncated")
+}
+
+impl Table {
+
+    fn byte(&self, offset: usize) -> io::Result<u8> {
+        self.bytes.get(offset).copied().ok_or_else(out_of_range)
+    }
+
+    fn u16_le(&self, offset: usize) -> io::Result<u16> {
+        Ok(u16::from(self.byte(offset)?) | (u16::from(self.byte(offset + 1)?) << 8))
+    }
+
+    fn u32_le(&self, offset: usize) -> io::Result<u32> {
+        Ok(u32::from(self.u16_le(offset)?) | (u32::from(self.u16_le(offset + 2)?) << 16))
+    }
+
+    fn u32_be(&self, offset: usize) -> u32 {
+        let mut value = 0;
+        for i in 0..4 {
+            // Reads past the end of the data only happen while prefetching bits that won't be used
+            value = (value << 8) | u32::from(*self.bytes.get(offset + i).unwrap_or(&0));
+        }
+        value
+    }
+
+    /// Decoding data for a side to move and leading pawn file
+    pub fn get(&self, stm: usize, file: usize) -> &PairsData {
+        let sides = if self.dtz { 1 } else { 2 };
+        &self.items[(stm % sides) * 4 + if self.has_pawns { file } else { 0 }]
+    }
+
+    fn get_mut(&mut self, stm: usize, file: usize) -> &mut PairsData {
+        let sides = if self.dtz { 1 } else { 2 };
+        let has_pawns = self.has_pawns;
+        &mut self.items[(stm % sides) * 4 + if has_pawns { file } else { 0 }]
+    }
+
+    fn set_groups(&mut self, stm: usize, order: [u8; 2], file: usize) {
+        let has_pawns = self.has_pawns;
+        let has_unique_pieces = self.has_unique_pieces;
+        let piece_count = self.piece_count;
+        let pawns_on_both_sides = has_pawns && self.pawn_count[1] > 0;
+        let d = self.get_mut(stm, file);
+
+        let mut n = 0;
+        let mut first_len: i32 = if has_pawns { 0 } else if has_unique_pieces { 3 } else { 2 };
+        d.group_len[n] = 1;
+        for i in 1..piece_count {
+            first_len -= 1;
+            if first_len > 0 || d.pieces[i] == d.pieces[i - 1] {
+                d.group_len[n] += 1;
+            } else {
+                n += 1;
+                d.group_len[n] = 1;
+            }
+        }
+        n += 1;
+        d.group_len[n] = 0;
+
+        let mut next = if pawns_on_both_sides { 2 } else { 1 };
+        let mut free_squares = 64 - d.group_len[0] - if pawns_on_both_sides { d.group_len[1] } else { 0 };
+        let mut idx: u64 = 1;
+        let mut k = 0;
+        while next < n || k == usize::from(order[0]) || k == usize::from(order[1]) {
+            if k == usize::from(order[0]) {
+                d.group_idx[0] = idx;
+                idx *= if has_pawns {
+                    ENCODING.lead_pawns_size[d.group_len[0]][file]
+                } else if has_unique_pieces {
+                    31332
+                } else {
+                    462
+                };
+            } else if k == usize::from(order[1]) {
+                d.group_idx[1] = idx;
+                idx *= ENCODING.binomial[d.group_len[1]][48 - d.group_len[0]];
+            } else {
+                d.group_idx[next] = idx;
+                idx *= ENCODING.binomial[d.group_len[next]][free_squares];
+                free_squares -= d.group_len[next];
+                next += 1;
+            }
+            k += 1;
+        }
+        d.group_idx[n] = idx;
+    }
+
+    fn set_symlen(&self, d: &mut PairsData, symbol: usize, visited: &mut Vec<bool>) -> io::Result<u8> {
+        visited[symbol] = true;
+        let (left, right) = self.btree_entry(d.btree, symbol)?;
+        if right == 0xFFF {
+            return Ok(0);
+        }
+        if left >= visited.len() || right >= visited.len() {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "tablebase symbol out of range"));
+        }
+        if !visited[left] {
+            d.symlen[left] = self.set_symlen(d, left, visited)?;
+        }
+        if !visited[right] {
+            d.symlen[right] = self.set_symlen(d, right, visited)?;
+        }
+        Ok(d.symlen[left].wrapping_add(d.symlen[right]).wrapping_add(1))
+    }
+
+    /// Left and right child symbols of a Recursive Pairing symbol
+    fn btree_entry(&self, btree: usize, symbol: usize) -> io::Result<(usize, usize)> {
+        let offset = btree + 3 * symbol;
+        let (b0, b1, b2) = (self.byte(offset)?, self.byte(offset + 1)?, self.byte(offset + 2)?);
+        let left = (usize::from(b1 & 0xF) << 8) | usize::from(b0);
+        let right = (usize::from(b2) << 4) | usize::from(b1 >> 4);
+        Ok((left, right))
+    }
+
+    fn set_sizes(&mut self, stm: usize, file: usize, mut offset: usize) -> io::Result<usize> {
+        let mut d = self.get(stm, file).clone();
+        d.flags = self.byte(offset)?;
+        offset += 1;
+        if d.flags & FLAG_SINGLE_VALUE != 0 {
+            d.num_blocks = 0;
+            d.span = 0;
+            d.block_length_size = 0;
+            d.sparse_index_size = 0;
+            // The single value is kept in min_sym_len
+            d.min_sym_len = self.byte(offset)?;
+            *self.get_mut(stm, file) = d;
+            return Ok(offset + 1);
+        }
+
+        let groups = d.group_len.iter().position(|len| *len == 0).unwrap_or(TB_PIECES);
+        let tb_size = d.group_idx[groups];
+
+        let (block_bits, span_bits) = (self.byte(offset)?, self.byte(offset + 1)?);
+        if block_bits > 32 || span_bits > 32 {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "tablebase block sizes are invalid"));
+        }
+        d.block_size = 1 << block_bits;
+        d.span = 1 << span_bits;
+        d.sparse_index_size = (tb_size + d.span - 1) / d.span;
+        let padding = self.byte(offset + 2)?;
+        d.num_blocks = self.u32_le(offset + 3)?;
+        d.block_length_size = d.num_blocks + u32::from(padding);
+        d.max_sym_len = self.byte(offset + 7)?;
+        d.min_sym_len = self.byte(offset + 8)?;
+        offset += 9;
+        d.lowest_sym = offset;
+        if d.max_sym_len < d.min_sym_len || d.min_sym_len == 0 || d.max_sym_len > 32 {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "tablebase symbol lengths are invalid"));
+        }
+
+        let base_len = usize::from(d.max_sym_len - d.min_sym_len) + 1;
+        d.base64 = vec![0; base_len];
+        for i in (0..base_len - 1).rev() {
+            let lowest = u64::from(self.u16_le(d.lowest_sym + 2 * i)?);
+            let lowest_next = u64::from(self.u16_le(d.lowest_sym + 2 * (i + 1))?);
+            d.base64[i] = d.base64[i + 1].wrapping_add(lowest).wrapping_sub(lowest_next) / 2;
+        }
+        for (i, base) in d.base64.iter_mut().enumerate() {
+            let shift = 64 - i as u32 - u32::from(d.min_sym_len);
+            *base = base.checked_shl(shift).unwrap_or(0);
+        }
+        offset += base_len * 2;
+
+        let symbols = usize::from(self.u16_le(offset)?);
+        offset += 2;
+        d.btree = offset;
+        d.symlen = vec![0; symbols];
+        let mut visited = vec![false; symbols];
+        for symbol in 0..symbols {
+            if !visited[symbol] {
+                d.symlen[symbol] = self.set_symlen(&mut d, symbol, &mut visited)?;
+            }
+        }
+        *self.get_mut(stm, file) = d;
+        Ok(offset + symbols * 3 + (symbols & 1))
+    }
+
+    fn set_dtz_map(&mut self, mut offset: usize, max_file: usize) -> io::Result<usize> {
+        self.map = offset;
+        for file in 0..=max_file {
+            let flags = self.get(0, file).flags;
+            if flags & FLAG_MAPPED != 0 {
+                let mut map_idx = [0; 4];
+                if flags & FLAG_WIDE != 0 {
+                    offset += offset & 1;
+                    for entry in map_idx.iter_mut() {
+                        *entry = ((offset - self.map) / 2 + 1) as u16;
+                        offset += 2 * usize::from(self.u16_le(offset)?) + 2;
+                    }
+                } else {
+                    for entry in map_idx.iter_mut() {
+                        *entry = (offset - self.map + 1) as u16;
+                        offset += usize::from(self.byte(offset)?) + 1;
+                    }
+                }
+                self.get_mut(0, file).map_idx = map_idx;
+            }
+        }
+        Ok(offset + (offset & 1))
+    }
+
+    /// The value stored at `idx`, decoded from its block of canonical Huffman symbols
+    pub fn decompress_pairs(&self, d: &PairsData, idx: u64) -> io::Result<u16> {
+        if d.flags & FLAG_SINGLE_VALUE != 0 {
+            return Ok(u16::from(d.min_sym_len));
+        }
+        if d.span == 0 || idx / d.span >= d.sparse_index_size {
+            return Err(out_of_range());
+        }
+
+        // The sparse index points into the block list every `span` values, near the middle
+        let k = (idx / d.span) as usize;
+        let mut block = self.u32_le(d.sparse_index + 6 * k)? as usize;
+        let mut offset = i64::from(self.u16_le(d.sparse_index + 6 * k + 4)?);
+        offset += (idx % d.span) as i64 - (d.span / 2) as i64;
+
+        let block_length = |block: usize| -> io::Result<i64> {
+            if block >= d.block_length_size as usize {
+                return Err(out_of_range());
+            }
+            Ok(i64::from(self.u16_le(d.block_length + 2 * block)?))
+        };
+        while offset < 0 {
+            if block == 0 {
+                return Err(out_of_range());
+            }
+            block -= 1;
+            offset += block_length(block)? + 1;
+        }
+        while offset > block_length(block)? {
+            offset -= block_length(block)? + 1;
+            block += 1;
+        }
+
+        let mut pointer = d.data + block * d.block_size as usize;
+        let mut buffer: u64 = (u64::from(self.u32_be(pointer)) << 32) | u64::from(self.u32_be(pointer + 4));
+        pointer += 8;
+        let mut buffer_size = 64;
+        let mut symbol: usize;
+        let min_sym_len = usize::from(d.min_sym_len);
+
+        loop {
+            let mut len = 0;
+            while len + 1 < d.base64.len() && buffer < d.base64[len] {
+                len += 1;
+            }
+            symbol = ((buffer - d.base64[len]) >> (64 - len - min_sym_len)) as usize;
+            symbol = (symbol + usize::from(self.u16_le(d.lowest_sym + 2 * len)?)) & 0xFFFF;
+            if symbol >= d.symlen.len() {
+                return Err(io::Error::new(io::ErrorKind::InvalidData, "tablebase symbol out of range"));
+            }
+            if offset < i64::from(d.symlen[symbol]) + 1 {
+                break;
+            }
+            offset -= i64::from(d.symlen[symbol]) + 1;
+            len += min_sym_len;
+            buffer <<= len;
+            buffer_size -= len;
+            if buffer_size <= 32 {
+                buffer_size += 32;
+                buffer |= u64::from(self.u32_be(pointer)) << (64 - buffer_size);
+                pointer += 4;
+            }
+        }
+
+        // Expand the pair symbols until we reach the single value we want
+        while d.symlen[symbol] != 0 {
+            let (left, right) = self.btree_entry(d.btree, symbol)?;
+            if offset < i64::from(d.symlen[left]) + 1 {
+                symbol = left;
+            } else {
+                offset -= i64::from(d.symlen[left]) + 1;
+                symbol = right;
+            }
+        }
+        Ok(self.btree_entry(d.btree, symbol)?.0 as u16)
+    }
+
+    /// Convert a stored DTZ value into plies for the given WDL result
+    pub fn map_dtz(&self, file: usize, value: u16, wdl: i8) -> io::Result<i32> {
+        const WDL_MAP: [usize; 5] = [1, 3, 0, 2, 0];
+        let d = self.get(0, file);
+        let mut value = i32::from(value);
+        if d.flags & FLAG_MAPPED != 0 {
+            let index = usize::from(d.map_idx[WDL_MAP[(wdl + 2) as usize]]) + value as usize;
+            value = if d.flags & FLAG_WIDE != 0 {
+                i32::from(self.u16_le(self.map + 2 * index)?)
+            } else {
+                i32::from(self.byte(self.map + index)?)
+            };
+        }
+        if (wdl == 2 && d.flags & FLAG_WIN_PLIES == 0) || (wdl == -2 && d.flags & FLAG_LOSS_PLIES == 0) || wdl == 1 || wdl == -1 {
+            value *= 2;
+        }
+        Ok(value + 1)
+    }
+
+    /// Is this DTZ table stored for the given side to move?  (DTZ tables hold only one side.)
+    pub fn dtz_has_side(&self, stm: usize, file: usize) -> bool {
+        usize::from(self.get(stm, file).flags & FLAG_STM) == stm || (self.symmetric && !self.has_pawns)
+    }
+
+}
+
+/// Parses a table file's contents
+///
+/// `name` is the material part of the file name (e.g. "KRPvKR"); the pieces it lists decide how
+/// positions are indexed.
+pub fn from_bytes(name: &str, bytes: Vec<u8>, dtz: bool) -> io::Result<Table> {
+    let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}",name,message));
+    let sides: Vec<&str> = name.split('v').collect();
+    if sides.len() != 2 || !sides.iter().all(|side| side.starts_with('K') && side.chars().all(|c| "KQRBNP".contains(c))) {
+        return Err(invalid("not a material signature"));
+    }
+    let magic = if dtz { DTZ_MAGIC } else { WDL_MAGIC };
+    if bytes.len() < 5 || bytes[0..4] != magic {
+        return Err(invalid("bad magic number"));
+    }
+
+    let count = |side: &str, letter: char| side.chars().filter(|c| *c == letter).count();
+    let white_pawns = count(sides[0], 'P');
+    let black_pawns = count(sides[1], 'P');
+    let has_unique_pieces = sides.iter().any(|side| "QRBNP".chars().any(|letter| count(side, letter) == 1));
+    let lead_white = black_pawns == 0 || (white_pawns > 0 && black_pawns >= white_pawns);
+    let mut table = Table {
+        bytes,
+        dtz,
+        piece_count: sides[0].len() + sides[1].len(),
+        has_pawns: white_pawns + black_pawns > 0,
+        has_unique_pieces,
+        symmetric: sides[0] == sides[1],
+        pawn_count: if lead_white { [white_pawns, black_pawns] } else { [black_pawns, white_pawns] },
+        items: vec![PairsData::default(); 8],
+        map: 0,
+    };
+    if table.piece_count > TB_PIECES {
+        return Err(invalid("too many pieces"));
+    }
+
+    let header = table.byte(4)?;
+    if (header & 2 != 0) != table.has_pawns || (header & 1 == 0) != table.symmetric {
+        return Err(invalid("header doesn't match the file name"));
+    }
+    let mut offset = 5;
+    let sides_stored = if !dtz && !table.symmetric { 2 } else { 1 };
+    let max_file = if table.has_pawns { 3 } else { 0 };
+    let pawns_on_both_sides = table.has_pawns && table.pawn_count[1] > 0;
+
+    for file in 0..=max_file {
+        let first = table.byte(offset)?;
+        let second = if pawns_on_both_sides { table.byte(offset + 1)? } else { 0xFF };
+        let order = [[first & 0xF, second & 0xF], [first >> 4, second >> 4]];
+        offset += if pawns_on_both_sides { 2 } else { 1 };
+        for k in 0..table.piece_count {
+            let pieces = table.byte(offset)?;
+            for i in 0..sides_stored {
+                table.get_mut(i, file).pieces[k] = if i == 1 { pieces >> 4 } else { pieces & 0xF };
+            }
+            offset += 1;
+        }
+        for (i, side_order) in order.iter().enumerate().take(sides_stored) {
+            table.set_groups(i, *side_order, file);
+        }
+    }
+    offset += offset & 1;
+
+    for file in 0..=max_file {
+        for i in 0..sides_stored {
+            offset = table.set_sizes(i, file, offset)?;
+        }
+    }
+    if dtz {
+        offset = table.set_dtz_map(offset, max_file)?;
+    }
+    for file in 0..=max_file {
+        for i in 0..sides_stored {
+            table.get_mut(i, file).sparse_index = offset;
+            offset += table.get(i, file).sparse_index_size as usize * 6;
+        }
+    }
+    for file in 0..=max_file {
+        for i in 0..sides_stored {
+            table.get_mut(i, file).block_length = offset;
+            offset += table.get(i, file).block_length_size as usize * 2;
+        }
+    }
+    for file in 0..=max_file {
+        for i in 0..sides_stored {
+            offset = (offset + 0x3F) & !0x3F;
+            table.get_mut(i, file).data = offset;
+            let d = table.get(i, file);
+            offset += d.num_blocks as usize * d.block_size as usize;
+        }
+    }
+    if offset > table.bytes.len() {
+        return Err(invalid("file is truncated"));
+    }
+    Ok(table)
+}
+
+fn pawns_order(square: &usize) -> usize {
+    ENCODING.map_pawns[*square]
+}
+
+impl Table {
+
+    /// Value stored for a position, given as (square, piece code) pairs from the point of view
+    /// of the table (stronger side as white)
+    ///
+    /// Returns `Ok(None)` for a DTZ table that doesn't store this side to move.
+    pub fn probe(&self, pieces: &[(usize, u8)], stm: usize, wdl: i8) -> io::Result<Option<i32>> {
+        let (file, idx) = match self.encode(pieces, stm)? {
+            Some(encoded) => encoded,
+            None => return Ok(None),
+        };
+        let value = self.decompress_pairs(self.get(stm, file), idx)?;
+        if self.dtz {
+            Ok(Some(self.map_dtz(file, value, wdl)?))
+        } else {
+            Ok(Some(i32::from(value) - 2))
+        }
+    }
+
+    /// Leading pawn file (0 without pawns) and index of a position within the table
+    ///
+    /// Mirror images of a position share an index.  Returns `Ok(None)` for a DTZ table that
+    /// doesn't store this side to move.
+    pub fn encode(&self, pieces: &[(usize, u8)], stm: usize) -> io::Result<Option<(usize, u64)>> {
+        let e = &*ENCODING;
+        let mut squares: Vec<usize> = Vec::with_capacity(TB_PIECES);
+        let mut codes: Vec<u8> = Vec::with_capacity(TB_PIECES);
+        let mut lead_pawns_count = 0;
+        let mut tb_file = 0;
+
+        if self.has_pawns {
+            let lead_pawn = self.get(0, 0).pieces[0];
+            for (square, code) in pieces.iter() {
+                if *code == lead_pawn {
+                    squares.push(*square);
+                    codes.push(*code);
+                }
+            }
+            lead_pawns_count = squares.len();
+            let mut lead = 0;
+            for i in 1..lead_pawns_count {
+                if pawns_order(&squares[i]) > pawns_order(&squares[lead]) {
+                    lead = i;
+                }
+            }
+            squares.swap(0, lead);
+            let file = file_of(squares[0]);
+            tb_file = file.min(7 - file);
+        }
+
+        if self.dtz && !self.dtz_has_side(stm, tb_file) {
+            return Ok(None);
+        }
+
+        for (square, code) in pieces.iter() {
+            if !(self.has_pawns && *code == self.get(0, 0).pieces[0]) {
+                squares.push(*square);
+                codes.push(*code);
+            }
+        }
+        let size = squares.len();
+        if size != self.piece_count {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "position doesn't match the table"));
+        }
+
+        let d = self.get(stm, tb_file);
+        for i in lead_pawns_count..size.saturating_sub(1) {
+            for j in i + 1..size {
+                if d.pieces[i] == codes[j] {
+                    codes.swap(i, j);
+                    squares.swap(i, j);
+                    break;
+                }
+            }
+        }
+
+        if file_of(squares[0]) > 3 {
+            for square in squares.iter_mut() {
+                *square ^= 7;
+            }
+        }
+
+        let mut idx: u64;
+        if self.has_pawns {
+            idx = e.lead_pawn_idx[lead_pawns_count][squares[0]];
+            squares[1..lead_pawns_count].sort_by_key(pawns_order);
+            for (i, square) in squares.iter().enumerate().take(lead_pawns_count).skip(1) {
+                idx += e.binomial[i][e.map_pawns[*square]];
+            }
+        } else {
+            if rank_of(squares[0]) > 3 {
+                for square in squares.iter_mut() {
+                    *square ^= 56;
+                }
+            }
+            for i in 0..d.group_len[0] {
+                if off_diagonal(squares[i]) == 0 {
+                    continue;
+                }
+                if off_diagonal(squares[i]) > 0 {
+                    for square in squares[i..].iter_mut() {
+                        *square = ((*square >> 3) | (*square << 3)) & 63;
+                    }
+                }
+                break;
+            }
+
+            if self.has_unique_pieces {
+                let s = [squares[0], squares[1], squares[2]];
+                let adjust1 = (s[1] > s[0]) as usize;
+                let adjust2 = (s[2] > s[0]) as usize + (s[2] > s[1]) as usize;
+                idx = if off_diagonal(s[0]) != 0 {
+                    ((e.map_a1d1d4[s[0]] * 63 + (s[1] - adjust1)) * 62 + s[2] - adjust2) as u64
+                } else if off_diagonal(s[1]) != 0 {
+                    ((6 * 63 + rank_of(s[0]) * 28 + e.map_b1h1h7[s[1]]) * 62 + s[2] - adjust2) as u64
+                } else if off_diagonal(s[2]) != 0 {
+                    (6 * 63 * 62 + 4 * 28 * 62 + rank_of(s[0]) * 7 * 28 + (rank_of(s[1]) - adjust1) * 28 + e.map_b1h1h7[s[2]]) as u64
+                } else {
+                    (6 * 63 * 62 + 4 * 28 * 62 + 4 * 7 * 28 + rank_of(s[0]) * 7 * 6 + (rank_of(s[1]) - adjust1) * 6 + (rank_of(s[2]) - adjust2)) as u64
+                };
+            } else {
+                idx = e.map_kk[e.map_a1d1d4[squares[0]]][squares[1]] as u64;
+            }
+        }
+
+        idx *= d.group_idx[0];
+        let mut group_start = d.group_len[0];
+        let mut remaining_pawns = self.has_pawns && self.pawn_count[1] > 0;
+        let mut next = 1;
+        while d.group_len[next] != 0 {
+            let group_end = group_start + d.group_len[next];
+            squares[group_start..group_end].sort_unstable();
+            let mut n: u64 = 0;
+            for i in 0..d.group_len[next] {
+                let square = squares[group_start + i];
+                let adjust = squares[..group_start].iter().filter(|s| square > **s).count();
+                let available = square - adjust - if remaining_pawns { 8 } else { 0 };
+                n += e.binomial[i + 1][available];
+            }
+            remaining_pawns = false;
+            idx += n * d.group_idx[next];
+            group_start = group_end;
+            next += 1;
+        }
+
+        Ok(Some((tb_file, idx)))
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::syzygy::table;
+    use crate::syzygy::table::ENCODING;
+
+    #[test]
+    fn syzygy_king_placements() {
+        // Every one of the 462 king placements gets its own code
+        let mut codes = Vec::new();
+        for idx in 0..10 {
+            for square in 0..64 {
+                if ENCODING.map_kk[idx][square] != 0 {
+                    codes.push(ENCODING.map_kk[idx][square]);
+                }
+            }
+        }
+        codes.sort_unstable();
+        codes.dedup();
+        assert_eq!(codes.len(),461);
+        assert_eq!(*codes.last().unwrap(),461);
+        assert_eq!(ENCODING.binomial[2][62],62 * 61 / 2);
+        assert_eq!(ENCODING.binomial[0][5],1);
+        // a2 is the leading pawn square with most room behind it
+        assert_eq!(ENCODING.map_pawns[8],47);
+        assert_eq!(ENCODING.map_pawns[15],46);
+        assert_eq!(ENCODING.lead_pawns_size[1][0],6);
+    }
+
+    #[test]
+    fn syzygy_rejects_bad_files() {
+        assert!(table::from_bytes("KQvK", vec![0; 64], false).is_err());
+        assert!(table::from_bytes("KQvK", table::DTZ_MAGIC.to_vec(), false).is_err());
+        assert!(table::from_bytes("KQK", table::WDL_MAGIC.to_vec(), false).is_err());
+        let mut truncated = table::WDL_MAGIC.to_vec();
+        truncated.extend_from_slice(&[0x01, 0x00, 0x06, 0x05, 0x0e]);
+        assert!(table::from_bytes("KQvK", truncated, false).is_err());
+    }
+
+    /// Value stored at each index of the synthetic table, with runs of draws to pair up
+    fn synthetic_value(idx: u64) -> u16 {
+        if idx % 7 < 3 { 2 } else { (idx * 5 / 3 % 5) as u16 }
+    }
+
+    /// A KQvK WDL table with white to move compressed into 32-byte blocks of 3-bit symbols:
+    /// symbols 0-4 are the values themselves and symbol 5 is a pair of draws
+    fn synthetic_kqvk() -> table::Table {
+        const TB_SIZE: u64 = 31332;
+        const SPAN: u64 = 64;
+        let mut blocks: Vec<Vec<u8>> = Vec::new();
+        let mut block_starts = Vec::new();
+        let mut block_counts = Vec::new();
+        let mut idx = 0;
+        while idx < TB_SIZE {
+            block_starts.push(idx);
+            let mut block = vec![0u8; 32];
+            let mut bits = 0;
+            while bits + 3 <= 256 && idx < TB_SIZE {
+                let pair = synthetic_value(idx) == 2 && idx + 1 < TB_SIZE && synthetic_value(idx + 1) == 2;
+                let symbol = if pair { 5 } else { synthetic_value(idx) };
+                for bit in 0..3 {
+                    if symbol & (4 >> bit) != 0 {
+                        block[(bits + bit) / 8] |= 0x80 >> ((bits + bit) % 8);
+                    }
+                }
+                bits += 3;
+                idx += if pair { 2 } else { 1 };
+            }
+            block_counts.push(idx - block_starts[block_starts.len() - 1]);
+            blocks.push(block);
+        }
+
+        let mut bytes = table::WDL_MAGIC.to_vec();
+        bytes.extend_from_slice(&[0x01, 0x00, 0x66, 0x55, 0xEE, 0x00]);
+        // 32-byte blocks, span of 64 values, no padding blocks, all symbols 3 bits long
+        bytes.extend_from_slice(&[0x00, 5, 6, 0]);
+        bytes.extend_from_slice(&(blocks.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&[3, 3, 0, 0, 6, 0]);
+        for value in 0..5u8 {
+            bytes.extend_from_slice(&[value, 0xF0, 0xFF]);
+        }
+        bytes.extend_from_slice(&[2, 0x20, 0x00]);
+        // Black to move is a single value
+        bytes.extend_from_slice(&[0x80, 0]);
+        for k in 0..(TB_SIZE + SPAN - 1) / SPAN {
+            let middle = k * SPAN + SPAN / 2;
+            let block = block_starts.iter().rposition(|start| *start <= middle).unwrap();
+            bytes.extend_from_slice(&(block as u32).to_le_bytes());
+            bytes.extend_from_slice(&((middle - block_starts[block]) as u16).to_le_bytes());
+        }
+        for count in block_counts.iter() {
+            bytes.extend_from_slice(&((count - 1) as u16).to_le_bytes());
+        }
+        bytes.resize((bytes.len() + 0x3F) & !0x3F, 0);
+        for block in blocks.iter() {
+            bytes.extend_from_slice(block);
+        }
+        table::from_bytes("KQvK", bytes, false).unwrap()
+    }
+
+    #[test]
+    fn syzygy_decompress_pairs() {
+        let t = synthetic_kqvk();
+        let d = t.get(0, 0);
+        assert_eq!(d.symlen,vec![0, 0, 0, 0, 0, 1]);
+        for idx in 0..31332 {
+            assert_eq!(t.decompress_pairs(d, idx).unwrap(),synthetic_value(idx),"index {}",idx);
+        }
+        assert!(t.decompress_pairs(d, 31332 + 64).is_err());
+        assert_eq!(t.decompress_pairs(t.get(1, 0), 1234).unwrap(),0);
+    }
+
+    #[test]
+    fn syzygy_encode_symmetry() {
+        let t = synthetic_kqvk();
+        let mirrors: [fn(usize) -> usize; 3] = [|s| s ^ 7, |s| s ^ 56, |s| ((s >> 3) | (s << 3)) & 63];
+        let mut used = vec![false; 31332];
+        for king in 0..64 {
+            for queen in 0..64 {
+                for other_king in 0..64 {
+                    if queen == king || queen == other_king || other_king == king || super::king_attacks(king, other_king) {
+                        continue;
+                    }
+                    let pieces = [(king, 6), (queen, 5), (other_king, 14)];
+                    let (_, idx) = t.encode(&pieces, 0).unwrap().unwrap();
+                    assert!(idx < 31332);
+                    used[idx as usize] = true;
+                    for mirror in mirrors.iter() {
+                        let mirrored: Vec<(usize, u8)> = pieces.iter().map(|(s, c)| (mirror(*s), *c)).collect();
+                        assert_eq!(t.encode(&mirrored, 0).unwrap().unwrap().1,idx);
+                    }
+                }
+            }
+        }
+        assert!(used.iter().filter(|u| **u).count() > 25000);
+    }
+
+    #[test]
+    fn syzygy_encode_worked_examples() {
+        // Indices worked out by hand from the Syzygy format rather than from the fixture
+        // generator, one for each way three unique pieces are encoded
+        assert_eq!(ENCODING.map_a1d1d4[1],0);
+        assert_eq!(ENCODING.map_a1d1d4[0],6);
+        assert_eq!(ENCODING.map_a1d1d4[27],9);
+        assert_eq!(ENCODING.map_b1h1h7[7],6);
+        // b1 against d1 is the first placement of two kings
+        assert_eq!(ENCODING.map_kk[0][3],0);
+        assert_eq!(ENCODING.map_kk[0][4],1);
+
+        let t = synthetic_kqvk();
+        let index = |king: usize, queen: usize, other_king: usize| t.encode(&[(king, 6), (queen, 5), (other_king, 14)], 0).unwrap().unwrap().1;
+        // Kb1 Qe4 kh8: (0 * 63 + 27) * 62 + 61
+        assert_eq!(index(1, 28, 63),1735);
+        // Kg8 Qd5 ka1 mirrors onto it
+        assert_eq!(index(62, 35, 0),1735);
+        // Ka1 Qb1 kh8: (6 * 63 + 0 * 28 + 0) * 62 + 61, and Ka1 Qa2 kh8 flips onto it
+        assert_eq!(index(0, 1, 63),23497);
+        assert_eq!(index(0, 8, 63),23497);
+        // Ka1 Qb2 kh1: 6 * 63 * 62 + 4 * 28 * 62 + 0 * 7 * 28 + 0 * 28 + 6
+        assert_eq!(index(0, 9, 7),30386);
+        // Kb2 Qa1 kh8: 6 * 63 * 62 + 4 * 28 * 62 + 4 * 7 * 28 + 1 * 7 * 6 + 0 * 6 + 5
+        assert_eq!(index(9, 0, 63),31211);
+    }
+}
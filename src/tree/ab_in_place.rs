@@ -1,53 +1,138 @@
 use crate::tree::Tree;
-use crate::reset::r#const::SCORE_STALEMATE;
-use crate::reset::r#const::SCORE_BLACK_CHECKMATE;
-use crate::reset::r#const::SCORE_WHITE_CHECKMATE;
+use crate::tree::search_options::SearchOptions;
+use crate::tree::ordering::MoveOrdering;
+use crate::tree::move_stack;
+use crate::tree::move_stack::MoveStack;
+use crate::reset::mate;
+use crate::syzygy;
+use crate::nnue;
+use crate::nnue::accumulator;
 
 impl Tree {
 
-    pub fn alpha_beta_in_place(&mut self, depth: u8, mut min: i32, mut max: i32, move_count: &mut u64) -> i32 {
-        let mut moves_generated: bool = false;
-        if depth == 0 {
-            *move_count += 1;
-            self.reset.score()
+    #[allow(clippy::too_many_arguments)]
+    pub fn alpha_beta_in_place(&mut self, depth: u8, ply: u8, min: i32, max: i32, options: &SearchOptions, ordering: &mut MoveOrdering, move_count: &mut u64) -> i32 {
+        self.accumulator = nnue::network().map(|network| Box::new(accumulator::refresh(&network, &self.reset)));
+        // NNUE accumulators are updated from parent/child pairs, so they stay on copy-make
+        if options.make_unmake && self.accumulator.is_none() {
+            ab_in_place(&mut self.reset, depth, ply, min, max, options, ordering, move_count)
         } else {
-            let mut child: Tree = crate::tree::new();
-            self.reset.conditionally_complete_move_initialization();
-            while self.reset.generate_next_move(&mut child.reset) {
-                moves_generated = true;
-                let temp_score: i32 = child.alpha_beta_in_place(depth-1,min,max,move_count);
-                if self.reset.white_to_move() {
-                    if temp_score > max {
-                        max = temp_score;
-                    }
-                } else if temp_score < min {
-                    min = temp_score;
-                }
-                if min <= max {
-                    break;
-                }
+            let mut stack = move_stack::copy_make(std::mem::replace(self, crate::tree::new()));
+            let score = ab_in_place(&mut stack, depth, ply, min, max, options, ordering, move_count);
+            *self = stack.into_root();
+            score
+        }
+    }
+}
+
+/// Alpha-beta search of the position at the top of `node`, playing each move on it and taking
+/// it back again
+#[allow(clippy::too_many_arguments)]
+fn ab_in_place<S: MoveStack>(node: &mut S, mut depth: u8, ply: u8, mut min: i32, mut max: i32, options: &SearchOptions, ordering: &mut MoveOrdering, move_count: &mut u64) -> i32 {
+    let mut moves_generated: bool = false;
+    if node.reset().in_check() {
+        // Check extension: never stop the search with the side to move in check
+        depth = depth.saturating_add(1);
+    }
+    if depth == 0 {
+        *move_count += 1;
+        mate::adjust_for_ply(node.evaluate(), ply)
+    } else {
+        (min, max) = mate::mate_distance_window(min, max, ply);
+        if min <= max {
+            return if node.reset().white_to_move() { max } else { min };
+        }
+        if let Some(score) = syzygy::probe::search_score(node.reset(), ply) {
+            *move_count += 1;
+            return score;
+        }
+        if options.try_null_move(node.reset(), depth, ply) {
+            if let Some(score) = null_move_in_place(node, depth, ply, min, max, options, ordering, move_count) {
+                return score;
             }
-            if moves_generated {
-                if self.reset.white_to_move() {
-                    max
-                } else {
-                    min
-                }
-            } else if self.reset.in_check() {
-                if self.reset.white_to_move() {
-                    //println!("Found Black Checkmate???");
-                    SCORE_BLACK_CHECKMATE
-                } else {
-                    //println!("Found White Checkmate???");
-                    SCORE_WHITE_CHECKMATE
+        }
+        let mut move_index: usize = 0;
+        node.reset().conditionally_complete_move_initialization();
+        let mut picker = S::picker(ply);
+        while let Some(picked) = node.pick(&mut picker, ordering) {
+            moves_generated = true;
+            let reduce = node.reduce_late_move(options, &picked, depth, move_index);
+            let undo = node.make(&picked);
+            let temp_score: i32 = if reduce {
+                late_move_in_place(node, depth, ply, min, max, options, ordering, move_count)
+            } else {
+                ab_in_place(node, depth-1, ply+1, min, max, options, ordering, move_count)
+            };
+            node.unmake(undo);
+            move_index += 1;
+            if node.reset().white_to_move() {
+                if temp_score > max {
+                    max = temp_score;
                 }
+            } else if temp_score < min {
+                min = temp_score;
+            }
+            if min <= max {
+                node.record_cutoff(&picked, ply, depth, ordering);
+                break;
+            }
+        }
+        if moves_generated {
+            if node.reset().white_to_move() {
+                max
             } else {
-                    //println!("Found Stalemate???");
-                SCORE_STALEMATE
+                min
             }
+        } else {
+            // Checkmate, stalemate, or a variant's own ending
+            mate::adjust_for_ply(node.reset().terminal_score(), ply)
         }
     }
+}
+
+/// Let the side to move pass and search the opponent's reply with a null window at the edge
+/// of the side to move's bound.  Returns the bound if the pass alone is enough to cut off.
+#[allow(clippy::too_many_arguments)]
+fn null_move_in_place<S: MoveStack>(node: &mut S, depth: u8, ply: u8, min: i32, max: i32, options: &SearchOptions, ordering: &mut MoveOrdering, move_count: &mut u64) -> Option<i32> {
+    let white = node.reset().white_to_move();
+    let reduced_depth = depth - 1 - options.null_move_reduction;
+    let undo = node.make_null();
+    let result = if white {
+        let score = ab_in_place(node, reduced_depth, ply+1, min, min - 1, options, ordering, move_count);
+        if score >= min { Some(min) } else { None }
+    } else {
+        let score = ab_in_place(node, reduced_depth, ply+1, max + 1, max, options, ordering, move_count);
+        if score <= max { Some(max) } else { None }
+    };
+    node.unmake(undo);
+    // Made on this node, the null move search used up its move generation
+    node.reset().restart_move_generation();
+    result
+}
 
+/// Search a late quiet move, already made on `node`, one ply shallower with a null window.  If
+/// it turns out better than the best move so far, verify that with a full-depth, full-window
+/// re-search.
+#[allow(clippy::too_many_arguments)]
+fn late_move_in_place<S: MoveStack>(node: &mut S, depth: u8, ply: u8, min: i32, max: i32, options: &SearchOptions, ordering: &mut MoveOrdering, move_count: &mut u64) -> i32 {
+    // The move is made, so the side that chose it is the opponent of the side to move now
+    let white = !node.reset().white_to_move();
+    let reduced_score = if white {
+        ab_in_place(node, depth.saturating_sub(2), ply+1, max + 1, max, options, ordering, move_count)
+    } else {
+        ab_in_place(node, depth.saturating_sub(2), ply+1, min, min - 1, options, ordering, move_count)
+    };
+    let improves = if white {
+        reduced_score > max
+    } else {
+        reduced_score < min
+    };
+    if improves {
+        node.reset().restart_move_generation();
+        ab_in_place(node, depth-1, ply+1, min, max, options, ordering, move_count)
+    } else {
+        reduced_score
+    }
 }
 
 
@@ -59,19 +144,23 @@ mod tests {
     use crate::reset::r#const::SCORE_STALEMATE;
     use crate::reset::r#const::SCORE_WHITE_CHECKMATE;
     use crate::reset::r#const::SCORE_BLACK_CHECKMATE;
+    use crate::tree::search_options;
+    use crate::tree::ordering;
+    use crate::reset::mate::checkmate_score;
+    use crate::reset::variant::Variant;
 
     #[test]
     fn ab_in_place_stalemate_test() {
         let fen = String::from("8/8/8/8/8/3K4/3B4/3k4 b - - 0 1");
         let mut t: Tree = crate::tree::from_fen(fen);
         let mut move_count: u64 = 0;
-        let score = t.alpha_beta_in_place(8, SCORE_MAX, SCORE_MIN, &mut move_count);
+        let score = t.alpha_beta_in_place(8, 0, SCORE_MAX, SCORE_MIN, &search_options::new(), &mut ordering::new(), &mut move_count);
         assert_eq!(score,SCORE_STALEMATE);
 
         let fen = String::from("7K/5k2/p4n2/Pp2b3/1P6/8/8/8 w - - 0 1");
         let mut t: Tree = crate::tree::from_fen(fen);
         let mut move_count: u64 = 0;
-        let score = t.alpha_beta_in_place(8, SCORE_MAX, SCORE_MIN, &mut move_count);
+        let score = t.alpha_beta_in_place(8, 0, SCORE_MAX, SCORE_MIN, &search_options::new(), &mut ordering::new(), &mut move_count);
         assert_eq!(score,SCORE_STALEMATE);
     }
 
@@ -80,14 +169,131 @@ mod tests {
         let fen = String::from("r1bqkbnr/pppp1Qpp/8/4p3/2BnP3/8/PPPP1PPP/RNB1K1NR b KQkq - 0 1");
         let mut t: Tree = crate::tree::from_fen(fen);
         let mut move_count: u64 = 0;
-        let score = t.alpha_beta_in_place(8, SCORE_MAX, SCORE_MIN, &mut move_count);
+        let score = t.alpha_beta_in_place(8, 0, SCORE_MAX, SCORE_MIN, &search_options::new(), &mut ordering::new(), &mut move_count);
         assert_eq!(score,SCORE_WHITE_CHECKMATE);
 
         let fen = String::from("8/7P/5n2/1P6/2P2p2/4k3/8/r3K3 w - - 0 1");
         let mut t: Tree = crate::tree::from_fen(fen);
         let mut move_count: u64 = 0;
-        let score = t.alpha_beta_in_place(8, SCORE_MAX, SCORE_MIN, &mut move_count);
+        let score = t.alpha_beta_in_place(8, 0, SCORE_MAX, SCORE_MIN, &search_options::new(), &mut ordering::new(), &mut move_count);
         assert_eq!(score,SCORE_BLACK_CHECKMATE);
     }
-}
 
+    #[test]
+    fn ab_in_place_shortest_mate_test() {
+        // Ra8 mates at once; the engine must not prefer a slower mate
+        let fen = String::from("6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1");
+        let mut t: Tree = crate::tree::from_fen(fen);
+        let mut move_count: u64 = 0;
+        let score = t.alpha_beta_in_place(5, 0, SCORE_MAX, SCORE_MIN, &search_options::new(), &mut ordering::new(), &mut move_count);
+        assert_eq!(score,checkmate_score(false, 1));
+
+        // 1. Kb6 Kb8 2. Rh8#
+        let fen = String::from("k7/8/2K5/8/8/8/8/7R w - - 0 1");
+        let mut t: Tree = crate::tree::from_fen(fen);
+        let mut move_count: u64 = 0;
+        let score = t.alpha_beta_in_place(5, 0, SCORE_MAX, SCORE_MIN, &search_options::new(), &mut ordering::new(), &mut move_count);
+        assert_eq!(score,checkmate_score(false, 3));
+    }
+
+    #[test]
+    fn ab_in_place_variant_goal_test() {
+        // Kd4 wins King of the Hill, Ra8+ is a third check, Kg8 wins the race
+        let goals = [
+            ("7k/8/8/8/8/4K3/8/8 w - - 0 1", Variant::KingOfTheHill),
+            ("4k3/8/8/8/8/8/8/R3K3 w - - 0 1 +2+0", Variant::ThreeCheck),
+            ("8/6K1/8/k7/8/8/8/8 w - - 0 1", Variant::RacingKings),
+        ];
+        for (fen, variant) in goals {
+            let mut t: Tree = crate::tree::from_fen(fen.to_string());
+            t.reset.set_variant(variant);
+            let mut move_count: u64 = 0;
+            let score = t.alpha_beta_in_place(4, 0, SCORE_MAX, SCORE_MIN, &search_options::new(), &mut ordering::new(), &mut move_count);
+            assert_eq!(score,checkmate_score(false, 1),"{:?}",variant);
+        }
+    }
+
+    #[test]
+    fn ab_in_place_check_extension_test() {
+        // 1. Re8+ Rxe8 2. Rxe8# is three plies deep, but only two of them are searched without
+        // being in check
+        let fen = String::from("r5k1/5ppp/8/8/8/8/4RPPP/4R1K1 w - - 0 1");
+        let mut t: Tree = crate::tree::from_fen(fen);
+        let mut move_count: u64 = 0;
+        let score = t.alpha_beta_in_place(2, 0, SCORE_MAX, SCORE_MIN, &search_options::new(), &mut ordering::new(), &mut move_count);
+        assert_eq!(score,checkmate_score(false, 3));
+    }
+
+    #[test]
+    fn ab_in_place_selectivity_test() {
+        let mut options = search_options::new();
+        options.null_move = true;
+        options.late_move_reductions = true;
+
+        let fen = String::from("r1bqkbnr/pppp1Qpp/8/4p3/2BnP3/8/PPPP1PPP/RNB1K1NR b KQkq - 0 1");
+        let mut t: Tree = crate::tree::from_fen(fen);
+        let mut move_count: u64 = 0;
+        let score = t.alpha_beta_in_place(6, 0, SCORE_MAX, SCORE_MIN, &options, &mut ordering::new(), &mut move_count);
+        assert_eq!(score,SCORE_WHITE_CHECKMATE);
+
+        let fen = String::from("8/8/8/8/8/3K4/3B4/3k4 b - - 0 1");
+        let mut t: Tree = crate::tree::from_fen(fen);
+        let mut move_count: u64 = 0;
+        let score = t.alpha_beta_in_place(6, 0, SCORE_MAX, SCORE_MIN, &options, &mut ordering::new(), &mut move_count);
+        assert_eq!(score,SCORE_STALEMATE);
+    }
+
+    #[test]
+    fn ab_in_place_selectivity_saves_nodes() {
+        let fen = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+        let mut full_count: u64 = 0;
+        let mut t: Tree = crate::tree::from_fen(fen.to_string());
+        t.alpha_beta_in_place(5, 0, SCORE_MAX, SCORE_MIN, &search_options::new(), &mut ordering::new(), &mut full_count);
+
+        let mut options = search_options::new();
+        options.null_move = true;
+        let mut null_move_count: u64 = 0;
+        let mut t: Tree = crate::tree::from_fen(fen.to_string());
+        t.alpha_beta_in_place(5, 0, SCORE_MAX, SCORE_MIN, &options, &mut ordering::new(), &mut null_move_count);
+        assert!(null_move_count < full_count,"null move {} vs {}",null_move_count,full_count);
+
+        let mut options = search_options::new();
+        options.late_move_reductions = true;
+        let mut lmr_count: u64 = 0;
+        let mut t: Tree = crate::tree::from_fen(fen.to_string());
+        t.alpha_beta_in_place(5, 0, SCORE_MAX, SCORE_MIN, &options, &mut ordering::new(), &mut lmr_count);
+        assert!(lmr_count < full_count,"lmr {} vs {}",lmr_count,full_count);
+    }
+
+    #[test]
+    fn ab_make_unmake_matches_copy_make() {
+        let positions = [
+            ("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1", Variant::Standard, 4),
+            ("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1", Variant::Standard, 3),
+            ("r1bqkbnr/pppp1Qpp/8/4p3/2BnP3/8/PPPP1PPP/RNB1K1NR b KQkq - 0 1", Variant::Standard, 4),
+            ("r1b1k2r/ppp2ppp/2n5/3q4/1b1P4/2N2N2/PP3PPP/R2QKB1R w KQkq - 0 1", Variant::Crazyhouse, 3),
+            ("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1", Variant::Atomic, 3),
+            ("4k3/8/8/8/8/8/8/R3K3 w - - 0 1 +2+0", Variant::ThreeCheck, 4),
+        ];
+        for selective in [false, true] {
+            let mut options = search_options::new();
+            options.null_move = selective;
+            options.late_move_reductions = selective;
+            for (fen, variant, depth) in positions {
+                let mut t: Tree = crate::tree::from_fen(fen.to_string());
+                t.reset.set_variant(variant);
+                let mut copy_make_count: u64 = 0;
+                let copy_make_score = t.alpha_beta_in_place(depth, 0, SCORE_MAX, SCORE_MIN, &options, &mut ordering::new(), &mut copy_make_count);
+
+                let mut make_unmake_options = options;
+                make_unmake_options.make_unmake = true;
+                let mut t: Tree = crate::tree::from_fen(fen.to_string());
+                t.reset.set_variant(variant);
+                let mut make_unmake_count: u64 = 0;
+                let make_unmake_score = t.alpha_beta_in_place(depth, 0, SCORE_MAX, SCORE_MIN, &make_unmake_options, &mut ordering::new(), &mut make_unmake_count);
+                assert_eq!(make_unmake_score,copy_make_score,"{} selective={}",fen,selective);
+                assert_eq!(make_unmake_count,copy_make_count,"{} selective={}",fen,selective);
+            }
+        }
+    }
+}
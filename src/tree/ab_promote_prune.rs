@@ -1,28 +1,48 @@
 use crate::tree::Tree;
-use crate::reset::r#const::SCORE_STALEMATE;
-use crate::reset::r#const::SCORE_BLACK_CHECKMATE;
-use crate::reset::r#const::SCORE_WHITE_CHECKMATE;
+use crate::tree::search_options::SearchOptions;
+use crate::tree::ordering::MoveOrdering;
+use crate::tree::move_picker;
+use crate::reset::mate;
+use crate::syzygy;
 use crate::tree::r#const::MAX_CHILDREN_KEPT;
 use std::sync::atomic::{AtomicBool, Ordering};
 
-#[allow(clippy::never_loop)]
+#[allow(clippy::never_loop, clippy::too_many_arguments)]
 impl Tree {
 
     /// Use Alpha-Beta search to that promotes the best move found and prunes when done searching
     ///
-    pub fn alpha_beta_promote_prune(&mut self, depth: u8, max_depth: u8, mut min: i32, mut max: i32, red_light: &AtomicBool, move_count: &mut u64) -> i32 {
+    pub fn alpha_beta_promote_prune(&mut self, depth: u8, mut max_depth: u8, mut min: i32, mut max: i32, options: &SearchOptions, ordering: &mut MoveOrdering, red_light: &AtomicBool, move_count: &mut u64) -> i32 {
         let mut moves_generated: bool = false;
         let mut boards_seen: Vec<u32> = Vec::new();
-        if depth == max_depth {
+        let mut move_index: usize = 0;
+        if self.reset.in_check() {
+            // Check extension: never stop the search with the side to move in check
+            max_depth = max_depth.saturating_add(1);
+        }
+        if depth >= max_depth {
             *move_count += 1;
-            self.reset.score()
+            mate::adjust_for_ply(self.reset.score(), depth)
         } else {
+            (min, max) = mate::mate_distance_window(min, max, depth);
+            if min <= max {
+                return if self.reset.white_to_move() { max } else { min };
+            }
+            if let Some(score) = syzygy::probe::search_score(&self.reset, depth) {
+                *move_count += 1;
+                return score;
+            }
+            if options.try_null_move(&self.reset, max_depth - depth, depth) {
+                if let Some(score) = self.null_move_promote_prune(depth, max_depth, min, max, options, ordering, red_light, move_count) {
+                    return score;
+                }
+            }
             'outer: loop {
                 for c in 0..self.children.len() {
-                    let child = &mut self.children[c];
                     moves_generated = true;
-                    boards_seen.push(child.reset.child_hash());
-                    let temp_score: i32 = child.alpha_beta_promote_prune(depth+1,max_depth,min,max,red_light, move_count);
+                    boards_seen.push(self.children[c].reset.child_hash());
+                    let temp_score: i32 = self.search_child_promote_prune(c, depth, max_depth, min, max, move_index, options, ordering, red_light, move_count);
+                    move_index += 1;
                     if red_light.load(Ordering::Relaxed) {
                         break 'outer;
                     }
@@ -36,20 +56,19 @@ impl Tree {
                         min = temp_score;
                     }
                     if min <= max {
+                        ordering.record_cutoff(depth, max_depth - depth, &self.reset, &self.children[0].reset);
                         break 'outer;
                     }
                 }
                 self.reset.initialize_move_generation();
                 self.reset.complete_move_initialization();
-                while self.add_next_child() {
-                    let child = self.children.last_mut().unwrap();
-                    if boards_seen.contains(&child.reset.child_hash()) {
-                        self.children.truncate(MAX_CHILDREN_KEPT);
-                        continue;
-                    } else {
-                        moves_generated = true;
-                    }
-                    let temp_score: i32 = child.alpha_beta_promote_prune(depth+1,max_depth,min,max,red_light,move_count);
+                let mut picker = move_picker::new(depth, &boards_seen);
+                while let Some(child) = picker.next_child(&mut self.reset, ordering) {
+                    self.add_child_last(child);
+                    let c = self.children.len()-1;
+                    moves_generated = true;
+                    let temp_score: i32 = self.search_child_promote_prune(c, depth, max_depth, min, max, move_index, options, ordering, red_light, move_count);
+                    move_index += 1;
                     if red_light.load(Ordering::Relaxed) {
                         break 'outer;
                     }
@@ -64,6 +83,7 @@ impl Tree {
                     }
                     self.children.truncate(MAX_CHILDREN_KEPT);
                     if min <= max {
+                        ordering.record_cutoff(depth, max_depth - depth, &self.reset, &self.children[0].reset);
                         break 'outer;
                     }
                 }
@@ -75,16 +95,56 @@ impl Tree {
                 } else {
                     min
                 }
-            } else if self.reset.in_check() {
-                if self.reset.white_to_move() {
-                    SCORE_BLACK_CHECKMATE
-                } else {
-                    SCORE_WHITE_CHECKMATE
-                }
             } else {
-                SCORE_STALEMATE
+                // Checkmate, stalemate, or a variant's own ending
+                mate::adjust_for_ply(self.reset.terminal_score(), depth)
+            }
+        }
+    }
+
+    /// Search one child, reducing it if it is a late quiet move.  A reduced search that beats
+    /// the best score so far is verified with a full-depth, full-window re-search.
+    fn search_child_promote_prune(&mut self, c: usize, depth: u8, max_depth: u8, min: i32, max: i32, move_index: usize, options: &SearchOptions, ordering: &mut MoveOrdering, red_light: &AtomicBool, move_count: &mut u64) -> i32 {
+        let white_to_move = self.reset.white_to_move();
+        let reduce = options.reduce_late_move(&self.reset, &self.children[c].reset, max_depth - depth, move_index);
+        let child = &mut self.children[c];
+        if reduce {
+            let reduced_depth = (depth+2).min(max_depth);
+            let reduced_score = if white_to_move {
+                child.alpha_beta_promote_prune(reduced_depth, max_depth, max + 1, max, options, ordering, red_light, move_count)
+            } else {
+                child.alpha_beta_promote_prune(reduced_depth, max_depth, min, min - 1, options, ordering, red_light, move_count)
+            };
+            let improves = if white_to_move {
+                reduced_score > max
+            } else {
+                reduced_score < min
+            };
+            if !improves {
+                return reduced_score;
             }
         }
+        child.alpha_beta_promote_prune(depth+1, max_depth, min, max, options, ordering, red_light, move_count)
+    }
+
+    /// Let the side to move pass and search the opponent's reply with a null window at the edge
+    /// of the side to move's bound.  Returns the bound if the pass alone is enough to cut off.
+    fn null_move_promote_prune(&mut self, depth: u8, max_depth: u8, min: i32, max: i32, options: &SearchOptions, ordering: &mut MoveOrdering, red_light: &AtomicBool, move_count: &mut u64) -> Option<i32> {
+        let mut null_child: Tree = crate::tree::new();
+        self.reset.init_null_child(&mut null_child.reset);
+        let null_depth = depth + 1 + options.null_move_reduction;
+        if self.reset.white_to_move() {
+            let score = null_child.alpha_beta_promote_prune(null_depth, max_depth, min, min - 1, options, ordering, red_light, move_count);
+            if score >= min && !red_light.load(Ordering::Relaxed) {
+                return Some(min);
+            }
+        } else {
+            let score = null_child.alpha_beta_promote_prune(null_depth, max_depth, max + 1, max, options, ordering, red_light, move_count);
+            if score <= max && !red_light.load(Ordering::Relaxed) {
+                return Some(max);
+            }
+        }
+        None
     }
 
 }
@@ -100,6 +160,10 @@ mod tests {
     use crate::reset::r#const::SCORE_BLACK_CHECKMATE;
     use std::sync::Arc;
     use std::sync::atomic::{AtomicBool};
+    use crate::tree::search_options;
+    use crate::tree::ordering;
+    use crate::reset::mate::checkmate_score;
+    use crate::reset::variant::Variant;
 
 
     #[test]
@@ -109,13 +173,13 @@ mod tests {
         let fen = String::from("8/8/8/8/8/3K4/3B4/3k4 b - - 0 1");
         let mut t: Tree = crate::tree::from_fen(fen);
         let mut move_count: u64 = 0;
-        let score = t.alpha_beta_promote_prune(0, 8, SCORE_MAX, SCORE_MIN, &red_light, &mut move_count);
+        let score = t.alpha_beta_promote_prune(0, 8, SCORE_MAX, SCORE_MIN, &search_options::new(), &mut ordering::new(), &red_light, &mut move_count);
         assert_eq!(score,SCORE_STALEMATE);
 
         let fen = String::from("7K/5k2/p4n2/Pp2b3/1P6/8/8/8 w - - 0 1");
         let mut t: Tree = crate::tree::from_fen(fen);
         let mut move_count: u64 = 0;
-        let score = t.alpha_beta_promote_prune(0, 8, SCORE_MAX, SCORE_MIN, &red_light, &mut move_count);
+        let score = t.alpha_beta_promote_prune(0, 8, SCORE_MAX, SCORE_MIN, &search_options::new(), &mut ordering::new(), &red_light, &mut move_count);
         assert_eq!(score,SCORE_STALEMATE);
     }
 
@@ -126,15 +190,61 @@ mod tests {
         let fen = String::from("r1bqkbnr/pppp1Qpp/8/4p3/2BnP3/8/PPPP1PPP/RNB1K1NR b KQkq - 0 1");
         let mut t: Tree = crate::tree::from_fen(fen);
         let mut move_count: u64 = 0;
-        let score = t.alpha_beta_promote_prune(0, 8, SCORE_MAX, SCORE_MIN,&red_light, &mut move_count);
+        let score = t.alpha_beta_promote_prune(0, 8, SCORE_MAX, SCORE_MIN, &search_options::new(), &mut ordering::new(), &red_light, &mut move_count);
         assert_eq!(score,SCORE_WHITE_CHECKMATE);
 
         let fen = String::from("8/7P/5n2/1P6/2P2p2/4k3/8/r3K3 w - - 0 1");
         let mut t: Tree = crate::tree::from_fen(fen);
         let mut move_count: u64 = 0;
-        let score = t.alpha_beta_promote_prune(0, 8, SCORE_MAX, SCORE_MIN, &red_light, &mut move_count);
+        let score = t.alpha_beta_promote_prune(0, 8, SCORE_MAX, SCORE_MIN, &search_options::new(), &mut ordering::new(), &red_light, &mut move_count);
         assert_eq!(score,SCORE_BLACK_CHECKMATE);
     }
-}
 
+    #[test]
+    fn ab_keep_depth_variant_goal_test() {
+        // The black king reaches the eighth rank first
+        let red_light = Arc::new(AtomicBool::new(false));
+        let fen = String::from("8/k7/8/8/8/6K1/8/8 b - - 0 1");
+        let mut t: Tree = crate::tree::from_fen(fen);
+        t.reset.set_variant(Variant::RacingKings);
+        let mut move_count: u64 = 0;
+        let score = t.alpha_beta_promote_prune(0, 4, SCORE_MAX, SCORE_MIN, &search_options::new(), &mut ordering::new(), &red_light, &mut move_count);
+        assert_eq!(score,checkmate_score(true, 1));
+    }
+
+    #[test]
+    fn ab_keep_depth_mate_distance_test() {
+        let red_light = Arc::new(AtomicBool::new(false));
+        let fen = String::from("k7/8/2K5/8/8/8/8/7R w - - 0 1");
+        let mut t: Tree = crate::tree::from_fen(fen);
+        let mut move_count: u64 = 0;
+        let score = t.alpha_beta_promote_prune(0, 5, SCORE_MAX, SCORE_MIN, &search_options::new(), &mut ordering::new(), &red_light, &mut move_count);
+        assert_eq!(score,checkmate_score(false, 3));
 
+        let fen = String::from("r5k1/5ppp/8/8/8/8/4RPPP/4R1K1 w - - 0 1");
+        let mut t: Tree = crate::tree::from_fen(fen);
+        let mut move_count: u64 = 0;
+        let score = t.alpha_beta_promote_prune(0, 2, SCORE_MAX, SCORE_MIN, &search_options::new(), &mut ordering::new(), &red_light, &mut move_count);
+        assert_eq!(score,checkmate_score(false, 3));
+    }
+
+    #[test]
+    fn ab_keep_depth_selectivity_test() {
+        let red_light = Arc::new(AtomicBool::new(false));
+        let mut options = search_options::new();
+        options.null_move = true;
+        options.late_move_reductions = true;
+
+        let fen = String::from("8/7P/5n2/1P6/2P2p2/4k3/8/r3K3 w - - 0 1");
+        let mut t: Tree = crate::tree::from_fen(fen);
+        let mut move_count: u64 = 0;
+        let score = t.alpha_beta_promote_prune(0, 6, SCORE_MAX, SCORE_MIN, &options, &mut ordering::new(), &red_light, &mut move_count);
+        assert_eq!(score,SCORE_BLACK_CHECKMATE);
+
+        let fen = String::from("7K/5k2/p4n2/Pp2b3/1P6/8/8/8 w - - 0 1");
+        let mut t: Tree = crate::tree::from_fen(fen);
+        let mut move_count: u64 = 0;
+        let score = t.alpha_beta_promote_prune(0, 6, SCORE_MAX, SCORE_MIN, &options, &mut ordering::new(), &red_light, &mut move_count);
+        assert_eq!(score,SCORE_STALEMATE);
+    }
+}
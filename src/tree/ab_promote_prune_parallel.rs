@@ -1,11 +1,12 @@
 use crate::tree::Tree;
-use crate::reset::r#const::SCORE_STALEMATE;
-use crate::reset::r#const::SCORE_BLACK_CHECKMATE;
-use crate::reset::r#const::SCORE_WHITE_CHECKMATE;
+use crate::tree::search_options::SearchOptions;
+use crate::tree::ordering::MoveOrdering;
+use crate::tree::move_picker;
+use crate::reset::mate;
 use crate::tree::r#const::MAX_CHILDREN_KEPT;
 use std::sync::atomic::{AtomicI32, AtomicBool, Ordering};
 
-#[allow(clippy::never_loop)]
+#[allow(clippy::never_loop, clippy::too_many_arguments)]
 impl Tree {
 
     /// Use Alpha-Beta search to that promotes the best move found and prunes when done searching
@@ -13,9 +14,11 @@ impl Tree {
     pub fn alpha_beta_promote_prune_parallel(
         &mut self,
         depth: u8,
-        max_depth: u8,
+        mut max_depth: u8,
         min: &AtomicI32,
         max: &AtomicI32,
+        options: &SearchOptions,
+        ordering: &mut MoveOrdering,
         red_light: &AtomicBool,
         move_count: &mut u64) -> (bool,i32)
     {
@@ -25,9 +28,13 @@ impl Tree {
         let mut moves_generated: bool = false;
         let mut boards_seen: Vec<u32> = Vec::new();
         let mut successful_search: bool = true;
-        if depth == max_depth {
+        if self.reset.in_check() {
+            // Check extension: never stop the search with the side to move in check
+            max_depth = max_depth.saturating_add(1);
+        }
+        if depth >= max_depth {
             *move_count += 1;
-            (true, self.reset.score())
+            (true, mate::adjust_for_ply(self.reset.score(), depth))
         } else {
             'outer: loop {
                 for c in 0..self.children.len() {
@@ -35,7 +42,7 @@ impl Tree {
                     moves_generated = true;
                     boards_seen.push(child.reset.child_hash());
 
-                    let temp_score: i32 = child.alpha_beta_promote_prune(depth+1, max_depth, local_min, local_max, red_light, move_count);
+                    let temp_score: i32 = child.alpha_beta_promote_prune(depth+1, max_depth, local_min, local_max, options, ordering, red_light, move_count);
                     if red_light.load(Ordering::Relaxed) {
                         successful_search = false;
                         break 'outer;
@@ -60,15 +67,12 @@ impl Tree {
                 }
                 self.reset.initialize_move_generation();
                 self.reset.complete_move_initialization();
-                while self.add_next_child() {
+                let mut picker = move_picker::new(depth, &boards_seen);
+                while let Some(child) = picker.next_child(&mut self.reset, ordering) {
+                    self.add_child_last(child);
                     let child = self.children.last_mut().unwrap();
-                    if boards_seen.contains(&child.reset.child_hash()) {
-                        self.children.truncate(MAX_CHILDREN_KEPT);
-                        continue;
-                    } else {
-                        moves_generated = true;
-                    }
-                    let temp_score: i32 = child.alpha_beta_promote_prune(depth+1, max_depth, local_min, local_max, red_light, move_count);
+                    moves_generated = true;
+                    let temp_score: i32 = child.alpha_beta_promote_prune(depth+1, max_depth, local_min, local_max, options, ordering, red_light, move_count);
                     if red_light.load(Ordering::Relaxed) {
                         successful_search = false;
                         break 'outer;
@@ -101,14 +105,9 @@ impl Tree {
                 } else {
                     (successful_search, local_min)
                 }
-            } else if self.reset.in_check() {
-                if self.reset.white_to_move() {
-                    (true, SCORE_BLACK_CHECKMATE)
-                } else {
-                    (true, SCORE_WHITE_CHECKMATE)
-                }
             } else {
-                (true, SCORE_STALEMATE)
+                // Checkmate, stalemate, or a variant's own ending
+                (true, mate::adjust_for_ply(self.reset.terminal_score(), depth))
             }
         }
     }
@@ -126,6 +125,8 @@ mod tests {
     use crate::reset::r#const::SCORE_BLACK_CHECKMATE;
     use std::sync::Arc;
     use std::sync::atomic::{AtomicBool,AtomicI32};
+    use crate::tree::search_options;
+    use crate::tree::ordering;
 
 
     #[test]
@@ -137,14 +138,14 @@ mod tests {
         let fen = String::from("8/8/8/8/8/3K4/3B4/3k4 b - - 0 1");
         let mut t: Tree = crate::tree::from_fen(fen);
         let mut move_count: u64 = 0;
-        let (success, score) = t.alpha_beta_promote_prune_parallel(0, 8, &search_min, &search_max, &red_light, &mut move_count);
+        let (success, score) = t.alpha_beta_promote_prune_parallel(0, 8, &search_min, &search_max, &search_options::new(), &mut ordering::new(), &red_light, &mut move_count);
         assert!(success);
         assert_eq!(score,SCORE_STALEMATE);
 
         let fen = String::from("7K/5k2/p4n2/Pp2b3/1P6/8/8/8 w - - 0 1");
         let mut t: Tree = crate::tree::from_fen(fen);
         let mut move_count: u64 = 0;
-        let (success, score) = t.alpha_beta_promote_prune_parallel(0, 8, &search_min, &search_max, &red_light, &mut move_count);
+        let (success, score) = t.alpha_beta_promote_prune_parallel(0, 8, &search_min, &search_max, &search_options::new(), &mut ordering::new(), &red_light, &mut move_count);
         assert!(success);
         assert_eq!(score,SCORE_STALEMATE);
     }
@@ -158,14 +159,14 @@ mod tests {
         let fen = String::from("r1bqkbnr/pppp1Qpp/8/4p3/2BnP3/8/PPPP1PPP/RNB1K1NR b KQkq - 0 1");
         let mut t: Tree = crate::tree::from_fen(fen);
         let mut move_count: u64 = 0;
-        let (success, score) = t.alpha_beta_promote_prune_parallel(0, 8, &search_min, &search_max, &red_light, &mut move_count);
+        let (success, score) = t.alpha_beta_promote_prune_parallel(0, 8, &search_min, &search_max, &search_options::new(), &mut ordering::new(), &red_light, &mut move_count);
         assert!(success);
         assert_eq!(score,SCORE_WHITE_CHECKMATE);
 
         let fen = String::from("8/7P/5n2/1P6/2P2p2/4k3/8/r3K3 w - - 0 1");
         let mut t: Tree = crate::tree::from_fen(fen);
         let mut move_count: u64 = 0;
-        let (success, score) = t.alpha_beta_promote_prune_parallel(0, 8, &search_min, &search_max, &red_light, &mut move_count);
+        let (success, score) = t.alpha_beta_promote_prune_parallel(0, 8, &search_min, &search_max, &search_options::new(), &mut ordering::new(), &red_light, &mut move_count);
         assert!(success);
         assert_eq!(score,SCORE_BLACK_CHECKMATE);
     }
@@ -5,3 +5,15 @@
 //
 pub const MAX_EXPECTED_CHILD_MOVES: usize = 40;
 pub const MAX_CHILDREN_KEPT: usize = 3;
+
+// Move ordering bands used by the move picker.  Captures and promotions (ordered by MVV-LVA)
+// come first, then the two killer moves for the ply, then quiet moves ordered by history.
+//
+pub const ORDER_CAPTURE: i32 = 30000000;
+pub const ORDER_KILLER_1: i32 = 20000001;
+pub const ORDER_KILLER_2: i32 = 20000000;
+pub const ORDER_HISTORY_MAX: i32 = 19999999;
+
+// Proof and disproof numbers of a node that can never be proven (or disproven)
+//
+pub const PN_INFINITY: u32 = u32::MAX;
@@ -0,0 +1,153 @@
+use crate::tree::Tree;
+
+impl Tree {
+
+    /// Look for a forced checkmate by the side to move in at most `max_moves` moves
+    ///
+    /// This is a proof search rather than an alpha-beta search: an attacking node is won if any
+    /// move leads to a defending node that is lost, and a defending node is lost only if it is
+    /// checkmated or every reply leads to a won attacking node.  No evaluation is involved, so a
+    /// `None` result is a proof that no mate exists within `max_moves` (or, with `checks_only`,
+    /// that no mate consisting only of checking moves exists).
+    ///
+    /// The search deepens one move at a time, so the first mate found is the shortest.  The
+    /// mating line is left in the tree (see `mating_line`), with the defender choosing the reply
+    /// that holds out longest.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut t = chessica::tree::from_fen("k7/8/2K5/8/8/8/8/7R w - - 0 1".to_string());
+    /// let mut move_count: u64 = 0;
+    /// assert_eq!(t.mate_search(3, false, &mut move_count),Some(2));
+    /// assert_eq!(t.mating_line(),"c6c7 a8a7 h1a1");
+    /// ```
+    pub fn mate_search(&mut self, max_moves: u8, checks_only: bool, move_count: &mut u64) -> Option<u8> {
+        (1..=max_moves).find(|&moves| {
+            self.purge_children();
+            self.reset.restart_move_generation();
+            self.attacker_mates(moves, checks_only, move_count)
+        })
+    }
+
+    /// The mating line found by `mate_search`, in UCI move notation
+    pub fn mating_line(&mut self) -> String {
+        let mut line: Vec<String> = Vec::new();
+        let mut node = &mut *self;
+        while !node.children.is_empty() {
+            node = &mut node.children[0];
+            line.push(node.reset.uci_move_text());
+        }
+        line.join(" ")
+    }
+
+    /// Can the side to move force mate in `moves` moves?  On success the mating move is left
+    /// as the only child.
+    fn attacker_mates(&mut self, moves: u8, checks_only: bool, move_count: &mut u64) -> bool {
+        self.reset.conditionally_complete_move_initialization();
+        while self.add_next_child() {
+            *move_count += 1;
+            let child = self.children.last_mut().unwrap();
+            if (!checks_only || child.reset.in_check()) && child.defender_loses(moves, checks_only, move_count) {
+                let mating_move = self.children.pop().unwrap();
+                self.children.clear();
+                self.add_child_last(mating_move);
+                return true;
+            }
+            self.children.clear();
+        }
+        false
+    }
+
+    /// Is the side to move checkmated, or mated in `moves` moves (counting the attacker's move
+    /// that led here as the first)?  On success the longest defence is left as the only child.
+    fn defender_loses(&mut self, moves: u8, checks_only: bool, move_count: &mut u64) -> bool {
+        self.reset.conditionally_complete_move_initialization();
+        let mut longest_defence: Option<(u8, Tree)> = None;
+        while self.add_next_child() {
+            *move_count += 1;
+            if moves <= 1 {
+                // The defender has a legal move, so this isn't checkmate
+                self.children.clear();
+                return false;
+            }
+            let mut reply = self.children.pop().unwrap();
+            let mated_in = (1..moves).find(|&m| {
+                reply.purge_children();
+                reply.reset.restart_move_generation();
+                reply.attacker_mates(m, checks_only, move_count)
+            });
+            match mated_in {
+                None => return false,
+                Some(m) => {
+                    let longer = match &longest_defence {
+                        Some((longest, _)) => m > *longest,
+                        None => true,
+                    };
+                    if longer {
+                        longest_defence = Some((m, reply));
+                    }
+                },
+            }
+        }
+        match longest_defence {
+            Some((_, reply)) => {
+                self.add_child_last(reply);
+                true
+            },
+            None => self.reset.in_check(),
+        }
+    }
+
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::tree::Tree;
+
+    fn mate(fen: &str, max_moves: u8, checks_only: bool) -> (Option<u8>, String) {
+        let mut t: Tree = crate::tree::from_fen(fen.to_string());
+        let mut move_count: u64 = 0;
+        let result = t.mate_search(max_moves, checks_only, &mut move_count);
+        (result, t.mating_line())
+    }
+
+    #[test]
+    fn mate_search_mate_in_one() {
+        let (result, line) = mate("6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1", 3, false);
+        assert_eq!(result,Some(1));
+        assert_eq!(line,"a1a8");
+
+        let (result, line) = mate("r5k1/5ppp/8/8/8/8/5PPP/6K1 b - - 0 1", 3, false);
+        assert_eq!(result,Some(1));
+        assert_eq!(line,"a8a1");
+    }
+
+    #[test]
+    fn mate_search_mate_in_two() {
+        let (result, line) = mate("r5k1/5ppp/8/8/8/8/4RPPP/4R1K1 w - - 0 1", 2, true);
+        assert_eq!(result,Some(2));
+        assert_eq!(line,"e2e8 a8e8 e1e8");
+    }
+
+    #[test]
+    fn mate_search_no_mate() {
+        let (result, line) = mate("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1", 2, false);
+        assert_eq!(result,None);
+        assert_eq!(line,"");
+
+        // Stalemate is not mate
+        let (result, _line) = mate("k7/8/1Q6/8/8/8/8/7K b - - 0 1", 1, false);
+        assert_eq!(result,None);
+    }
+
+    #[test]
+    fn mate_search_checks_only() {
+        // Every mate in 2 starts with a quiet king move
+        let (result, _line) = mate("k7/8/2K5/8/8/8/8/7R w - - 0 1", 2, false);
+        assert_eq!(result,Some(2));
+        let (result, _line) = mate("k7/8/2K5/8/8/8/8/7R w - - 0 1", 2, true);
+        assert_eq!(result,None);
+    }
+}
@@ -0,0 +1,222 @@
+use crate::tree::Tree;
+use crate::tree::mcts_options::MctsOptions;
+use crate::tree::mcts_options::PlayoutType;
+use crate::reset::Reset;
+use crate::reset::r#const::SCORE_PAWN;
+use crate::reset::r#const::SCORE_BLACK_CHECKMATE;
+use crate::reset::r#const::SCORE_WHITE_CHECKMATE;
+use crate::utils::rng;
+use crate::utils::rng::Rng;
+
+const FIFTY_MOVE_RULE_PLIES: u16 = 100;
+
+/// Value of a game over position, from white's point of view (1 = white wins, 0 = black wins)
+fn terminal_value(reset: &Reset) -> f64 {
+    match reset.terminal_score() {
+        SCORE_WHITE_CHECKMATE => 1.0,
+        SCORE_BLACK_CHECKMATE => 0.0,
+        _ => 0.5,
+    }
+}
+
+/// Map an absolute score onto an expected result for white, using the usual logistic curve of
+/// a 400 centipawn scale
+fn score_to_value(score: i32) -> f64 {
+    let centipawns = score as f64 / (SCORE_PAWN / 100) as f64;
+    1.0 / (1.0 + 10f64.powf(-centipawns / 400.0))
+}
+
+impl Tree {
+
+    /// Monte Carlo tree search with UCT (UCB1 applied to trees)
+    ///
+    /// Each iteration walks down the tree choosing the child with the best upper confidence
+    /// bound, expands one new child with `add_next_child`, values it with a playout and backs
+    /// the result up.  Every node keeps its visit count and the sum of the results (from
+    /// white's point of view) that passed through it.  The tree is rebuilt from scratch.
+    ///
+    /// Returns the index of the most visited child, which is the move to play.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut t = chessica::tree::from_fen("6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1".to_string());
+    /// let mut options = chessica::tree::mcts_options::new();
+    /// options.iterations = 2000;
+    /// let mut move_count: u64 = 0;
+    /// let best = t.mcts_search(&options, &mut move_count).unwrap();
+    /// assert_eq!(t.children[best].reset.move_text(),"a1a8");
+    /// ```
+    pub fn mcts_search(&mut self, options: &MctsOptions, move_count: &mut u64) -> Option<usize> {
+        let mut rng = rng::new(options.seed);
+        self.purge_children();
+        self.visits = 0;
+        self.value_sum = 0.0;
+        self.reset.restart_move_generation();
+        for _i in 0..options.iterations {
+            self.mcts_iterate(options, &mut rng, move_count);
+        }
+        self.mcts_best_child()
+    }
+
+    /// Index of the child with the most visits
+    pub fn mcts_best_child(&self) -> Option<usize> {
+        (0..self.children.len()).max_by_key(|&c| self.children[c].visits)
+    }
+
+    /// Expected result for white of this node, from the playouts that passed through it
+    pub fn mcts_value(&self) -> f64 {
+        if self.visits == 0 {
+            0.5
+        } else {
+            self.value_sum / self.visits as f64
+        }
+    }
+
+    /// The line MCTS expects, following the most visited child at each level
+    pub fn mcts_principal_variation(&mut self) -> String {
+        let mut line: Vec<String> = Vec::new();
+        let mut node = &mut *self;
+        while let Some(best) = node.mcts_best_child() {
+            node = &mut node.children[best];
+            line.push(node.reset.uci_move_text());
+        }
+        line.join(" ")
+    }
+
+    /// One select/expand/playout/backup cycle.  Returns the playout result for white.
+    fn mcts_iterate(&mut self, options: &MctsOptions, rng: &mut Rng, move_count: &mut u64) -> f64 {
+        self.reset.conditionally_complete_move_initialization();
+        let value = if self.reset.halfmove_clock() >= FIFTY_MOVE_RULE_PLIES {
+            0.5
+        } else if self.add_next_child() {
+            let child = self.children.last_mut().unwrap();
+            let value = child.playout(options, rng, move_count);
+            child.visits += 1;
+            child.value_sum += value;
+            value
+        } else if self.children.is_empty() {
+            terminal_value(&self.reset)
+        } else {
+            let best = self.uct_select(options);
+            self.children[best].mcts_iterate(options, rng, move_count)
+        };
+        self.visits += 1;
+        self.value_sum += value;
+        value
+    }
+
+    /// Pick the child with the highest UCB1 value for the side to move
+    fn uct_select(&self, options: &MctsOptions) -> usize {
+        let log_visits = (self.visits.max(1) as f64).ln();
+        let white_to_move = self.reset.white_to_move();
+        let mut best = 0;
+        let mut best_bound = f64::MIN;
+        for (c, child) in self.children.iter().enumerate() {
+            let mean = if white_to_move {
+                child.mcts_value()
+            } else {
+                1.0 - child.mcts_value()
+            };
+            let bound = mean + options.exploration * (log_visits / child.visits.max(1) as f64).sqrt();
+            if bound > best_bound {
+                best_bound = bound;
+                best = c;
+            }
+        }
+        best
+    }
+
+    fn playout(&mut self, options: &MctsOptions, rng: &mut Rng, move_count: &mut u64) -> f64 {
+        *move_count += 1;
+        match options.playout {
+            PlayoutType::Evaluation => score_to_value(self.reset.score()),
+            PlayoutType::Random => random_rollout(&mut self.reset, options, rng, move_count),
+        }
+    }
+
+}
+
+/// Play random legal moves from `reset` until the game ends or `rollout_depth` runs out
+fn random_rollout(reset: &mut Reset, options: &MctsOptions, rng: &mut Rng, move_count: &mut u64) -> f64 {
+    let mut current = crate::reset::clone::clone_from(reset);
+    let mut child = crate::reset::new();
+    let mut moves: Vec<Reset> = Vec::new();
+    for _ply in 0..options.rollout_depth {
+        if current.halfmove_clock() >= FIFTY_MOVE_RULE_PLIES {
+            return 0.5;
+        }
+        moves.clear();
+        current.restart_move_generation();
+        while current.generate_next_move(&mut child) {
+            moves.push(crate::reset::clone::clone_from(&mut child));
+        }
+        if moves.is_empty() {
+            return terminal_value(&current);
+        }
+        *move_count += 1;
+        current = moves.swap_remove(rng.below(moves.len()));
+    }
+    score_to_value(current.score())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::tree::Tree;
+    use crate::tree::mcts_options;
+    use crate::tree::mcts_options::PlayoutType;
+
+    #[test]
+    fn mcts_finds_mate_in_one() {
+        for playout in [PlayoutType::Evaluation, PlayoutType::Random] {
+            let mut t: Tree = crate::tree::from_fen("6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1".to_string());
+            let mut options = mcts_options::new();
+            options.iterations = 3000;
+            options.playout = playout;
+            let mut move_count: u64 = 0;
+            let best = t.mcts_search(&options, &mut move_count).unwrap();
+            assert_eq!(t.children[best].reset.move_text(),"a1a8","{:?}",playout);
+            assert_eq!(t.visits,3000);
+            assert!(t.mcts_value() > 0.5);
+        }
+    }
+
+    #[test]
+    fn mcts_visits_add_up() {
+        let mut t: Tree = crate::tree::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1".to_string());
+        let mut options = mcts_options::new();
+        options.iterations = 500;
+        let mut move_count: u64 = 0;
+        t.mcts_search(&options, &mut move_count);
+        assert_eq!(t.children.len(),20);
+        let child_visits: u32 = t.children.iter().map(|c| c.visits).sum();
+        assert_eq!(child_visits,500);
+        assert!(!t.mcts_principal_variation().is_empty());
+    }
+
+    #[test]
+    fn mcts_repeatable() {
+        let fen = "r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3";
+        let mut options = mcts_options::new();
+        options.iterations = 300;
+        options.playout = PlayoutType::Random;
+        options.rollout_depth = 20;
+        let mut lines = Vec::new();
+        for _i in 0..2 {
+            let mut t: Tree = crate::tree::from_fen(fen.to_string());
+            let mut move_count: u64 = 0;
+            t.mcts_search(&options, &mut move_count);
+            lines.push((t.mcts_principal_variation(), t.value_sum));
+        }
+        assert_eq!(lines[0],lines[1]);
+    }
+
+    #[test]
+    fn mcts_game_over() {
+        let mut t: Tree = crate::tree::from_fen("k7/8/1Q6/8/8/8/8/7K b - - 0 1".to_string());
+        let mut move_count: u64 = 0;
+        assert_eq!(t.mcts_search(&mcts_options::new(), &mut move_count),None);
+        assert_eq!(t.mcts_value(),0.5);
+    }
+}
@@ -0,0 +1,45 @@
+/// How a Monte Carlo tree search values a newly expanded node
+#[derive(PartialEq,Eq,Copy,Clone,Debug)]
+pub enum PlayoutType {
+    /// Play random legal moves until the game ends (or `rollout_depth` runs out)
+    Random,
+    /// Use the static evaluation of the node directly
+    Evaluation,
+}
+
+/// Settings for the MCTS/UCT search
+///
+/// | field          | description |
+/// | -------------- | ----------- |
+/// | iterations     | Number of select/expand/playout/backup cycles per search |
+/// | exploration    | UCB1 exploration constant; larger values spread visits more evenly |
+/// | playout        | Random rollouts or static evaluation |
+/// | rollout_depth  | Longest random rollout, in plies, before falling back to the evaluation |
+/// | seed           | Seed for the random number generator used by rollouts |
+#[derive(PartialEq,Copy,Clone,Debug)]
+pub struct MctsOptions {
+    pub iterations: u32,
+    pub exploration: f64,
+    pub playout: PlayoutType,
+    pub rollout_depth: u16,
+    pub seed: u64,
+}
+
+/// Constructs a new set of MctsOptions
+///
+/// # Examples
+///
+/// ```
+/// use chessica::tree::mcts_options::PlayoutType;
+/// let options = chessica::tree::mcts_options::new();
+/// assert_eq!(options.playout,PlayoutType::Evaluation);
+/// ```
+pub fn new() -> MctsOptions {
+    MctsOptions {
+        iterations: 10000,
+        exploration: std::f64::consts::SQRT_2,
+        playout: PlayoutType::Evaluation,
+        rollout_depth: 200,
+        seed: 0,
+    }
+}
@@ -4,14 +4,29 @@ pub mod ab_in_place;
 pub mod ab_promote_prune;
 pub mod ab_iterative_promote_prune;
 pub mod ab_promote_prune_parallel;
+pub mod search_options;
+pub mod ordering;
+pub mod move_picker;
+pub mod move_stack;
+pub mod mate_search;
+pub mod pn_search;
+pub mod mcts_options;
+pub mod mcts;
 
 use crate::reset::Reset;
+use crate::nnue::accumulator::Accumulator;
 
 pub struct Tree
 {
     pub reset: Reset,
     pub children: Vec<Tree>,
     pub score: Option<i32>,
+    pub proof_number: u32,
+    pub disproof_number: u32,
+    pub visits: u32,
+    pub value_sum: f64,
+    /// NNUE accumulator, kept up to date by the in-place search
+    pub accumulator: Option<Box<Accumulator>>,
 }
 
 unsafe impl Send for Tree {}
@@ -24,6 +39,11 @@ pub fn new() -> Tree {
         reset: crate::reset::new(),
         children: emptyvec,
         score: None,
+        proof_number: 1,
+        disproof_number: 1,
+        visits: 0,
+        value_sum: 0.0,
+        accumulator: None,
     }
 }
 
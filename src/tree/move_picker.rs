@@ -0,0 +1,348 @@
+use crate::reset::Reset;
+use crate::reset::make::Move;
+use crate::reset::moves::MoveFilter;
+use crate::tree::Tree;
+use crate::tree::ordering::MoveOrdering;
+use crate::tree::ordering::OrderedMove;
+
+/// Where a MovePicker is in handing out a node's moves
+#[derive(PartialEq,Eq,Clone,Copy,Debug)]
+enum Stage {
+    Captures,
+    Quiet,
+    Done,
+}
+
+/// Something a MovePicker can hand out for each legal move of a parent
+pub trait Picked: Sized + OrderedMove {
+    /// Append one of each for the parent's moves that get past `filter`, in move generation
+    /// order
+    fn generate(parent: &mut Reset, filter: MoveFilter, picked: &mut Vec<Self>);
+}
+
+/// Children are built by the move generator, which is left ready to generate every move again
+impl Picked for Reset {
+    fn generate(parent: &mut Reset, filter: MoveFilter, picked: &mut Vec<Reset>) {
+        parent.restart_move_generation();
+        parent.set_move_filter(filter);
+        let mut child = crate::reset::new();
+        while parent.generate_next_move(&mut child) {
+            picked.push(crate::reset::clone::clone_from(&mut child));
+        }
+        parent.restart_move_generation();
+    }
+}
+
+/// Moves come straight from the parent's bitboards, without building any children
+impl Picked for Move {
+    fn generate(parent: &mut Reset, filter: MoveFilter, picked: &mut Vec<Move>) {
+        parent.generate_moves(filter, picked);
+    }
+}
+
+/// Hands out the children of a Reset best-first, generating them a stage at a time
+///
+/// The first stage generates only the captures and promotions, ordered by MVV-LVA.  Only once
+/// those are used up does the picker generate the quiet moves: the ply's killer moves first,
+/// then the rest by history score.  A node that cuts off on a capture never generates its quiet
+/// moves at all.  Within a stage, children are selected lazily and ties keep generation order.
+///
+/// Children whose `child_hash` is in the skip list are left out.  The promote-prune searches
+/// use this to search the children they kept from the last iteration (their best moves so
+/// far, in place of a hash move) before the picker's stages.
+///
+/// A picker built by `new_moves` hands out `Move`s to make on the parent instead of children.
+pub struct MovePicker<T = Reset> {
+    children: Vec<T>,
+    scores: Vec<i32>,
+    next_index: usize,
+    stage: Stage,
+    ply: u8,
+    skip: Vec<u32>,
+    generated: usize,
+}
+
+fn new_picker<T>(ply: u8, skip: &[u32]) -> MovePicker<T> {
+    MovePicker {
+        children: Vec::new(),
+        scores: Vec::new(),
+        next_index: 0,
+        stage: Stage::Captures,
+        ply,
+        skip: skip.to_vec(),
+        generated: 0,
+    }
+}
+
+/// Constructs a MovePicker for children at `ply`, skipping any child whose `child_hash` is in
+/// `skip`
+///
+/// Nothing is generated until the first child is asked for.  The parent passed to each call
+/// must be the same position, ready to generate moves.
+///
+/// # Examples
+///
+/// ```
+/// let mut r = chessica::reset::new();
+/// r.init_from_fen("4k3/8/8/3q4/4P3/8/8/4K3 w - - 0 1".to_string());
+/// let ordering = chessica::tree::ordering::new();
+/// let mut picker = chessica::tree::move_picker::new(0, &[]);
+/// let mut child = chessica::reset::new();
+/// assert!(picker.next_move(&mut r, &ordering, &mut child));
+/// assert_eq!(child.move_text(),"e4d5");
+/// assert_eq!(picker.generated(),1);
+/// ```
+pub fn new(ply: u8, skip: &[u32]) -> MovePicker {
+    new_picker(ply, skip)
+}
+
+/// Constructs a MovePicker that hands out a parent's moves rather than its children
+///
+/// # Examples
+///
+/// ```
+/// let mut r = chessica::reset::new();
+/// r.init_from_fen("4k3/8/8/3q4/4P3/8/8/4K3 w - - 0 1".to_string());
+/// let ordering = chessica::tree::ordering::new();
+/// let mut picker = chessica::tree::move_picker::new_moves(0);
+/// let mv = picker.next_made_move(&mut r, &ordering).unwrap();
+/// r.make_move(mv);
+/// assert_eq!(r.move_text(),"e4d5");
+/// ```
+pub fn new_moves(ply: u8) -> MovePicker<Move> {
+    new_picker(ply, &[])
+}
+
+impl<T: Picked> MovePicker<T> {
+
+    /// Number of children generated so far, over all the stages reached
+    pub fn generated(&self) -> usize {
+        self.generated
+    }
+
+    /// Generate the children of one stage
+    fn generate_stage(&mut self, parent: &mut Reset, ordering: &MoveOrdering, filter: MoveFilter) {
+        self.children.clear();
+        self.scores.clear();
+        self.next_index = 0;
+        T::generate(parent, filter, &mut self.children);
+        if !self.skip.is_empty() {
+            let skip = &self.skip;
+            self.children.retain(|child| !skip.contains(&child.child_hash()));
+        }
+        for child in self.children.iter() {
+            self.scores.push(ordering.order_score(self.ply, parent, child));
+        }
+        self.generated += self.children.len();
+    }
+
+    /// Index of the next-best child, moving on to the next stage when this one is used up
+    fn select_next(&mut self, parent: &mut Reset, ordering: &MoveOrdering) -> Option<usize> {
+        while self.next_index >= self.children.len() {
+            match self.stage {
+                Stage::Captures => {
+                    self.generate_stage(parent, ordering, MoveFilter::Captures);
+                    self.stage = Stage::Quiet;
+                },
+                Stage::Quiet => {
+                    self.generate_stage(parent, ordering, MoveFilter::Quiet);
+                    self.stage = Stage::Done;
+                },
+                Stage::Done => return None,
+            }
+        }
+        let mut best = self.next_index;
+        for i in (self.next_index+1)..self.children.len() {
+            if self.scores[i] > self.scores[best] {
+                best = i;
+            }
+        }
+        // Rotate rather than swap so that equally-scored moves keep generation order
+        self.children[self.next_index..=best].rotate_right(1);
+        self.scores[self.next_index..=best].rotate_right(1);
+        self.next_index += 1;
+        Some(self.next_index - 1)
+    }
+}
+
+impl MovePicker<Move> {
+
+    /// The next-best move, or `None` once all moves are used up
+    ///
+    /// Any earlier move must be unmade before asking for the next.
+    pub fn next_made_move(&mut self, parent: &mut Reset, ordering: &MoveOrdering) -> Option<Move> {
+        let index = self.select_next(parent, ordering)?;
+        Some(self.children[index])
+    }
+}
+
+impl MovePicker {
+
+    /// Copy the next-best child into `child`.  Returns `false` once all children are used up.
+    pub fn next_move(&mut self, parent: &mut Reset, ordering: &MoveOrdering, child: &mut Reset) -> bool {
+        match self.select_next(parent, ordering) {
+            Some(index) => {
+                self.children[index].clone_to(child);
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Build a Tree node for the next-best child
+    pub fn next_child(&mut self, parent: &mut Reset, ordering: &MoveOrdering) -> Option<Tree> {
+        let index = self.select_next(parent, ordering)?;
+        let mut tree = crate::tree::new();
+        self.children[index].clone_to(&mut tree.reset);
+        Some(tree)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::reset;
+    use crate::reset::moves::MoveFilter;
+    use crate::tree::move_picker;
+    use crate::tree::ordering;
+
+    #[test]
+    fn move_picker_order() {
+        let mut r = reset::new();
+        r.init_from_fen("4k3/8/8/r6n/8/8/8/R3K2R w - - 0 1".to_string());
+        let mut o = ordering::new();
+
+        // Find a quiet move to make into a killer
+        let mut picker = move_picker::new(2, &[]);
+        let mut child = reset::new();
+        let mut quiet_moves = Vec::new();
+        while picker.next_move(&mut r, &o, &mut child) {
+            if !child.is_capture_or_promotion() {
+                quiet_moves.push(child.move_text());
+            }
+            if child.move_text() == "e1d2" {
+                o.record_cutoff(2, 1, &r, &child);
+            }
+        }
+        assert_eq!(picker.generated(),quiet_moves.len()+2);
+
+        let mut picker = move_picker::new(2, &[]);
+        let mut order = Vec::new();
+        while let Some(mut tree) = picker.next_child(&mut r, &o) {
+            order.push(tree.reset.move_text());
+        }
+        assert_eq!(order[0],"a1a5");
+        assert_eq!(order[1],"h1h5");
+        assert_eq!(order[2],"e1d2");
+        let mut remaining = quiet_moves.clone();
+        remaining.retain(|m| m != "e1d2");
+        assert_eq!(order[3..].to_vec(),remaining,"quiet moves keep generation order");
+    }
+
+    #[test]
+    fn move_picker_stages() {
+        // Quiet moves aren't generated until the captures are used up
+        let mut r = reset::new();
+        r.init_from_fen("4k3/8/8/r6n/8/8/8/R3K2R w - - 0 1".to_string());
+        let o = ordering::new();
+        let mut picker = move_picker::new(0, &[]);
+        let mut child = reset::new();
+        assert!(picker.next_move(&mut r, &o, &mut child));
+        assert!(picker.next_move(&mut r, &o, &mut child));
+        assert_eq!(picker.generated(),2);
+        assert!(picker.next_move(&mut r, &o, &mut child));
+        assert!(picker.generated() > 2);
+
+        // The parent is left generating every move
+        let mut all = 0;
+        while r.generate_next_move(&mut child) {
+            all += 1;
+        }
+        while picker.next_move(&mut r, &o, &mut child) {}
+        assert_eq!(picker.generated(),all);
+
+        // A position without captures goes straight on to the quiet moves
+        let mut r = reset::new();
+        r.init_from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1".to_string());
+        let mut picker = move_picker::new(0, &[]);
+        assert!(picker.next_move(&mut r, &o, &mut child));
+        assert_eq!(picker.generated(),20);
+    }
+
+    #[test]
+    fn move_picker_filters_partition_moves() {
+        // Between them the capture and quiet stages hand out every move exactly once
+        for fen in [
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+            "8/8/3p4/KPp4r/1R3p1k/8/4P1P1/8 w - c6 0 2",
+            "n1n5/PPPk4/8/8/8/8/4Kppp/5N1N b - - 0 1",
+            "2k5/8/8/8/8/8/8/4K3[QRBNPqrbnp] w - - 0 1",
+        ] {
+            let mut r = reset::fen::from_fen(fen).unwrap();
+            let mut child = reset::new();
+            let mut moves = |filter: MoveFilter| {
+                let mut texts = Vec::new();
+                r.restart_move_generation();
+                r.set_move_filter(filter);
+                while r.generate_next_move(&mut child) {
+                    assert_eq!(child.is_capture_or_promotion(),filter == MoveFilter::Captures,"{} {}",fen,child.move_text());
+                    texts.push(child.move_text());
+                }
+                texts
+            };
+            let mut staged = moves(MoveFilter::Captures);
+            staged.extend(moves(MoveFilter::Quiet));
+            staged.sort();
+            let mut all = Vec::new();
+            r.restart_move_generation();
+            while r.generate_next_move(&mut child) {
+                all.push(child.move_text());
+            }
+            all.sort();
+            assert_eq!(staged,all,"{}",fen);
+        }
+    }
+
+    #[test]
+    fn move_picker_skip() {
+        let mut r = reset::new();
+        r.init_from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1".to_string());
+        let o = ordering::new();
+        let mut child = reset::new();
+        r.generate_next_move(&mut child);
+        let skip = [child.child_hash()];
+        r.restart_move_generation();
+        let mut picker = move_picker::new(0, &skip);
+        let mut count = 0;
+        while picker.next_move(&mut r, &o, &mut child) {
+            assert_ne!(child.child_hash(),skip[0]);
+            count += 1;
+        }
+        assert_eq!(count,19);
+    }
+
+    #[test]
+    fn move_picker_moves_match_children() {
+        let mut r = reset::new();
+        r.init_from_fen("4k3/8/8/r6n/8/8/8/R3K2R w - - 0 1".to_string());
+        let mut o = ordering::new();
+        let mut child = reset::new();
+        r.generate_next_move(&mut child);
+        o.record_cutoff(2, 1, &r, &child);
+
+        r.restart_move_generation();
+        let mut picker = move_picker::new(2, &[]);
+        let mut children = Vec::new();
+        while picker.next_move(&mut r, &o, &mut child) {
+            children.push(child.move_text());
+        }
+        let mut picker = move_picker::new_moves(2);
+        let mut moves = Vec::new();
+        while let Some(mv) = picker.next_made_move(&mut r, &o) {
+            let undo = r.make_move(mv);
+            moves.push(r.move_text());
+            r.unmake_move(&undo);
+        }
+        assert_eq!(moves,children);
+    }
+}
@@ -0,0 +1,191 @@
+use crate::reset::Reset;
+use crate::reset::make::Move;
+use crate::reset::make::Undo;
+use crate::tree::Tree;
+use crate::tree::move_picker;
+use crate::tree::move_picker::MovePicker;
+use crate::tree::ordering::MoveOrdering;
+use crate::tree::search_options::SearchOptions;
+
+/// The position a search is at, with every move that led to it able to be taken back
+///
+/// The in-place alpha-beta search is written once over this.  `CopyMake` copies each move into
+/// the next of a stack of Trees; a `Reset` plays each move on itself with `Reset::make_move`.
+/// Either way the search sees the same moves in the same order.
+pub trait MoveStack {
+    /// Hands out the moves of a position
+    type Picker;
+    /// A move handed out by the picker, not yet played
+    type Picked;
+    /// What taking a played move back needs
+    type Undo;
+
+    /// Constructs a picker for the moves at `ply`
+    fn picker(ply: u8) -> Self::Picker;
+
+    /// The position the search is at
+    fn reset(&mut self) -> &mut Reset;
+
+    /// Static score of the position, from white's point of view
+    fn evaluate(&mut self) -> i32;
+
+    /// The next-best move from `picker`, or `None` once all moves are used up
+    fn pick(&mut self, picker: &mut Self::Picker, ordering: &MoveOrdering) -> Option<Self::Picked>;
+
+    /// `SearchOptions::reduce_late_move` for a picked move
+    fn reduce_late_move(&self, options: &SearchOptions, picked: &Self::Picked, remaining_depth: u8, move_index: usize) -> bool;
+
+    /// Play a picked move
+    fn make(&mut self, picked: &Self::Picked) -> Self::Undo;
+
+    /// Let the side to move pass
+    fn make_null(&mut self) -> Self::Undo;
+
+    /// Take back the last move played
+    fn unmake(&mut self, undo: Self::Undo);
+
+    /// `MoveOrdering::record_cutoff` for a picked move that has been taken back
+    fn record_cutoff(&mut self, picked: &Self::Picked, ply: u8, depth: u8, ordering: &mut MoveOrdering);
+}
+
+/// Plays each move into a child Tree, one per ply, keeping the NNUE accumulators up to date
+pub struct CopyMake {
+    nodes: Vec<Tree>,
+    top: usize,
+}
+
+/// Constructs a copy-make stack with `root` at the bottom
+pub fn copy_make(root: Tree) -> CopyMake {
+    CopyMake {
+        nodes: vec![root],
+        top: 0,
+    }
+}
+
+impl CopyMake {
+
+    /// Hand back the Tree the stack was constructed with
+    pub fn into_root(mut self) -> Tree {
+        self.nodes.swap_remove(0)
+    }
+
+    /// The node at the top of the stack and the one above it, for its child
+    fn parent_and_child(&mut self) -> (&mut Tree, &mut Tree) {
+        if self.nodes.len() == self.top + 1 {
+            self.nodes.push(crate::tree::new());
+        }
+        let (parents, children) = self.nodes.split_at_mut(self.top + 1);
+        (&mut parents[self.top], &mut children[0])
+    }
+}
+
+/// Bring a child's NNUE accumulator up to date from its parent's
+fn update_child_accumulator(parent: &Tree, child: &mut Tree) {
+    match (&parent.accumulator, &mut child.accumulator) {
+        (None, _) => child.accumulator = None,
+        (Some(from), Some(to)) => to.update(from, &parent.reset, &child.reset),
+        (Some(from), to) => {
+            let mut updated = from.clone();
+            updated.update(from, &parent.reset, &child.reset);
+            *to = Some(updated);
+        },
+    }
+}
+
+impl MoveStack for CopyMake {
+    type Picker = MovePicker;
+    type Picked = ();
+    type Undo = ();
+
+    fn picker(ply: u8) -> MovePicker {
+        move_picker::new(ply, &[])
+    }
+
+    fn reset(&mut self) -> &mut Reset {
+        &mut self.nodes[self.top].reset
+    }
+
+    fn evaluate(&mut self) -> i32 {
+        let node = &mut self.nodes[self.top];
+        node.reset.score_with(node.accumulator.as_deref())
+    }
+
+    /// The picked move is copied into the child, ready for `make`
+    fn pick(&mut self, picker: &mut MovePicker, ordering: &MoveOrdering) -> Option<()> {
+        let (parent, child) = self.parent_and_child();
+        if picker.next_move(&mut parent.reset, ordering, &mut child.reset) {
+            Some(())
+        } else {
+            None
+        }
+    }
+
+    fn reduce_late_move(&self, options: &SearchOptions, _picked: &(), remaining_depth: u8, move_index: usize) -> bool {
+        options.reduce_late_move(&self.nodes[self.top].reset, &self.nodes[self.top + 1].reset, remaining_depth, move_index)
+    }
+
+    fn make(&mut self, _picked: &()) {
+        let (parent, child) = self.parent_and_child();
+        update_child_accumulator(parent, child);
+        self.top += 1;
+    }
+
+    fn make_null(&mut self) {
+        let (parent, child) = self.parent_and_child();
+        parent.reset.init_null_child(&mut child.reset);
+        child.accumulator = parent.accumulator.clone();
+        self.top += 1;
+    }
+
+    fn unmake(&mut self, _undo: ()) {
+        self.top -= 1;
+    }
+
+    fn record_cutoff(&mut self, _picked: &(), ply: u8, depth: u8, ordering: &mut MoveOrdering) {
+        let (parent, child) = self.parent_and_child();
+        ordering.record_cutoff(ply, depth, &parent.reset, &child.reset);
+    }
+}
+
+/// Plays each move on the Reset itself with `Reset::make_move`, evaluating without NNUE
+impl MoveStack for Reset {
+    type Picker = MovePicker<Move>;
+    type Picked = Move;
+    type Undo = Undo;
+
+    fn picker(ply: u8) -> MovePicker<Move> {
+        move_picker::new_moves(ply)
+    }
+
+    fn reset(&mut self) -> &mut Reset {
+        self
+    }
+
+    fn evaluate(&mut self) -> i32 {
+        self.score_with(None)
+    }
+
+    fn pick(&mut self, picker: &mut MovePicker<Move>, ordering: &MoveOrdering) -> Option<Move> {
+        picker.next_made_move(self, ordering)
+    }
+
+    fn reduce_late_move(&self, options: &SearchOptions, picked: &Move, remaining_depth: u8, move_index: usize) -> bool {
+        options.reduce_late_made_move(self, *picked, remaining_depth, move_index)
+    }
+
+    fn make(&mut self, picked: &Move) -> Undo {
+        self.make_move(*picked)
+    }
+
+    fn make_null(&mut self) -> Undo {
+        self.make_null_move()
+    }
+
+    fn unmake(&mut self, undo: Undo) {
+        self.unmake_move(&undo);
+    }
+
+    fn record_cutoff(&mut self, picked: &Move, ply: u8, depth: u8, ordering: &mut MoveOrdering) {
+        ordering.record_cutoff(ply, depth, self, picked);
+    }
+}
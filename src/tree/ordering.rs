@@ -0,0 +1,254 @@
+use crate::reset::Reset;
+use crate::reset::make::Move;
+use crate::tree::r#const::ORDER_CAPTURE;
+use crate::tree::r#const::ORDER_KILLER_1;
+use crate::tree::r#const::ORDER_KILLER_2;
+use crate::tree::r#const::ORDER_HISTORY_MAX;
+
+const NO_KILLER: u32 = 0xffffffff;
+
+/// Move ordering heuristics learned during a search
+///
+/// * Killer moves: the last two quiet moves that caused a beta cutoff at each ply.  A move that
+///   refuted one line is likely to refute its siblings too.
+/// * History (butterfly) table: for each side, from-square and to-square, how much cutoff work
+///   a quiet move has done anywhere in the tree (depth squared per cutoff).
+///
+/// A MoveOrdering belongs to a single search thread and is kept between iterations, so each
+/// iterative deepening pass benefits from what the previous one learned.
+pub struct MoveOrdering {
+    killers: Vec<[u32; 2]>,
+    history: Vec<u32>,
+}
+
+/// Constructs a new, empty MoveOrdering
+///
+/// # Examples
+///
+/// ```
+/// let mut ordering = chessica::tree::ordering::new();
+/// ```
+pub fn new() -> MoveOrdering {
+    MoveOrdering {
+        killers: Vec::new(),
+        history: vec![0; 2 * 64 * 64],
+    }
+}
+
+/// A move as the ordering heuristics see it: either a child the move generator built, or a
+/// `Move` to make on the parent
+pub trait OrderedMove {
+    /// Does the move capture or promote?
+    fn is_capture_or_promotion(&self) -> bool;
+    /// Was it the null move?
+    fn is_null_move(&self) -> bool;
+    /// `Reset::child_hash` of the child the move leads to
+    fn child_hash(&self) -> u32;
+    /// Square numbers moved from (0 for a Crazyhouse drop) and to
+    fn squares(&self) -> (u8, u8);
+    /// `Reset::mvv_lva` of the move, made from `parent`
+    fn mvv_lva(&self, parent: &Reset) -> i32;
+}
+
+impl OrderedMove for Reset {
+    fn is_capture_or_promotion(&self) -> bool {
+        Reset::is_capture_or_promotion(self)
+    }
+
+    fn is_null_move(&self) -> bool {
+        Reset::is_null_move(self)
+    }
+
+    fn child_hash(&self) -> u32 {
+        Reset::child_hash(self)
+    }
+
+    fn squares(&self) -> (u8, u8) {
+        (self.bi_from(), self.bi_to())
+    }
+
+    fn mvv_lva(&self, parent: &Reset) -> i32 {
+        parent.mvv_lva(self)
+    }
+}
+
+impl OrderedMove for Move {
+    fn is_capture_or_promotion(&self) -> bool {
+        Move::is_capture_or_promotion(self)
+    }
+
+    fn is_null_move(&self) -> bool {
+        false
+    }
+
+    fn child_hash(&self) -> u32 {
+        Move::child_hash(self)
+    }
+
+    fn squares(&self) -> (u8, u8) {
+        (self.bi_from(), self.bi_to())
+    }
+
+    fn mvv_lva(&self, parent: &Reset) -> i32 {
+        parent.move_mvv_lva(self)
+    }
+}
+
+fn history_index<M: OrderedMove>(parent: &Reset, child: &M) -> usize {
+    let side: usize = if parent.white_to_move() { 0 } else { 1 };
+    // Crazyhouse drops have no origin; they share the to-square's slot
+    let (from, to) = child.squares();
+    let from = if from == 0 { to } else { from };
+    side * 4096 + (from as usize - 1) * 64 + (to as usize - 1)
+}
+
+impl MoveOrdering {
+
+    /// Killer moves (as `child_hash` values) recorded for a ply
+    pub fn killers(&self, ply: u8) -> [u32; 2] {
+        match self.killers.get(ply as usize) {
+            Some(killers) => *killers,
+            None => [NO_KILLER, NO_KILLER],
+        }
+    }
+
+    /// History score for a child move
+    pub fn history<M: OrderedMove>(&self, parent: &Reset, child: &M) -> u32 {
+        self.history[history_index(parent, child)]
+    }
+
+    /// Ordering score of a child move; higher scores should be searched first
+    pub fn order_score<M: OrderedMove>(&self, ply: u8, parent: &Reset, child: &M) -> i32 {
+        if child.is_capture_or_promotion() {
+            return ORDER_CAPTURE + child.mvv_lva(parent);
+        }
+        let killers = self.killers(ply);
+        let hash = child.child_hash();
+        if hash == killers[0] {
+            ORDER_KILLER_1
+        } else if hash == killers[1] {
+            ORDER_KILLER_2
+        } else {
+            (self.history(parent, child).min(ORDER_HISTORY_MAX as u32)) as i32
+        }
+    }
+
+    /// Record that `child` caused a beta cutoff at `ply` with `depth` plies left to search
+    ///
+    /// Captures and promotions are already ordered first by MVV-LVA, so only quiet moves are
+    /// remembered.
+    pub fn record_cutoff<M: OrderedMove>(&mut self, ply: u8, depth: u8, parent: &Reset, child: &M) {
+        if child.is_capture_or_promotion() || child.is_null_move() {
+            return;
+        }
+        let hash = child.child_hash();
+        let ply = ply as usize;
+        if self.killers.len() <= ply {
+            self.killers.resize(ply + 1, [NO_KILLER, NO_KILLER]);
+        }
+        if self.killers[ply][0] != hash {
+            self.killers[ply][1] = self.killers[ply][0];
+            self.killers[ply][0] = hash;
+        }
+        let index = history_index(parent, child);
+        let bonus = depth as u32 * depth as u32;
+        self.history[index] = self.history[index].saturating_add(bonus);
+    }
+
+    /// Halve all history scores, so that what was learned in earlier iterations counts for less
+    pub fn age(&mut self) {
+        for value in self.history.iter_mut() {
+            *value /= 2;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::reset;
+    use crate::reset::Reset;
+    use crate::tree::ordering;
+    use crate::tree::ordering::OrderedMove;
+    use crate::tree::r#const::ORDER_KILLER_1;
+    use crate::tree::r#const::ORDER_KILLER_2;
+    use crate::tree::r#const::ORDER_CAPTURE;
+
+    fn children(fen: &str) -> (Reset, Vec<Reset>) {
+        let mut r = reset::new();
+        r.init_from_fen(fen.to_string());
+        let mut list = Vec::new();
+        let mut child = reset::new();
+        while r.generate_next_move(&mut child) {
+            list.push(reset::clone::clone_from(&mut child));
+        }
+        (r, list)
+    }
+
+    #[test]
+    fn ordering_killers() {
+        let mut o = ordering::new();
+        let (r, list) = children("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+        assert_eq!(o.order_score(3, &r, &list[4]),0);
+        o.record_cutoff(3, 2, &r, &list[4]);
+        assert_eq!(o.order_score(3, &r, &list[4]),ORDER_KILLER_1);
+        assert_eq!(o.order_score(2, &r, &list[4]),4,"history only at other plies");
+        o.record_cutoff(3, 2, &r, &list[7]);
+        assert_eq!(o.order_score(3, &r, &list[7]),ORDER_KILLER_1);
+        assert_eq!(o.order_score(3, &r, &list[4]),ORDER_KILLER_2);
+        o.record_cutoff(3, 2, &r, &list[7]);
+        assert_eq!(o.order_score(3, &r, &list[4]),ORDER_KILLER_2,"no duplicate killers");
+    }
+
+    #[test]
+    fn ordering_history() {
+        let mut o = ordering::new();
+        let (r, list) = children("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+        o.record_cutoff(0, 3, &r, &list[2]);
+        o.record_cutoff(0, 2, &r, &list[2]);
+        assert_eq!(o.history(&r, &list[2]),13);
+        o.age();
+        assert_eq!(o.history(&r, &list[2]),6);
+
+        // The same squares for the other side have their own history
+        let (b, list_black) = children("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR b KQkq - 0 1");
+        assert_eq!(o.history(&b, &list_black[2]),0);
+    }
+
+    #[test]
+    fn ordering_captures_not_remembered() {
+        let mut o = ordering::new();
+        let (r, list) = children("4k3/8/8/3q4/4P3/8/8/4K3 w - - 0 1");
+        let capture = list.iter().position(|c| c.is_capture_or_promotion()).unwrap();
+        o.record_cutoff(0, 4, &r, &list[capture]);
+        assert_eq!(o.killers(0),ordering::new().killers(0));
+        assert!(o.order_score(0, &r, &list[capture]) > ORDER_CAPTURE);
+    }
+
+    #[test]
+    fn ordering_moves_match_children() {
+        // A Move is scored and remembered just as the child it leads to
+        for fen in [
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "n1n5/PPPk4/8/8/8/8/4Kppp/5N1N b - - 0 1",
+            "2k5/8/8/8/8/8/8/4K3[QRBNPqrbnp] w - - 0 1",
+        ] {
+            let (mut r, list) = children(fen);
+            let mut moves = Vec::new();
+            r.legal_moves(&mut moves);
+            assert_eq!(moves.len(),list.len());
+            let mut o = ordering::new();
+            for (index, (mv, child)) in moves.iter().zip(list.iter()).enumerate() {
+                assert_eq!(OrderedMove::child_hash(mv),child.child_hash(),"{:?}",mv);
+                assert_eq!(o.order_score(1, &r, mv),o.order_score(1, &r, child),"{:?}",mv);
+                if index % 3 == 0 {
+                    o.record_cutoff(1, 2, &r, mv);
+                } else if index % 3 == 1 {
+                    o.record_cutoff(1, 2, &r, child);
+                }
+            }
+            for (mv, child) in moves.iter().zip(list.iter()) {
+                assert_eq!(o.order_score(1, &r, mv),o.order_score(1, &r, child),"{:?}",mv);
+            }
+        }
+    }
+}
@@ -0,0 +1,278 @@
+use crate::tree::Tree;
+use crate::tree::r#const::PN_INFINITY;
+use crate::reset::r#const::SCORE_BLACK_CHECKMATE;
+use crate::reset::r#const::SCORE_WHITE_CHECKMATE;
+
+const FIFTY_MOVE_RULE_PLIES: u16 = 100;
+
+/// Outcome of a proof-number search for "the side to move wins"
+#[derive(PartialEq,Eq,Copy,Clone,Debug)]
+pub enum ProofResult {
+    Proven,
+    Disproven,
+    Unknown,
+}
+
+/// Bookkeeping shared by every node of one proof-number search
+struct ProofSearch {
+    second_level: bool,
+    max_nodes: u64,
+    nodes: u64,
+}
+
+impl Tree {
+
+    /// Proof-number search for a forced checkmate by the side to move
+    ///
+    /// Each node carries a proof number (the fewest leaves that must still be proven to prove
+    /// it) and a disproof number (likewise for disproving it).  The search repeatedly walks down
+    /// to the most-proving leaf, expands it and backs the numbers up, so it spends its effort
+    /// wherever the win looks cheapest to establish regardless of depth.  The tree stays in
+    /// memory between iterations, and `max_nodes` caps the number of expansions.
+    ///
+    /// A node is disproven when the side to move is stalemated, when the fifty move rule has run
+    /// out, or when the attacker has nothing left but a king.  Repetitions aren't tracked, so
+    /// other drawn positions usually end `Unknown` once the node budget runs out.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chessica::tree::pn_search::ProofResult;
+    /// let mut t = chessica::tree::from_fen("r5k1/5ppp/8/8/8/8/4RPPP/4R1K1 w - - 0 1".to_string());
+    /// let mut node_count: u64 = 0;
+    /// assert_eq!(t.pn_search(10000, &mut node_count),ProofResult::Proven);
+    /// assert_eq!(t.proof_line(),"e2e8 a8e8 e1e8");
+    /// ```
+    pub fn pn_search(&mut self, max_nodes: u64, node_count: &mut u64) -> ProofResult {
+        self.proof_search(max_nodes, false, node_count)
+    }
+
+    /// PN² search: like `pn_search`, but each leaf is expanded by a second, nested proof-number
+    /// search whose budget is the size of the main tree so far
+    ///
+    /// Only the children of the expanded leaf are kept (along with any subtrees that were proven,
+    /// so the proof line survives), which lets the search look much deeper for the same amount
+    /// of memory at the cost of some repeated work.
+    pub fn pn2_search(&mut self, max_nodes: u64, node_count: &mut u64) -> ProofResult {
+        self.proof_search(max_nodes, true, node_count)
+    }
+
+    fn proof_search(&mut self, max_nodes: u64, second_level: bool, node_count: &mut u64) -> ProofResult {
+        let mut search = ProofSearch {
+            second_level,
+            max_nodes,
+            nodes: 0,
+        };
+        self.purge_children();
+        self.proof_number = 1;
+        self.disproof_number = 1;
+        while !self.proof_solved() && search.nodes < search.max_nodes {
+            self.pn_descend(true, &mut search);
+        }
+        *node_count += search.nodes;
+        if self.proof_number == 0 {
+            ProofResult::Proven
+        } else if self.disproof_number == 0 {
+            ProofResult::Disproven
+        } else {
+            ProofResult::Unknown
+        }
+    }
+
+    /// Has this node been proven or disproven?
+    pub fn proof_solved(&self) -> bool {
+        self.proof_number == 0 || self.disproof_number == 0
+    }
+
+    /// The proof found by `pn_search`/`pn2_search` as a move sequence: the attacker's quickest
+    /// proven win against the defence that holds out longest
+    pub fn proof_line(&mut self) -> String {
+        let mut line: Vec<String> = Vec::new();
+        let mut node = &mut *self;
+        let mut or_node = true;
+        while node.proof_number == 0 && !node.children.is_empty() {
+            let index = if or_node {
+                (0..node.children.len())
+                    .filter(|&c| node.children[c].proof_number == 0)
+                    .min_by_key(|&c| node.children[c].proof_depth(false))
+            } else {
+                (0..node.children.len())
+                    .max_by_key(|&c| node.children[c].proof_depth(true))
+            };
+            match index {
+                Some(index) => node = &mut node.children[index],
+                None => break,
+            }
+            line.push(node.reset.uci_move_text());
+            or_node = !or_node;
+        }
+        line.join(" ")
+    }
+
+    /// Length in plies of the proof below a proven node
+    fn proof_depth(&self, or_node: bool) -> u32 {
+        let depths = self.children.iter()
+            .filter(|child| child.proof_number == 0)
+            .map(|child| child.proof_depth(!or_node) + 1);
+        if self.children.is_empty() {
+            0
+        } else if or_node {
+            depths.min().unwrap_or(0)
+        } else {
+            depths.max().unwrap_or(0)
+        }
+    }
+
+    /// Walk down to the most-proving leaf, expand it, and update the numbers on the way back up
+    fn pn_descend(&mut self, or_node: bool, search: &mut ProofSearch) {
+        if self.children.is_empty() {
+            if search.second_level {
+                self.pn2_expand(or_node, search);
+            } else {
+                self.pn_expand(or_node, search);
+            }
+        } else {
+            let index = self.most_proving_child(or_node);
+            self.children[index].pn_descend(!or_node, search);
+            self.update_proof_numbers(or_node);
+        }
+    }
+
+    /// At OR nodes (attacker to move) follow the child that's cheapest to prove; at AND nodes
+    /// follow the child that's cheapest to disprove
+    fn most_proving_child(&self, or_node: bool) -> usize {
+        let mut best = 0;
+        for c in 1..self.children.len() {
+            let (candidate, current) = if or_node {
+                (self.children[c].proof_number, self.children[best].proof_number)
+            } else {
+                (self.children[c].disproof_number, self.children[best].disproof_number)
+            };
+            if candidate < current {
+                best = c;
+            }
+        }
+        best
+    }
+
+    fn update_proof_numbers(&mut self, or_node: bool) {
+        let proof_numbers = self.children.iter().map(|child| child.proof_number);
+        let disproof_numbers = self.children.iter().map(|child| child.disproof_number);
+        if or_node {
+            self.proof_number = proof_numbers.min().unwrap_or(PN_INFINITY);
+            self.disproof_number = disproof_numbers.fold(0, |sum: u32, n| sum.saturating_add(n));
+        } else {
+            self.proof_number = proof_numbers.fold(0, |sum: u32, n| sum.saturating_add(n));
+            self.disproof_number = disproof_numbers.min().unwrap_or(PN_INFINITY);
+        }
+    }
+
+    fn set_proof_result(&mut self, proven: bool) {
+        if proven {
+            self.proof_number = 0;
+            self.disproof_number = PN_INFINITY;
+        } else {
+            self.proof_number = PN_INFINITY;
+            self.disproof_number = 0;
+        }
+    }
+
+    fn pn_expand(&mut self, or_node: bool, search: &mut ProofSearch) {
+        search.nodes += 1;
+        let attacker_is_white = self.reset.white_to_move() == or_node;
+        if self.reset.has_lone_king(attacker_is_white) {
+            self.set_proof_result(false);
+            return;
+        }
+        self.reset.restart_move_generation();
+        while self.add_next_child() {}
+        if self.children.is_empty() {
+            // A win only if the defender is the one mated (or has lost by a variant's goal);
+            // stalemate is never a win
+            let attacker_wins = if attacker_is_white { SCORE_WHITE_CHECKMATE } else { SCORE_BLACK_CHECKMATE };
+            self.set_proof_result(self.reset.terminal_score() == attacker_wins);
+        } else if self.reset.halfmove_clock() >= FIFTY_MOVE_RULE_PLIES {
+            self.purge_children();
+            self.set_proof_result(false);
+        } else {
+            self.update_proof_numbers(or_node);
+        }
+    }
+
+    /// Expand a leaf by running a nested proof-number search below it, then throw away
+    /// everything below its children except proven subtrees
+    fn pn2_expand(&mut self, or_node: bool, search: &mut ProofSearch) {
+        let mut nested = ProofSearch {
+            second_level: false,
+            max_nodes: search.nodes.max(1),
+            nodes: 0,
+        };
+        self.pn_expand(or_node, &mut nested);
+        while !self.proof_solved() && nested.nodes < nested.max_nodes {
+            self.pn_descend(or_node, &mut nested);
+        }
+        for child in self.children.iter_mut() {
+            if child.proof_number != 0 {
+                child.purge_children();
+            }
+        }
+        search.nodes += 1;
+    }
+
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::tree::Tree;
+    use crate::tree::pn_search::ProofResult;
+
+    #[test]
+    fn pn_search_proven() {
+        let mut t: Tree = crate::tree::from_fen("6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1".to_string());
+        let mut node_count: u64 = 0;
+        assert_eq!(t.pn_search(10000, &mut node_count),ProofResult::Proven);
+        assert_eq!(t.proof_line(),"a1a8");
+
+        let mut t: Tree = crate::tree::from_fen("k7/8/2K5/8/8/8/8/7R w - - 0 1".to_string());
+        let mut node_count: u64 = 0;
+        assert_eq!(t.pn_search(10000, &mut node_count),ProofResult::Proven);
+        // Proof-number search proves *a* win, not necessarily the shortest one
+        assert_eq!(t.proof_line().split_whitespace().count() % 2,1);
+    }
+
+    #[test]
+    fn pn_search_disproven() {
+        // Lone king
+        let mut t: Tree = crate::tree::from_fen("8/8/8/4k3/8/8/8/R3K3 b - - 0 1".to_string());
+        let mut node_count: u64 = 0;
+        assert_eq!(t.pn_search(10000, &mut node_count),ProofResult::Disproven);
+        assert_eq!(t.proof_line(),"");
+
+        // Stalemated
+        let mut t: Tree = crate::tree::from_fen("k7/8/1Q6/8/8/8/8/7K b - - 0 1".to_string());
+        let mut node_count: u64 = 0;
+        assert_eq!(t.pn_search(10000, &mut node_count),ProofResult::Disproven);
+
+        // No mate in one, and any other move runs out the fifty move rule
+        let mut t: Tree = crate::tree::from_fen("8/8/8/8/8/2k5/8/K6R w - - 99 80".to_string());
+        let mut node_count: u64 = 0;
+        assert_eq!(t.pn_search(10000, &mut node_count),ProofResult::Disproven);
+    }
+
+    #[test]
+    fn pn_search_unknown() {
+        let mut t: Tree = crate::tree::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1".to_string());
+        let mut node_count: u64 = 0;
+        assert_eq!(t.pn_search(200, &mut node_count),ProofResult::Unknown);
+        assert_eq!(node_count,200);
+    }
+
+    #[test]
+    fn pn2_search_proven() {
+        let mut t: Tree = crate::tree::from_fen("r5k1/5ppp/8/8/8/8/4RPPP/4R1K1 w - - 0 1".to_string());
+        let mut node_count: u64 = 0;
+        assert_eq!(t.pn2_search(10000, &mut node_count),ProofResult::Proven);
+        assert_eq!(t.proof_line(),"e2e8 a8e8 e1e8");
+    }
+}
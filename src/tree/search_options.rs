@@ -0,0 +1,153 @@
+use crate::reset::Reset;
+use crate::reset::make::Move;
+
+/// Tunable selectivity settings shared by the alpha-beta searches
+///
+/// Everything here is switched off by `new()`, so a search that is handed the default options
+/// examines exactly the same nodes it did before these settings existed.
+///
+/// | field                 | description |
+/// | --------------------- | ----------- |
+/// | null_move             | Try a "pass" before searching real moves and cut off if the opponent still can't reach the window |
+/// | null_move_reduction   | Extra plies (R) removed from the null-move search |
+/// | late_move_reductions  | Search quiet moves ordered late at reduced depth, re-searching if they surprise us |
+/// | lmr_full_depth_moves  | Number of moves at each node that are always searched to full depth |
+/// | lmr_min_depth         | Minimum remaining depth at which late moves may be reduced |
+/// | make_unmake           | Play moves on one Reset with make/unmake instead of building a child per move |
+#[derive(PartialEq,Eq,Copy,Clone,Debug)]
+pub struct SearchOptions {
+    pub null_move: bool,
+    pub null_move_reduction: u8,
+    pub late_move_reductions: bool,
+    pub lmr_full_depth_moves: u8,
+    pub lmr_min_depth: u8,
+    pub make_unmake: bool,
+}
+
+/// Constructs a new set of SearchOptions with all selectivity disabled
+///
+/// # Examples
+///
+/// ```
+/// let options = chessica::tree::search_options::new();
+/// assert!(!options.null_move);
+/// assert!(!options.late_move_reductions);
+/// ```
+pub fn new() -> SearchOptions {
+    SearchOptions {
+        null_move: false,
+        null_move_reduction: 2,
+        late_move_reductions: false,
+        lmr_full_depth_moves: 4,
+        lmr_min_depth: 3,
+        make_unmake: false,
+    }
+}
+
+impl SearchOptions {
+
+    /// Should this node try a null move, given the remaining search depth?
+    ///
+    /// A null move is never tried at the root (a cutoff there would leave no move to play), while
+    /// in check (passing would be illegal), directly after another null move, or when the side to
+    /// move has nothing but king and pawns.  That last guard keeps us out of trouble in pawn
+    /// endings, where zugzwang makes "passing is never best" untrue.
+    pub fn try_null_move(&self, reset: &Reset, remaining_depth: u8, ply: u8) -> bool {
+        self.null_move &&
+            ply > 0 &&
+            remaining_depth > self.null_move_reduction &&
+            !reset.in_check() &&
+            !reset.game_over() &&
+            !reset.is_null_move() &&
+            reset.has_non_pawn_material()
+    }
+
+    /// Should the move at `move_index` (0 == first move searched) be searched at reduced depth?
+    ///
+    /// Only quiet moves are reduced: captures, promotions, checking moves and evasions while in
+    /// check are always searched to full depth.
+    pub fn reduce_late_move(&self, parent: &Reset, child: &Reset, remaining_depth: u8, move_index: usize) -> bool {
+        self.late_move_reductions &&
+            remaining_depth >= self.lmr_min_depth &&
+            move_index >= self.lmr_full_depth_moves as usize &&
+            !parent.in_check() &&
+            child.is_quiet()
+    }
+
+    /// `reduce_late_move` for a move that is about to be made on `parent`
+    pub fn reduce_late_made_move(&self, parent: &Reset, mv: Move, remaining_depth: u8, move_index: usize) -> bool {
+        self.late_move_reductions &&
+            remaining_depth >= self.lmr_min_depth &&
+            move_index >= self.lmr_full_depth_moves as usize &&
+            !parent.in_check() &&
+            mv.is_quiet()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::reset;
+    use crate::reset::Reset;
+    use crate::tree::search_options;
+
+    fn prep_board(fen: &str) -> Reset {
+        let mut r = reset::new();
+        r.init_from_fen(fen.to_string());
+        r
+    }
+
+    #[test]
+    fn search_options_default_off() {
+        let options = search_options::new();
+        let r = prep_board("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+        assert!(!options.try_null_move(&r,8,1));
+    }
+
+    #[test]
+    fn search_options_null_move_guards() {
+        let mut options = search_options::new();
+        options.null_move = true;
+
+        let mut r = prep_board("rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 2");
+        let mut child = reset::new();
+        r.generate_next_move(&mut child);
+        assert!(options.try_null_move(&child,8,1));
+        assert!(!options.try_null_move(&child,8,0),"root");
+        assert!(!options.try_null_move(&child,2,1),"not enough depth left");
+
+        let mut null_child = reset::new();
+        child.init_null_child(&mut null_child);
+        assert!(!options.try_null_move(&null_child,8,2),"two null moves in a row");
+
+        let r = prep_board("4k3/pppp4/8/8/8/8/4PPPP/4K3 w - - 0 1");
+        assert!(!options.try_null_move(&r,8,1),"pawn ending");
+
+        let r = prep_board("3k4/3r4/5PP1/2P2QP1/5PP1/8/2P5/3K4 w - - 0 1");
+        assert!(!options.try_null_move(&r,8,1),"in check");
+    }
+
+    #[test]
+    fn search_options_late_move_reduction() {
+        let mut options = search_options::new();
+        options.late_move_reductions = true;
+
+        let mut r = prep_board("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+        let mut child = reset::new();
+        r.generate_next_move(&mut child);
+        assert!(options.reduce_late_move(&r,&child,4,4));
+        assert!(!options.reduce_late_move(&r,&child,4,3),"too early in the move list");
+        assert!(!options.reduce_late_move(&r,&child,2,4),"not enough depth left");
+
+        let mut r = prep_board("rnbqkbnr/ppp1pppp/8/3p4/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 2");
+        let mut child = reset::new();
+        while r.generate_next_move(&mut child) && child.is_quiet() {}
+        assert!(!options.reduce_late_move(&r,&child,4,10),"capture");
+        assert!(!options.reduce_late_made_move(&r,r.move_to(&child),4,10),"capture");
+
+        let mut r = prep_board("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+        let mut moves = Vec::new();
+        r.legal_moves(&mut moves);
+        assert!(options.reduce_late_made_move(&r,moves[0],4,4));
+        assert!(!options.reduce_late_made_move(&r,moves[0],4,3),"too early in the move list");
+    }
+}
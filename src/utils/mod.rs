@@ -1,3 +1,5 @@
+pub mod rng;
+
 
 /// Convert a chess "square" (e.g., "b6") to a position number
 /// 
@@ -17,7 +19,6 @@ pub fn convert_square_to_number(square: String) -> u8 {
             },
             '1'|'2'|'3'|'4'|'5'|'6'|'7'|'8' => {
                 squarenumber += 8 * ((c as u8) - b'1');
-                println!("squarenumber = {}",squarenumber);
             },
             _ => println!("I don't know what to do with {}",c),
         }
@@ -0,0 +1,80 @@
+/// Small, fast, seedable pseudo-random number generator (xorshift64*)
+///
+/// Nothing here needs cryptographic quality; what matters is that the same seed always produces
+/// the same sequence, so that searches and generated positions can be reproduced.
+#[derive(PartialEq,Eq,Copy,Clone,Debug)]
+pub struct Rng {
+    state: u64,
+}
+
+/// Constructs a new Rng from a seed.  Any seed is fine, including zero.
+///
+/// # Examples
+///
+/// ```
+/// let mut a = chessica::utils::rng::new(42);
+/// let mut b = chessica::utils::rng::new(42);
+/// assert_eq!(a.next_u64(),b.next_u64());
+/// assert!(a.below(6) < 6);
+/// ```
+pub fn new(seed: u64) -> Rng {
+    // Run the seed through splitmix64 so that similar seeds give unrelated sequences, and so the
+    // state is never zero (which xorshift can't leave)
+    let mut z = seed.wrapping_add(0x9e3779b97f4a7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^= z >> 31;
+    Rng {
+        state: if z == 0 { 0x9e3779b97f4a7c15 } else { z },
+    }
+}
+
+impl Rng {
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545f4914f6cdd1d)
+    }
+
+    /// Uniformly distributed number in `0..n` (`n` must not be zero)
+    pub fn below(&mut self, n: usize) -> usize {
+        (((self.next_u64() >> 32) * n as u64) >> 32) as usize
+    }
+
+    /// Uniformly distributed number in `[0,1)`
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::rng;
+
+    #[test]
+    fn rng_repeatable() {
+        let mut a = rng::new(7);
+        let mut b = rng::new(7);
+        let mut c = rng::new(8);
+        let a_values: Vec<u64> = (0..10).map(|_| a.next_u64()).collect();
+        let b_values: Vec<u64> = (0..10).map(|_| b.next_u64()).collect();
+        let c_values: Vec<u64> = (0..10).map(|_| c.next_u64()).collect();
+        assert_eq!(a_values,b_values);
+        assert_ne!(a_values,c_values);
+    }
+
+    #[test]
+    fn rng_ranges() {
+        let mut r = rng::new(0);
+        let mut seen = [false; 6];
+        for _i in 0..1000 {
+            let n = r.below(6);
+            seen[n] = true;
+            let f = r.next_f64();
+            assert!((0.0..1.0).contains(&f));
+        }
+        assert!(seen.iter().all(|&s| s));
+    }
+}
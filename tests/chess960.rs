@@ -0,0 +1,26 @@
+use chessica::reset;
+use chessica::reset::chess960;
+
+// UCI_Chess960 is a process-wide switch, so this test has a binary of its own rather than sitting
+// beside the library tests that expect castling written as the king's move.
+
+#[test]
+fn test_uci_chess960_move_text() {
+    let mut r = reset::new();
+    r.init_from_fen("1k6/8/8/8/8/8/8/RK4R1 w AG - 0 1".to_string());
+    let mut queenside = r.child_from_san("O-O-O").unwrap();
+    let mut kingside = r.child_from_san("O-O").unwrap();
+    let mut standard = reset::new();
+    standard.init_from_fen("4k3/8/8/8/8/8/8/4K2R w K - 0 1".to_string());
+    let mut standard_castle = standard.child_from_san("O-O").unwrap();
+    let mut king_move = standard.child_from_uci("e1d1").unwrap();
+
+    chess960::set_enabled(true);
+    let texts = [queenside.uci_move_text(), kingside.uci_move_text(), standard_castle.uci_move_text(), king_move.uci_move_text()];
+    chess960::set_enabled(false);
+    assert_eq!(texts,["b1a1", "b1g1", "e1h1", "e1d1"]);
+
+    assert_eq!(queenside.uci_move_text(),"b1c1");
+    assert_eq!(kingside.uci_move_text(),"b1g1");
+    assert_eq!(standard_castle.uci_move_text(),"e1g1");
+}
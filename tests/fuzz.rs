@@ -0,0 +1,50 @@
+use chessica::reference::fuzz;
+use chessica::reference::fuzz::FUZZ_STARTS;
+use chessica::reset::random;
+use chessica::reset::random::PositionSource;
+
+#[test]
+fn test_fuzz_games() {
+    // Long enough games to reach castling, en passant, promotion and the endgame
+    for seed in 1..=3 {
+        let result = fuzz::fuzz(seed, 10, 120);
+        assert!(result.is_ok(),"seed {}: {}",seed,result.unwrap_err());
+    }
+}
+
+#[test]
+fn test_fuzz_starts() {
+    for fen in FUZZ_STARTS {
+        assert_eq!(fuzz::compare(fen),Ok(()),"{}",fen);
+    }
+}
+
+#[test]
+fn test_fuzz_check_flags() {
+    // Pieces beside a king that don't attack it along that line once passed for checks
+    for fen in [
+        "8/8/8/K7/7k/8/6P1/8 w - - 0 1",
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "4k3/8/8/8/8/8/3r4/4K2R b K - 0 1",
+        "4k3/8/8/8/8/8/4b3/3NK3 b - - 0 1",
+    ] {
+        assert_eq!(fuzz::compare(fen),Ok(()),"{}",fen);
+    }
+}
+
+#[test]
+fn test_fuzz_random_positions() {
+    // Positions no game from the fuzzer's starts is likely to reach
+    let sources = [
+        PositionSource::Placement { signature: "KQRBNPPvKQRBNPP".to_string() },
+        PositionSource::Placement { signature: "KRRPPPvKQPPP".to_string() },
+        PositionSource::Placement { signature: "KBBvKNN".to_string() },
+        PositionSource::Playout { fen: FUZZ_STARTS[1].to_string(), min_plies: 1, max_plies: 12 },
+    ];
+    for source in sources {
+        for mut r in random::new(source, 21).unwrap().take(200) {
+            let fen = r.to_fen();
+            assert_eq!(fuzz::compare(&fen),Ok(()),"{}",fen);
+        }
+    }
+}
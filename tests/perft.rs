@@ -1,4 +1,7 @@
 use chessica::reset::profiling::count_possible_games;
+use chessica::reset::profiling::count_possible_variant_games;
+use chessica::reset::profiling::count_possible_games_make_unmake;
+use chessica::reset::variant::Variant;
 
 #[test]
 fn test_perft_numpty2_short() {
@@ -335,6 +338,238 @@ fn test_perft_castling_long() {
 
 /**************************************************************************/
 
+#[test]
+fn test_perft_chess960_1_short() {
+    // Chess960 position 1 - https://www.chessprogramming.org/Chess960_Perft_Results
+    let fen = String::from("bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9");
+    assert_eq!(count_possible_games(&fen,1),21,"Chess960 1, ply=1");
+    assert_eq!(count_possible_games(&fen,2),528,"Chess960 1, ply=2");
+    assert_eq!(count_possible_games(&fen,3),12189,"Chess960 1, ply=3");
+    assert_eq!(count_possible_games(&fen,4),326672,"Chess960 1, ply=4");
+}
+
+#[test]
+#[ignore]
+fn test_perft_chess960_1_long() {
+    // Chess960 position 1 - https://www.chessprogramming.org/Chess960_Perft_Results
+    let fen = String::from("bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9");
+    assert_eq!(count_possible_games(&fen,5),8146062,"Chess960 1, ply=5");
+    assert_eq!(count_possible_games(&fen,6),227689589,"Chess960 1, ply=6");
+}
+
+#[test]
+fn test_perft_chess960_2_short() {
+    // Chess960 position 2 - https://www.chessprogramming.org/Chess960_Perft_Results
+    let fen = String::from("2nnrbkr/p1qppppp/8/1ppb4/6PP/3PP3/PPP2P2/BQNNRBKR w HEhe - 1 9");
+    assert_eq!(count_possible_games(&fen,1),21,"Chess960 2, ply=1");
+    assert_eq!(count_possible_games(&fen,2),807,"Chess960 2, ply=2");
+    assert_eq!(count_possible_games(&fen,3),18002,"Chess960 2, ply=3");
+    assert_eq!(count_possible_games(&fen,4),667366,"Chess960 2, ply=4");
+}
+
+#[test]
+#[ignore]
+fn test_perft_chess960_2_long() {
+    // Chess960 position 2 - https://www.chessprogramming.org/Chess960_Perft_Results
+    let fen = String::from("2nnrbkr/p1qppppp/8/1ppb4/6PP/3PP3/PPP2P2/BQNNRBKR w HEhe - 1 9");
+    assert_eq!(count_possible_games(&fen,5),16253601,"Chess960 2, ply=5");
+    assert_eq!(count_possible_games(&fen,6),590751109,"Chess960 2, ply=6");
+}
 
+#[test]
+fn test_perft_chess960_3_short() {
+    // Chess960 position 3 - https://www.chessprogramming.org/Chess960_Perft_Results
+    let fen = String::from("b1q1rrkb/pppppppp/3nn3/8/P7/1PPP4/4PPPP/BQNNRKRB w GE - 1 9");
+    assert_eq!(count_possible_games(&fen,1),20,"Chess960 3, ply=1");
+    assert_eq!(count_possible_games(&fen,2),479,"Chess960 3, ply=2");
+    assert_eq!(count_possible_games(&fen,3),10471,"Chess960 3, ply=3");
+    assert_eq!(count_possible_games(&fen,4),273318,"Chess960 3, ply=4");
+}
 
+#[test]
+#[ignore]
+fn test_perft_chess960_3_long() {
+    // Chess960 position 3 - https://www.chessprogramming.org/Chess960_Perft_Results
+    let fen = String::from("b1q1rrkb/pppppppp/3nn3/8/P7/1PPP4/4PPPP/BQNNRKRB w GE - 1 9");
+    assert_eq!(count_possible_games(&fen,5),6417013,"Chess960 3, ply=5");
+    assert_eq!(count_possible_games(&fen,6),177654692,"Chess960 3, ply=6");
+}
 
+#[test]
+fn test_perft_chess960_4_short() {
+    // Chess960 position 4 - https://www.chessprogramming.org/Chess960_Perft_Results
+    let fen = String::from("qbbnnrkr/2pp2pp/p7/1p2pp2/8/P3PP2/1PPP1KPP/QBBNNR1R w hf - 0 9");
+    assert_eq!(count_possible_games(&fen,1),22,"Chess960 4, ply=1");
+    assert_eq!(count_possible_games(&fen,2),593,"Chess960 4, ply=2");
+    assert_eq!(count_possible_games(&fen,3),13440,"Chess960 4, ply=3");
+    assert_eq!(count_possible_games(&fen,4),382958,"Chess960 4, ply=4");
+}
+
+#[test]
+#[ignore]
+fn test_perft_chess960_4_long() {
+    // Chess960 position 4 - https://www.chessprogramming.org/Chess960_Perft_Results
+    let fen = String::from("qbbnnrkr/2pp2pp/p7/1p2pp2/8/P3PP2/1PPP1KPP/QBBNNR1R w hf - 0 9");
+    assert_eq!(count_possible_games(&fen,5),9183776,"Chess960 4, ply=5");
+    assert_eq!(count_possible_games(&fen,6),274103539,"Chess960 4, ply=6");
+}
+
+#[test]
+fn test_perft_chess960_5_short() {
+    // Chess960 position 5 - https://www.chessprogramming.org/Chess960_Perft_Results
+    let fen = String::from("1nbbnrkr/p1p1ppp1/3p4/1p3P1p/3Pq2P/8/PPP1P1P1/QNBBNRKR w HFhf - 0 9");
+    assert_eq!(count_possible_games(&fen,1),28,"Chess960 5, ply=1");
+    assert_eq!(count_possible_games(&fen,2),1120,"Chess960 5, ply=2");
+    assert_eq!(count_possible_games(&fen,3),31058,"Chess960 5, ply=3");
+    assert_eq!(count_possible_games(&fen,4),1171749,"Chess960 5, ply=4");
+}
+
+#[test]
+#[ignore]
+fn test_perft_chess960_5_long() {
+    // Chess960 position 5 - https://www.chessprogramming.org/Chess960_Perft_Results
+    let fen = String::from("1nbbnrkr/p1p1ppp1/3p4/1p3P1p/3Pq2P/8/PPP1P1P1/QNBBNRKR w HFhf - 0 9");
+    assert_eq!(count_possible_games(&fen,5),34030312,"Chess960 5, ply=5");
+    assert_eq!(count_possible_games(&fen,6),1250970898,"Chess960 5, ply=6");
+}
+
+/**************************************************************************/
+
+#[test]
+fn test_perft_crazyhouse_start_short() {
+    // Crazyhouse start position - drops only become possible after the first capture
+    let fen = String::from("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR[] w KQkq - 0 1");
+    assert_eq!(count_possible_games(&fen,1),20,"Crazyhouse start, ply=1");
+    assert_eq!(count_possible_games(&fen,2),400,"Crazyhouse start, ply=2");
+    assert_eq!(count_possible_games(&fen,3),8902,"Crazyhouse start, ply=3");
+    assert_eq!(count_possible_games(&fen,4),197281,"Crazyhouse start, ply=4");
+}
+
+#[test]
+#[ignore]
+fn test_perft_crazyhouse_start_long() {
+    // Crazyhouse start position - drops only become possible after the first capture
+    let fen = String::from("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR[] w KQkq - 0 1");
+    assert_eq!(count_possible_games(&fen,5),4888832,"Crazyhouse start, ply=5");
+}
+
+#[test]
+fn test_perft_crazyhouse_all_drops_short() {
+    // Every droppable piece in both pockets
+    let fen = String::from("2k5/8/8/8/8/8/8/4K3[QRBNPqrbnp] w - - 0 1");
+    assert_eq!(count_possible_games(&fen,1),301,"Crazyhouse all drops, ply=1");
+    assert_eq!(count_possible_games(&fen,2),75353,"Crazyhouse all drops, ply=2");
+}
+
+/**************************************************************************/
+
+#[test]
+fn test_perft_atomic_start_short() {
+    // Atomic start position
+    let fen = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+    assert_eq!(count_possible_variant_games(fen,Variant::Atomic,1),20,"Atomic start, ply=1");
+    assert_eq!(count_possible_variant_games(fen,Variant::Atomic,2),400,"Atomic start, ply=2");
+    assert_eq!(count_possible_variant_games(fen,Variant::Atomic,3),8902,"Atomic start, ply=3");
+    assert_eq!(count_possible_variant_games(fen,Variant::Atomic,4),197326,"Atomic start, ply=4");
+}
+
+#[test]
+#[ignore]
+fn test_perft_atomic_start_long() {
+    // Atomic start position
+    let fen = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+    assert_eq!(count_possible_variant_games(fen,Variant::Atomic,5),4864979,"Atomic start, ply=5");
+}
+
+/**************************************************************************/
+
+#[test]
+fn test_perft_antichess_start_short() {
+    // Antichess start position - no castling
+    let fen = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w - - 0 1";
+    assert_eq!(count_possible_variant_games(fen,Variant::Antichess,1),20,"Antichess start, ply=1");
+    assert_eq!(count_possible_variant_games(fen,Variant::Antichess,2),400,"Antichess start, ply=2");
+    assert_eq!(count_possible_variant_games(fen,Variant::Antichess,3),8067,"Antichess start, ply=3");
+    assert_eq!(count_possible_variant_games(fen,Variant::Antichess,4),153299,"Antichess start, ply=4");
+}
+
+#[test]
+#[ignore]
+fn test_perft_antichess_start_long() {
+    // Antichess start position - no castling
+    let fen = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w - - 0 1";
+    assert_eq!(count_possible_variant_games(fen,Variant::Antichess,5),2732672,"Antichess start, ply=5");
+}
+
+#[test]
+fn test_perft_antichess_pawn_race() {
+    // One pawn each: captures are forced, and a side with nothing left has no moves
+    let fen = "8/1p6/8/8/8/8/P7/8 w - - 0 1";
+    assert_eq!(count_possible_variant_games(fen,Variant::Antichess,1),2,"Antichess pawn race, ply=1");
+    assert_eq!(count_possible_variant_games(fen,Variant::Antichess,2),4,"Antichess pawn race, ply=2");
+    assert_eq!(count_possible_variant_games(fen,Variant::Antichess,3),4,"Antichess pawn race, ply=3");
+    assert_eq!(count_possible_variant_games(fen,Variant::Antichess,4),3,"Antichess pawn race, ply=4");
+    assert_eq!(count_possible_variant_games(fen,Variant::Antichess,5),1,"Antichess pawn race, ply=5");
+    assert_eq!(count_possible_variant_games(fen,Variant::Antichess,6),0,"Antichess pawn race, ply=6");
+}
+
+/**************************************************************************/
+
+#[test]
+fn test_perft_king_of_the_hill() {
+    // The start position plays as standard chess this shallow
+    let fen = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+    assert_eq!(count_possible_variant_games(fen,Variant::KingOfTheHill,4),197281,"King of the Hill start, ply=4");
+    // Kd4 and Ke4 end the game
+    let fen = "8/8/8/8/8/4K3/8/7k w - - 0 1";
+    assert_eq!(count_possible_variant_games(fen,Variant::KingOfTheHill,1),8,"King of the Hill race, ply=1");
+    assert_eq!(count_possible_variant_games(fen,Variant::KingOfTheHill,2),15,"King of the Hill race, ply=2");
+}
+
+#[test]
+fn test_perft_three_check() {
+    let fen = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1 +0+0";
+    assert_eq!(count_possible_variant_games(fen,Variant::ThreeCheck,4),197281,"Three-check start, ply=4");
+    // Ra8+ is white's third check, which ends the game
+    let fen = "4k3/8/8/8/8/8/8/R3K3 w - - 0 1 +2+0";
+    assert_eq!(count_possible_variant_games(fen,Variant::ThreeCheck,1),15,"Three-check third check, ply=1");
+    assert_eq!(count_possible_variant_games(fen,Variant::ThreeCheck,2),65,"Three-check third check, ply=2");
+}
+
+#[test]
+fn test_perft_racing_kings_start_short() {
+    // Racing Kings start position
+    let fen = "8/8/8/8/8/8/krbnNBRK/qrbnNBRQ w - - 0 1";
+    assert_eq!(count_possible_variant_games(fen,Variant::RacingKings,1),21,"Racing Kings start, ply=1");
+    assert_eq!(count_possible_variant_games(fen,Variant::RacingKings,2),421,"Racing Kings start, ply=2");
+    assert_eq!(count_possible_variant_games(fen,Variant::RacingKings,3),11264,"Racing Kings start, ply=3");
+    assert_eq!(count_possible_variant_games(fen,Variant::RacingKings,4),296242,"Racing Kings start, ply=4");
+}
+
+#[test]
+#[ignore]
+fn test_perft_racing_kings_start_long() {
+    // Racing Kings start position
+    let fen = "8/8/8/8/8/8/krbnNBRK/qrbnNBRQ w - - 0 1";
+    assert_eq!(count_possible_variant_games(fen,Variant::RacingKings,5),9472927,"Racing Kings start, ply=5");
+}
+
+/**************************************************************************/
+
+#[test]
+fn test_perft_make_unmake_short() {
+    // Make/unmake must count exactly what copy-make does
+    let positions = [
+        ("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1", Variant::Standard, 5, 4865609),
+        ("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1", Variant::Standard, 4, 4085603),
+        ("8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1", Variant::Standard, 5, 674624),
+        ("2k5/8/8/8/8/8/8/4K3[QRBNPqrbnp] w - - 0 1", Variant::Crazyhouse, 2, 75353),
+        ("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1", Variant::Atomic, 4, 197326),
+        ("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w - - 0 1", Variant::Antichess, 4, 153299),
+        ("4k3/8/8/8/8/8/8/R3K3 w - - 0 1 +2+0", Variant::ThreeCheck, 2, 65),
+        ("8/8/8/8/8/8/krbnNBRK/qrbnNBRQ w - - 0 1", Variant::RacingKings, 4, 296242),
+    ];
+    for (fen, variant, depth, count) in positions {
+        assert_eq!(count_possible_games_make_unmake(fen,variant,depth),count,"{} make/unmake, ply={}",fen,depth);
+    }
+}
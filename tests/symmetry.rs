@@ -0,0 +1,101 @@
+use chessica::eval;
+use chessica::reset;
+use chessica::reset::Reset;
+use chessica::reset::symmetry::Symmetry;
+use chessica::reset::symmetry::SYMMETRIES;
+use chessica::reset::variant::Variant;
+
+// Positions to turn over, with how deep to count their games.  Between them they cover
+// castling (standard and Chess960), en passant, promotion, pins and checks for both colours,
+// pawnless endings for the rotations, and each variant.
+const CORPUS: [(&str, Variant, u8); 21] = [
+    ("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1", Variant::Standard, 4),
+    ("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1", Variant::Standard, 4),
+    ("8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1", Variant::Standard, 4),
+    ("r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1", Variant::Standard, 4),
+    ("rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8", Variant::Standard, 4),
+    ("r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10", Variant::Standard, 3),
+    ("8/8/3p4/KPp4r/1R3p1k/8/4P1P1/8 w - c6 0 2", Variant::Standard, 4),
+    ("8/p7/8/1P6/K1k3p1/6P1/7P/8 w - - 0 1", Variant::Standard, 5),
+    ("n1n5/PPPk4/8/8/8/8/4Kppp/5N1N b - - 0 1", Variant::Standard, 3),
+    ("bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9", Variant::Standard, 3),
+    ("8/kr6/r7/8/8/8/6RK/7R w - - 0 1", Variant::Standard, 4),
+    ("4k3/8/8/2b5/8/5N2/8/1K2B3 b - - 0 1", Variant::Standard, 4),
+    ("8/8/8/3k4/8/8/1Q6/K7 w - - 0 1", Variant::Standard, 4),
+    ("2k5/8/8/8/8/8/8/4K3[QRBNPqrbnp] w - - 0 1", Variant::Crazyhouse, 2),
+    ("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1", Variant::Atomic, 3),
+    ("4k3/8/8/3q4/8/8/3R4/4K3 w - - 0 1", Variant::Atomic, 3),
+    ("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w - - 0 1", Variant::Antichess, 3),
+    ("8/8/2n5/8/8/5B2/8/1R6 w - - 0 1", Variant::Antichess, 4),
+    ("8/8/8/8/8/4K3/8/7k w - - 0 1", Variant::KingOfTheHill, 4),
+    ("4k3/8/8/8/8/8/8/R3K3 w - - 0 1 +2+0", Variant::ThreeCheck, 3),
+    ("8/8/8/8/8/8/krbnNBRK/qrbnNBRQ w - - 0 1", Variant::RacingKings, 3),
+];
+
+fn prep_board(fen: &str, variant: Variant) -> Reset {
+    let mut r = reset::new();
+    r.init_from_fen(fen.to_string());
+    r.set_variant(variant);
+    r
+}
+
+fn perft(r: &mut Reset, depth: u8) -> u64 {
+    let mut move_count = 0;
+    reset::clone::clone_from(r).in_place_move_tree(depth, &mut move_count);
+    move_count
+}
+
+/// The position's score and its children's, from the point of view of the side to move, sorted
+fn scores(r: &mut Reset) -> Vec<i32> {
+    let side = |r: &Reset| if r.white_to_move() { 1 } else { -1 };
+    let mut scores = vec![side(r) * r.score()];
+    let mut parent = reset::clone::clone_from(r);
+    let mut child = reset::new();
+    parent.restart_move_generation();
+    while parent.generate_next_move(&mut child) {
+        scores.push(side(&child) * child.score());
+    }
+    scores.sort_unstable();
+    scores
+}
+
+#[test]
+fn test_symmetry_perft() {
+    for (fen, variant, depth) in CORPUS {
+        let mut r = prep_board(fen, variant);
+        for symmetry in SYMMETRIES {
+            if let Some(mut transformed) = r.transformed(symmetry) {
+                let transformed_fen = transformed.to_fen();
+                for ply in 1..=depth {
+                    assert_eq!(perft(&mut transformed, ply),perft(&mut r, ply),"{:?} of {} ({}), ply={}",symmetry,fen,transformed_fen,ply);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_symmetry_scores() {
+    // The tie-breaking noise depends on where the pieces stand
+    eval::set_noise(false);
+    for (fen, variant, _) in CORPUS {
+        let mut r = prep_board(fen, variant);
+        let expected = scores(&mut r);
+        for symmetry in SYMMETRIES {
+            if let Some(mut transformed) = r.transformed(symmetry) {
+                let transformed_fen = transformed.to_fen();
+                assert_eq!(scores(&mut transformed),expected,"{:?} of {} ({})",symmetry,fen,transformed_fen);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_symmetry_coverage() {
+    // Every symmetry is exercised by some position of the corpus
+    for symmetry in SYMMETRIES {
+        let applies = CORPUS.iter().filter(|(fen, variant, _)| symmetry.applies_to(&prep_board(fen, *variant))).count();
+        assert!(applies >= 4,"{:?} applies to only {} positions",symmetry,applies);
+    }
+    assert_eq!(prep_board(CORPUS[0].0, CORPUS[0].1).transformed(Symmetry::Rotate90).map(|mut r| r.to_fen()),None);
+}
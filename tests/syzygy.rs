@@ -0,0 +1,81 @@
+use chessica::syzygy;
+use chessica::syzygy::Tablebases;
+use chessica::syzygy::Wdl;
+use chessica::syzygy::probe;
+
+// These tests read the KQvK, KRvK and KPvK tables in tests/fixtures/syzygy, which are written by
+// `cargo run --release --example syzygy_fixtures`.  To run them against another set of tables
+// (which must include those three, both .rtbw and .rtbz):
+//
+//     CHESSICA_SYZYGY_PATH=/path/to/syzygy cargo test --test syzygy
+
+fn tablebases() -> Tablebases {
+    let path = std::env::var("CHESSICA_SYZYGY_PATH").unwrap_or(String::from("tests/fixtures/syzygy"));
+    let mut tablebases = syzygy::new();
+    let found = tablebases.add_directory(&path).expect("no Syzygy directory");
+    assert!(found > 0,"no Syzygy tables in {}",path);
+    tablebases
+}
+
+fn reset_from(fen: &str) -> chessica::reset::Reset {
+    let mut r = chessica::reset::new();
+    r.init_from_fen(fen.to_string());
+    r
+}
+
+#[test]
+fn test_syzygy_wdl() {
+    let tablebases = tablebases();
+    let cases = [
+        ("8/8/8/4k3/8/8/8/KQ6 w - - 0 1", Wdl::Win),
+        ("8/8/8/4k3/8/8/8/KQ6 b - - 0 1", Wdl::Loss),
+        ("8/8/8/4K3/8/8/8/kq6 b - - 0 1", Wdl::Win),
+        ("8/8/8/4k3/8/8/8/KR6 w - - 0 1", Wdl::Win),
+        ("8/8/8/4k3/8/8/8/KR6 b - - 0 1", Wdl::Loss),
+        // White takes the rook
+        ("k7/8/8/8/8/8/1r6/K7 w - - 0 1", Wdl::Draw),
+        ("8/8/8/8/8/4k3/4P3/4K3 w - - 0 1", Wdl::Draw),
+        ("4k3/8/4K3/4P3/8/8/8/8 b - - 0 1", Wdl::Loss),
+        ("8/8/8/8/8/8/1k6/1Q5K b - - 0 1", Wdl::Draw),
+        // Rook pawns don't win against a king in the corner
+        ("k7/8/K7/P7/8/8/8/8 w - - 0 1", Wdl::Draw),
+    ];
+    for (fen, wdl) in cases.iter() {
+        assert_eq!(probe::wdl_with(&tablebases, &reset_from(fen)),Some(*wdl),"{}",fen);
+    }
+}
+
+#[test]
+fn test_syzygy_root_move() {
+    let tablebases = tablebases();
+    let r = reset_from("k7/8/1K6/8/8/8/8/6Q1 w - - 0 1");
+    assert_eq!(probe::dtz_with(&tablebases, &r),Some(1));
+    let (mut child, dtz) = probe::root_move_with(&tablebases, &r).unwrap();
+    assert_eq!(dtz,1);
+    assert_eq!(child.uci_move_text(),"g1g8");
+
+    // Losing side: every move loses, and the table still answers
+    let r = reset_from("8/8/8/4k3/8/8/8/KQ6 b - - 0 1");
+    let (_, dtz) = probe::root_move_with(&tablebases, &r).unwrap();
+    assert!(dtz < 0);
+}
+
+#[test]
+fn test_syzygy_dtz() {
+    let tablebases = tablebases();
+    // The KRvK DTZ table stores black to move, so white to move is worked out one ply ahead
+    for fen in ["8/8/8/4k3/8/8/8/KR6 w - - 0 1", "4k3/8/4K3/4P3/8/8/8/8 w - - 0 1"].iter() {
+        let r = reset_from(fen);
+        let dtz = probe::dtz_with(&tablebases, &r).unwrap();
+        assert!(dtz > 1,"{}",fen);
+        let (child, root_dtz) = probe::root_move_with(&tablebases, &r).unwrap();
+        assert_eq!(root_dtz,dtz,"{}",fen);
+        assert_eq!(probe::dtz_with(&tablebases, &child),Some(1 - dtz),"{}",fen);
+    }
+
+    // Black to move is read straight from the KRvK table
+    let r = reset_from("8/8/8/8/8/2k5/8/K6R b - - 0 1");
+    assert!(probe::dtz_with(&tablebases, &r).unwrap() < 0);
+    assert_eq!(probe::dtz_with(&tablebases, &reset_from("4k3/8/4K3/4P3/8/8/8/8 b - - 0 1")).map(|dtz| dtz < 0),Some(true));
+    assert_eq!(probe::dtz_with(&tablebases, &reset_from("8/8/8/8/8/4k3/4P3/4K3 w - - 0 1")),Some(0));
+}